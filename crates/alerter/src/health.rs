@@ -0,0 +1,479 @@
+//! Health checks of the protocol beyond the missing trades of matchable
+//! orders. Every check is derived from observations of the orderbook API and
+//! is either healthy or failing with a description of the problem.
+
+use {
+    model::{AuctionId, solver_competition_v2},
+    std::{
+        collections::{BTreeMap, VecDeque},
+        time::{Duration, Instant},
+    },
+};
+
+/// Time a winning solution gets to be settled before it counts as failed.
+const SETTLEMENT_GRACE_PERIOD: Duration = Duration::from_secs(120);
+
+/// Maximum number of competitions checked for their settlement per update, so
+/// an outage of the alerter doesn't result in a burst of requests.
+const MAX_COMPETITIONS_PER_UPDATE: AuctionId = 100;
+
+/// Orderbook API methods serving quotes as labeled in its metrics.
+const QUOTE_METHODS: &[&str] = &["v1/post_quote", "v1/post_quote_stream"];
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Check {
+    /// No trades even though an order has been matchable for a while.
+    NoTradesButMatchableOrder,
+    /// The auction ID did not advance.
+    StaleAuction,
+    /// Too many quote requests of the matchability oracles failed.
+    QuoteErrors,
+    /// Too many quote requests served by the orderbook failed.
+    OrderbookQuoteErrors,
+    /// No new solver competition got stored.
+    SolverCompetitionGap,
+    /// Several winning solutions in a row did not get settled.
+    SettlementFailures,
+}
+
+impl Check {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::NoTradesButMatchableOrder => "no_trades_but_matchable_order",
+            Self::StaleAuction => "stale_auction",
+            Self::QuoteErrors => "quote_errors",
+            Self::OrderbookQuoteErrors => "orderbook_quote_errors",
+            Self::SolverCompetitionGap => "solver_competition_gap",
+            Self::SettlementFailures => "settlement_failures",
+        }
+    }
+}
+
+/// Thresholds of the health checks. Checks without a threshold are disabled.
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+    /// Alert if the auction ID didn't change for this long.
+    pub max_auction_age: Option<Duration>,
+    /// Alert if more than this share of the recent quotes failed.
+    pub max_quote_error_rate: Option<f64>,
+    /// Number of recent quotes the error rate is computed over.
+    pub quote_error_window: usize,
+    /// Alert if more than this share of the quote requests served by the
+    /// orderbook between two updates failed. Requires at least
+    /// `quote_error_window` requests between the updates.
+    pub max_orderbook_quote_error_rate: Option<f64>,
+    /// Alert if no new solver competition got stored for this long.
+    pub max_solver_competition_gap: Option<Duration>,
+    /// Alert if this many winning solutions in a row didn't get settled.
+    pub max_settlement_failures: Option<u32>,
+}
+
+pub struct HealthChecks {
+    config: Config,
+    /// Current auction ID and when it was first seen.
+    auction: Option<(AuctionId, Instant)>,
+    /// Outcomes of the most recent quotes, `true` for failures.
+    quote_errors: VecDeque<bool>,
+    /// Quote counters of the orderbook at the last update.
+    orderbook_quotes: Option<QuoteCounters>,
+    /// Quotes served by the orderbook between the last two updates.
+    recent_orderbook_quotes: Option<QuoteCounters>,
+    /// Latest solver competition and when it was first seen.
+    competition: Option<(AuctionId, Instant)>,
+    /// Competitions waiting for their grace period to pass before checking
+    /// whether they got settled.
+    unsettled_competitions: VecDeque<(AuctionId, Instant)>,
+    /// Winning solutions in a row that didn't get settled.
+    settlement_failures: u32,
+}
+
+impl HealthChecks {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            auction: None,
+            quote_errors: VecDeque::new(),
+            orderbook_quotes: None,
+            recent_orderbook_quotes: None,
+            competition: None,
+            unsettled_competitions: VecDeque::new(),
+            settlement_failures: 0,
+        }
+    }
+
+    /// Whether quote outcomes are needed by any check.
+    pub fn tracks_quotes(&self) -> bool {
+        self.config.max_quote_error_rate.is_some()
+    }
+
+    /// Whether the orderbook's quote metrics are needed by any check.
+    pub fn tracks_orderbook_quotes(&self) -> bool {
+        self.config.max_orderbook_quote_error_rate.is_some()
+    }
+
+    /// Whether solver competitions are needed by any check.
+    pub fn tracks_competitions(&self) -> bool {
+        self.config.max_solver_competition_gap.is_some()
+            || self.config.max_settlement_failures.is_some()
+    }
+
+    pub fn observe_auction(&mut self, id: AuctionId, now: Instant) {
+        if self.auction.is_none_or(|(current, _)| current != id) {
+            self.auction = Some((id, now));
+        }
+    }
+
+    pub fn observe_quotes(&mut self, errors: impl IntoIterator<Item = bool>) {
+        self.quote_errors.extend(errors);
+        let excess = self
+            .quote_errors
+            .len()
+            .saturating_sub(self.config.quote_error_window);
+        self.quote_errors.drain(..excess);
+    }
+
+    /// Records the current quote counters of the orderbook.
+    pub fn observe_orderbook_quotes(&mut self, counters: QuoteCounters) {
+        self.recent_orderbook_quotes = self.orderbook_quotes.map(|previous| {
+            match (
+                counters.requests.checked_sub(previous.requests),
+                counters.errors.checked_sub(previous.errors),
+            ) {
+                (Some(requests), Some(errors)) => QuoteCounters { requests, errors },
+                // The counters got reset by a restart of the orderbook.
+                _ => counters,
+            }
+        });
+        self.orderbook_quotes = Some(counters);
+    }
+
+    /// Records the ID of the latest solver competition. All competitions since
+    /// the previously observed one get checked for their settlement.
+    pub fn observe_latest_competition(&mut self, id: AuctionId, now: Instant) {
+        let first = match self.competition {
+            Some((current, _)) if current >= id => return,
+            Some((current, _)) => (current + 1).max(id - MAX_COMPETITIONS_PER_UPDATE + 1),
+            None => id,
+        };
+        self.competition = Some((id, now));
+        self.unsettled_competitions
+            .extend((first..=id).map(|id| (id, now)));
+    }
+
+    /// Competitions whose grace period has passed and that should now be
+    /// checked with [`Self::observe_settlement`].
+    pub fn competitions_to_check(&mut self, now: Instant) -> Vec<AuctionId> {
+        let mut ids = Vec::new();
+        while let Some((id, seen)) = self.unsettled_competitions.front()
+            && now.duration_since(*seen) >= SETTLEMENT_GRACE_PERIOD
+        {
+            ids.push(*id);
+            self.unsettled_competitions.pop_front();
+        }
+        ids
+    }
+
+    /// Records whether the winners of a competition got settled.
+    pub fn observe_settlement(&mut self, competition: &solver_competition_v2::Response) {
+        let has_winner = competition
+            .solutions
+            .iter()
+            .any(|solution| solution.is_winner && !solution.filtered_out);
+        if !has_winner {
+            return;
+        }
+        if competition.transaction_hashes.is_empty() {
+            self.settlement_failures += 1;
+        } else {
+            self.settlement_failures = 0;
+        }
+    }
+
+    /// Evaluates all enabled checks. Failing checks come with a description.
+    pub fn evaluate(&self, now: Instant) -> BTreeMap<Check, Option<String>> {
+        let mut checks = BTreeMap::new();
+        if let Some(max_age) = self.config.max_auction_age {
+            let failure = self.auction.and_then(|(id, since)| {
+                let age = now.duration_since(since);
+                (age > max_age)
+                    .then(|| format!("auction {id} has not changed for {}s", age.as_secs()))
+            });
+            checks.insert(Check::StaleAuction, failure);
+        }
+        if let Some(max_rate) = self.config.max_quote_error_rate {
+            let failure = (self.config.quote_error_window > 0
+                && self.quote_errors.len() >= self.config.quote_error_window)
+                .then(|| {
+                    let errors = self.quote_errors.iter().filter(|error| **error).count();
+                    errors as f64 / self.quote_errors.len() as f64
+                })
+                .filter(|rate| *rate > max_rate)
+                .map(|rate| {
+                    format!(
+                        "{:.0}% of the last {} quotes failed",
+                        rate * 100.,
+                        self.quote_errors.len()
+                    )
+                });
+            checks.insert(Check::QuoteErrors, failure);
+        }
+        if let Some(max_rate) = self.config.max_orderbook_quote_error_rate {
+            let failure = self
+                .recent_orderbook_quotes
+                .filter(|quotes| {
+                    quotes.requests > 0 && quotes.requests >= self.config.quote_error_window as u64
+                })
+                .map(|quotes| (quotes, quotes.errors as f64 / quotes.requests as f64))
+                .filter(|(_, rate)| *rate > max_rate)
+                .map(|(quotes, rate)| {
+                    format!(
+                        "{:.0}% of the {} quotes served by the orderbook since the last update \
+                         failed",
+                        rate * 100.,
+                        quotes.requests
+                    )
+                });
+            checks.insert(Check::OrderbookQuoteErrors, failure);
+        }
+        if let Some(max_gap) = self.config.max_solver_competition_gap {
+            let failure = self.competition.and_then(|(id, since)| {
+                let gap = now.duration_since(since);
+                (gap > max_gap).then(|| {
+                    format!(
+                        "no solver competition after auction {id} was stored for {}s",
+                        gap.as_secs()
+                    )
+                })
+            });
+            checks.insert(Check::SolverCompetitionGap, failure);
+        }
+        if let Some(max_failures) = self.config.max_settlement_failures {
+            let failure = (self.settlement_failures >= max_failures).then(|| {
+                format!(
+                    "the last {} winning solutions were not settled",
+                    self.settlement_failures
+                )
+            });
+            checks.insert(Check::SettlementFailures, failure);
+        }
+        checks
+    }
+}
+
+/// Number of quote requests the orderbook served and how many of them failed
+/// with a server error.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct QuoteCounters {
+    pub requests: u64,
+    pub errors: u64,
+}
+
+impl QuoteCounters {
+    /// Sums up the quote requests from the orderbook's metrics in the
+    /// Prometheus text format.
+    pub fn from_metrics(metrics: &str) -> Self {
+        let mut counters = Self::default();
+        for line in metrics.lines() {
+            let Some(sample) = line.strip_prefix("gp_v2_api_api_requests_complete{") else {
+                continue;
+            };
+            let Some((labels, value)) = sample.split_once('}') else {
+                continue;
+            };
+            let label = |name: &str| {
+                labels.split(',').find_map(|label| {
+                    let (key, value) = label.split_once('=')?;
+                    (key.trim() == name).then(|| value.trim().trim_matches('"'))
+                })
+            };
+            if !label("method").is_some_and(|method| QUOTE_METHODS.contains(&method)) {
+                continue;
+            }
+            let Ok(value) = value.trim().parse::<f64>() else {
+                continue;
+            };
+            let value = value as u64;
+            counters.requests += value;
+            if label("status_code").is_some_and(|status| status.starts_with('5')) {
+                counters.errors += value;
+            }
+        }
+        counters
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, alloy::primitives::B256};
+
+    fn config() -> Config {
+        Config {
+            max_auction_age: Some(Duration::from_secs(60)),
+            max_quote_error_rate: Some(0.5),
+            quote_error_window: 4,
+            max_orderbook_quote_error_rate: Some(0.5),
+            max_solver_competition_gap: Some(Duration::from_secs(60)),
+            max_settlement_failures: Some(2),
+        }
+    }
+
+    fn competition(auction_id: AuctionId, settled: bool) -> solver_competition_v2::Response {
+        solver_competition_v2::Response {
+            auction_id,
+            transaction_hashes: if settled { vec![B256::ZERO] } else { vec![] },
+            solutions: vec![solver_competition_v2::Solution {
+                is_winner: true,
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn disabled_checks_are_not_evaluated() {
+        let checks = HealthChecks::new(Config::default());
+        assert!(checks.evaluate(Instant::now()).is_empty());
+        assert!(!checks.tracks_quotes());
+        assert!(!checks.tracks_orderbook_quotes());
+        assert!(!checks.tracks_competitions());
+    }
+
+    #[test]
+    fn detects_stale_auction() {
+        let start = Instant::now();
+        let mut checks = HealthChecks::new(config());
+        checks.observe_auction(1, start);
+        checks.observe_auction(1, start + Duration::from_secs(30));
+        assert_eq!(
+            checks.evaluate(start + Duration::from_secs(60))[&Check::StaleAuction],
+            None
+        );
+        assert!(checks.evaluate(start + Duration::from_secs(61))[&Check::StaleAuction].is_some());
+
+        checks.observe_auction(2, start + Duration::from_secs(61));
+        assert_eq!(
+            checks.evaluate(start + Duration::from_secs(62))[&Check::StaleAuction],
+            None
+        );
+    }
+
+    #[test]
+    fn detects_quote_errors_over_full_window() {
+        let now = Instant::now();
+        let mut checks = HealthChecks::new(config());
+        checks.observe_quotes([true, true, true]);
+        assert_eq!(checks.evaluate(now)[&Check::QuoteErrors], None);
+
+        checks.observe_quotes([false]);
+        assert!(checks.evaluate(now)[&Check::QuoteErrors].is_some());
+
+        // Old outcomes drop out of the window.
+        checks.observe_quotes([false, false]);
+        assert_eq!(checks.evaluate(now)[&Check::QuoteErrors], None);
+    }
+
+    #[test]
+    fn detects_orderbook_quote_errors_between_updates() {
+        let now = Instant::now();
+        let mut checks = HealthChecks::new(config());
+        let counters = |requests, errors| QuoteCounters { requests, errors };
+        // The counters since the start of the orderbook don't count.
+        checks.observe_orderbook_quotes(counters(100, 90));
+        assert_eq!(checks.evaluate(now)[&Check::OrderbookQuoteErrors], None);
+
+        checks.observe_orderbook_quotes(counters(110, 96));
+        assert!(checks.evaluate(now)[&Check::OrderbookQuoteErrors].is_some());
+
+        // Too few requests to compute a meaningful rate.
+        checks.observe_orderbook_quotes(counters(113, 99));
+        assert_eq!(checks.evaluate(now)[&Check::OrderbookQuoteErrors], None);
+
+        checks.observe_orderbook_quotes(counters(123, 100));
+        assert_eq!(checks.evaluate(now)[&Check::OrderbookQuoteErrors], None);
+
+        // Restarting the orderbook resets its counters.
+        checks.observe_orderbook_quotes(counters(8, 8));
+        assert!(checks.evaluate(now)[&Check::OrderbookQuoteErrors].is_some());
+    }
+
+    #[test]
+    fn parses_orderbook_quote_metrics() {
+        let metrics = r#"
+# HELP gp_v2_api_api_requests_complete Number of completed API requests.
+# TYPE gp_v2_api_api_requests_complete counter
+gp_v2_api_api_requests_complete{method="v1/post_quote",status_code="200"} 10
+gp_v2_api_api_requests_complete{method="v1/post_quote",status_code="404"} 3
+gp_v2_api_api_requests_complete{method="v1/post_quote",status_code="500"} 2
+gp_v2_api_api_requests_complete{method="v1/post_quote_stream",status_code="503"} 1
+gp_v2_api_api_requests_complete{method="v1/create_order",status_code="500"} 7
+gp_v2_api_api_requests_duration_seconds_count{method="v1/post_quote"} 16
+"#;
+        assert_eq!(
+            QuoteCounters::from_metrics(metrics),
+            QuoteCounters {
+                requests: 16,
+                errors: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn checks_all_competitions_since_last_update() {
+        let start = Instant::now();
+        let mut checks = HealthChecks::new(config());
+        checks.observe_latest_competition(1, start);
+        checks.observe_latest_competition(4, start);
+        assert_eq!(
+            checks.competitions_to_check(start + SETTLEMENT_GRACE_PERIOD),
+            vec![1, 2, 3, 4]
+        );
+
+        checks.observe_latest_competition(1000, start);
+        let ids = checks.competitions_to_check(start + SETTLEMENT_GRACE_PERIOD);
+        assert_eq!(ids.len(), MAX_COMPETITIONS_PER_UPDATE as usize);
+        assert_eq!(ids.last(), Some(&1000));
+    }
+
+    #[test]
+    fn detects_solver_competition_gap() {
+        let start = Instant::now();
+        let mut checks = HealthChecks::new(config());
+        checks.observe_latest_competition(1, start);
+        checks.observe_latest_competition(1, start + Duration::from_secs(50));
+        assert!(
+            checks.evaluate(start + Duration::from_secs(61))[&Check::SolverCompetitionGap]
+                .is_some()
+        );
+        checks.observe_latest_competition(3, start + Duration::from_secs(61));
+        assert_eq!(
+            checks.evaluate(start + Duration::from_secs(61))[&Check::SolverCompetitionGap],
+            None
+        );
+    }
+
+    #[test]
+    fn detects_settlement_failure_streaks() {
+        let start = Instant::now();
+        let mut checks = HealthChecks::new(config());
+        checks.observe_latest_competition(1, start);
+        checks.observe_latest_competition(2, start + Duration::from_secs(10));
+        assert!(checks.competitions_to_check(start).is_empty());
+        assert_eq!(
+            checks.competitions_to_check(start + SETTLEMENT_GRACE_PERIOD),
+            vec![1]
+        );
+        assert_eq!(
+            checks.competitions_to_check(start + SETTLEMENT_GRACE_PERIOD * 2),
+            vec![2]
+        );
+
+        checks.observe_settlement(&competition(1, false));
+        // Competitions without winners don't affect the streak.
+        checks.observe_settlement(&solver_competition_v2::Response::default());
+        assert_eq!(checks.evaluate(start)[&Check::SettlementFailures], None);
+        checks.observe_settlement(&competition(2, false));
+        assert!(checks.evaluate(start)[&Check::SettlementFailures].is_some());
+        checks.observe_settlement(&competition(3, true));
+        assert_eq!(checks.evaluate(start)[&Check::SettlementFailures], None);
+    }
+}
@@ -1,22 +1,46 @@
 // This application observes the order book api and tries to determine if the
 // solver is down. It does this by checking if no trades have been made recently
-// and if so checking if it finds a matchable order according to an external
-// price api (0x). If this is the case it alerts.
+// and if so checking if it finds a matchable order according to the configured
+// matchability oracles (0x, the orderbook's quotes or a driver). If this is the
+// case it alerts.
+//
+// Additionally it runs health checks on the auctions, quotes and solver
+// competitions and notifies the configured webhooks whenever a check starts or
+// stops failing.
+
+mod health;
+mod oracle;
+mod webhook;
 
 use {
-    alloy::primitives::{Address, U256, address},
+    alloy::primitives::{Address, U256},
     anyhow::{Context, Result},
     clap::Parser,
-    model::order::{BUY_ETH_ADDRESS, OrderClass, OrderKind, OrderStatus, OrderUid},
+    health::{Check, HealthChecks},
+    model::{
+        AuctionId,
+        order::{OrderClass, OrderKind, OrderStatus, OrderUid},
+        solver_competition_v2,
+    },
     number::serialization::HexOrDecimalU256,
-    prometheus::IntGauge,
+    oracle::{
+        DriverQuoter,
+        MatchabilityOracle,
+        OracleKind,
+        Oracles,
+        OrderbookQuoter,
+        Thresholds,
+        ZeroExApi,
+    },
+    prometheus::{IntGauge, IntGaugeVec, Opts},
     reqwest::Client,
     serde_with::serde_as,
     std::{
-        collections::HashMap,
+        collections::{BTreeMap, HashMap},
         time::{Duration, Instant},
     },
     url::Url,
+    webhook::{Notification, Notifier, Status, Webhook},
 };
 
 #[serde_as]
@@ -46,34 +70,36 @@ impl Order {
     }
 }
 
+#[derive(serde::Deserialize)]
+struct Auction {
+    id: AuctionId,
+    orders: Vec<Order>,
+}
+
 struct OrderBookApi {
     base: Url,
+    metrics: Option<Url>,
     client: Client,
 }
 
 impl OrderBookApi {
-    pub fn new(client: Client, base_url: &str) -> Self {
+    pub fn new(client: Client, base_url: &str, metrics_url: Option<Url>) -> Self {
         Self {
             base: base_url.parse().unwrap(),
+            metrics: metrics_url,
             client,
         }
     }
 
-    pub async fn solvable_orders(&self) -> reqwest::Result<Vec<Order>> {
-        #[derive(serde::Deserialize)]
-        struct Auction {
-            orders: Vec<Order>,
-        }
+    pub async fn auction(&self) -> reqwest::Result<Auction> {
         let url = shared::url::join(&self.base, "api/v1/auction");
-        let auction: Auction = self
-            .client
+        self.client
             .get(url)
             .send()
             .await?
             .error_for_status()?
             .json()
-            .await?;
-        Ok(auction.orders)
+            .await
     }
 
     pub async fn order(&self, uid: &OrderUid) -> reqwest::Result<Order> {
@@ -86,83 +112,57 @@ impl OrderBookApi {
             .json()
             .await
     }
-}
 
-// Converts the eth placeholder address to weth. Leaves other addresses
-// untouched.
-fn convert_eth_to_weth(token: Address) -> Address {
-    const WETH: Address = address!("0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2");
-    if token.as_slice() == BUY_ETH_ADDRESS.as_bytes() {
-        WETH
-    } else {
-        token
+    pub async fn latest_solver_competition(
+        &self,
+    ) -> reqwest::Result<solver_competition_v2::Response> {
+        let url = shared::url::join(&self.base, "api/v2/solver_competition/latest");
+        self.client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
     }
-}
-
-struct ZeroExApi {
-    base: Url,
-    client: Client,
-    api_key: String,
-}
 
-impl ZeroExApi {
-    pub fn new(client: Client, api_key: String) -> Self {
-        Self {
-            base: "https://api.0x.org".parse().unwrap(),
-            client,
-            api_key,
-        }
+    pub async fn solver_competition(
+        &self,
+        auction_id: AuctionId,
+    ) -> reqwest::Result<solver_competition_v2::Response> {
+        let url = shared::url::join(
+            &self.base,
+            &format!("api/v2/solver_competition/{auction_id}"),
+        );
+        self.client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
     }
 
-    pub async fn can_be_settled(&self, order: &Order) -> Result<bool> {
-        let mut url = shared::url::join(&self.base, "swap/v1/price");
-
-        let (amount_name, amount) = match order.kind {
-            OrderKind::Buy => ("buyAmount", order.buy_amount),
-            OrderKind::Sell => ("sellAmount", order.sell_amount),
-        };
-
-        let buy_token = convert_eth_to_weth(order.buy_token);
-        url.query_pairs_mut()
-            .append_pair("sellToken", &format!("{:#x}", order.sell_token))
-            .append_pair("buyToken", &format!("{buy_token:#x}"))
-            .append_pair(amount_name, &amount.to_string());
-
-        #[serde_as]
-        #[derive(Debug, serde::Deserialize)]
-        #[serde(rename_all = "camelCase")]
-        pub struct Response {
-            #[serde_as(as = "HexOrDecimalU256")]
-            pub sell_amount: U256,
-            #[serde_as(as = "HexOrDecimalU256")]
-            pub buy_amount: U256,
-        }
-
-        let response: Response = self
+    /// Fetches the Prometheus metrics of the orderbook.
+    pub async fn metrics(&self) -> Result<String> {
+        let url = self
+            .metrics
+            .clone()
+            .context("no orderbook metrics url configured")?;
+        Ok(self
             .client
-            .get(url.clone())
-            .header("0x-api-key", self.api_key.clone())
+            .get(url)
             .send()
             .await?
             .error_for_status()?
-            .json()
-            .await?;
-
-        tracing::debug!(url = url.as_str(), ?response, "0x");
-
-        let can_settle =
-            response.sell_amount <= order.sell_amount && response.buy_amount >= order.buy_amount;
-        if can_settle {
-            tracing::debug!(%order.uid, "marking order as settleable");
-        }
-
-        Ok(can_settle)
+            .text()
+            .await?)
     }
 }
 
 struct Alerter {
     orderbook_api: OrderBookApi,
-    zeroex_api: ZeroExApi,
+    oracles: Oracles,
     config: AlertConfig,
     last_observed_trade: Instant,
     last_alert: Option<Instant>,
@@ -174,7 +174,15 @@ struct Alerter {
     // hasn't been a trade for some time and that there is an order that has been matchable for
     // some time.
     no_trades_but_matchable_order: IntGauge,
+    // Describes the matchable order while the above alert condition is satisfied.
+    matchable_order: Option<String>,
     api_get_order_min_interval: Duration,
+    health: HealthChecks,
+    notifier: Notifier,
+    // Failing health checks and when their last notification was sent.
+    failing_checks: HashMap<Check, Instant>,
+    // Set to 0 or 1 for every health check depending on whether it is failing.
+    health_checks: IntGaugeVec,
 }
 
 struct AlertConfig {
@@ -189,9 +197,11 @@ struct AlertConfig {
 impl Alerter {
     pub fn new(
         orderbook_api: OrderBookApi,
-        zeroex_api: ZeroExApi,
+        oracles: Oracles,
         config: AlertConfig,
         api_get_order_min_interval: Duration,
+        health: HealthChecks,
+        notifier: Notifier,
     ) -> Self {
         let registry = observe::metrics::get_registry();
         let no_trades_but_matchable_order =
@@ -199,24 +209,34 @@ impl Alerter {
         registry
             .register(Box::new(no_trades_but_matchable_order.clone()))
             .unwrap();
+        let health_checks = IntGaugeVec::new(
+            Opts::new("health_check_failing", "0 or 1 per health check"),
+            &["check"],
+        )
+        .unwrap();
+        registry.register(Box::new(health_checks.clone())).unwrap();
         Self {
             orderbook_api,
-            zeroex_api,
+            oracles,
             config,
             last_observed_trade: Instant::now(),
             last_alert: None,
             open_orders: HashMap::new(),
             no_trades_but_matchable_order,
+            matchable_order: None,
             api_get_order_min_interval,
+            health,
+            notifier,
+            failing_checks: HashMap::new(),
+            health_checks,
         }
     }
 
     async fn update_open_orders(&mut self) -> Result<()> {
-        let mut orders = self
-            .orderbook_api
-            .solvable_orders()
-            .await
-            .context("solvable_orders")?
+        let auction = self.orderbook_api.auction().await.context("auction")?;
+        self.health.observe_auction(auction.id, Instant::now());
+        let mut orders = auction
+            .orders
             .into_iter()
             .filter(|order| !order.is_liquidity_order() && !order.partially_fillable)
             .map(|order| {
@@ -258,9 +278,22 @@ impl Alerter {
     }
 
     pub async fn update(&mut self) -> Result<()> {
+        let result = self.update_matchable_orders().await;
+        self.update_health().await;
+        let mut checks = self.health.evaluate(Instant::now());
+        checks.insert(
+            Check::NoTradesButMatchableOrder,
+            self.matchable_order.clone(),
+        );
+        self.notify(checks).await;
+        result
+    }
+
+    async fn update_matchable_orders(&mut self) -> Result<()> {
         self.update_open_orders().await?;
         if self.last_observed_trade.elapsed() <= self.config.time_without_trade {
             self.no_trades_but_matchable_order.set(0);
+            self.matchable_order = None;
             // Delete all matchable timestamps.
             //
             // If we didn't do this what could happen is that first we mark an order as
@@ -279,13 +312,13 @@ impl Alerter {
         }
 
         for (order, last_solvable) in self.open_orders.values_mut() {
-            let can_be_settled = self
-                .zeroex_api
-                .can_be_settled(order)
+            let matchable_by = self
+                .oracles
+                .matchable_by(order)
                 .await
-                .context("can_be_settled")?;
+                .context("matchability oracles")?;
             let now = Instant::now();
-            if can_be_settled {
+            if let Some(oracle) = matchable_by {
                 let solvable_since = *last_solvable.get_or_insert(now);
                 if now.duration_since(solvable_since) > self.config.min_order_solvable_time {
                     let should_alert = match self.last_alert {
@@ -294,9 +327,10 @@ impl Alerter {
                     };
                     if should_alert {
                         self.last_alert = Some(now);
-                        self.config.alert(&order.uid);
+                        self.config.alert(&order.uid, oracle);
                     }
                     self.no_trades_but_matchable_order.set(1);
+                    self.matchable_order = Some(self.config.description(&order.uid, oracle));
                 }
                 return Ok(());
             } else {
@@ -305,18 +339,132 @@ impl Alerter {
         }
 
         self.no_trades_but_matchable_order.set(0);
+        self.matchable_order = None;
         Ok(())
     }
+
+    /// Feeds the health checks. Every source is updated independently so a
+    /// failing one doesn't keep the other checks from being evaluated.
+    async fn update_health(&mut self) {
+        self.update_quote_health().await;
+        if self.health.tracks_orderbook_quotes()
+            && let Err(err) = self.update_orderbook_quote_health().await
+        {
+            tracing::warn!(?err, "failed to update orderbook quote health");
+        }
+        if self.health.tracks_competitions()
+            && let Err(err) = self.update_competition_health().await
+        {
+            tracing::warn!(?err, "failed to update solver competition health");
+        }
+    }
+
+    async fn update_quote_health(&mut self) {
+        // Always take the recorded quote outcomes so they don't pile up.
+        let mut quote_errors = self.oracles.take_quote_errors();
+        if !self.health.tracks_quotes() {
+            return;
+        }
+        // Without any matchability checks the quote health is sampled with an
+        // open order.
+        if quote_errors.is_empty()
+            && let Some((order, _)) = self.open_orders.values().next()
+        {
+            let _ = self.oracles.matchable_by(order).await;
+            quote_errors = self.oracles.take_quote_errors();
+        }
+        self.health.observe_quotes(quote_errors);
+    }
+
+    async fn update_orderbook_quote_health(&mut self) -> Result<()> {
+        let metrics = self
+            .orderbook_api
+            .metrics()
+            .await
+            .context("orderbook metrics")?;
+        self.health
+            .observe_orderbook_quotes(health::QuoteCounters::from_metrics(&metrics));
+        Ok(())
+    }
+
+    async fn update_competition_health(&mut self) -> Result<()> {
+        let now = Instant::now();
+        let latest = self
+            .orderbook_api
+            .latest_solver_competition()
+            .await
+            .context("latest solver competition")?;
+        self.health
+            .observe_latest_competition(latest.auction_id, now);
+        for auction_id in self.health.competitions_to_check(now) {
+            match self.orderbook_api.solver_competition(auction_id).await {
+                Ok(competition) => self.health.observe_settlement(&competition),
+                // No competition got stored for auctions without solutions.
+                Err(err) if err.status() == Some(reqwest::StatusCode::NOT_FOUND) => (),
+                Err(err) => {
+                    tracing::warn!(?err, auction_id, "failed to fetch solver competition")
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Notifies the webhooks about checks that started failing, are still
+    /// failing after `min_alert_interval` or recovered.
+    async fn notify(&mut self, checks: BTreeMap<Check, Option<String>>) {
+        let now = Instant::now();
+        for (check, failure) in checks {
+            self.health_checks
+                .with_label_values(&[check.name()])
+                .set(failure.is_some().into());
+            let notification = match failure {
+                Some(description) => {
+                    if self.failing_checks.get(&check).is_some_and(|last| {
+                        now.duration_since(*last) < self.config.min_alert_interval
+                    }) {
+                        continue;
+                    }
+                    self.failing_checks.insert(check, now);
+                    // The missing trades already get logged as an error.
+                    if check != Check::NoTradesButMatchableOrder {
+                        tracing::error!(check = check.name(), "{description}");
+                    }
+                    Notification {
+                        check,
+                        status: Status::Firing,
+                        description,
+                    }
+                }
+                None => {
+                    if self.failing_checks.remove(&check).is_none() {
+                        continue;
+                    }
+                    tracing::info!(check = check.name(), "health check recovered");
+                    Notification {
+                        check,
+                        status: Status::Resolved,
+                        description: format!("{} recovered", check.name()),
+                    }
+                }
+            };
+            self.notifier.notify(&notification).await;
+        }
+    }
 }
 
 impl AlertConfig {
-    fn alert(&self, order_uid: &OrderUid) {
-        tracing::error!(
+    fn alert(&self, order_uid: &OrderUid, oracle: &str) {
+        tracing::error!("{}", self.description(order_uid, oracle));
+    }
+
+    fn description(&self, order_uid: &OrderUid, oracle: &str) -> String {
+        format!(
             "No orders have been settled in the last {} seconds even though order {} is solvable \
-             and has a price that allows it to be settled according to 0x.",
+             and has a price that allows it to be settled according to {}.",
             self.time_without_trade.as_secs(),
             order_uid,
-        );
+            oracle,
+        )
     }
 }
 
@@ -366,6 +514,12 @@ struct Arguments {
     #[clap(long, env, default_value = "https://api.cow.fi/mainnet/")]
     orderbook_api: String,
 
+    /// Prometheus metrics endpoint of the orderbook, e.g.
+    /// `http://orderbook:9586/metrics`. Required by
+    /// `--max-orderbook-quote-error-rate`.
+    #[clap(long, env)]
+    orderbook_metrics_url: Option<Url>,
+
     #[clap(long, env, default_value = "9588")]
     metrics_port: u16,
 
@@ -374,8 +528,81 @@ struct Arguments {
     #[clap(long, env, default_value = "200ms", value_parser = humantime::parse_duration)]
     api_get_order_min_interval: Duration,
 
+    /// Oracles deciding whether an order could be settled, separated by
+    /// commas. An order counts as matchable if any of them can settle it.
+    #[clap(
+        long,
+        env,
+        value_enum,
+        use_value_delimiter = true,
+        default_value = "zero-ex"
+    )]
+    matchability_oracles: Vec<OracleKind>,
+
+    /// Required by the `zero-ex` oracle.
+    #[clap(long, env)]
+    zero_ex_api_key: Option<String>,
+
+    /// Dedicated account the `orderbook` oracle requests quotes for. Required
+    /// by the `orderbook` oracle.
+    #[clap(long, env)]
+    orderbook_quote_account: Option<Address>,
+
+    /// Base url of the driver quoting orders for the `driver` oracle, including
+    /// the solver path, e.g. `http://driver/solver`.
+    #[clap(long, env)]
+    driver_url: Option<Url>,
+
+    /// Time the driver gets to quote an order.
+    #[clap(long, env, default_value = "5s", value_parser = humantime::parse_duration)]
+    driver_quote_timeout: Duration,
+
+    /// How much better (in basis points) than the limit price of a market
+    /// order a quote has to be for the order to count as matchable.
+    #[clap(long, env, default_value = "0")]
+    market_order_threshold_bps: u32,
+
+    /// How much better (in basis points) than the limit price of a limit
+    /// order a quote has to be for the order to count as matchable.
+    #[clap(long, env, default_value = "0")]
+    limit_order_threshold_bps: u32,
+
+    /// Webhooks notified when a health check starts or stops failing,
+    /// separated by commas. Accepts `slack|<url>`, `pagerduty|<routing key>`
+    /// and `generic|<url>` or a plain URL for generic JSON webhooks.
+    #[clap(long, env, use_value_delimiter = true)]
+    webhooks: Vec<Webhook>,
+
+    /// Name of the observed deployment in notifications. Defaults to the
+    /// orderbook url.
+    #[clap(long, env)]
+    deployment_name: Option<String>,
+
+    /// Alert if the auction ID didn't change for this long.
+    #[clap(long, env, value_parser = humantime::parse_duration)]
+    max_auction_age: Option<Duration>,
+
+    /// Alert if more than this share (between 0 and 1) of the recent quotes
+    /// of the matchability oracles failed.
+    #[clap(long, env)]
+    max_quote_error_rate: Option<f64>,
+
+    /// Number of recent quotes the quote error rate is computed over.
+    #[clap(long, env, default_value = "20")]
+    quote_error_window: usize,
+
+    /// Alert if more than this share (between 0 and 1) of the quote requests
+    /// the orderbook served between two updates failed with a server error.
+    #[clap(long, env)]
+    max_orderbook_quote_error_rate: Option<f64>,
+
+    /// Alert if no new solver competition got stored for this long.
+    #[clap(long, env, value_parser = humantime::parse_duration)]
+    max_solver_competition_gap: Option<Duration>,
+
+    /// Alert if this many winning solutions in a row did not get settled.
     #[clap(long, env)]
-    zero_ex_api_key: String,
+    max_settlement_failures: Option<u32>,
 
     /// Whether to use JSON format for the logs.
     #[clap(long, env, default_value = "false")]
@@ -406,15 +633,78 @@ async fn run(args: Arguments) {
         .build()
         .unwrap();
 
+    let oracles = args
+        .matchability_oracles
+        .iter()
+        .map(|kind| -> Box<dyn MatchabilityOracle> {
+            match kind {
+                OracleKind::ZeroEx => Box::new(ZeroExApi::new(
+                    client.clone(),
+                    args.zero_ex_api_key
+                        .clone()
+                        .expect("the zero-ex oracle requires --zero-ex-api-key"),
+                )),
+                OracleKind::Orderbook => Box::new(OrderbookQuoter::new(
+                    client.clone(),
+                    &args.orderbook_api,
+                    args.orderbook_quote_account
+                        .expect("the orderbook oracle requires --orderbook-quote-account"),
+                )),
+                OracleKind::Driver => Box::new(DriverQuoter::new(
+                    client.clone(),
+                    args.driver_url
+                        .clone()
+                        .expect("the driver oracle requires --driver-url"),
+                    args.driver_quote_timeout,
+                )),
+            }
+        })
+        .collect::<Vec<_>>();
+    assert!(
+        !oracles.is_empty(),
+        "at least one matchability oracle is required"
+    );
+
+    assert!(
+        args.max_orderbook_quote_error_rate.is_none() || args.orderbook_metrics_url.is_some(),
+        "--max-orderbook-quote-error-rate requires --orderbook-metrics-url"
+    );
+
     let mut alerter = Alerter::new(
-        OrderBookApi::new(client.clone(), &args.orderbook_api),
-        ZeroExApi::new(client, args.zero_ex_api_key),
+        OrderBookApi::new(
+            client.clone(),
+            &args.orderbook_api,
+            args.orderbook_metrics_url.clone(),
+        ),
+        Oracles {
+            oracles,
+            thresholds: Thresholds {
+                market_bps: args.market_order_threshold_bps,
+                limit_bps: args.limit_order_threshold_bps,
+            },
+            quote_errors: Default::default(),
+        },
         AlertConfig {
             time_without_trade: args.time_without_trade,
             min_order_solvable_time: args.min_order_age,
             min_alert_interval: args.min_alert_interval,
         },
         args.api_get_order_min_interval,
+        HealthChecks::new(health::Config {
+            max_auction_age: args.max_auction_age,
+            max_quote_error_rate: args.max_quote_error_rate,
+            quote_error_window: args.quote_error_window,
+            max_orderbook_quote_error_rate: args.max_orderbook_quote_error_rate,
+            max_solver_competition_gap: args.max_solver_competition_gap,
+            max_settlement_failures: args.max_settlement_failures,
+        }),
+        Notifier::new(
+            client.clone(),
+            args.webhooks.clone(),
+            args.deployment_name
+                .clone()
+                .unwrap_or_else(|| args.orderbook_api.clone()),
+        ),
     );
 
     let mut errors_in_a_row = 0;
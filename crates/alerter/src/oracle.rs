@@ -0,0 +1,405 @@
+//! Oracles telling the alerter at which price an order could be settled right
+//! now, which decides whether the order counts as matchable.
+
+use {
+    crate::Order,
+    alloy::primitives::{Address, U256, address},
+    anyhow::{Context, Result},
+    model::order::{BUY_ETH_ADDRESS, OrderClass, OrderKind},
+    number::{
+        conversions::alloy::{big_uint_to_u256, u256_to_big_uint},
+        serialization::HexOrDecimalU256,
+    },
+    reqwest::Client,
+    serde_with::serde_as,
+    std::{collections::HashMap, sync::Mutex, time::Duration},
+    url::Url,
+};
+
+/// Amounts an oracle could trade the order's tokens at.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Quote {
+    pub sell_amount: U256,
+    pub buy_amount: U256,
+}
+
+#[async_trait::async_trait]
+pub trait MatchabilityOracle: Send + Sync {
+    /// Name of the oracle used in logs and alerts.
+    fn name(&self) -> &'static str;
+
+    /// Quotes the order's fixed amount. Returns `None` if the oracle found no
+    /// route for the order.
+    async fn quote(&self, order: &Order) -> Result<Option<Quote>>;
+}
+
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+pub enum OracleKind {
+    /// The 0x price API.
+    ZeroEx,
+    /// The orderbook's quote endpoint.
+    Orderbook,
+    /// The quote endpoint of a driver.
+    Driver,
+}
+
+/// How much better than the order's limit price a quote has to be for the
+/// order to count as matchable, per order class.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Thresholds {
+    pub market_bps: u32,
+    pub limit_bps: u32,
+}
+
+impl Thresholds {
+    fn bps(&self, class: &OrderClass) -> u32 {
+        match class {
+            OrderClass::Market => self.market_bps,
+            OrderClass::Limit | OrderClass::Liquidity => self.limit_bps,
+        }
+    }
+
+    /// Whether the quote's price beats the order's limit price by the
+    /// threshold of the order's class.
+    pub fn can_be_settled(&self, order: &Order, quote: &Quote) -> bool {
+        const BPS: u32 = 10_000;
+        // quote.buy / quote.sell >= order.buy / order.sell * (1 + threshold)
+        u256_to_big_uint(&quote.buy_amount) * u256_to_big_uint(&order.sell_amount) * BPS
+            >= u256_to_big_uint(&order.buy_amount)
+                * u256_to_big_uint(&quote.sell_amount)
+                * (BPS + self.bps(&order.class))
+    }
+}
+
+/// All configured oracles. An order counts as matchable if any of them can
+/// settle it.
+pub struct Oracles {
+    pub oracles: Vec<Box<dyn MatchabilityOracle>>,
+    pub thresholds: Thresholds,
+    /// Outcomes of the quotes since they were last taken, `true` for
+    /// failures.
+    pub quote_errors: Mutex<Vec<bool>>,
+}
+
+impl Oracles {
+    /// Returns the name of the first oracle that can settle the order. Fails
+    /// only if no oracle could be queried.
+    pub async fn matchable_by(&self, order: &Order) -> Result<Option<&'static str>> {
+        let mut errors = Vec::new();
+        for oracle in &self.oracles {
+            let quote = oracle.quote(order).await;
+            self.quote_errors.lock().unwrap().push(quote.is_err());
+            match quote {
+                Ok(Some(quote)) if self.thresholds.can_be_settled(order, &quote) => {
+                    tracing::debug!(%order.uid, oracle = oracle.name(), "marking order as settleable");
+                    return Ok(Some(oracle.name()));
+                }
+                Ok(_) => (),
+                Err(err) => {
+                    tracing::debug!(?err, oracle = oracle.name(), "failed to quote order");
+                    errors.push(err.context(oracle.name()));
+                }
+            }
+        }
+        match errors.pop() {
+            Some(err) if errors.len() + 1 == self.oracles.len() => Err(err),
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns the quote outcomes recorded since the last call.
+    pub fn take_quote_errors(&self) -> Vec<bool> {
+        std::mem::take(&mut *self.quote_errors.lock().unwrap())
+    }
+}
+
+// Converts the eth placeholder address to weth. Leaves other addresses
+// untouched.
+fn convert_eth_to_weth(token: Address) -> Address {
+    const WETH: Address = address!("0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2");
+    if token.as_slice() == BUY_ETH_ADDRESS.as_bytes() {
+        WETH
+    } else {
+        token
+    }
+}
+
+pub struct ZeroExApi {
+    base: Url,
+    client: Client,
+    api_key: String,
+}
+
+impl ZeroExApi {
+    pub fn new(client: Client, api_key: String) -> Self {
+        Self {
+            base: "https://api.0x.org".parse().unwrap(),
+            client,
+            api_key,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl MatchabilityOracle for ZeroExApi {
+    fn name(&self) -> &'static str {
+        "0x"
+    }
+
+    async fn quote(&self, order: &Order) -> Result<Option<Quote>> {
+        let mut url = shared::url::join(&self.base, "swap/v1/price");
+
+        let (amount_name, amount) = match order.kind {
+            OrderKind::Buy => ("buyAmount", order.buy_amount),
+            OrderKind::Sell => ("sellAmount", order.sell_amount),
+        };
+
+        let buy_token = convert_eth_to_weth(order.buy_token);
+        url.query_pairs_mut()
+            .append_pair("sellToken", &format!("{:#x}", order.sell_token))
+            .append_pair("buyToken", &format!("{buy_token:#x}"))
+            .append_pair(amount_name, &amount.to_string());
+
+        #[serde_as]
+        #[derive(Debug, serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        pub struct Response {
+            #[serde_as(as = "HexOrDecimalU256")]
+            pub sell_amount: U256,
+            #[serde_as(as = "HexOrDecimalU256")]
+            pub buy_amount: U256,
+        }
+
+        let response: Response = self
+            .client
+            .get(url.clone())
+            .header("0x-api-key", self.api_key.clone())
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        tracing::debug!(url = url.as_str(), ?response, "0x");
+
+        Ok(Some(Quote {
+            sell_amount: response.sell_amount,
+            buy_amount: response.buy_amount,
+        }))
+    }
+}
+
+/// Quotes orders with the orderbook's `POST api/v1/quote` endpoint, i.e. with
+/// the solvers of the protocol.
+pub struct OrderbookQuoter {
+    base: Url,
+    client: Client,
+    /// Dedicated account the quotes are requested for so they can be told
+    /// apart from the quotes of real users.
+    from: Address,
+}
+
+impl OrderbookQuoter {
+    pub fn new(client: Client, base_url: &str, from: Address) -> Self {
+        Self {
+            base: base_url.parse().unwrap(),
+            client,
+            from,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl MatchabilityOracle for OrderbookQuoter {
+    fn name(&self) -> &'static str {
+        "orderbook"
+    }
+
+    async fn quote(&self, order: &Order) -> Result<Option<Quote>> {
+        let url = shared::url::join(&self.base, "api/v1/quote");
+        let request = match order.kind {
+            OrderKind::Sell => serde_json::json!({
+                "from": self.from,
+                "sellToken": order.sell_token,
+                "buyToken": order.buy_token,
+                "kind": "sell",
+                "sellAmountBeforeFee": order.sell_amount.to_string(),
+            }),
+            OrderKind::Buy => serde_json::json!({
+                "from": self.from,
+                "sellToken": order.sell_token,
+                "buyToken": order.buy_token,
+                "kind": "buy",
+                "buyAmountAfterFee": order.buy_amount.to_string(),
+            }),
+        };
+
+        #[serde_as]
+        #[derive(Debug, serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct OrderQuote {
+            #[serde_as(as = "HexOrDecimalU256")]
+            sell_amount: U256,
+            #[serde_as(as = "HexOrDecimalU256")]
+            buy_amount: U256,
+            #[serde_as(as = "HexOrDecimalU256")]
+            fee_amount: U256,
+        }
+        #[derive(Debug, serde::Deserialize)]
+        struct Response {
+            quote: OrderQuote,
+        }
+
+        let response = self.client.post(url).json(&request).send().await?;
+        // The orderbook signals missing routes with a dedicated error.
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let response: Response = response.error_for_status()?.json().await?;
+        tracing::debug!(?response, "orderbook quote");
+
+        Ok(Some(Quote {
+            // The quoted sell amount excludes the fee, which the order pays
+            // out of its own sell amount.
+            sell_amount: response.quote.sell_amount + response.quote.fee_amount,
+            buy_amount: response.quote.buy_amount,
+        }))
+    }
+}
+
+/// Quotes orders with the `GET /quote` endpoint of a single driver, i.e. with
+/// the solver behind that driver.
+pub struct DriverQuoter {
+    base: Url,
+    client: Client,
+    timeout: Duration,
+}
+
+impl DriverQuoter {
+    pub fn new(client: Client, base: Url, timeout: Duration) -> Self {
+        Self {
+            base,
+            client,
+            timeout,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl MatchabilityOracle for DriverQuoter {
+    fn name(&self) -> &'static str {
+        "driver"
+    }
+
+    async fn quote(&self, order: &Order) -> Result<Option<Quote>> {
+        let mut url = shared::url::join(&self.base, "quote");
+        let (kind, amount) = match order.kind {
+            OrderKind::Sell => ("sell", order.sell_amount),
+            OrderKind::Buy => ("buy", order.buy_amount),
+        };
+        let deadline = chrono::Utc::now()
+            + chrono::Duration::from_std(self.timeout).context("invalid timeout")?;
+        url.query_pairs_mut()
+            .append_pair("sellToken", &format!("{:#x}", order.sell_token))
+            .append_pair("buyToken", &format!("{:#x}", order.buy_token))
+            .append_pair("amount", &amount.to_string())
+            .append_pair("kind", kind)
+            .append_pair("deadline", &deadline.to_rfc3339());
+
+        #[serde_as]
+        #[derive(Debug, serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Response {
+            #[serde_as(as = "HashMap<_, HexOrDecimalU256>")]
+            clearing_prices: HashMap<Address, U256>,
+        }
+
+        let response: Response = self
+            .client
+            .get(url)
+            .timeout(self.timeout)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        tracing::debug!(?response, "driver quote");
+
+        let (Some(sell_price), Some(buy_price)) = (
+            response.clearing_prices.get(&order.sell_token),
+            response.clearing_prices.get(&order.buy_token),
+        ) else {
+            // The solver found no solution for the order.
+            return Ok(None);
+        };
+        if buy_price.is_zero() || sell_price.is_zero() {
+            return Ok(None);
+        }
+        // Uniform clearing prices: sell_amount * sell_price = buy_amount * buy_price
+        let convert = |amount: &U256, from: &U256, to: &U256| {
+            big_uint_to_u256(
+                &(u256_to_big_uint(amount) * u256_to_big_uint(from) / u256_to_big_uint(to)),
+            )
+        };
+        Ok(Some(match order.kind {
+            OrderKind::Sell => Quote {
+                sell_amount: amount,
+                buy_amount: convert(&amount, sell_price, buy_price)?,
+            },
+            OrderKind::Buy => Quote {
+                sell_amount: convert(&amount, buy_price, sell_price)?,
+                buy_amount: amount,
+            },
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, model::order::OrderUid};
+
+    fn order(kind: OrderKind, class: OrderClass) -> Order {
+        Order {
+            kind,
+            buy_token: Address::repeat_byte(1),
+            buy_amount: U256::from(1_000),
+            sell_token: Address::repeat_byte(2),
+            sell_amount: U256::from(1_000),
+            uid: OrderUid::default(),
+            partially_fillable: false,
+            class,
+            status: None,
+        }
+    }
+
+    fn quote(sell_amount: u64, buy_amount: u64) -> Quote {
+        Quote {
+            sell_amount: U256::from(sell_amount),
+            buy_amount: U256::from(buy_amount),
+        }
+    }
+
+    #[test]
+    fn applies_thresholds_per_class() {
+        let thresholds = Thresholds {
+            market_bps: 0,
+            limit_bps: 100,
+        };
+        let market = order(OrderKind::Sell, OrderClass::Market);
+        let limit = order(OrderKind::Sell, OrderClass::Limit);
+
+        assert!(thresholds.can_be_settled(&market, &quote(1_000, 1_000)));
+        assert!(!thresholds.can_be_settled(&market, &quote(1_000, 999)));
+        assert!(!thresholds.can_be_settled(&limit, &quote(1_000, 1_000)));
+        assert!(thresholds.can_be_settled(&limit, &quote(1_000, 1_010)));
+    }
+
+    #[test]
+    fn compares_buy_orders_by_sell_amount() {
+        let thresholds = Thresholds::default();
+        let order = order(OrderKind::Buy, OrderClass::Market);
+
+        assert!(thresholds.can_be_settled(&order, &quote(999, 1_000)));
+        assert!(!thresholds.can_be_settled(&order, &quote(1_001, 1_000)));
+    }
+}
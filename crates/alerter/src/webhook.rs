@@ -0,0 +1,224 @@
+//! Sends health check state changes to webhooks.
+
+use {
+    crate::health::Check,
+    anyhow::{Context, Result, anyhow},
+    reqwest::Client,
+    serde_json::json,
+    std::{fmt, str::FromStr},
+    url::Url,
+};
+
+const PAGERDUTY_EVENTS_URL: &str = "https://events.pagerduty.com/v2/enqueue";
+
+/// A webhook notifications get posted to.
+///
+/// Parsed from `slack|<url>`, `pagerduty|<routing key>` or `generic|<url>`. A
+/// plain URL is a generic webhook.
+#[derive(Clone)]
+pub enum Webhook {
+    /// Posts the notification as a JSON object.
+    Generic(Url),
+    /// Posts to a Slack incoming webhook.
+    Slack(Url),
+    /// Triggers and resolves PagerDuty incidents via the events API.
+    PagerDuty { routing_key: String },
+}
+
+impl FromStr for Webhook {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let webhook = match s.split_once('|') {
+            Some(("generic", url)) => Self::Generic(url.parse()?),
+            Some(("slack", url)) => Self::Slack(url.parse()?),
+            Some(("pagerduty", routing_key)) if !routing_key.is_empty() => Self::PagerDuty {
+                routing_key: routing_key.to_string(),
+            },
+            Some((kind, _)) => return Err(anyhow!("unknown webhook kind {kind:?}")),
+            None => Self::Generic(s.parse()?),
+        };
+        Ok(webhook)
+    }
+}
+
+// Webhook URLs and routing keys are secrets.
+impl fmt::Debug for Webhook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            Self::Generic(_) => "generic",
+            Self::Slack(_) => "slack",
+            Self::PagerDuty { .. } => "pagerduty",
+        };
+        write!(f, "{kind}|SECRET")
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Status {
+    Firing,
+    Resolved,
+}
+
+#[derive(Clone, Debug)]
+pub struct Notification {
+    pub check: Check,
+    pub status: Status,
+    pub description: String,
+}
+
+impl Webhook {
+    fn url(&self) -> &str {
+        match self {
+            Self::Generic(url) | Self::Slack(url) => url.as_str(),
+            Self::PagerDuty { .. } => PAGERDUTY_EVENTS_URL,
+        }
+    }
+
+    fn payload(&self, deployment: &str, notification: &Notification) -> serde_json::Value {
+        let status = match notification.status {
+            Status::Firing => "firing",
+            Status::Resolved => "resolved",
+        };
+        match self {
+            Self::Generic(_) => json!({
+                "deployment": deployment,
+                "check": notification.check.name(),
+                "status": status,
+                "description": notification.description,
+            }),
+            Self::Slack(_) => {
+                let emoji = match notification.status {
+                    Status::Firing => ":rotating_light:",
+                    Status::Resolved => ":white_check_mark:",
+                };
+                json!({
+                    "text": format!(
+                        "{emoji} [{deployment}] {} {status}: {}",
+                        notification.check.name(),
+                        notification.description,
+                    ),
+                })
+            }
+            Self::PagerDuty { routing_key } => {
+                let event_action = match notification.status {
+                    Status::Firing => "trigger",
+                    Status::Resolved => "resolve",
+                };
+                json!({
+                    "routing_key": routing_key,
+                    "event_action": event_action,
+                    "dedup_key": format!("{deployment}/{}", notification.check.name()),
+                    "payload": {
+                        "summary": notification.description,
+                        "source": deployment,
+                        "severity": "critical",
+                        "component": notification.check.name(),
+                    },
+                })
+            }
+        }
+    }
+}
+
+pub struct Notifier {
+    client: Client,
+    webhooks: Vec<Webhook>,
+    /// Name of the observed deployment included in every notification.
+    deployment: String,
+}
+
+impl Notifier {
+    pub fn new(client: Client, webhooks: Vec<Webhook>, deployment: String) -> Self {
+        Self {
+            client,
+            webhooks,
+            deployment,
+        }
+    }
+
+    /// Posts the notification to all webhooks. Failing webhooks don't prevent
+    /// the others from being notified.
+    pub async fn notify(&self, notification: &Notification) {
+        for webhook in &self.webhooks {
+            if let Err(err) = self.post(webhook, notification).await {
+                tracing::warn!(?err, ?webhook, "failed to notify webhook");
+            }
+        }
+    }
+
+    async fn post(&self, webhook: &Webhook, notification: &Notification) -> Result<()> {
+        self.client
+            .post(webhook.url())
+            .json(&webhook.payload(&self.deployment, notification))
+            .send()
+            .await
+            .context("send")?
+            .error_for_status()
+            .context("status")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notification(status: Status) -> Notification {
+        Notification {
+            check: Check::StaleAuction,
+            status,
+            description: "auction 1 has not changed for 90s".to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_webhooks() {
+        assert!(matches!(
+            "https://example.com/hook".parse::<Webhook>().unwrap(),
+            Webhook::Generic(_)
+        ));
+        assert!(matches!(
+            "slack|https://hooks.slack.com/services/abc"
+                .parse::<Webhook>()
+                .unwrap(),
+            Webhook::Slack(_)
+        ));
+        assert!(matches!(
+            "pagerduty|key".parse::<Webhook>().unwrap(),
+            Webhook::PagerDuty { routing_key } if routing_key == "key"
+        ));
+        assert!("pagerduty|".parse::<Webhook>().is_err());
+        assert!("teams|https://example.com".parse::<Webhook>().is_err());
+        assert_eq!(
+            format!(
+                "{:?}",
+                "slack|https://example.com".parse::<Webhook>().unwrap()
+            ),
+            "slack|SECRET"
+        );
+    }
+
+    #[test]
+    fn builds_payloads() {
+        let slack: Webhook = "slack|https://example.com".parse().unwrap();
+        assert_eq!(
+            slack.payload("mainnet", &notification(Status::Firing)),
+            json!({
+                "text": ":rotating_light: [mainnet] stale_auction firing: auction 1 has not \
+                         changed for 90s",
+            })
+        );
+
+        let pagerduty: Webhook = "pagerduty|key".parse().unwrap();
+        let payload = pagerduty.payload("mainnet", &notification(Status::Resolved));
+        assert_eq!(payload["event_action"], "resolve");
+        assert_eq!(payload["dedup_key"], "mainnet/stale_auction");
+        assert_eq!(payload["payload"]["source"], "mainnet");
+
+        let generic: Webhook = "https://example.com".parse().unwrap();
+        let payload = generic.payload("mainnet", &notification(Status::Firing));
+        assert_eq!(payload["check"], "stale_auction");
+        assert_eq!(payload["status"], "firing");
+    }
+}
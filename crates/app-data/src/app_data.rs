@@ -1,5 +1,5 @@
 use {
-    crate::{AppDataHash, Hooks, app_data_hash::hash_full_app_data},
+    crate::{AppDataHash, Hooks, Permit, Trigger, Twap, app_data_hash::hash_full_app_data},
     alloy::primitives::{Address, U256},
     anyhow::{Context, Result, anyhow},
     bytes_hex::BytesHex,
@@ -35,6 +35,9 @@ pub struct ProtocolAppData {
     pub flashloan: Option<Flashloan>,
     #[serde(default)]
     pub wrappers: Vec<WrapperCall>,
+    pub twap: Option<Twap>,
+    pub trigger: Option<Trigger>,
+    pub permit: Option<Permit>,
 }
 
 /// Contains information to hint at how a solver could make
@@ -285,6 +288,18 @@ pub fn parse(full_app_data: &[u8]) -> Result<ProtocolAppData> {
     Ok(parsed)
 }
 
+/// Returns the `appCode` identifying the application that created the app
+/// data if there is one.
+pub fn app_code(full_app_data: &[u8]) -> Option<String> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Root {
+        app_code: Option<String>,
+    }
+
+    serde_json::from_slice::<Root>(full_app_data).ok()?.app_code
+}
+
 /// The root app data JSON object.
 ///
 /// App data JSON is organised in an object of the form
@@ -465,6 +480,9 @@ impl From<BackendAppData> for ProtocolAppData {
             replaced_order: None,
             partner_fee: PartnerFees::default(),
             flashloan: None,
+            twap: None,
+            trigger: None,
+            permit: None,
         }
     }
 }
@@ -693,6 +711,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn twap() {
+        assert_app_data!(
+            r#"
+                {
+                    "appCode": "CoW Swap",
+                    "metadata": {
+                        "twap": {
+                            "parts": 10,
+                            "partDuration": 3600,
+                            "startTime": 1700000000
+                        }
+                    },
+                    "version": "1.1.0"
+                }
+            "#,
+            ProtocolAppData {
+                twap: Some(Twap {
+                    parts: 10,
+                    part_duration: 3600,
+                    start_time: Some(1700000000),
+                }),
+                ..Default::default()
+            },
+        );
+    }
+
     #[test]
     fn legacy() {
         assert_app_data!(
@@ -801,4 +846,14 @@ mod tests {
         let err = validator.validate(size_limit).unwrap_err();
         dbg!(err);
     }
+
+    #[test]
+    fn app_code_() {
+        assert_eq!(
+            app_code(br#"{"appCode":"CoW Swap","metadata":{}}"#),
+            Some("CoW Swap".to_string())
+        );
+        assert_eq!(app_code(br#"{"metadata":{}}"#), None);
+        assert_eq!(app_code(b"invalid"), None);
+    }
 }
@@ -1,5 +1,8 @@
 mod app_data;
 mod app_data_hash;
 mod hooks;
+mod permit;
+mod trigger;
+mod twap;
 
-pub use {app_data::*, app_data_hash::*, hooks::*};
+pub use {app_data::*, app_data_hash::*, hooks::*, permit::*, trigger::*, twap::*};
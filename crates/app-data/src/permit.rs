@@ -0,0 +1,98 @@
+use {
+    alloy::primitives::{B256, U256},
+    anyhow::{Result, ensure},
+    bytes_hex::BytesHex,
+    number::serialization::HexOrDecimalU256,
+    serde::Deserialize,
+    serde_with::serde_as,
+};
+
+/// An EIP-2612 permit of the order's sell token that approves the vault
+/// relayer. It gets executed as a pre-hook before the order is settled for the
+/// first time which allows trading without a prior approval transaction.
+///
+/// Permit2 signatures are not supported because the vault relayer pulls sell
+/// tokens with a plain ERC20 `transferFrom` and never calls into Permit2, so a
+/// Permit2 allowance can not be used to settle an order.
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[cfg_attr(any(test, feature = "test_helpers"), derive(serde::Serialize))]
+#[serde(rename_all = "camelCase")]
+pub struct Permit {
+    /// The approved amount.
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub value: U256,
+    /// Unix timestamp after which the permit can no longer be used.
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub deadline: U256,
+    /// The 65 bytes `r || s || v` ECDSA signature of the permit.
+    #[serde_as(as = "BytesHex")]
+    pub signature: Vec<u8>,
+}
+
+impl Permit {
+    /// Gas limit of the pre-hook executing the permit. Generous enough for
+    /// common EIP-2612 implementations.
+    pub const GAS_LIMIT: u64 = 100_000;
+
+    /// Splits the signature into the `(v, r, s)` arguments of the `permit`
+    /// function.
+    pub fn signature_parts(&self) -> Result<(u8, B256, B256)> {
+        ensure!(
+            self.signature.len() == 65,
+            "permit signature must be 65 bytes long"
+        );
+        let r = B256::from_slice(&self.signature[..32]);
+        let s = B256::from_slice(&self.signature[32..64]);
+        // Some signers produce a recovery id instead of `v`.
+        let v = match self.signature[64] {
+            v @ (0 | 1) => v + 27,
+            v => v,
+        };
+        Ok((v, r, s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_parts() {
+        let mut signature = [[1; 32], [2; 32]].concat();
+        signature.push(28);
+        let permit = Permit {
+            value: U256::from(1),
+            deadline: U256::from(2),
+            signature,
+        };
+        assert_eq!(
+            permit.signature_parts().unwrap(),
+            (28, B256::repeat_byte(1), B256::repeat_byte(2))
+        );
+
+        let mut permit = permit;
+        permit.signature[64] = 0;
+        assert_eq!(permit.signature_parts().unwrap().0, 27);
+
+        permit.signature.pop();
+        assert!(permit.signature_parts().is_err());
+    }
+
+    #[test]
+    fn deserialization() {
+        let permit: Permit = serde_json::from_str(&format!(
+            r#"{{"value": "1000", "deadline": "2000", "signature": "0x{}"}}"#,
+            "01".repeat(65)
+        ))
+        .unwrap();
+        assert_eq!(
+            permit,
+            Permit {
+                value: U256::from(1000),
+                deadline: U256::from(2000),
+                signature: vec![1; 65],
+            }
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use {
+    alloy::primitives::{U256, U512},
+    number::serialization::HexOrDecimalU256,
+    serde::Deserialize,
+    serde_with::serde_as,
+};
+
+/// Keeps an order dormant until the market price of its sell token in terms of
+/// its buy token crosses a threshold.
+///
+/// The threshold price is `buy_amount / sell_amount` (i.e. how many units of
+/// the buy token one unit of the sell token is worth). The market price is
+/// derived from the native prices of both tokens.
+#[serde_as]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[cfg_attr(any(test, feature = "test_helpers"), derive(serde::Serialize))]
+#[serde(rename_all = "camelCase")]
+pub struct Trigger {
+    pub kind: TriggerKind,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub sell_amount: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub buy_amount: U256,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[cfg_attr(any(test, feature = "test_helpers"), derive(serde::Serialize))]
+#[serde(rename_all = "camelCase")]
+pub enum TriggerKind {
+    /// Triggers once the market price drops to or below the threshold.
+    StopLoss,
+    /// Triggers once the market price rises to or above the threshold.
+    TakeProfit,
+}
+
+impl Trigger {
+    /// Returns whether the trigger condition is met given the native prices
+    /// of the order's sell and buy token.
+    pub fn is_met(&self, sell_token_price: U256, buy_token_price: U256) -> bool {
+        // Using let here because widening_mul isn't able to infer the result size
+        let market: U512 = sell_token_price.widening_mul(self.sell_amount);
+        let threshold: U512 = buy_token_price.widening_mul(self.buy_amount);
+        match self.kind {
+            TriggerKind::StopLoss => market <= threshold,
+            TriggerKind::TakeProfit => market >= threshold,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trigger_conditions() {
+        // Trigger once 1 sell token is worth 2 buy tokens.
+        let stop_loss = Trigger {
+            kind: TriggerKind::StopLoss,
+            sell_amount: U256::from(1),
+            buy_amount: U256::from(2),
+        };
+        let take_profit = Trigger {
+            kind: TriggerKind::TakeProfit,
+            ..stop_loss
+        };
+
+        // 1 sell token is worth 3 buy tokens
+        let (sell_price, buy_price) = (U256::from(300), U256::from(100));
+        assert!(!stop_loss.is_met(sell_price, buy_price));
+        assert!(take_profit.is_met(sell_price, buy_price));
+
+        // 1 sell token is worth 2 buy tokens
+        let (sell_price, buy_price) = (U256::from(200), U256::from(100));
+        assert!(stop_loss.is_met(sell_price, buy_price));
+        assert!(take_profit.is_met(sell_price, buy_price));
+
+        // 1 sell token is worth 1 buy token
+        let (sell_price, buy_price) = (U256::from(100), U256::from(100));
+        assert!(stop_loss.is_met(sell_price, buy_price));
+        assert!(!take_profit.is_met(sell_price, buy_price));
+
+        // no overflows
+        assert!(take_profit.is_met(U256::MAX, U256::MAX / U256::from(2)));
+    }
+
+    #[test]
+    fn deserialization() {
+        let trigger: Trigger = serde_json::from_str(
+            r#"{"kind": "stopLoss", "sellAmount": "1000", "buyAmount": "2000"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            trigger,
+            Trigger {
+                kind: TriggerKind::StopLoss,
+                sell_amount: U256::from(1000),
+                buy_amount: U256::from(2000),
+            }
+        );
+    }
+}
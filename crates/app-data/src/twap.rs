@@ -0,0 +1,143 @@
+use {
+    alloy::primitives::U256,
+    anyhow::{Result, ensure},
+    serde::Deserialize,
+};
+
+/// Time-weighted average price execution of an order.
+///
+/// The order is split into `parts` equally sized slices. The first slice
+/// becomes tradeable at `start_time` and every `part_duration` seconds another
+/// one gets added. Slices that did not get executed in their interval remain
+/// tradeable. The limit price of each slice is the limit price of the order.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[cfg_attr(any(test, feature = "test_helpers"), derive(serde::Serialize))]
+#[serde(rename_all = "camelCase")]
+pub struct Twap {
+    /// Number of slices the order gets split into.
+    pub parts: u32,
+    /// Number of seconds between two slices becoming tradeable.
+    pub part_duration: u32,
+    /// Unix timestamp at which the first slice becomes tradeable. Defaults to
+    /// the creation time of the order.
+    #[serde(default)]
+    pub start_time: Option<u32>,
+}
+
+impl Twap {
+    /// Upper bound for the number of slices to keep the per slice amounts
+    /// meaningful.
+    pub const MAX_PARTS: u32 = 1000;
+
+    /// Checks that the parameters describe a schedule that can be completed
+    /// before the order expires.
+    pub fn validate(&self, start_time: u32, valid_to: u32) -> Result<()> {
+        ensure!(self.parts >= 2, "TWAP orders need at least 2 parts");
+        ensure!(
+            self.parts <= Self::MAX_PARTS,
+            "TWAP orders can have at most {} parts",
+            Self::MAX_PARTS
+        );
+        ensure!(self.part_duration > 0, "TWAP part duration must not be 0");
+        let end = u64::from(start_time) + u64::from(self.parts) * u64::from(self.part_duration);
+        ensure!(
+            end <= u64::from(valid_to),
+            "TWAP order expires before its last part ends at {end}"
+        );
+        Ok(())
+    }
+
+    /// Returns how many slices are tradeable at `now` given that the first one
+    /// becomes tradeable at `start_time`.
+    pub fn unlocked_parts(&self, start_time: u32, now: u32) -> u32 {
+        let Some(elapsed) = now.checked_sub(start_time) else {
+            return 0;
+        };
+        (elapsed / self.part_duration.max(1))
+            .saturating_add(1)
+            .min(self.parts)
+    }
+
+    /// Returns the cumulative amount of `total` that may be executed by `now`.
+    pub fn unlocked_amount(&self, total: U256, start_time: u32, now: u32) -> U256 {
+        let unlocked = self.unlocked_parts(start_time, now);
+        if unlocked >= self.parts {
+            // Avoids leaving rounding dust in the last slice.
+            return total;
+        }
+        total / U256::from(self.parts) * U256::from(unlocked)
+            + total % U256::from(self.parts) * U256::from(unlocked) / U256::from(self.parts)
+    }
+
+    /// Returns the time at which the next slice becomes tradeable or `None` if
+    /// all slices are already tradeable.
+    pub fn next_part_time(&self, start_time: u32, now: u32) -> Option<u32> {
+        let unlocked = self.unlocked_parts(start_time, now);
+        (unlocked < self.parts)
+            .then(|| start_time.saturating_add(unlocked.saturating_mul(self.part_duration)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TWAP: Twap = Twap {
+        parts: 4,
+        part_duration: 100,
+        start_time: None,
+    };
+
+    #[test]
+    fn validation() {
+        assert!(TWAP.validate(1000, 1400).is_ok());
+        assert!(TWAP.validate(1000, 1399).is_err());
+        assert!(Twap { parts: 1, ..TWAP }.validate(1000, 2000).is_err());
+        assert!(
+            Twap {
+                parts: Twap::MAX_PARTS + 1,
+                ..TWAP
+            }
+            .validate(0, u32::MAX)
+            .is_err()
+        );
+        assert!(
+            Twap {
+                part_duration: 0,
+                ..TWAP
+            }
+            .validate(1000, 2000)
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn slices() {
+        assert_eq!(TWAP.unlocked_parts(1000, 999), 0);
+        assert_eq!(TWAP.unlocked_parts(1000, 1000), 1);
+        assert_eq!(TWAP.unlocked_parts(1000, 1199), 2);
+        assert_eq!(TWAP.unlocked_parts(1000, 1300), 4);
+        assert_eq!(TWAP.unlocked_parts(1000, 5000), 4);
+
+        let total = U256::from(1001);
+        assert_eq!(TWAP.unlocked_amount(total, 1000, 999), U256::ZERO);
+        assert_eq!(TWAP.unlocked_amount(total, 1000, 1000), U256::from(250));
+        assert_eq!(TWAP.unlocked_amount(total, 1000, 1100), U256::from(500));
+        assert_eq!(TWAP.unlocked_amount(total, 1000, 1200), U256::from(750));
+        assert_eq!(TWAP.unlocked_amount(total, 1000, 1300), total);
+        assert_eq!(
+            TWAP.unlocked_amount(U256::MAX, 1000, 1200),
+            U256::MAX / U256::from(4) * U256::from(3) + U256::from(2)
+        );
+
+        assert_eq!(TWAP.next_part_time(1000, 999), Some(1000));
+        assert_eq!(TWAP.next_part_time(1000, 1150), Some(1200));
+        assert_eq!(TWAP.next_part_time(1000, 1300), None);
+    }
+
+    #[test]
+    fn deserialization() {
+        let twap: Twap = serde_json::from_str(r#"{"parts": 4, "partDuration": 100}"#).unwrap();
+        assert_eq!(twap, TWAP);
+    }
+}
@@ -14,7 +14,7 @@ use {
     std::{
         fmt::{self, Display, Formatter},
         net::SocketAddr,
-        num::NonZeroUsize,
+        num::{NonZeroU64, NonZeroUsize},
         str::FromStr,
         time::Duration,
     },
@@ -29,6 +29,9 @@ pub struct Arguments {
     #[clap(flatten)]
     pub order_quoting: shared::arguments::OrderQuotingArguments,
 
+    #[clap(flatten)]
+    pub banned_users_sources: shared::arguments::BannedUsersArguments,
+
     #[clap(flatten)]
     pub http_client: http_client::Arguments,
 
@@ -251,6 +254,10 @@ pub struct Arguments {
     #[clap(flatten)]
     pub db_based_solver_participation_guard: DbBasedSolverParticipationGuardConfig,
 
+    /// Configuration for the solver rewards accounting.
+    #[clap(flatten)]
+    pub solver_rewards: SolverRewardsConfig,
+
     /// Configures whether the autopilot filters out orders with insufficient
     /// balances.
     #[clap(long, env, default_value = "false", action = clap::ArgAction::Set)]
@@ -357,11 +364,52 @@ pub struct LowSettlingSolversFinderConfig {
     pub solver_max_settlement_failure_rate: f64,
 }
 
+#[derive(Debug, clap::Parser)]
+pub struct SolverRewardsConfig {
+    /// Enables the accounting of solver rewards and penalties.
+    #[clap(
+        id = "solver_rewards_enabled",
+        long = "solver-rewards-enabled",
+        env = "SOLVER_REWARDS_ENABLED",
+        default_value = "false",
+        action = clap::ArgAction::Set,
+    )]
+    pub enabled: bool,
+
+    /// Block at which the first accounting period starts. Required when
+    /// solver rewards are enabled.
+    #[clap(long, env, required_if_eq("solver_rewards_enabled", "true"))]
+    pub solver_rewards_first_block: Option<u64>,
+
+    /// Number of blocks of every accounting period.
+    #[clap(long, env, default_value = "50400")]
+    pub solver_rewards_period_length: NonZeroU64,
+
+    /// Maximum reward of a solver per auction in Ether, on top of the share
+    /// of its protocol fees.
+    #[clap(long, env, default_value = "0.012", value_parser = shared::arguments::wei_from_ether)]
+    pub solver_rewards_upper_cap: U256,
+
+    /// Maximum penalty of a solver per auction in Ether.
+    #[clap(long, env, default_value = "0.01", value_parser = shared::arguments::wei_from_ether)]
+    pub solver_rewards_lower_cap: U256,
+
+    /// Share of the protocol fees a solver generated in an auction that gets
+    /// added to its maximum reward.
+    #[clap(long, env, default_value = "0")]
+    pub solver_rewards_protocol_fee_cap_factor: f64,
+
+    /// How often to check for completed accounting periods.
+    #[clap(long, env, default_value = "10m", value_parser = humantime::parse_duration)]
+    pub solver_rewards_update_interval: Duration,
+}
+
 impl std::fmt::Display for Arguments {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let Self {
             shared,
             order_quoting,
+            banned_users_sources,
             http_client,
             token_owner_finder,
             price_estimation,
@@ -401,6 +449,7 @@ impl std::fmt::Display for Arguments {
             archive_node_url,
             max_solutions_per_solver,
             db_based_solver_participation_guard,
+            solver_rewards,
             disable_order_balance_filter,
             disable_1271_order_balance_filter,
             disable_1271_order_sig_filter,
@@ -409,6 +458,7 @@ impl std::fmt::Display for Arguments {
 
         write!(f, "{shared}")?;
         write!(f, "{order_quoting}")?;
+        write!(f, "{banned_users_sources}")?;
         write!(f, "{http_client}")?;
         write!(f, "{token_owner_finder}")?;
         write!(f, "{price_estimation}")?;
@@ -475,6 +525,7 @@ impl std::fmt::Display for Arguments {
             f,
             "db_based_solver_participation_guard: {db_based_solver_participation_guard:?}"
         )?;
+        writeln!(f, "solver_rewards: {solver_rewards:?}")?;
         writeln!(
             f,
             "disable_order_balance_filter: {disable_order_balance_filter}"
@@ -10,6 +10,7 @@ pub use {
         order::{
             BuyTokenDestination,
             EthflowData,
+            HooksGas,
             OnchainOrderData,
             Order,
             OrderClass,
@@ -34,6 +34,7 @@ pub fn to_domain(
         owner: order.metadata.owner.into_legacy().into(),
         partially_fillable: order.data.partially_fillable,
         executed: remaining_order.executed_amount.into(),
+        available: None,
         pre_interactions: if order_is_untouched {
             order.interactions.pre.into_iter().map(Into::into).collect()
         } else {
@@ -45,6 +46,19 @@ pub fn to_domain(
             .into_iter()
             .map(Into::into)
             .collect(),
+        hooks_gas: order
+            .metadata
+            .hooks_gas
+            .map(|gas| domain::auction::order::HooksGas {
+                // The pre-interactions are dropped for orders that already
+                // got executed so the same has to happen to their gas.
+                pre: if order_is_untouched {
+                    gas.pre
+                } else {
+                    Default::default()
+                },
+                post: gas.post,
+            }),
         sell_token_balance: order.data.sell_token_balance.into(),
         buy_token_balance: order.data.buy_token_balance.into(),
         app_data: order.data.app_data.into(),
@@ -1,7 +1,13 @@
 use {
     super::{OnchainOrderCustomData, OnchainOrderParsing},
     crate::database::events::log_to_event_index,
-    alloy::{eips::BlockNumberOrTag, rpc::types::Log},
+    alloy::{
+        eips::BlockNumberOrTag,
+        primitives::Address,
+        providers::Provider,
+        rpc::types::{Filter, Log},
+        sol_types::SolEvent,
+    },
     anyhow::{Context, Result, anyhow},
     contracts::alloy::{
         CoWSwapOnchainOrders::CoWSwapOnchainOrders::{
@@ -12,6 +18,7 @@ use {
     },
     database::{
         PgTransaction,
+        TransactionHash,
         byte_array::ByteArray,
         ethflow_orders::EthOrderPlacement,
         events::EventIndex,
@@ -24,6 +31,7 @@ use {
         block_stream::{BlockNumberHash, block_number_to_block_number_hash},
     },
     hex_literal::hex,
+    itertools::Itertools,
     sqlx::{PgPool, types::BigDecimal},
     std::{collections::HashMap, convert::TryInto},
     tracing::instrument,
@@ -38,6 +46,7 @@ pub struct EthFlowOnchainOrderParser;
 #[derive(Copy, Debug, Clone)]
 pub struct EthFlowData {
     user_valid_to: u32,
+    placement_tx_hash: Option<TransactionHash>,
 }
 
 #[derive(Debug, Clone)]
@@ -67,7 +76,12 @@ impl OnchainOrderParsing<EthFlowData, EthFlowDataForDb> for EthFlowOnchainOrderP
                         event_index,
                         OnchainOrderCustomData {
                             quote_id,
-                            additional_data: Some(EthFlowData { user_valid_to }),
+                            additional_data: Some(EthFlowData {
+                                user_valid_to,
+                                placement_tx_hash: log
+                                    .transaction_hash
+                                    .map(|hash| ByteArray(hash.0)),
+                            }),
                         },
                     ))),
                     Err(err) => {
@@ -114,12 +128,15 @@ impl OnchainOrderParsing<EthFlowData, EthFlowDataForDb> for EthFlowOnchainOrderP
         hashmap: &HashMap<EventIndex, EthFlowData>,
         _onchain_order_placement: &OnchainOrderPlacement,
     ) -> EthFlowDataForDb {
+        // unwrap is allowed, as any missing event_index would have been filtered
+        // beforehand by the implementation of the function
+        // parse_custom_event_data
+        let data = hashmap.get(event_index).unwrap();
         EthFlowDataForDb {
             eth_order_placement: EthOrderPlacement {
                 uid: order.uid,
-                // unwrap is allowed, as any missing event_index would have been filtered beforehand
-                // by the implementation of the function parse_custom_event_data
-                valid_to: hashmap.get(event_index).unwrap().user_valid_to as i64,
+                valid_to: data.user_valid_to as i64,
+                placement_tx_hash: data.placement_tx_hash,
             },
             // The following interaction calls the wrap_all() function on the ethflow contract
             // in order to wrap all existing ether to weth, such that the eth can be used as
@@ -136,6 +153,103 @@ impl OnchainOrderParsing<EthFlowData, EthFlowDataForDb> for EthFlowOnchainOrderP
     }
 }
 
+/// Ethflow orders indexed before their placement tx hash got stored don't
+/// have one. Backfills it from the placement events of the blocks they got
+/// indexed in. Orders whose event can't be found get flagged so they aren't
+/// looked up again. Failures are only logged since the backfill resumes on the
+/// next start.
+pub async fn backfill_placement_tx_hashes(pool: PgPool, provider: AlloyProvider) {
+    let mut after = (0, 0);
+    loop {
+        match backfill_placement_tx_hashes_batch(&pool, &provider, after).await {
+            Ok(Some(last)) => after = last,
+            Ok(None) => break,
+            Err(err) => {
+                tracing::warn!(?err, "failed to backfill ethflow placement tx hashes");
+                return;
+            }
+        }
+    }
+    tracing::debug!("backfilled ethflow placement tx hashes");
+}
+
+/// Backfills the placement tx hashes of the next batch of orders whose
+/// placement events come after `after`. Returns the position of the last
+/// processed event or `None` if there was nothing left to backfill.
+async fn backfill_placement_tx_hashes_batch(
+    pool: &PgPool,
+    provider: &AlloyProvider,
+    after: (i64, i64),
+) -> Result<Option<(i64, i64)>> {
+    const BATCH_SIZE: i64 = 100;
+    /// Most blocks to request logs for at once. Nodes limit the range of
+    /// `eth_getLogs` requests.
+    const MAX_BLOCK_RANGE: u64 = 5_000;
+
+    let mut ex = pool.acquire().await?;
+    let missing =
+        database::ethflow_orders::missing_placement_tx_hashes(&mut ex, after, BATCH_SIZE).await?;
+    let Some(last) = missing.last() else {
+        return Ok(None);
+    };
+    let last = (last.block_number, last.log_index);
+
+    // The owner of an ethflow order is the ethflow contract that placed it.
+    let contracts: Vec<_> = missing
+        .iter()
+        .map(|order| Address::from_slice(&order.uid.0[32..52]))
+        .unique()
+        .collect();
+    let blocks = missing
+        .iter()
+        .map(|order| u64::try_from(order.block_number).context("negative block number"))
+        .collect::<Result<Vec<_>>>()?;
+    let mut tx_hashes = HashMap::new();
+    for (from, to) in block_ranges(&blocks, MAX_BLOCK_RANGE) {
+        let filter = Filter::new()
+            .address(contracts.clone())
+            .event_signature(ContractOrderPlacement::SIGNATURE_HASH)
+            .from_block(from)
+            .to_block(to);
+        let logs = provider
+            .get_logs(&filter)
+            .await
+            .with_context(|| format!("could not get logs of blocks {from} to {to}"))?;
+        tx_hashes.extend(logs.into_iter().filter_map(|log| {
+            let block_number = i64::try_from(log.block_number?).ok()?;
+            let log_index = i64::try_from(log.log_index?).ok()?;
+            Some(((block_number, log_index), log.transaction_hash?))
+        }));
+    }
+    for order in &missing {
+        let Some(tx_hash) = tx_hashes.get(&(order.block_number, order.log_index)) else {
+            tracing::warn!(
+                uid = const_hex::encode_prefixed(order.uid.0),
+                "placement event of ethflow order not found"
+            );
+            database::ethflow_orders::mark_placement_tx_hash_unavailable(&mut ex, &order.uid)
+                .await?;
+            continue;
+        };
+        database::ethflow_orders::set_placement_tx_hash(&mut ex, &order.uid, &ByteArray(tx_hash.0))
+            .await?;
+    }
+    Ok(Some(last))
+}
+
+/// Groups the sorted block numbers into inclusive ranges spanning at most
+/// `max_range` blocks.
+fn block_ranges(blocks: &[u64], max_range: u64) -> Vec<(u64, u64)> {
+    let mut ranges: Vec<(u64, u64)> = Vec::new();
+    for &block in blocks {
+        match ranges.last_mut() {
+            Some((from, to)) if block - *from < max_range => *to = block,
+            _ => ranges.push((block, block)),
+        }
+    }
+    ranges
+}
+
 fn convert_to_quote_id_and_user_valid_to(
     order_placement: &ContractOrderPlacement,
 ) -> Result<(i64, u32)> {
@@ -292,11 +406,20 @@ async fn find_indexing_start_block(
 mod test {
     use {
         super::*,
-        alloy::primitives::{Address, U256},
+        alloy::primitives::U256,
         contracts::alloy::CoWSwapOnchainOrders,
         model::order::{BuyTokenDestination, OrderKind, SellTokenSource},
     };
 
+    #[test]
+    fn groups_blocks_into_ranges() {
+        assert!(block_ranges(&[], 10).is_empty());
+        assert_eq!(
+            block_ranges(&[1, 1, 5, 10, 11, 30], 10),
+            vec![(1, 10), (11, 11), (30, 30)]
+        );
+    }
+
     #[test]
     pub fn test_convert_to_quote_id_and_user_valid_to() {
         let event_data = ContractOrderPlacement {
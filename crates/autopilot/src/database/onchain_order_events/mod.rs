@@ -573,6 +573,7 @@ async fn get_quote(
         &parameters.clone(),
         Some(*quote_id),
         Some(order_data.fee_amount.into_legacy()),
+        true,
     )
     .await
     .map_err(|err| match err {
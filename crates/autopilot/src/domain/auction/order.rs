@@ -20,10 +20,17 @@ pub struct Order {
     pub owner: eth::Address,
     pub partially_fillable: bool,
     pub executed: TargetAmount,
+    /// How much of the target amount can be executed at most right now if
+    /// that is less than what remains of the order (e.g. TWAP orders whose
+    /// later slices are still locked). The signed amounts are left untouched.
+    pub available: Option<TargetAmount>,
     // Partially fillable orders should have their pre-interactions only executed
     // on the first fill.
     pub pre_interactions: Vec<Interaction>,
     pub post_interactions: Vec<Interaction>,
+    /// Gas used by the interactions when they got simulated at order
+    /// placement, if they got simulated.
+    pub hooks_gas: Option<HooksGas>,
     pub sell_token_balance: SellTokenSource,
     pub buy_token_balance: BuyTokenDestination,
     pub app_data: AppDataHash,
@@ -81,6 +88,13 @@ pub struct Interaction {
     pub call_data: Vec<u8>,
 }
 
+/// Gas used by every pre- and post-interaction of an order in execution order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HooksGas {
+    pub pre: Vec<u64>,
+    pub post: Vec<u64>,
+}
+
 /// Source from which the sellAmount should be drawn upon order fulfillment
 #[derive(Clone, Debug, PartialEq)]
 pub enum SellTokenSource {
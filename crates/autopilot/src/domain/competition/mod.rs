@@ -1,6 +1,6 @@
 use {
     super::auction::order,
-    crate::domain::{self, auction, eth},
+    crate::domain::{self, auction, eth, settlement::math},
     derive_more::Display,
     num::Saturating,
     std::collections::HashMap,
@@ -11,7 +11,7 @@ mod participation_guard;
 pub mod winner_selection;
 
 pub use {
-    participant::{Participant, Ranked, Unranked},
+    participant::{FilterReason, Participant, Ranked, Unranked},
     participation_guard::SolverParticipationGuard,
 };
 
@@ -30,6 +30,9 @@ pub struct Solution {
     /// of the solver.
     // TODO: refactor this to compute the score in the constructor
     computed_score: Option<Score>,
+    /// How each order contributed to the computed score. Orders that are not
+    /// allowed to capture surplus have no entry.
+    order_scores: HashMap<domain::OrderUid, math::ScoreBreakdown>,
 }
 
 impl Solution {
@@ -47,6 +50,7 @@ impl Solution {
             orders,
             prices,
             computed_score: None,
+            order_scores: Default::default(),
         }
     }
 
@@ -66,6 +70,10 @@ impl Solution {
         self.computed_score.as_ref()
     }
 
+    pub fn order_scores(&self) -> &HashMap<domain::OrderUid, math::ScoreBreakdown> {
+        &self.order_scores
+    }
+
     pub fn order_ids(&self) -> impl Iterator<Item = &domain::OrderUid> + std::fmt::Debug {
         self.orders.keys()
     }
@@ -1,7 +1,10 @@
 use {
     super::{Score, Solution},
-    crate::infra,
-    std::sync::Arc,
+    crate::{
+        domain::{self, settlement::math},
+        infra,
+    },
+    std::{collections::HashMap, sync::Arc},
 };
 
 #[derive(Clone)]
@@ -16,7 +19,15 @@ pub struct Unranked;
 pub enum Ranked {
     Winner,
     NonWinner,
-    FilteredOut,
+    FilteredOut(FilterReason),
+}
+
+/// Why a solution got filtered out before picking the winners.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterReason {
+    /// The solution executed a directed token pair at a lower score than the
+    /// best solution only trading that token pair (the fairness baseline).
+    Unfair,
 }
 
 impl<T> Participant<T> {
@@ -28,6 +39,10 @@ impl<T> Participant<T> {
         self.solution.computed_score = Some(score);
     }
 
+    pub fn set_order_scores(&mut self, scores: HashMap<domain::OrderUid, math::ScoreBreakdown>) {
+        self.solution.order_scores = scores;
+    }
+
     pub fn driver(&self) -> &Arc<infra::Driver> {
         &self.driver
     }
@@ -57,6 +72,13 @@ impl Participant<Ranked> {
     }
 
     pub fn filtered_out(&self) -> bool {
-        matches!(self.state, Ranked::FilteredOut)
+        matches!(self.state, Ranked::FilteredOut(_))
+    }
+
+    pub fn filter_reason(&self) -> Option<FilterReason> {
+        match self.state {
+            Ranked::FilteredOut(reason) => Some(reason),
+            Ranked::Winner | Ranked::NonWinner => None,
+        }
     }
 }
@@ -32,7 +32,7 @@ use {
             Prices,
             order::{self, TargetAmount},
         },
-        competition::{Participant, Ranked, Score, Solution, Unranked},
+        competition::{FilterReason, Participant, Ranked, Score, Solution, Unranked},
         eth::{self, WrappedNativeToken},
         fee,
         settlement::{
@@ -55,16 +55,16 @@ use {
 /// changing the ordering or the `participants`.
 impl Arbitrator {
     /// Runs the entire auction mechanism on the passed in solutions.
-    pub fn arbitrate(
+    pub fn arbitrate<'a>(
         &self,
         participants: Vec<Participant<Unranked>>,
-        auction: &domain::Auction,
+        auction: impl Into<Auction<'a>>,
     ) -> Ranking {
-        let partitioned = self.partition_unfair_solutions(participants, auction);
+        let partitioned = self.partition_unfair_solutions(participants, &auction.into());
         let filtered_out = partitioned
             .discarded
             .into_iter()
-            .map(|participant| participant.rank(Ranked::FilteredOut))
+            .map(|participant| participant.rank(Ranked::FilteredOut(FilterReason::Unfair)))
             .collect();
 
         let mut ranked = self.mark_winners(partitioned.kept);
@@ -79,6 +79,7 @@ impl Arbitrator {
         Ranking {
             filtered_out,
             ranked,
+            fairness_baselines: partitioned.baseline_scores,
         }
     }
 
@@ -86,11 +87,12 @@ impl Arbitrator {
     fn partition_unfair_solutions(
         &self,
         mut participants: Vec<Participant<Unranked>>,
-        auction: &domain::Auction,
+        auction: &Auction,
     ) -> PartitionedSolutions {
         // Discard all solutions where we can't compute the aggregate scores
         // accurately because the fairness guarantees heavily rely on them.
-        let scores_by_solution = compute_scores_by_solution(&mut participants, auction);
+        let scores_by_solution =
+            compute_scores_by_solution(&mut participants, auction, self.score_definition);
         participants.sort_by_key(|participant| {
             std::cmp::Reverse(
                 // we use the computed score to not trust the score provided by solvers
@@ -112,16 +114,21 @@ impl Arbitrator {
                 .expect("every remaining participant has an entry");
             // only keep solutions where each order execution is at least as good as
             // the baseline solution.
-            // we only filter out unfair solutions with more than one token pair,
-            // to avoid reference scores set to 0.
+            // by default we only filter out unfair solutions with more than one
+            // token pair, to avoid reference scores set to 0.
             // see https://github.com/fhenneke/comb_auctions/issues/2
-            if aggregated_scores.len() == 1
-                || aggregated_scores.iter().all(|(pair, score)| {
-                    baseline_scores
-                        .get(pair)
-                        .is_none_or(|baseline| score >= baseline)
-                })
-            {
+            let is_fair = match self.fairness {
+                Fairness::Disabled => true,
+                Fairness::Baseline if aggregated_scores.len() == 1 => true,
+                Fairness::Baseline | Fairness::StrictBaseline => {
+                    aggregated_scores.iter().all(|(pair, score)| {
+                        baseline_scores
+                            .get(pair)
+                            .is_none_or(|baseline| score >= baseline)
+                    })
+                }
+            };
+            if is_fair {
                 Either::Left(p)
             } else {
                 Either::Right(p)
@@ -130,6 +137,7 @@ impl Arbitrator {
         PartitionedSolutions {
             kept: fair,
             discarded: unfair,
+            baseline_scores,
         }
     }
 
@@ -250,13 +258,24 @@ fn compute_baseline_scores(scores_by_solution: &ScoresBySolution) -> ScoreByDire
 /// depend on these scores being accurate.
 fn compute_scores_by_solution(
     participants: &mut Vec<Participant<Unranked>>,
-    auction: &domain::Auction,
+    auction: &Auction,
+    score_definition: ScoreDefinition,
 ) -> ScoresBySolution {
-    let auction = Auction::from(auction);
     let mut scores = HashMap::default();
 
-    participants.retain_mut(|p| match score_by_token_pair(p.solution(), &auction) {
-        Ok(score) => {
+    participants.retain_mut(|p| match score_by_order(p.solution(), auction) {
+        Ok(order_scores) => {
+            let mut score = ScoreByDirection::default();
+            for (uid, order_score) in &order_scores {
+                let trade = &p.solution().orders()[uid];
+                score
+                    .entry(DirectedTokenPair {
+                        sell: trade.sell.token,
+                        buy: trade.buy.token,
+                    })
+                    .or_default()
+                    .saturating_add_assign(score_definition.score(order_score));
+            }
             let total_score = score
                 .values()
                 .fold(Score::default(), |acc, score| acc.saturating_add(*score));
@@ -268,6 +287,7 @@ fn compute_scores_by_solution(
                 score,
             );
             p.set_computed_score(total_score);
+            p.set_order_scores(order_scores);
             true
         }
         Err(err) => {
@@ -284,16 +304,20 @@ fn compute_scores_by_solution(
     scores
 }
 
-/// Returns the total scores for each directed token pair of the solution.
-/// E.g. if a solution contains 3 orders like:
+/// Returns the score of every order of the solution that contributes to the
+/// score. The scores get aggregated by directed token pair for the fairness
+/// check, e.g. if a solution contains 3 orders like:
 ///     sell A for B with a score of 10
 ///     sell A for B with a score of 5
 ///     sell B for C with a score of 5
-/// it will return a map like:
+/// the aggregated scores are:
 ///     (A, B) => 15
 ///     (B, C) => 5
-fn score_by_token_pair(solution: &Solution, auction: &Auction) -> Result<ScoreByDirection> {
-    let mut scores: HashMap<DirectedTokenPair, Score> = HashMap::default();
+fn score_by_order(
+    solution: &Solution,
+    auction: &Auction,
+) -> Result<HashMap<OrderUid, math::ScoreBreakdown>> {
+    let mut scores = HashMap::default();
     for (uid, trade) in solution.orders() {
         if !auction.contributes_to_score(uid) {
             continue;
@@ -308,15 +332,26 @@ fn score_by_token_pair(solution: &Solution, auction: &Auction) -> Result<ScoreBy
             .get(&trade.buy.token)
             .context("no uniform clearing price for buy token")?;
 
+        let executed = match trade.side {
+            order::Side::Buy => TargetAmount(trade.executed_buy.into()),
+            order::Side::Sell => TargetAmount(trade.executed_sell.into()),
+        };
+        // The signed amounts of orders with locked parts (e.g. TWAP orders)
+        // cover the whole order so neither the driver of the solver nor the
+        // settlement contract can be trusted to enforce this limit.
+        if let Some(available) = auction.available.get(uid) {
+            anyhow::ensure!(
+                executed.0 <= available.0,
+                "order {uid} executed more than the available amount"
+            );
+        }
+
         let trade = math::Trade {
             uid: *uid,
             sell: trade.sell,
             buy: trade.buy,
             side: trade.side,
-            executed: match trade.side {
-                order::Side::Buy => TargetAmount(trade.executed_buy.into()),
-                order::Side::Sell => TargetAmount(trade.executed_sell.into()),
-            },
+            executed,
             prices: transaction::Prices {
                 // clearing prices are denominated in the same underlying
                 // unit so we assign sell to sell and buy to buy
@@ -335,18 +370,9 @@ fn score_by_token_pair(solution: &Solution, auction: &Auction) -> Result<ScoreBy
             },
         };
         let score = trade
-            .score(&auction.fee_policies, auction.native_prices)
+            .score_breakdown(&auction.fee_policies, auction.native_prices)
             .context("failed to compute score")?;
-
-        let token_pair = DirectedTokenPair {
-            sell: trade.sell.token,
-            buy: trade.buy.token,
-        };
-
-        scores
-            .entry(token_pair)
-            .or_default()
-            .saturating_add_assign(Score(score));
+        scores.insert(*uid, score);
     }
     Ok(scores)
 }
@@ -354,19 +380,94 @@ fn score_by_token_pair(solution: &Solution, auction: &Auction) -> Result<ScoreBy
 pub struct Arbitrator {
     pub max_winners: usize,
     pub weth: WrappedNativeToken,
+    pub fairness: Fairness,
+    pub score_definition: ScoreDefinition,
+}
+
+/// Rule deciding which solutions get filtered out as unfair before picking
+/// the winners.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    clap::ValueEnum,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum Fairness {
+    /// Solutions trading multiple directed token pairs must execute each pair
+    /// at least as well as the best solution only trading that pair.
+    #[default]
+    Baseline,
+    /// Like `Baseline` but also applies to solutions only trading a single
+    /// directed token pair.
+    StrictBaseline,
+    /// No solution gets filtered out.
+    Disabled,
+}
+
+/// Which parts of a trade count towards the score of a solution.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    clap::ValueEnum,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScoreDefinition {
+    /// Surplus of the user plus the protocol fees (CIP-38).
+    #[default]
+    SurplusAndProtocolFees,
+    /// Only the surplus of the user.
+    Surplus,
+}
+
+impl ScoreDefinition {
+    fn score(&self, breakdown: &math::ScoreBreakdown) -> Score {
+        match self {
+            Self::SurplusAndProtocolFees => Score(breakdown.total()),
+            Self::Surplus => Score(breakdown.surplus),
+        }
+    }
 }
 
 /// Relevant data from `domain::Auction` but with data structures
 /// optimized for the winner selection logic.
 /// Avoids clones whenever possible.
-struct Auction<'a> {
+pub struct Auction<'a> {
     /// Fee policies for **all** orders that were in the original auction.
     fee_policies: HashMap<OrderUid, &'a Vec<fee::Policy>>,
     surplus_capturing_jit_order_owners: HashSet<eth::Address>,
     native_prices: &'a Prices,
+    /// Maximum amount orders can be executed by if that is less than what
+    /// remains of them. Solutions executing more get discarded.
+    available: HashMap<OrderUid, TargetAmount>,
 }
 
-impl Auction<'_> {
+impl<'a> Auction<'a> {
+    pub fn new(
+        fee_policies: HashMap<OrderUid, &'a Vec<fee::Policy>>,
+        surplus_capturing_jit_order_owners: HashSet<eth::Address>,
+        native_prices: &'a Prices,
+        available: HashMap<OrderUid, TargetAmount>,
+    ) -> Self {
+        Self {
+            fee_policies,
+            surplus_capturing_jit_order_owners,
+            native_prices,
+            available,
+        }
+    }
+
     /// Returns whether an order is allowed to capture surplus and
     /// therefore contributes to the total score of a solution.
     fn contributes_to_score(&self, uid: &OrderUid) -> bool {
@@ -391,14 +492,19 @@ impl<'a> From<&'a domain::Auction> for Auction<'a> {
                 .iter()
                 .cloned()
                 .collect(),
+            available: original
+                .orders
+                .iter()
+                .filter_map(|o| Some((o.uid, o.available?)))
+                .collect(),
         }
     }
 }
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
-struct DirectedTokenPair {
-    sell: eth::TokenAddress,
-    buy: eth::TokenAddress,
+pub struct DirectedTokenPair {
+    pub sell: eth::TokenAddress,
+    pub buy: eth::TokenAddress,
 }
 
 /// Key to uniquely identify every solution.
@@ -425,6 +531,9 @@ pub struct Ranking {
     /// check. Winners come before non-winners and higher total
     /// scores come before lower scores.
     ranked: Vec<Participant<Ranked>>,
+    /// Best score of a solution only trading the directed token pair. Used as
+    /// the baseline solutions trading multiple pairs have to beat to be fair.
+    fairness_baselines: ScoreByDirection,
 }
 
 impl Ranking {
@@ -452,16 +561,24 @@ impl Ranking {
     pub fn ranked(&self) -> impl Iterator<Item = &Participant<Ranked>> {
         self.ranked.iter()
     }
+
+    /// Fairness baseline scores of all directed token pairs that were traded
+    /// by a solution only containing that pair.
+    pub fn fairness_baselines(&self) -> impl Iterator<Item = (&DirectedTokenPair, &Score)> {
+        self.fairness_baselines.iter()
+    }
 }
 
 struct PartitionedSolutions {
     kept: Vec<Participant<Unranked>>,
     discarded: Vec<Participant<Unranked>>,
+    baseline_scores: ScoreByDirection,
 }
 
 #[cfg(test)]
 mod tests {
     use {
+        super::{Fairness, ScoreDefinition},
         crate::{
             domain::{
                 Auction,
@@ -471,13 +588,15 @@ mod tests {
                     Price,
                     order::{self, AppDataHash},
                 },
-                competition::{Participant, Score, Solution, TradedOrder, Unranked},
+                competition::{FilterReason, Participant, Score, Solution, TradedOrder, Unranked},
                 eth::{self, TokenAddress},
+                fee,
             },
             infra::Driver,
         },
         ethcontract::H160,
         hex_literal::hex,
+        num::Saturating,
         number::serialization::HexOrDecimalU256,
         serde::Deserialize,
         serde_json::json,
@@ -543,6 +662,58 @@ mod tests {
         TestCase::from_json(case).validate().await;
     }
 
+    #[tokio::test]
+    // A solution executing more of an order than is available in the auction
+    // (e.g. a TWAP order with locked slices) gets discarded
+    async fn over_filled_available_amount() {
+        let case = json!({
+            "tokens": [
+                ["Token A", address(0)],
+                ["Token B", address(1)]
+            ],
+            "auction": {
+                "orders": {
+                    "TWAP order": {
+                        "side": "sell",
+                        "sell_token": "Token A",
+                        "sell_amount": amount(1_000),
+                        "buy_token": "Token B",
+                        "buy_amount": amount(1_000),
+                        "available": amount(250)
+                    }
+                }
+            },
+            "solutions": {
+                // executes the whole order at once
+                "Over-filling solution": {
+                    "solver": "Solver 1",
+                    "trades": {
+                        "TWAP order": {
+                            "sell_amount": amount(1_000),
+                            "buy_amount": amount(1_100)
+                        }
+                    }
+                },
+                // only executes the unlocked slice
+                "Slice solution": {
+                    "solver": "Solver 2",
+                    "trades": {
+                        "TWAP order": {
+                            "sell_amount": amount(250),
+                            "buy_amount": amount(275)
+                        }
+                    }
+                }
+            },
+            "expected_fair_solutions": ["Slice solution"],
+            "expected_winners": ["Slice solution"],
+            "expected_reference_scores": {
+                "Solver 2": "0",
+            },
+        });
+        TestCase::from_json(case).validate().await;
+    }
+
     #[tokio::test]
     // Two compatible batches are both selected as winners
     async fn compatible_bids() {
@@ -883,6 +1054,122 @@ mod tests {
         TestCase::from_json(case).validate().await;
     }
 
+    #[tokio::test]
+    // Unfair batch is kept if fairness filtering is disabled
+    async fn fairness_filtering_disabled() {
+        let case = json!({
+            "tokens": [
+                ["Token A", address(0)],
+                ["Token B", address(1)],
+                ["Token C", address(2)],
+                ["Token D", address(3)]
+            ],
+            "auction": {
+                "orders": {
+                    "Order 1": {
+                        "side": "sell",
+                        "sell_token": "Token A",
+                        "sell_amount": amount(1_000),
+                        "buy_token": "Token B",
+                        "buy_amount": amount(1_000)
+                    },
+                    "Order 2": {
+                        "side": "sell",
+                        "sell_token": "Token C",
+                        "sell_amount": amount(1_000),
+                        "buy_token": "Token D",
+                        "buy_amount": amount(1_000)
+                    },
+                }
+            },
+            "solutions": {
+                // score = 200
+                "Unfair batch": {
+                    "solver": "Unfair batch solver",
+                    "trades": {
+                        "Order 1": {
+                            "sell_amount": amount(1_000),
+                            "buy_amount": amount(1_100)
+                        },
+                        "Order 2": {
+                            "sell_amount": amount(1_000),
+                            "buy_amount": amount(1_100)
+                        }
+                    }
+                },
+                // score = 150
+                "Filtering batch": {
+                    "solver": "Filtering batch solver",
+                    "trades": {
+                        "Order 1": {
+                            "sell_amount": amount(1_000),
+                            "buy_amount": amount(1_150)
+                        }
+                    }
+                }
+            },
+            "fairness": "disabled",
+            "expected_fair_solutions": ["Unfair batch", "Filtering batch"],
+            "expected_winners": ["Unfair batch"],
+            "expected_reference_scores": {
+                "Unfair batch solver": "150",
+            },
+        });
+        TestCase::from_json(case).validate().await;
+    }
+
+    #[tokio::test]
+    // Single token pair solutions worse than the baseline are filtered with the
+    // strict fairness rule
+    async fn strict_fairness_filtering() {
+        let case = json!({
+            "tokens": [
+                ["Token A", address(0)],
+                ["Token B", address(1)],
+            ],
+            "auction": {
+                "orders": {
+                    "Order 1": {
+                        "side": "sell",
+                        "sell_token": "Token A",
+                        "sell_amount": amount(1_000),
+                        "buy_token": "Token B",
+                        "buy_amount": amount(1_000)
+                    },
+                }
+            },
+            "solutions": {
+                // score = 150
+                "Best solution": {
+                    "solver": "Best solver",
+                    "trades": {
+                        "Order 1": {
+                            "sell_amount": amount(1_000),
+                            "buy_amount": amount(1_150)
+                        }
+                    }
+                },
+                // score = 100
+                "Worse solution": {
+                    "solver": "Worse solver",
+                    "trades": {
+                        "Order 1": {
+                            "sell_amount": amount(1_000),
+                            "buy_amount": amount(1_100)
+                        }
+                    }
+                }
+            },
+            "fairness": "strict-baseline",
+            "expected_fair_solutions": ["Best solution"],
+            "expected_winners": ["Best solution"],
+            "expected_reference_scores": {
+                "Best solver": "0",
+            },
+        });
+        TestCase::from_json(case).validate().await;
+    }
+
     #[tokio::test]
     // Multiple trades on the same (directed) token pair are aggregated for
     // filtering
@@ -1096,6 +1383,91 @@ mod tests {
         TestCase::from_json(case).validate().await;
     }
 
+    #[tokio::test]
+    // Only counting the surplus of the users picks a different winner than
+    // counting protocol fees as well
+    async fn surplus_score_definition() {
+        let case = |score_definition, winner, expected_scores, expected_reference_scores| {
+            json!({
+                "tokens": [
+                    ["Token A", address(0)],
+                    ["Token B", address(1)],
+                    ["Token C", address(2)],
+                    ["Token D", address(3)]
+                ],
+                "auction": {
+                    "orders": {
+                        "Order 1": {
+                            "side": "sell",
+                            "sell_token": "Token A",
+                            "sell_amount": amount(1_000),
+                            "buy_token": "Token B",
+                            "buy_amount": amount(1_000),
+                            "surplus_fee_factor": 0.5
+                        },
+                        "Order 2": {
+                            "side": "sell",
+                            "sell_token": "Token C",
+                            "sell_amount": amount(1_000),
+                            "buy_token": "Token D",
+                            "buy_amount": amount(1_000)
+                        }
+                    }
+                },
+                "solutions": {
+                    // surplus = 100, protocol fees = 100
+                    "Solution 1": {
+                        "solver": "Solver 1",
+                        "trades": {
+                            "Order 1": {
+                                "sell_amount": amount(1_000),
+                                "buy_amount": amount(1_100)
+                            },
+                            "Order 2": {
+                                "sell_amount": amount(1_000),
+                                "buy_amount": amount(1_000)
+                            }
+                        }
+                    },
+                    // surplus = 150
+                    "Solution 2": {
+                        "solver": "Solver 2",
+                        "trades": {
+                            "Order 2": {
+                                "sell_amount": amount(1_000),
+                                "buy_amount": amount(1_150)
+                            }
+                        }
+                    }
+                },
+                // Solution 1 executes Order 2 worse than Solution 2 so the
+                // fairness check would always filter it out
+                "fairness": "disabled",
+                "score_definition": score_definition,
+                "expected_fair_solutions": ["Solution 1", "Solution 2"],
+                "expected_winners": [winner],
+                "expected_scores": expected_scores,
+                "expected_reference_scores": expected_reference_scores,
+            })
+        };
+        TestCase::from_json(case(
+            "surplus-and-protocol-fees",
+            "Solution 1",
+            json!({ "Solution 1": "200", "Solution 2": "150" }),
+            json!({ "Solver 1": "150" }),
+        ))
+        .validate()
+        .await;
+        TestCase::from_json(case(
+            "surplus",
+            "Solution 2",
+            json!({ "Solution 1": "100", "Solution 2": "150" }),
+            json!({ "Solver 2": "100" }),
+        ))
+        .validate()
+        .await;
+    }
+
     #[serde_as]
     #[derive(Deserialize, Debug)]
     struct TestCase {
@@ -1106,6 +1478,14 @@ mod tests {
         pub expected_winners: Vec<String>,
         #[serde_as(as = "HashMap<_, HexOrDecimalU256>")]
         pub expected_reference_scores: HashMap<String, eth::U256>,
+        #[serde(default)]
+        pub fairness: Fairness,
+        #[serde(default)]
+        pub score_definition: ScoreDefinition,
+        /// Computed score of the solutions, checked if present.
+        #[serde(default)]
+        #[serde_as(as = "HashMap<_, HexOrDecimalU256>")]
+        pub expected_scores: HashMap<String, eth::U256>,
     }
 
     impl TestCase {
@@ -1114,7 +1494,11 @@ mod tests {
         }
 
         pub async fn validate(&self) {
-            let arbitrator = create_test_arbitrator();
+            let arbitrator = super::Arbitrator {
+                fairness: self.fairness,
+                score_definition: self.score_definition,
+                ..create_test_arbitrator()
+            };
 
             // map (token id -> token address) for later reference during the test
             let token_map: HashMap<String, H160> = self.tokens.iter().cloned().collect();
@@ -1133,12 +1517,14 @@ mod tests {
                             sell_amount,
                             buy_token,
                             buy_amount,
+                            surplus_fee_factor,
+                            available,
                         },
                     )| {
                         let order_uid = hash(order_id);
                         let sell_token = token_map.get(sell_token).unwrap();
                         let buy_token = token_map.get(buy_token).unwrap();
-                        let order = create_order(
+                        let mut order = create_order(
                             order_uid,
                             *sell_token,
                             *sell_amount,
@@ -1146,6 +1532,16 @@ mod tests {
                             *buy_amount,
                             *side,
                         );
+                        if let Some(factor) = surplus_fee_factor {
+                            order.protocol_fees = vec![fee::Policy::Surplus {
+                                factor: (*factor).try_into().unwrap(),
+                                max_volume_factor: 0.9.try_into().unwrap(),
+                            }];
+                        }
+                        if let Some(available) = available {
+                            order.partially_fillable = true;
+                            order.available = Some(order::TargetAmount(*available));
+                        }
                         (order_id.clone(), order)
                     },
                 )
@@ -1199,6 +1595,33 @@ mod tests {
             let participants = solution_map.values().cloned().collect();
             let ranking = arbitrator.arbitrate(participants, &auction);
             assert_eq!(ranking.ranked.len(), self.expected_fair_solutions.len());
+            // the order scores add up to the score of the solution
+            for participant in ranking.all() {
+                let order_scores = participant
+                    .solution()
+                    .order_scores()
+                    .values()
+                    .map(|score| self.score_definition.score(score))
+                    .fold(Score::default(), Score::saturating_add);
+                assert_eq!(Some(&order_scores), participant.solution().computed_score());
+            }
+            assert!(
+                ranking
+                    .filtered_out
+                    .iter()
+                    .all(|p| p.filter_reason() == Some(FilterReason::Unfair))
+            );
+            for (solution_id, expected_score) in &self.expected_scores {
+                let solution_uid = solution_map.get(&solution_id).unwrap().solution().id;
+                let participant = ranking
+                    .all()
+                    .find(|p| p.solution().id == solution_uid)
+                    .unwrap();
+                assert_eq!(
+                    participant.solution().computed_score(),
+                    Some(&Score(eth::Ether(*expected_score)))
+                );
+            }
             for solution_id in &self.expected_fair_solutions {
                 let solution_uid = solution_map.get(&solution_id).unwrap().solution().id;
                 assert!(
@@ -1253,6 +1676,13 @@ mod tests {
         pub buy_token: String,
         #[serde_as(as = "HexOrDecimalU256")]
         pub buy_amount: eth::U256,
+        /// Factor of a surplus protocol fee charged on the order.
+        #[serde(default)]
+        pub surplus_fee_factor: Option<f64>,
+        /// Amount of the order that can be executed in this auction.
+        #[serde(default)]
+        #[serde_as(as = "Option<HexOrDecimalU256>")]
+        pub available: Option<eth::U256>,
     }
 
     #[derive(Deserialize, Debug)]
@@ -1274,6 +1704,8 @@ mod tests {
         super::Arbitrator {
             max_winners: 10,
             weth: H160::from_slice(&hex!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2")).into(),
+            fairness: Default::default(),
+            score_definition: Default::default(),
         }
     }
 
@@ -1305,8 +1737,10 @@ mod tests {
             owner: Default::default(),
             partially_fillable: false,
             executed: eth::U256::zero().into(),
+            available: None,
             pre_interactions: vec![],
             post_interactions: vec![],
+            hooks_gas: None,
             sell_token_balance: order::SellTokenSource::Erc20,
             buy_token_balance: order::BuyTokenDestination::Erc20,
             app_data: AppDataHash(hex!(
@@ -3,6 +3,7 @@ pub mod competition;
 pub mod eth;
 pub mod fee;
 pub mod quote;
+pub mod rewards;
 pub mod settlement;
 
 pub use {
@@ -0,0 +1,346 @@
+//! Solver rewards and penalties.
+//!
+//! Every winning solver of an auction gets paid the difference between the
+//! total score of all winning solutions that got settled on-chain and its
+//! reference score, i.e. the total score the auction would have had without
+//! the solver. Solvers that fail to settle their solution therefore end up
+//! with a penalty. Rewards are capped from below by the lower cap and from
+//! above by the upper cap plus a share of the protocol fees the solver
+//! generated.
+
+use {
+    crate::{
+        domain::{self, eth},
+        util::conv::U256Ext,
+    },
+    num::{BigInt, Saturating},
+    number::conversions::u256_to_big_int,
+    std::{collections::BTreeMap, num::NonZeroU64},
+};
+
+pub type PeriodId = i64;
+
+/// Range of blocks `[start_block, end_block)` for which rewards get accounted
+/// together. Auctions belong to the period their start block falls into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Period {
+    pub id: PeriodId,
+    pub start_block: u64,
+    pub end_block: u64,
+}
+
+/// Splits the chain into consecutive accounting periods of a fixed number of
+/// blocks.
+#[derive(Debug, Clone, Copy)]
+pub struct Periods {
+    pub first_block: u64,
+    pub length: NonZeroU64,
+}
+
+impl Periods {
+    pub fn get(&self, id: PeriodId) -> Period {
+        let id_u64 = u64::try_from(id).expect("period ids are not negative");
+        let start_block = self.first_block + id_u64 * self.length.get();
+        Period {
+            id,
+            start_block,
+            end_block: start_block + self.length.get(),
+        }
+    }
+}
+
+/// Parameters of the reward computation.
+#[derive(Debug, Clone, Copy)]
+pub struct Formula {
+    /// Fixed part of the maximum reward per solver and auction.
+    pub upper_cap: eth::Ether,
+    /// Maximum penalty per solver and auction.
+    pub lower_cap: eth::Ether,
+    /// Share of the protocol fees of the solver that gets added to the upper
+    /// cap.
+    pub protocol_fee_cap_factor: f64,
+}
+
+/// A winning solution of an auction.
+#[derive(Debug, Clone)]
+pub struct WinningSolution {
+    pub auction_id: domain::auction::Id,
+    pub solver: eth::Address,
+    pub score: eth::Ether,
+    pub reference_score: eth::Ether,
+    /// Protocol fees observed on-chain for the solution in native token.
+    pub protocol_fees: eth::Ether,
+    /// Whether the solution got settled on-chain.
+    pub settled: bool,
+}
+
+/// Reward of a solver for a single auction. Negative rewards are penalties.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reward {
+    pub auction_id: domain::auction::Id,
+    pub solver: eth::Address,
+    /// Score of all winning solutions of the solver.
+    pub score: eth::Ether,
+    /// Score of the winning solutions of the solver that got settled.
+    pub observed_score: eth::Ether,
+    pub reference_score: eth::Ether,
+    /// Protocol fees of the settled solutions of the solver.
+    pub protocol_fees: eth::Ether,
+    pub reward: BigInt,
+}
+
+/// Sum of the rewards of a solver over an accounting period.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Payout {
+    pub solver: eth::Address,
+    pub wins: u64,
+    pub settlements: u64,
+    pub protocol_fees: eth::Ether,
+    pub reward: BigInt,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Accounting {
+    pub rewards: Vec<Reward>,
+    pub payouts: Vec<Payout>,
+}
+
+impl Formula {
+    /// Computes the rewards of all winning solvers of the passed in solutions
+    /// and sums them up per solver.
+    pub fn account(&self, solutions: &[WinningSolution]) -> Accounting {
+        let mut auctions: BTreeMap<_, Vec<&WinningSolution>> = BTreeMap::new();
+        for solution in solutions {
+            auctions
+                .entry(solution.auction_id)
+                .or_default()
+                .push(solution);
+        }
+
+        let mut rewards = Vec::new();
+        let mut payouts: BTreeMap<eth::Address, Payout> = BTreeMap::new();
+        for (auction_id, solutions) in auctions {
+            let observed_total = solutions
+                .iter()
+                .filter(|solution| solution.settled)
+                .fold(eth::Ether::default(), |acc, solution| {
+                    acc.saturating_add(solution.score)
+                });
+
+            let mut solvers: BTreeMap<eth::Address, Vec<&WinningSolution>> = BTreeMap::new();
+            for solution in solutions {
+                solvers.entry(solution.solver).or_default().push(solution);
+            }
+            for (solver, solutions) in solvers {
+                let mut score = eth::Ether::default();
+                let mut observed_score = eth::Ether::default();
+                let mut protocol_fees = eth::Ether::default();
+                let mut settlements = 0;
+                for solution in &solutions {
+                    score = score.saturating_add(solution.score);
+                    if solution.settled {
+                        observed_score = observed_score.saturating_add(solution.score);
+                        protocol_fees = protocol_fees.saturating_add(solution.protocol_fees);
+                        settlements += 1;
+                    }
+                }
+                // all winning solutions of a solver share the same reference score
+                let reference_score = solutions[0].reference_score;
+                let reward = self.reward(observed_total, reference_score, protocol_fees);
+
+                let payout = payouts.entry(solver).or_insert_with(|| Payout {
+                    solver,
+                    wins: 0,
+                    settlements: 0,
+                    protocol_fees: eth::Ether::default(),
+                    reward: BigInt::default(),
+                });
+                payout.wins += solutions.len() as u64;
+                payout.settlements += settlements;
+                payout.protocol_fees = payout.protocol_fees.saturating_add(protocol_fees);
+                payout.reward += &reward;
+
+                rewards.push(Reward {
+                    auction_id,
+                    solver,
+                    score,
+                    observed_score,
+                    reference_score,
+                    protocol_fees,
+                    reward,
+                });
+            }
+        }
+
+        Accounting {
+            rewards,
+            payouts: payouts.into_values().collect(),
+        }
+    }
+
+    fn reward(
+        &self,
+        observed_total: eth::Ether,
+        reference_score: eth::Ether,
+        protocol_fees: eth::Ether,
+    ) -> BigInt {
+        let upper_cap = protocol_fees
+            .0
+            .checked_mul_f64(self.protocol_fee_cap_factor)
+            .unwrap_or(eth::U256::MAX)
+            .saturating_add(self.upper_cap.0);
+        let uncapped = u256_to_big_int(&observed_total.0) - u256_to_big_int(&reference_score.0);
+        uncapped.clamp(
+            -u256_to_big_int(&self.lower_cap.0),
+            u256_to_big_int(&upper_cap),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, primitive_types::H160};
+
+    const FORMULA: Formula = Formula {
+        upper_cap: eth::Ether(eth::U256([12, 0, 0, 0])),
+        lower_cap: eth::Ether(eth::U256([10, 0, 0, 0])),
+        protocol_fee_cap_factor: 0.5,
+    };
+
+    fn solver(id: u64) -> eth::Address {
+        eth::Address(H160::from_low_u64_be(id))
+    }
+
+    fn solution(
+        auction_id: domain::auction::Id,
+        solver: eth::Address,
+        score: u64,
+        reference_score: u64,
+        protocol_fees: u64,
+        settled: bool,
+    ) -> WinningSolution {
+        WinningSolution {
+            auction_id,
+            solver,
+            score: eth::Ether(score.into()),
+            reference_score: eth::Ether(reference_score.into()),
+            protocol_fees: eth::Ether(protocol_fees.into()),
+            settled,
+        }
+    }
+
+    #[test]
+    fn single_winner() {
+        let accounting = FORMULA.account(&[
+            // reward 5
+            solution(1, solver(1), 15, 10, 0, true),
+            // capped at upper cap of 12
+            solution(2, solver(1), 30, 10, 0, true),
+            // failed settlement is penalized with the reference score
+            solution(3, solver(1), 15, 8, 0, false),
+            // penalty capped at lower cap of 10
+            solution(4, solver(1), 15, 14, 0, false),
+        ]);
+
+        let rewards: Vec<_> = accounting
+            .rewards
+            .iter()
+            .map(|reward| reward.reward.clone())
+            .collect();
+        assert_eq!(
+            rewards,
+            vec![5.into(), 12.into(), BigInt::from(-8), BigInt::from(-10)]
+        );
+        assert_eq!(
+            accounting.payouts,
+            vec![Payout {
+                solver: solver(1),
+                wins: 4,
+                settlements: 2,
+                protocol_fees: eth::Ether(0.into()),
+                reward: BigInt::from(-1),
+            }]
+        );
+    }
+
+    #[test]
+    fn protocol_fees_raise_upper_cap() {
+        let accounting = FORMULA.account(&[
+            // upper cap 12 + 0.5 * 10 = 17
+            solution(1, solver(1), 30, 10, 10, true),
+            // fees of failed settlements don't count
+            solution(2, solver(1), 30, 10, 10, false),
+        ]);
+
+        assert_eq!(accounting.rewards[0].reward, 17.into());
+        assert_eq!(accounting.rewards[0].protocol_fees, eth::Ether(10.into()));
+        assert_eq!(accounting.rewards[1].reward, BigInt::from(-10));
+        assert_eq!(accounting.rewards[1].protocol_fees, eth::Ether(0.into()));
+        assert_eq!(accounting.payouts[0].protocol_fees, eth::Ether(10.into()));
+    }
+
+    #[test]
+    fn multiple_winners() {
+        let accounting = FORMULA.account(&[
+            solution(1, solver(1), 10, 12, 0, true),
+            solution(1, solver(2), 6, 13, 0, true),
+            solution(2, solver(1), 10, 12, 0, true),
+            solution(2, solver(2), 6, 13, 0, false),
+        ]);
+
+        // auction 1: total score 16
+        // auction 2: total score 10 because solver 2 failed to settle
+        let rewards: Vec<_> = accounting
+            .rewards
+            .iter()
+            .map(|reward| (reward.auction_id, reward.solver, reward.reward.clone()))
+            .collect();
+        assert_eq!(
+            rewards,
+            vec![
+                (1, solver(1), 4.into()),
+                (1, solver(2), 3.into()),
+                (2, solver(1), BigInt::from(-2)),
+                (2, solver(2), BigInt::from(-3)),
+            ]
+        );
+        assert_eq!(accounting.rewards[3].observed_score, eth::Ether(0.into()));
+        assert_eq!(
+            accounting
+                .payouts
+                .iter()
+                .map(|payout| (
+                    payout.solver,
+                    payout.wins,
+                    payout.settlements,
+                    payout.reward.clone()
+                ))
+                .collect::<Vec<_>>(),
+            vec![(solver(1), 2, 2, 2.into()), (solver(2), 2, 1, 0.into()),]
+        );
+    }
+
+    #[test]
+    fn periods() {
+        let periods = Periods {
+            first_block: 100,
+            length: NonZeroU64::new(10).unwrap(),
+        };
+        assert_eq!(
+            periods.get(0),
+            Period {
+                id: 0,
+                start_block: 100,
+                end_block: 110
+            }
+        );
+        assert_eq!(
+            periods.get(3),
+            Period {
+                id: 3,
+                start_block: 130,
+                end_block: 140
+            }
+        );
+    }
+}
@@ -159,7 +159,10 @@ impl Settlement {
     ) -> Result<Self, Error> {
         let (auction, solver_winning_solutions) = tokio::try_join!(
             persistence
-                .get_auction(settled.auction_id, &settled.trades)
+                .get_auction(
+                    settled.auction_id,
+                    settled.trades.iter().map(|trade| trade.uid)
+                )
                 .map_err(Error::from),
             persistence
                 .get_solver_winning_solutions(settled.auction_id, settled.solver)
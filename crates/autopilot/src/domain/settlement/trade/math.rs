@@ -21,6 +21,23 @@ use {
     std::collections::HashMap,
 };
 
+/// Score of a trade split into its components.
+///
+/// Denominated in NATIVE token
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScoreBreakdown {
+    /// Surplus the user received over their limit price.
+    pub surplus: eth::Ether,
+    /// Protocol fees charged on the trade.
+    pub protocol_fees: eth::Ether,
+}
+
+impl ScoreBreakdown {
+    pub fn total(&self) -> eth::Ether {
+        eth::Ether(self.surplus.0.saturating_add(self.protocol_fees.0))
+    }
+}
+
 /// A trade containing bare minimum of onchain information required to calculate
 /// the surplus, fees and score.
 #[derive(Debug, Clone)]
@@ -47,25 +64,52 @@ impl Trade {
         &self,
         fee_policies: &HashMap<OrderUid, impl AsRef<[fee::Policy]>>,
         native_prices: &domain::auction::Prices,
+    ) -> Result<eth::Ether, Error> {
+        Ok(self.score_breakdown(fee_policies, native_prices)?.total())
+    }
+
+    /// [`Self::score`] split into the surplus of the user and the protocol
+    /// fees.
+    ///
+    /// Denominated in NATIVE token
+    pub fn score_breakdown(
+        &self,
+        fee_policies: &HashMap<OrderUid, impl AsRef<[fee::Policy]>>,
+        native_prices: &domain::auction::Prices,
+    ) -> Result<ScoreBreakdown, Error> {
+        let user_surplus = self.surplus_over_limit_price()?.0;
+        let fees: eth::U256 = self.protocol_fees(fee_policies)?.into_iter().try_fold(
+            eth::U256::zero(),
+            |acc, i| {
+                acc.checked_add(i.fee.amount.0)
+                    .ok_or(Error::Math(Math::Overflow))
+            },
+        )?;
+        let surplus_in_surplus_token = user_surplus
+            .checked_add(fees)
+            .ok_or(Error::Math(Math::Overflow))?;
+
+        let score = self.surplus_in_native_token(surplus_in_surplus_token, native_prices)?;
+        let surplus = self.surplus_in_native_token(user_surplus, native_prices)?;
+        // The protocol fees are derived from the total score so both parts
+        // always add up to exactly the score despite rounding.
+        Ok(ScoreBreakdown {
+            surplus,
+            protocol_fees: eth::Ether(score.0.saturating_sub(surplus.0)),
+        })
+    }
+
+    /// Converts an amount of the surplus token into the native token like the
+    /// score computation does.
+    fn surplus_in_native_token(
+        &self,
+        surplus_in_surplus_token: eth::U256,
+        native_prices: &domain::auction::Prices,
     ) -> Result<eth::Ether, Error> {
         let native_price_buy = native_prices
             .get(&self.buy.token)
             .ok_or(Error::MissingPrice(self.buy.token))?;
 
-        let surplus_in_surplus_token = {
-            let user_surplus = self.surplus_over_limit_price()?.0;
-            let fees: eth::U256 = self.protocol_fees(fee_policies)?.into_iter().try_fold(
-                eth::U256::zero(),
-                |acc, i| {
-                    acc.checked_add(i.fee.amount.0)
-                        .ok_or(Error::Math(Math::Overflow))
-                },
-            )?;
-            user_surplus
-                .checked_add(fees)
-                .ok_or(Error::Math(Math::Overflow))?
-        };
-
         let score = match self.side {
             // `surplus` of sell orders is already in buy tokens so we simply convert it to ETH
             Side::Sell => native_price_buy.in_eth(eth::TokenAmount(surplus_in_surplus_token)),
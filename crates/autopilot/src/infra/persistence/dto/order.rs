@@ -32,8 +32,15 @@ pub struct Order {
     pub partially_fillable: bool,
     #[serde_as(as = "HexOrDecimalU256")]
     pub executed: U256,
+    /// Upper bound of the amount that may be executed in this auction if it
+    /// is less than the remaining amount of the order.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<HexOrDecimalU256>")]
+    pub available: Option<U256>,
     pub pre_interactions: Vec<boundary::InteractionData>,
     pub post_interactions: Vec<boundary::InteractionData>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hooks_gas: Option<boundary::HooksGas>,
     pub sell_token_balance: boundary::SellTokenSource,
     pub buy_token_balance: boundary::BuyTokenDestination,
     #[serde(flatten)]
@@ -63,12 +70,17 @@ pub fn from_domain(order: domain::Order) -> Order {
         owner: order.owner.into(),
         partially_fillable: order.partially_fillable,
         executed: order.executed.into(),
+        available: order.available.map(Into::into),
         pre_interactions: order.pre_interactions.into_iter().map(Into::into).collect(),
         post_interactions: order
             .post_interactions
             .into_iter()
             .map(Into::into)
             .collect(),
+        hooks_gas: order.hooks_gas.map(|gas| boundary::HooksGas {
+            pre: gas.pre,
+            post: gas.post,
+        }),
         sell_token_balance: order.sell_token_balance.into(),
         buy_token_balance: order.buy_token_balance.into(),
         class: boundary::OrderClass::Limit,
@@ -101,12 +113,17 @@ pub fn to_domain(order: Order) -> domain::Order {
         owner: order.owner.into(),
         partially_fillable: order.partially_fillable,
         executed: order.executed.into(),
+        available: order.available.map(Into::into),
         pre_interactions: order.pre_interactions.into_iter().map(Into::into).collect(),
         post_interactions: order
             .post_interactions
             .into_iter()
             .map(Into::into)
             .collect(),
+        hooks_gas: order.hooks_gas.map(|gas| domain::auction::order::HooksGas {
+            pre: gas.pre,
+            post: gas.post,
+        }),
         sell_token_balance: order.sell_token_balance.into(),
         buy_token_balance: order.buy_token_balance.into(),
         app_data: order.app_data.into(),
@@ -374,3 +391,12 @@ impl From<domain::auction::order::Side> for database::orders::OrderKind {
         }
     }
 }
+
+impl From<database::orders::OrderKind> for domain::auction::order::Side {
+    fn from(kind: database::orders::OrderKind) -> Self {
+        match kind {
+            database::orders::OrderKind::Buy => Self::Buy,
+            database::orders::OrderKind::Sell => Self::Sell,
+        }
+    }
+}
@@ -2,7 +2,7 @@ use {
     crate::{
         boundary,
         database::{Postgres, order_events::store_order_events},
-        domain::{self, eth, settlement::transaction::EncodedTrade},
+        domain::{self, eth},
         infra::persistence::dto::AuctionId,
     },
     anyhow::Context,
@@ -19,7 +19,7 @@ use {
             SellTokenSource as DbSellTokenSource,
             SigningScheme as DbSigningScheme,
         },
-        solver_competition_v2::{Order, Solution},
+        solver_competition_v2::{FairnessBaseline, FilterReason, Order, Solution},
     },
     domain::auction::order::{
         BuyTokenDestination as DomainBuyTokenDestination,
@@ -136,11 +136,11 @@ impl Persistence {
     }
 
     /// Save all valid solutions that participated in the competition for an
-    /// auction.
+    /// auction together with the fairness baselines used to rank them.
     pub async fn save_solutions(
         &self,
         auction_id: domain::auction::Id,
-        solutions: impl Iterator<Item = &domain::competition::Participant>,
+        ranking: &domain::competition::winner_selection::Ranking,
     ) -> Result<(), DatabaseError> {
         let _timer = Metrics::get()
             .database_queries
@@ -152,8 +152,8 @@ impl Persistence {
         database::solver_competition_v2::save(
             &mut ex,
             auction_id,
-            &solutions
-                .enumerate()
+            &ranking
+                .enumerated()
                 .map(|(uid, participant)| {
                     let solution = Solution {
                         uid: uid.try_into().context("uid overflow")?,
@@ -161,20 +161,30 @@ impl Persistence {
                         solver: ByteArray(participant.solution().solver().0.0),
                         is_winner: participant.is_winner(),
                         filtered_out: participant.filtered_out(),
+                        filter_reason: participant.filter_reason().map(|reason| match reason {
+                            domain::competition::FilterReason::Unfair => FilterReason::Unfair,
+                        }),
                         score: u256_to_big_decimal(&participant.solution().score().get().0),
                         orders: participant
                             .solution()
                             .orders()
                             .iter()
-                            .map(|(order_uid, order)| Order {
-                                uid: ByteArray(order_uid.0),
-                                sell_token: ByteArray(order.sell.token.0.0),
-                                buy_token: ByteArray(order.buy.token.0.0),
-                                limit_sell: u256_to_big_decimal(&order.sell.amount.0),
-                                limit_buy: u256_to_big_decimal(&order.buy.amount.0),
-                                executed_sell: u256_to_big_decimal(&order.executed_sell.0),
-                                executed_buy: u256_to_big_decimal(&order.executed_buy.0),
-                                side: order.side.into(),
+                            .map(|(order_uid, order)| {
+                                let score = participant.solution().order_scores().get(order_uid);
+                                Order {
+                                    uid: ByteArray(order_uid.0),
+                                    sell_token: ByteArray(order.sell.token.0.0),
+                                    buy_token: ByteArray(order.buy.token.0.0),
+                                    limit_sell: u256_to_big_decimal(&order.sell.amount.0),
+                                    limit_buy: u256_to_big_decimal(&order.buy.amount.0),
+                                    executed_sell: u256_to_big_decimal(&order.executed_sell.0),
+                                    executed_buy: u256_to_big_decimal(&order.executed_buy.0),
+                                    side: order.side.into(),
+                                    surplus_score: score
+                                        .map(|score| u256_to_big_decimal(&score.surplus.0)),
+                                    protocol_fee_score: score
+                                        .map(|score| u256_to_big_decimal(&score.protocol_fees.0)),
+                                }
                             })
                             .collect(),
                         price_tokens: participant
@@ -193,6 +203,14 @@ impl Persistence {
                     Ok::<_, DatabaseError>(solution)
                 })
                 .collect::<Result<Vec<_>, DatabaseError>>()?,
+            &ranking
+                .fairness_baselines()
+                .map(|(pair, score)| FairnessBaseline {
+                    sell_token: ByteArray(pair.sell.0.0),
+                    buy_token: ByteArray(pair.buy.0.0),
+                    score: u256_to_big_decimal(&score.get().0),
+                })
+                .collect::<Vec<_>>(),
         )
         .await?;
 
@@ -238,6 +256,42 @@ impl Persistence {
         );
     }
 
+    /// Returns which of the given orders have already been triggered.
+    pub async fn triggered_orders(
+        &self,
+        orders: &[domain::OrderUid],
+    ) -> Result<HashSet<domain::OrderUid>, DatabaseError> {
+        let _timer = Metrics::get()
+            .database_queries
+            .with_label_values(&["triggered_orders"])
+            .start_timer();
+
+        let mut ex = self.postgres.pool.acquire().await.context("acquire")?;
+        let orders: Vec<_> = orders.iter().map(|uid| ByteArray(uid.0)).collect();
+        Ok(database::triggered_orders::triggered(&mut ex, &orders)
+            .await?
+            .into_iter()
+            .map(|uid| domain::OrderUid(uid.0))
+            .collect())
+    }
+
+    /// Persists that the given orders got triggered so they stay solvable even
+    /// if the price moves back.
+    pub async fn store_triggered_orders(
+        &self,
+        orders: &[domain::OrderUid],
+    ) -> Result<(), DatabaseError> {
+        let _timer = Metrics::get()
+            .database_queries
+            .with_label_values(&["store_triggered_orders"])
+            .start_timer();
+
+        let mut ex = self.postgres.pool.acquire().await.context("acquire")?;
+        let orders: Vec<_> = orders.iter().map(|uid| ByteArray(uid.0)).collect();
+        database::triggered_orders::insert(&mut ex, &orders, Utc::now()).await?;
+        Ok(())
+    }
+
     /// Saves the given fee policies to the DB as a single batch.
     pub async fn store_fee_policies(
         &self,
@@ -327,11 +381,11 @@ impl Persistence {
         Ok(ex.commit().await?)
     }
 
-    /// Get auction data to post-process the given trades.
+    /// Get auction data to post-process trades of the given orders.
     pub async fn get_auction(
         &self,
         auction_id: domain::auction::Id,
-        trades: &[EncodedTrade],
+        traded_orders: impl IntoIterator<Item = domain::OrderUid>,
     ) -> Result<domain::settlement::Auction, error::Auction> {
         let _timer = Metrics::get()
             .database_queries
@@ -381,10 +435,9 @@ impl Persistence {
             // a mapping it is assumed that this was a regular order and not a JIT order.
             // So in order to not misclassify JIT orders as regular orders we only fetch
             // fee policies for orders that were part of the original auction.
-            let relevant_orders: HashSet<_> = trades
-                .iter()
-                .filter(|t| auction_orders.contains(&t.uid))
-                .map(|t| t.uid)
+            let relevant_orders: HashSet<_> = traded_orders
+                .into_iter()
+                .filter(|uid| auction_orders.contains(uid))
                 .collect();
 
             // get fee policies for all orders that were part of the competition auction
@@ -952,6 +1005,221 @@ impl Persistence {
             .context("solver_competition::fetch_solver_winning_solutions")?,
         )
     }
+
+    /// Fetches all solutions proposed for the given auction.
+    pub async fn get_solutions(
+        &self,
+        auction_id: domain::auction::Id,
+    ) -> Result<Vec<domain::competition::Solution>, DatabaseError> {
+        let mut ex = self.postgres.pool.acquire().await.context("acquire")?;
+        let _timer = Metrics::get()
+            .database_queries
+            .with_label_values(&["fetch_solutions"])
+            .start_timer();
+
+        let solutions = database::solver_competition_v2::fetch(&mut ex, auction_id)
+            .await
+            .context("solver_competition_v2::fetch")?;
+        Ok(solutions
+            .into_iter()
+            .map(solution_into_domain)
+            .collect::<anyhow::Result<_>>()?)
+    }
+
+    /// Returns the id of the latest accounting period for which solver
+    /// rewards were stored.
+    pub async fn last_accounting_period(
+        &self,
+    ) -> Result<Option<domain::rewards::PeriodId>, DatabaseError> {
+        let mut ex = self.postgres.pool.acquire().await.context("acquire")?;
+        let _timer = Metrics::get()
+            .database_queries
+            .with_label_values(&["last_accounting_period"])
+            .start_timer();
+
+        let period = database::solver_rewards::fetch_latest_period(&mut ex)
+            .await
+            .context("solver_rewards::fetch_latest_period")?;
+        Ok(period.map(|period| period.id))
+    }
+
+    /// Returns the latest settlement deadline of all auctions of the period.
+    pub async fn latest_auction_deadline(
+        &self,
+        period: &domain::rewards::Period,
+    ) -> Result<Option<u64>, DatabaseError> {
+        let mut ex = self.postgres.pool.acquire().await.context("acquire")?;
+        let _timer = Metrics::get()
+            .database_queries
+            .with_label_values(&["latest_auction_deadline"])
+            .start_timer();
+
+        let deadline = database::solver_rewards::latest_deadline(
+            &mut ex,
+            i64::try_from(period.start_block).context("block overflow")?,
+            i64::try_from(period.end_block).context("block overflow")?,
+        )
+        .await
+        .context("solver_rewards::latest_deadline")?;
+        Ok(deadline
+            .map(|deadline| u64::try_from(deadline).context("negative deadline"))
+            .transpose()?)
+    }
+
+    /// Fetches all winning solutions of the auctions of the period.
+    pub async fn fetch_winning_solutions(
+        &self,
+        period: &domain::rewards::Period,
+    ) -> Result<Vec<domain::rewards::WinningSolution>, DatabaseError> {
+        let mut ex = self.postgres.pool.acquire().await.context("acquire")?;
+        let _timer = Metrics::get()
+            .database_queries
+            .with_label_values(&["fetch_winning_solutions"])
+            .start_timer();
+
+        let solutions = database::solver_rewards::fetch_winning_solutions(
+            &mut ex,
+            i64::try_from(period.start_block).context("block overflow")?,
+            i64::try_from(period.end_block).context("block overflow")?,
+        )
+        .await
+        .context("solver_rewards::fetch_winning_solutions")?;
+
+        let ether = |value: &bigdecimal::BigDecimal| {
+            big_decimal_to_u256(value)
+                .map(eth::Ether)
+                .with_context(|| format!("invalid U256 {value}"))
+        };
+        Ok(solutions
+            .into_iter()
+            .map(|solution| {
+                Ok(domain::rewards::WinningSolution {
+                    auction_id: solution.auction_id,
+                    solver: eth::Address(eth::H160(solution.solver.0)),
+                    score: ether(&solution.score)?,
+                    reference_score: ether(&solution.reference_score)?,
+                    protocol_fees: ether(&solution.protocol_fees)?,
+                    settled: solution.settled,
+                })
+            })
+            .collect::<anyhow::Result<_>>()?)
+    }
+
+    /// Stores the rewards of the period. Returns `false` without storing
+    /// anything if the period was already accounted, e.g. by another
+    /// autopilot instance.
+    pub async fn save_solver_rewards(
+        &self,
+        period: &domain::rewards::Period,
+        formula: &domain::rewards::Formula,
+        accounting: &domain::rewards::Accounting,
+    ) -> Result<bool, DatabaseError> {
+        let _timer = Metrics::get()
+            .database_queries
+            .with_label_values(&["save_solver_rewards"])
+            .start_timer();
+
+        let mut ex = self.postgres.pool.begin().await.context("begin")?;
+        let stored = database::solver_rewards::insert_period(
+            &mut ex,
+            &database::solver_rewards::AccountingPeriod {
+                id: period.id,
+                start_block: i64::try_from(period.start_block).context("block overflow")?,
+                end_block: i64::try_from(period.end_block).context("block overflow")?,
+                upper_cap: u256_to_big_decimal(&formula.upper_cap.0),
+                lower_cap: u256_to_big_decimal(&formula.lower_cap.0),
+                protocol_fee_cap_factor: formula.protocol_fee_cap_factor,
+            },
+        )
+        .await
+        .context("solver_rewards::insert_period")?;
+        if !stored {
+            return Ok(false);
+        }
+
+        let rewards: Vec<_> = accounting
+            .rewards
+            .iter()
+            .map(|reward| database::solver_rewards::SolverReward {
+                auction_id: reward.auction_id,
+                solver: ByteArray(reward.solver.0.0),
+                period_id: period.id,
+                score: u256_to_big_decimal(&reward.score.0),
+                observed_score: u256_to_big_decimal(&reward.observed_score.0),
+                reference_score: u256_to_big_decimal(&reward.reference_score.0),
+                protocol_fees: u256_to_big_decimal(&reward.protocol_fees.0),
+                reward: reward.reward.clone().into(),
+            })
+            .collect();
+        database::solver_rewards::insert_rewards(&mut ex, &rewards)
+            .await
+            .context("solver_rewards::insert_rewards")?;
+
+        let payouts: Vec<_> = accounting
+            .payouts
+            .iter()
+            .map(|payout| {
+                Ok(database::solver_rewards::SolverPayout {
+                    period_id: period.id,
+                    solver: ByteArray(payout.solver.0.0),
+                    wins: i64::try_from(payout.wins).context("wins overflow")?,
+                    settlements: i64::try_from(payout.settlements)
+                        .context("settlements overflow")?,
+                    protocol_fees: u256_to_big_decimal(&payout.protocol_fees.0),
+                    reward: payout.reward.clone().into(),
+                })
+            })
+            .collect::<anyhow::Result<_>>()?;
+        database::solver_rewards::insert_payouts(&mut ex, &payouts)
+            .await
+            .context("solver_rewards::insert_payouts")?;
+
+        ex.commit().await.context("commit")?;
+        Ok(true)
+    }
+}
+
+fn solution_into_domain(solution: Solution) -> anyhow::Result<domain::competition::Solution> {
+    let u256 = |value: &bigdecimal::BigDecimal| {
+        big_decimal_to_u256(value).with_context(|| format!("invalid U256 {value}"))
+    };
+    let orders = solution
+        .orders
+        .iter()
+        .map(|order| {
+            let traded = domain::competition::TradedOrder {
+                side: order.side.into(),
+                sell: eth::Asset {
+                    token: eth::H160(order.sell_token.0).into(),
+                    amount: u256(&order.limit_sell)?.into(),
+                },
+                buy: eth::Asset {
+                    token: eth::H160(order.buy_token.0).into(),
+                    amount: u256(&order.limit_buy)?.into(),
+                },
+                executed_sell: u256(&order.executed_sell)?.into(),
+                executed_buy: u256(&order.executed_buy)?.into(),
+            };
+            Ok((domain::OrderUid(order.uid.0), traded))
+        })
+        .collect::<anyhow::Result<_>>()?;
+    let prices = solution
+        .price_tokens
+        .iter()
+        .zip(&solution.price_values)
+        .map(|(token, price)| {
+            let price = domain::auction::Price::try_new(u256(price)?.into())?;
+            Ok((eth::H160(token.0).into(), price))
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    Ok(domain::competition::Solution::new(
+        solution.id.to_u64().context("solution id overflow")?,
+        eth::H160(solution.solver.0).into(),
+        domain::competition::Score::try_new(u256(&solution.score)?.into())?,
+        orders,
+        prices,
+    ))
 }
 
 #[derive(prometheus_metric_storage::MetricStorage)]
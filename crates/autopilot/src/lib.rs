@@ -11,7 +11,9 @@ pub mod run;
 pub mod run_loop;
 pub mod shadow;
 pub mod shutdown_controller;
+pub mod simulator;
 pub mod solvable_orders;
+pub mod solver_rewards;
 pub mod util;
 
 pub use self::run::{run, start};
@@ -9,6 +9,7 @@ use {
                 OnchainOrderParser,
                 ethflow_events::{
                     EthFlowOnchainOrderParser,
+                    backfill_placement_tx_hashes,
                     determine_ethflow_indexing_start,
                     determine_ethflow_refund_indexing_start,
                 },
@@ -525,11 +526,17 @@ pub async fn run(args: Arguments, shutdown_controller: ShutdownController) {
     let solvable_orders_cache = SolvableOrdersCache::new(
         args.min_order_validity_period,
         persistence.clone(),
-        infra::banned::Users::new(
-            eth.contracts().chainalysis_oracle().clone(),
-            args.banned_users,
-            args.banned_users_max_cache_size.get().to_u64().unwrap(),
-        ),
+        args.banned_users_sources
+            .apply(
+                infra::banned::Users::new(
+                    eth.contracts().chainalysis_oracle().clone(),
+                    args.banned_users,
+                    args.banned_users_max_cache_size.get().to_u64().unwrap(),
+                ),
+                http_factory.create(),
+            )
+            .await
+            .expect("failed to load banned users"),
         balance_fetcher.clone(),
         bad_token_detector.clone(),
         native_price_estimator.clone(),
@@ -571,6 +578,33 @@ pub async fn run(args: Arguments, shutdown_controller: ShutdownController) {
             .instrument(tracing::info_span!("order_events_cleaner")),
     );
 
+    if args.solver_rewards.enabled {
+        let config = &args.solver_rewards;
+        let solver_rewards = crate::solver_rewards::SolverRewards::new(
+            crate::solver_rewards::SolverRewardsConfig {
+                periods: domain::rewards::Periods {
+                    first_block: config
+                        .solver_rewards_first_block
+                        .expect("required by the argument parser"),
+                    length: config.solver_rewards_period_length,
+                },
+                formula: domain::rewards::Formula {
+                    upper_cap: config.solver_rewards_upper_cap.into(),
+                    lower_cap: config.solver_rewards_lower_cap.into(),
+                    protocol_fee_cap_factor: config.solver_rewards_protocol_fee_cap_factor,
+                },
+                update_interval: config.solver_rewards_update_interval,
+            },
+            persistence.clone(),
+            db_write.clone(),
+        );
+        tokio::task::spawn(
+            solver_rewards
+                .run_forever()
+                .instrument(tracing::info_span!("solver_rewards")),
+        );
+    }
+
     let market_makable_token_list_configuration = TokenListConfiguration {
         url: args.trusted_tokens_url,
         update_interval: args.trusted_tokens_update_interval,
@@ -649,6 +683,10 @@ pub async fn run(args: Arguments, shutdown_controller: ShutdownController) {
         .expect("Should be able to initialize event updater. Database read issues?");
 
         maintenance.spawn_ethflow_indexer(onchain_order_indexer);
+        tokio::task::spawn(
+            backfill_placement_tx_hashes(db_write.pool.clone(), web3.alloy.clone())
+                .instrument(info_span!("ethflow_placement_tx_hash_backfill")),
+        );
         // refunds are not critical for correctness and can therefore be indexed
         // sporadically in a background task
         let service_maintainer = ServiceMaintenance::new(vec![Arc::new(refund_event_handler)]);
@@ -120,7 +120,12 @@ impl RunLoop {
             probes,
             maintenance,
             competition_updates_sender,
-            winner_selection: winner_selection::Arbitrator { max_winners, weth },
+            winner_selection: winner_selection::Arbitrator {
+                max_winners,
+                weth,
+                fairness: Default::default(),
+                score_definition: Default::default(),
+            },
         }
     }
 
@@ -532,7 +537,7 @@ impl RunLoop {
                 .save_auction(auction, block_deadline)
                 .map_err(|e| e.0.context("failed to save auction")),
             self.persistence
-                .save_solutions(auction.id, ranking.all())
+                .save_solutions(auction.id, ranking)
                 .map_err(|e| e.0.context("failed to save solutions")),
         ) {
             Ok(_) => {
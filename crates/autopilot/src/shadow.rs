@@ -59,6 +59,8 @@ impl RunLoop {
             winner_selection: winner_selection::Arbitrator {
                 max_winners: max_winners_per_auction.get(),
                 weth,
+                fairness: Default::default(),
+                score_definition: Default::default(),
             },
             orderbook,
             drivers,
@@ -0,0 +1,313 @@
+//! Loading of stored competitions, either from the database or from JSON
+//! files previously exported by the simulator.
+
+use {
+    crate::{
+        boundary,
+        domain::{self, eth},
+        infra::{self, persistence::dto},
+    },
+    anyhow::{Context, Result},
+    number::serialization::HexOrDecimalU256,
+    primitive_types::{H160, U256},
+    serde::{Deserialize, Serialize},
+    serde_with::serde_as,
+    std::{
+        collections::{BTreeMap, HashMap, HashSet},
+        path::Path,
+    },
+};
+
+/// Everything the winner selection needs to know about a single auction.
+#[derive(Debug)]
+pub struct Competition {
+    pub auction_id: domain::auction::Id,
+    pub native_prices: domain::auction::Prices,
+    pub surplus_capturing_jit_order_owners: HashSet<eth::Address>,
+    /// Fee policies of the traded orders that were part of the auction.
+    /// Traded orders without an entry are treated as JIT orders.
+    pub fee_policies: HashMap<domain::OrderUid, Vec<domain::fee::Policy>>,
+    pub solutions: Vec<domain::competition::Solution>,
+}
+
+impl Competition {
+    /// Loads the auction and all solutions proposed for it from the
+    /// database.
+    pub async fn fetch(
+        persistence: &infra::Persistence,
+        auction_id: domain::auction::Id,
+    ) -> Result<Self> {
+        let solutions = persistence
+            .get_solutions(auction_id)
+            .await
+            .context("failed to fetch solutions")?;
+        let traded_orders: HashSet<_> = solutions
+            .iter()
+            .flat_map(|solution| solution.order_ids().copied())
+            .collect();
+        let auction = persistence
+            .get_auction(auction_id, traded_orders)
+            .await
+            .context("failed to fetch auction")?;
+
+        Ok(Self {
+            auction_id,
+            native_prices: auction.prices,
+            surplus_capturing_jit_order_owners: auction.surplus_capturing_jit_order_owners,
+            fee_policies: auction.orders,
+            solutions,
+        })
+    }
+
+    /// Reads a competition from a JSON file.
+    pub fn read(path: &Path) -> Result<Self> {
+        let file = std::fs::File::open(path)?;
+        let competition: Export = serde_json::from_reader(std::io::BufReader::new(file))?;
+        competition.into_domain()
+    }
+
+    /// Writes the competition to a JSON file in the format understood by
+    /// [`Competition::read`].
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let file = std::fs::File::create(path)?;
+        serde_json::to_writer_pretty(std::io::BufWriter::new(file), &Export::from_domain(self))?;
+        Ok(())
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Export {
+    auction_id: domain::auction::Id,
+    #[serde_as(as = "BTreeMap<_, HexOrDecimalU256>")]
+    native_prices: BTreeMap<H160, U256>,
+    surplus_capturing_jit_order_owners: Vec<H160>,
+    fee_policies: BTreeMap<boundary::OrderUid, Vec<dto::order::FeePolicy>>,
+    solutions: Vec<Solution>,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Solution {
+    solution_id: u64,
+    solver: H160,
+    #[serde_as(as = "HexOrDecimalU256")]
+    score: U256,
+    orders: BTreeMap<boundary::OrderUid, TradedOrder>,
+    #[serde_as(as = "BTreeMap<_, HexOrDecimalU256>")]
+    clearing_prices: BTreeMap<H160, U256>,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TradedOrder {
+    side: boundary::OrderKind,
+    sell_token: H160,
+    buy_token: H160,
+    #[serde_as(as = "HexOrDecimalU256")]
+    limit_sell: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    limit_buy: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    executed_sell: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    executed_buy: U256,
+}
+
+impl Export {
+    fn from_domain(competition: &Competition) -> Self {
+        Self {
+            auction_id: competition.auction_id,
+            native_prices: competition
+                .native_prices
+                .iter()
+                .map(|(token, price)| (token.0, price.get().0))
+                .collect(),
+            surplus_capturing_jit_order_owners: competition
+                .surplus_capturing_jit_order_owners
+                .iter()
+                .map(|owner| owner.0)
+                .collect(),
+            fee_policies: competition
+                .fee_policies
+                .iter()
+                .map(|(uid, policies)| {
+                    let policies = policies
+                        .iter()
+                        .cloned()
+                        .map(dto::order::FeePolicy::from_domain)
+                        .collect();
+                    ((*uid).into(), policies)
+                })
+                .collect(),
+            solutions: competition
+                .solutions
+                .iter()
+                .map(|solution| Solution {
+                    solution_id: solution.id(),
+                    solver: solution.solver().0,
+                    score: solution.score().get().0,
+                    orders: solution
+                        .orders()
+                        .iter()
+                        .map(|(uid, order)| {
+                            let order = TradedOrder {
+                                side: order.side.into(),
+                                sell_token: order.sell.token.0,
+                                buy_token: order.buy.token.0,
+                                limit_sell: order.sell.amount.0,
+                                limit_buy: order.buy.amount.0,
+                                executed_sell: order.executed_sell.0,
+                                executed_buy: order.executed_buy.0,
+                            };
+                            ((*uid).into(), order)
+                        })
+                        .collect(),
+                    clearing_prices: solution
+                        .prices()
+                        .iter()
+                        .map(|(token, price)| (token.0, price.get().0))
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    fn into_domain(self) -> Result<Competition> {
+        Ok(Competition {
+            auction_id: self.auction_id,
+            native_prices: self
+                .native_prices
+                .into_iter()
+                .map(|(token, price)| {
+                    let price = domain::auction::Price::try_new(price.into())
+                        .with_context(|| format!("invalid native price for {token:?}"))?;
+                    Ok((token.into(), price))
+                })
+                .collect::<Result<_>>()?,
+            surplus_capturing_jit_order_owners: self
+                .surplus_capturing_jit_order_owners
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            fee_policies: self
+                .fee_policies
+                .into_iter()
+                .map(|(uid, policies)| {
+                    let policies = policies
+                        .into_iter()
+                        .map(dto::order::FeePolicy::into_domain)
+                        .collect();
+                    (uid.into(), policies)
+                })
+                .collect(),
+            solutions: self
+                .solutions
+                .into_iter()
+                .map(Solution::into_domain)
+                .collect::<Result<_>>()?,
+        })
+    }
+}
+
+impl Solution {
+    fn into_domain(self) -> Result<domain::competition::Solution> {
+        let solution_id = self.solution_id;
+        Ok(domain::competition::Solution::new(
+            self.solution_id,
+            self.solver.into(),
+            domain::competition::Score::try_new(self.score.into())
+                .with_context(|| format!("invalid score of solution {solution_id}"))?,
+            self.orders
+                .into_iter()
+                .map(|(uid, order)| (uid.into(), order.into_domain()))
+                .collect(),
+            self.clearing_prices
+                .into_iter()
+                .map(|(token, price)| {
+                    let price =
+                        domain::auction::Price::try_new(price.into()).with_context(|| {
+                            format!("invalid price for {token:?} in solution {solution_id}")
+                        })?;
+                    Ok((token.into(), price))
+                })
+                .collect::<Result<_>>()?,
+        ))
+    }
+}
+
+impl TradedOrder {
+    fn into_domain(self) -> domain::competition::TradedOrder {
+        domain::competition::TradedOrder {
+            side: self.side.into(),
+            sell: eth::Asset {
+                token: self.sell_token.into(),
+                amount: self.limit_sell.into(),
+            },
+            buy: eth::Asset {
+                token: self.buy_token.into(),
+                amount: self.limit_buy.into(),
+            },
+            executed_sell: self.executed_sell.into(),
+            executed_buy: self.executed_buy.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, serde_json::json};
+
+    #[test]
+    fn competition_roundtrip() {
+        let uid = format!("0x{}", "01".repeat(56));
+        let export = json!({
+            "auctionId": 1,
+            "nativePrices": {
+                "0x0000000000000000000000000000000000000001": "1000",
+                "0x0000000000000000000000000000000000000002": "2000",
+            },
+            "surplusCapturingJitOrderOwners": ["0x0000000000000000000000000000000000000003"],
+            "feePolicies": {
+                &uid: [{ "surplus": { "factor": 0.5, "maxVolumeFactor": 0.01 } }],
+            },
+            "solutions": [{
+                "solutionId": 7,
+                "solver": "0x0000000000000000000000000000000000000004",
+                "score": "300",
+                "orders": {
+                    &uid: {
+                        "side": "sell",
+                        "sellToken": "0x0000000000000000000000000000000000000001",
+                        "buyToken": "0x0000000000000000000000000000000000000002",
+                        "limitSell": "100",
+                        "limitBuy": "200",
+                        "executedSell": "100",
+                        "executedBuy": "250",
+                    },
+                },
+                "clearingPrices": {
+                    "0x0000000000000000000000000000000000000001": "250",
+                    "0x0000000000000000000000000000000000000002": "100",
+                },
+            }],
+        });
+        let competition = serde_json::from_value::<Export>(export.clone())
+            .unwrap()
+            .into_domain()
+            .unwrap();
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        competition.write(file.path()).unwrap();
+        let read = Competition::read(file.path()).unwrap();
+
+        assert_eq!(read.auction_id, 1);
+        assert_eq!(
+            serde_json::to_value(Export::from_domain(&read)).unwrap(),
+            export
+        );
+    }
+}
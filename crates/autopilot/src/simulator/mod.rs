@@ -0,0 +1,229 @@
+//! Offline simulator for the combinatorial auction. Replays stored auctions
+//! and all solutions proposed for them through the winner selection with
+//! alternative parameters and reports how the outcome changes.
+
+use {
+    crate::{
+        arguments::Account,
+        database::Postgres,
+        domain::{
+            self,
+            competition::{
+                Participant,
+                Unranked,
+                winner_selection::{self, Auction, Fairness, ScoreDefinition},
+            },
+        },
+        infra,
+    },
+    anyhow::{Context, Result},
+    clap::Parser,
+    itertools::Itertools,
+    primitive_types::H160,
+    std::{collections::HashMap, num::NonZeroUsize, path::PathBuf, sync::Arc},
+    url::Url,
+};
+
+mod input;
+mod report;
+
+use input::Competition;
+
+#[derive(Debug, Parser)]
+struct Arguments {
+    /// Url of the Postgres database to load the auctions from.
+    #[clap(long, env, requires = "auction_ids")]
+    db_url: Option<Url>,
+
+    /// Ids of the auctions to load from the database.
+    #[clap(long, env, use_value_delimiter = true)]
+    auction_ids: Vec<domain::auction::Id>,
+
+    /// JSON files to load the auctions from instead of the database. Files
+    /// can be created with `--export-dir`.
+    #[clap(long, env, use_value_delimiter = true, conflicts_with = "db_url")]
+    files: Vec<PathBuf>,
+
+    /// Directory to store every loaded auction in as a JSON file, so it can
+    /// be simulated again later without database access.
+    #[clap(long, env)]
+    export_dir: Option<PathBuf>,
+
+    /// Address of the wrapped native token of the chain the auctions were
+    /// run on.
+    #[clap(long, env)]
+    native_token_address: H160,
+
+    /// Maximum numbers of winners per auction to simulate.
+    #[clap(long, env, use_value_delimiter = true, default_value = "10")]
+    max_winners: Vec<NonZeroUsize>,
+
+    /// Fairness rules to simulate.
+    #[clap(
+        long,
+        env,
+        use_value_delimiter = true,
+        value_enum,
+        default_value = "baseline"
+    )]
+    fairness: Vec<Fairness>,
+
+    /// Score definitions to simulate.
+    #[clap(
+        long,
+        env,
+        use_value_delimiter = true,
+        value_enum,
+        default_value = "surplus-and-protocol-fees"
+    )]
+    score_definition: Vec<ScoreDefinition>,
+
+    #[clap(long, env, default_value = "false")]
+    use_json_logs: bool,
+}
+
+pub async fn start(args: impl Iterator<Item = String>) {
+    let args = Arguments::parse_from(args);
+    let obs_config = observe::Config::new(
+        "autopilot=info",
+        tracing::Level::ERROR.into(),
+        args.use_json_logs,
+        None,
+    );
+    observe::tracing::initialize(&obs_config);
+    observe::panic_hook::install();
+    observe::metrics::setup_registry(Some("gp_v2_auction_simulator".into()), None);
+
+    if let Err(err) = run(args).await {
+        tracing::error!(?err, "auction simulation failed");
+        std::process::exit(1);
+    }
+}
+
+async fn run(args: Arguments) -> Result<()> {
+    let competitions = load(&args).await?;
+    if let Some(dir) = &args.export_dir {
+        std::fs::create_dir_all(dir)?;
+        for competition in &competitions {
+            let path = dir.join(format!("{}.json", competition.auction_id));
+            competition
+                .write(&path)
+                .with_context(|| format!("failed to export {}", path.display()))?;
+        }
+    }
+
+    // The first variant serves as the baseline the surplus deltas of all
+    // other variants are computed against.
+    let variants: Vec<_> = args
+        .max_winners
+        .iter()
+        .cartesian_product(&args.fairness)
+        .cartesian_product(&args.score_definition)
+        .map(
+            |((max_winners, fairness), score_definition)| winner_selection::Arbitrator {
+                max_winners: max_winners.get(),
+                weth: args.native_token_address.into(),
+                fairness: *fairness,
+                score_definition: *score_definition,
+            },
+        )
+        .collect();
+
+    let mut reports = Vec::new();
+    for competition in &competitions {
+        let participants = participants(competition).await?;
+        let outcomes = variants
+            .iter()
+            .map(|arbitrator| simulate(arbitrator, competition, participants.clone()))
+            .collect();
+        reports.push(report::Report::new(competition.auction_id, outcomes));
+    }
+
+    let simulation = report::Simulation::new(reports);
+    serde_json::to_writer_pretty(std::io::stdout().lock(), &simulation)?;
+    println!();
+    Ok(())
+}
+
+async fn load(args: &Arguments) -> Result<Vec<Competition>> {
+    if let Some(db_url) = &args.db_url {
+        let postgres = Postgres::new(db_url.as_str(), NonZeroUsize::new(1).unwrap()).await?;
+        let persistence = infra::Persistence::new(None, Arc::new(postgres)).await;
+        let mut competitions = Vec::new();
+        for auction_id in &args.auction_ids {
+            let competition = Competition::fetch(&persistence, *auction_id)
+                .await
+                .with_context(|| format!("failed to load auction {auction_id}"))?;
+            competitions.push(competition);
+        }
+        return Ok(competitions);
+    }
+
+    anyhow::ensure!(
+        !args.files.is_empty(),
+        "either --db-url and --auction-ids or --files must be provided"
+    );
+    args.files
+        .iter()
+        .map(|path| {
+            Competition::read(path).with_context(|| format!("failed to load {}", path.display()))
+        })
+        .collect()
+}
+
+/// Wraps the stored solutions into participants. The winner selection only
+/// cares about the submission address of the driver, so every solver gets a
+/// driver that is never contacted.
+async fn participants(competition: &Competition) -> Result<Vec<Participant<Unranked>>> {
+    let mut drivers = HashMap::new();
+    let mut participants = Vec::new();
+    for solution in &competition.solutions {
+        let solver = solution.solver();
+        let driver = match drivers.get(&solver) {
+            Some(driver) => Arc::clone(driver),
+            None => {
+                let driver = Arc::new(
+                    infra::Driver::try_new(
+                        Url::parse("http://localhost").unwrap(),
+                        solver.to_string(),
+                        None,
+                        Account::Address(solver.0),
+                        false,
+                    )
+                    .await?,
+                );
+                drivers.insert(solver, Arc::clone(&driver));
+                driver
+            }
+        };
+        participants.push(Participant::new(solution.clone(), driver));
+    }
+    Ok(participants)
+}
+
+fn simulate(
+    arbitrator: &winner_selection::Arbitrator,
+    competition: &Competition,
+    participants: Vec<Participant<Unranked>>,
+) -> report::Outcome {
+    let auction = Auction::new(
+        competition
+            .fee_policies
+            .iter()
+            .map(|(uid, policies)| (*uid, policies))
+            .collect(),
+        competition.surplus_capturing_jit_order_owners.clone(),
+        &competition.native_prices,
+        // the available amounts of orders are not stored with the auction
+        Default::default(),
+    );
+    let ranking = arbitrator.arbitrate(participants, auction);
+    let reference_scores = arbitrator.compute_reference_scores(&ranking);
+    report::Outcome::new(
+        arbitrator.max_winners,
+        arbitrator.fairness,
+        arbitrator.score_definition,
+        &ranking,
+        reference_scores,
+    )
+}
@@ -0,0 +1,262 @@
+use {
+    crate::domain::{
+        competition::{
+            Participant,
+            Score,
+            winner_selection::{Fairness, Ranking, ScoreDefinition},
+        },
+        eth,
+    },
+    num::BigInt,
+    number::{conversions::u256_to_big_int, serialization::HexOrDecimalU256},
+    primitive_types::{H160, U256},
+    serde::Serialize,
+    serde_with::{DisplayFromStr, serde_as},
+    std::collections::{BTreeMap, HashMap},
+};
+
+/// Outcomes of all simulated auctions together with totals per variant.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Simulation {
+    pub auctions: Vec<Report>,
+    pub summary: Vec<Summary>,
+}
+
+/// Totals of a single variant over all simulated auctions.
+#[serde_as]
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Summary {
+    pub max_winners: usize,
+    pub fairness: Fairness,
+    pub score_definition: ScoreDefinition,
+    pub winners: usize,
+    pub filtered_out: usize,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub total_score: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub surplus: U256,
+    #[serde_as(as = "DisplayFromStr")]
+    pub surplus_delta: BigInt,
+}
+
+/// Outcomes of all simulated variants for a single auction.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Report {
+    pub auction_id: i64,
+    pub variants: Vec<Outcome>,
+}
+
+/// Result of running the winner selection with one set of parameters.
+#[serde_as]
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Outcome {
+    pub max_winners: usize,
+    pub fairness: Fairness,
+    pub score_definition: ScoreDefinition,
+    pub winners: Vec<RankedSolution>,
+    pub filtered_out: Vec<RankedSolution>,
+    #[serde_as(as = "BTreeMap<_, HexOrDecimalU256>")]
+    pub reference_scores: BTreeMap<H160, U256>,
+    /// Sum of the scores of all winning solutions.
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub total_score: U256,
+    /// Surplus in native token the winning solutions provide to users.
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub surplus: U256,
+    /// Difference of `surplus` compared to the first simulated variant.
+    #[serde_as(as = "DisplayFromStr")]
+    pub surplus_delta: BigInt,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RankedSolution {
+    pub solver: H160,
+    pub solution_id: u64,
+    /// Score computed for the solution. Missing if it couldn't be computed.
+    #[serde_as(as = "Option<HexOrDecimalU256>")]
+    pub score: Option<U256>,
+}
+
+impl Outcome {
+    pub fn new(
+        max_winners: usize,
+        fairness: Fairness,
+        score_definition: ScoreDefinition,
+        ranking: &Ranking,
+        reference_scores: HashMap<eth::Address, Score>,
+    ) -> Self {
+        let ranked_solution = |participant: &Participant| RankedSolution {
+            solver: participant.driver().submission_address.0,
+            solution_id: participant.solution().id(),
+            score: participant
+                .solution()
+                .computed_score()
+                .map(|score| score.get().0),
+        };
+        let winners: Vec<_> = ranking.winners().map(ranked_solution).collect();
+        let total_score = winners
+            .iter()
+            .filter_map(|winner| winner.score)
+            .fold(U256::zero(), U256::saturating_add);
+        let surplus = ranking
+            .winners()
+            .flat_map(|winner| winner.solution().order_scores().values())
+            .fold(U256::zero(), |acc, score| {
+                acc.saturating_add(score.surplus.0)
+            });
+
+        Self {
+            max_winners,
+            fairness,
+            score_definition,
+            winners,
+            filtered_out: ranking
+                .all()
+                .filter(|participant| participant.filtered_out())
+                .map(ranked_solution)
+                .collect(),
+            reference_scores: reference_scores
+                .into_iter()
+                .map(|(solver, score)| (solver.0, score.get().0))
+                .collect(),
+            total_score,
+            surplus,
+            surplus_delta: BigInt::default(),
+        }
+    }
+}
+
+impl Report {
+    pub fn new(auction_id: i64, mut variants: Vec<Outcome>) -> Self {
+        if let Some(baseline) = variants.first().map(|outcome| outcome.surplus) {
+            let baseline = u256_to_big_int(&baseline);
+            for outcome in &mut variants {
+                outcome.surplus_delta = u256_to_big_int(&outcome.surplus) - &baseline;
+            }
+        }
+        Self {
+            auction_id,
+            variants,
+        }
+    }
+}
+
+impl Simulation {
+    pub fn new(auctions: Vec<Report>) -> Self {
+        let mut summary: Vec<Summary> = Vec::new();
+        for report in &auctions {
+            for (index, outcome) in report.variants.iter().enumerate() {
+                if summary.len() <= index {
+                    summary.push(Summary {
+                        max_winners: outcome.max_winners,
+                        fairness: outcome.fairness,
+                        score_definition: outcome.score_definition,
+                        winners: 0,
+                        filtered_out: 0,
+                        total_score: U256::zero(),
+                        surplus: U256::zero(),
+                        surplus_delta: BigInt::default(),
+                    });
+                }
+                let totals = &mut summary[index];
+                totals.winners += outcome.winners.len();
+                totals.filtered_out += outcome.filtered_out.len();
+                totals.total_score = totals.total_score.saturating_add(outcome.total_score);
+                totals.surplus = totals.surplus.saturating_add(outcome.surplus);
+                totals.surplus_delta += &outcome.surplus_delta;
+            }
+        }
+        Self { auctions, summary }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, serde_json::json};
+
+    fn outcome(winners: usize, filtered_out: usize, total_score: u64, surplus: u64) -> Outcome {
+        let solutions = |count| {
+            (0..count)
+                .map(|solution_id| RankedSolution {
+                    solver: H160::from_low_u64_be(1),
+                    solution_id,
+                    score: None,
+                })
+                .collect()
+        };
+        Outcome {
+            max_winners: 10,
+            fairness: Fairness::Baseline,
+            score_definition: ScoreDefinition::SurplusAndProtocolFees,
+            winners: solutions(winners as u64),
+            filtered_out: solutions(filtered_out as u64),
+            reference_scores: Default::default(),
+            total_score: total_score.into(),
+            surplus: surplus.into(),
+            surplus_delta: BigInt::default(),
+        }
+    }
+
+    #[test]
+    fn surplus_deltas_are_relative_to_first_variant() {
+        let report = Report::new(
+            1,
+            vec![
+                outcome(1, 0, 100, 100),
+                outcome(1, 0, 90, 80),
+                outcome(2, 1, 150, 130),
+            ],
+        );
+        assert_eq!(
+            serde_json::to_value(&report).unwrap()["variants"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|variant| &variant["surplusDelta"])
+                .collect::<Vec<_>>(),
+            [&json!("0"), &json!("-20"), &json!("30")]
+        );
+
+        let report = Report::new(2, Vec::new());
+        assert!(report.variants.is_empty());
+    }
+
+    #[test]
+    fn simulation_sums_up_variants() {
+        let simulation = Simulation::new(vec![
+            Report::new(1, vec![outcome(1, 0, 100, 100), outcome(2, 1, 150, 130)]),
+            Report::new(2, vec![outcome(2, 1, 200, 150), outcome(1, 3, 120, 100)]),
+        ]);
+        assert_eq!(
+            serde_json::to_value(&simulation.summary).unwrap(),
+            json!([
+                {
+                    "maxWinners": 10,
+                    "fairness": "baseline",
+                    "scoreDefinition": "surplus-and-protocol-fees",
+                    "winners": 3,
+                    "filteredOut": 1,
+                    "totalScore": "300",
+                    "surplus": "250",
+                    "surplusDelta": "0",
+                },
+                {
+                    "maxWinners": 10,
+                    "fairness": "baseline",
+                    "scoreDefinition": "surplus-and-protocol-fees",
+                    "winners": 3,
+                    "filteredOut": 4,
+                    "totalScore": "270",
+                    "surplus": "230",
+                    "surplusDelta": "-20",
+                },
+            ])
+        );
+    }
+}
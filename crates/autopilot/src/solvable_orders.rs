@@ -13,7 +13,7 @@ use {
     indexmap::IndexSet,
     itertools::Itertools,
     model::{
-        order::{Order, OrderClass, OrderUid},
+        order::{Order, OrderClass, OrderKind, OrderUid},
         signature::Signature,
         time::now_in_epoch_seconds,
     },
@@ -105,10 +105,20 @@ pub struct SolvableOrdersCache {
     disable_order_balance_filter: bool,
     disable_1271_order_sig_filter: bool,
     disable_1271_order_balance_filter: bool,
+    triggers: Mutex<Triggers>,
 }
 
 type Balances = HashMap<Query, U256>;
 
+#[derive(Default)]
+struct Triggers {
+    /// Orders whose trigger state was already looked up in the database.
+    checked: HashSet<OrderUid>,
+    /// Orders whose trigger condition was met at some point. They stay
+    /// triggered even if the price moves back.
+    triggered: HashSet<OrderUid>,
+}
+
 struct Inner {
     auction: domain::RawAuctionData,
     solvable_orders: boundary::SolvableOrders,
@@ -153,6 +163,7 @@ impl SolvableOrdersCache {
             disable_order_balance_filter,
             disable_1271_order_sig_filter,
             disable_1271_order_balance_filter,
+            triggers: Default::default(),
         })
     }
 
@@ -189,6 +200,11 @@ impl SolvableOrdersCache {
         let mut invalid_order_uids = HashSet::new();
         let mut filtered_order_events = Vec::new();
 
+        let (orders, twap_available) =
+            limit_twap_orders_to_unlocked_slices(orders, now_in_epoch_seconds());
+        let removed = counter.checkpoint("twap_slice_executed", &orders);
+        filtered_order_events.extend(removed);
+
         let (balances, orders, cow_amms) = {
             let queries = orders.iter().map(Query::from_order).collect::<Vec<_>>();
             tokio::join!(
@@ -255,6 +271,10 @@ impl SolvableOrdersCache {
         let removed = counter.checkpoint("missing_price", &orders);
         filtered_order_events.extend(removed);
 
+        let (orders, triggered_orders) = self.filter_untriggered_orders(orders, &prices).await;
+        let removed = counter.checkpoint("trigger_not_met", &orders);
+        filtered_order_events.extend(removed);
+
         let orders = filter_mispriced_limit_orders(orders, &prices, &self.limit_order_price_factor);
         let removed = counter.checkpoint("out_of_market", &orders);
         filtered_order_events.extend(removed);
@@ -275,6 +295,10 @@ impl SolvableOrdersCache {
                     .map(|id| domain::OrderUid(id.0)),
                 OrderEventLabel::Filtered,
             );
+            self.persistence.store_order_events(
+                triggered_orders.iter().map(|id| domain::OrderUid(id.0)),
+                OrderEventLabel::Triggered,
+            );
         }
 
         let surplus_capturing_jit_order_owners = cow_amms
@@ -303,8 +327,15 @@ impl SolvableOrdersCache {
                         .quotes
                         .get(&order.metadata.uid.into())
                         .cloned();
-                    self.protocol_fees
-                        .apply(order, quote, &surplus_capturing_jit_order_owners)
+                    let available = twap_available.get(&order.metadata.uid).copied();
+                    domain::Order {
+                        available: available.map(Into::into),
+                        ..self.protocol_fees.apply(
+                            order,
+                            quote,
+                            &surplus_capturing_jit_order_owners,
+                        )
+                    }
                 })
                 .collect(),
             prices: prices
@@ -431,6 +462,68 @@ impl SolvableOrdersCache {
         orders
     }
 
+    /// Removes stop-loss and take-profit orders whose trigger condition has not
+    /// been met yet. Returns the remaining orders and the orders that got
+    /// triggered in this update.
+    async fn filter_untriggered_orders(
+        &self,
+        orders: Vec<Order>,
+        prices: &BTreeMap<H160, U256>,
+    ) -> (Vec<Order>, Vec<OrderUid>) {
+        let trigger_orders: HashSet<_> = orders
+            .iter()
+            .filter(|order| trigger(order).is_some())
+            .map(|order| order.metadata.uid)
+            .collect();
+        if trigger_orders.is_empty() {
+            return (orders, Vec::new());
+        }
+
+        let mut triggers = self.triggers.lock().await;
+        triggers.checked.retain(|uid| trigger_orders.contains(uid));
+        triggers
+            .triggered
+            .retain(|uid| trigger_orders.contains(uid));
+
+        // Orders might have been triggered before a restart or by another
+        // autopilot instance so look them up once.
+        let unchecked: Vec<_> = trigger_orders
+            .iter()
+            .filter(|uid| !triggers.checked.contains(uid))
+            .map(|uid| domain::OrderUid(uid.0))
+            .collect();
+        if !unchecked.is_empty() {
+            match self.persistence.triggered_orders(&unchecked).await {
+                Ok(triggered) => {
+                    triggers
+                        .triggered
+                        .extend(triggered.into_iter().map(|uid| OrderUid(uid.0)));
+                    triggers
+                        .checked
+                        .extend(unchecked.into_iter().map(|uid| OrderUid(uid.0)));
+                }
+                Err(err) => tracing::warn!(?err, "failed to fetch triggered orders"),
+            }
+        }
+
+        let (orders, newly_triggered) = filter_by_trigger(orders, prices, &mut triggers.triggered);
+        if !newly_triggered.is_empty() {
+            let uids: Vec<_> = newly_triggered
+                .iter()
+                .map(|uid| domain::OrderUid(uid.0))
+                .collect();
+            if let Err(err) = self.persistence.store_triggered_orders(&uids).await {
+                // The orders are still triggered right now but forget about it
+                // so storing it gets retried in the next update.
+                tracing::warn!(?err, "failed to store triggered orders");
+                for uid in &newly_triggered {
+                    triggers.triggered.remove(uid);
+                }
+            }
+        }
+        (orders, newly_triggered)
+    }
+
     pub fn track_auction_update(&self, result: &str) {
         self.metrics
             .auction_update
@@ -464,7 +557,7 @@ async fn find_banned_user_orders(orders: &[Order], banned_users: &banned::Users)
         .filter_map(|order| {
             std::iter::once(order.metadata.owner)
                 .chain(order.data.receiver)
-                .any(|addr| banned.contains(&addr))
+                .any(|addr| banned.contains_key(&addr))
                 .then_some(order.metadata.uid)
         })
         .collect()
@@ -583,6 +676,92 @@ fn orders_with_balance(
     orders
 }
 
+fn protocol_app_data(order: &Order) -> Option<app_data::ProtocolAppData> {
+    let full_app_data = order.metadata.full_app_data.as_ref()?;
+    app_data::parse(full_app_data.as_bytes()).ok()
+}
+
+fn trigger(order: &Order) -> Option<app_data::Trigger> {
+    protocol_app_data(order)?.trigger
+}
+
+/// Removes orders with a trigger condition that is neither met based on the
+/// current native prices nor was met before. Orders that get triggered are
+/// added to `triggered` and returned separately.
+fn filter_by_trigger(
+    orders: Vec<Order>,
+    prices: &BTreeMap<H160, U256>,
+    triggered: &mut HashSet<OrderUid>,
+) -> (Vec<Order>, Vec<OrderUid>) {
+    let mut newly_triggered = Vec::new();
+    let orders = orders
+        .into_iter()
+        .filter(|order| {
+            let uid = order.metadata.uid;
+            let Some(trigger) = trigger(order) else {
+                return true;
+            };
+            if triggered.contains(&uid) {
+                return true;
+            }
+            let (Some(sell_price), Some(buy_price)) = (
+                prices.get(&order.data.sell_token.into_legacy()),
+                prices.get(&order.data.buy_token.into_legacy()),
+            ) else {
+                return false;
+            };
+            if !trigger.is_met(sell_price.into_alloy(), buy_price.into_alloy()) {
+                return false;
+            }
+            tracing::debug!(%uid, ?trigger, "order triggered");
+            triggered.insert(uid);
+            newly_triggered.push(uid);
+            true
+        })
+        .collect();
+    (orders, newly_triggered)
+}
+
+/// Limits TWAP orders to the part of the order whose slices are already
+/// unlocked. Orders that already executed all unlocked slices get filtered out
+/// until the next slice unlocks. For the remaining TWAP orders the returned map
+/// contains how much of their target amount is available for execution right
+/// now. The signed amounts of the orders are left untouched so the settlement
+/// contract can still verify them, which is why solutions executing more than
+/// the available amount get discarded during winner selection.
+fn limit_twap_orders_to_unlocked_slices(
+    mut orders: Vec<Order>,
+    now: u32,
+) -> (Vec<Order>, HashMap<OrderUid, U256>) {
+    let mut available = HashMap::new();
+    orders.retain(|order| {
+        if !order.data.partially_fillable {
+            return true;
+        }
+        let Some(twap) = protocol_app_data(order).and_then(|app_data| app_data.twap) else {
+            return true;
+        };
+
+        let total = match order.data.kind {
+            OrderKind::Buy => order.data.buy_amount,
+            OrderKind::Sell => order.data.sell_amount,
+        };
+        let start_time = twap.start_time.unwrap_or_else(|| {
+            u32::try_from(order.metadata.creation_date.timestamp()).unwrap_or_default()
+        });
+        let unlocked = twap.unlocked_amount(total, start_time, now).into_legacy();
+        let executed = remaining_amounts::Order::from(order).executed_amount;
+        if unlocked <= executed {
+            return false;
+        }
+        if unlocked != total.into_legacy() {
+            available.insert(order.metadata.uid, unlocked - executed);
+        }
+        true
+    });
+    (orders, available)
+}
+
 /// Filters out dust orders i.e. partially fillable orders that, when scaled
 /// have a 0 buy or sell amount.
 fn filter_dust_orders(mut orders: Vec<Order>, balances: &Balances) -> Vec<Order> {
@@ -1516,6 +1695,162 @@ mod tests {
         }
     }
 
+    #[test]
+    fn limits_twap_orders_to_unlocked_slices() {
+        let twap_app_data =
+            r#"{"metadata":{"twap":{"parts":4,"partDuration":100,"startTime":1000}}}"#;
+        let order = |uid: u8, full_app_data: Option<&str>, executed: u64| Order {
+            data: OrderData {
+                sell_amount: alloy::primitives::U256::from(1000),
+                buy_amount: alloy::primitives::U256::from(2000),
+                kind: OrderKind::Sell,
+                partially_fillable: true,
+                ..Default::default()
+            },
+            metadata: OrderMetadata {
+                uid: OrderUid([uid; 56]),
+                full_app_data: full_app_data.map(str::to_string),
+                executed_sell_amount_before_fees: executed.into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let orders = vec![
+            // regular order
+            order(1, None, 100),
+            // first slice partially executed
+            order(2, Some(twap_app_data), 100),
+            // first slice fully executed
+            order(3, Some(twap_app_data), 250),
+        ];
+
+        let (limited, available) = limit_twap_orders_to_unlocked_slices(orders.clone(), 1050);
+        // The signed amounts are left untouched and only 150 of the first
+        // slice remain available.
+        assert_eq!(limited, orders[..2]);
+        assert_eq!(
+            available,
+            HashMap::from([(orders[1].metadata.uid, U256::from(150))])
+        );
+
+        // Once the second slice unlocks the third order is tradeable again.
+        let (limited, available) = limit_twap_orders_to_unlocked_slices(orders.clone(), 1100);
+        assert_eq!(limited, orders);
+        assert_eq!(
+            available,
+            HashMap::from([
+                (orders[1].metadata.uid, U256::from(400)),
+                (orders[2].metadata.uid, U256::from(250)),
+            ])
+        );
+
+        // Once all slices are unlocked the whole remaining order is available.
+        let (limited, available) = limit_twap_orders_to_unlocked_slices(orders.clone(), 1400);
+        assert_eq!(limited, orders);
+        assert!(available.is_empty());
+
+        // Nothing is tradeable before the TWAP starts.
+        let (limited, _) = limit_twap_orders_to_unlocked_slices(orders, 999);
+        assert_eq!(limited.len(), 1);
+    }
+
+    #[test]
+    fn limited_twap_orders_keep_pre_interactions() {
+        let pre_hook = InteractionData {
+            target: Address::repeat_byte(0xe1),
+            value: alloy::primitives::U256::ZERO,
+            call_data: vec![1, 2],
+        };
+        let order = Order {
+            data: OrderData {
+                sell_amount: alloy::primitives::U256::from(1000),
+                buy_amount: alloy::primitives::U256::from(2000),
+                kind: OrderKind::Sell,
+                partially_fillable: true,
+                ..Default::default()
+            },
+            metadata: OrderMetadata {
+                full_app_data: Some(
+                    r#"{"metadata":{"twap":{"parts":4,"partDuration":100,"startTime":1000}}}"#
+                        .to_string(),
+                ),
+                ..Default::default()
+            },
+            interactions: Interactions {
+                pre: vec![pre_hook.clone()],
+                post: vec![],
+            },
+            ..Default::default()
+        };
+
+        let (limited, _) = limit_twap_orders_to_unlocked_slices(vec![order], 1050);
+        assert_eq!(limited.len(), 1);
+        let order = crate::boundary::order::to_domain(limited[0].clone(), vec![], None);
+        assert_eq!(
+            order.pre_interactions,
+            vec![crate::domain::auction::order::Interaction::from(pre_hook)]
+        );
+        assert!(order.executed.0.is_zero());
+    }
+
+    #[test]
+    fn filters_untriggered_orders() {
+        let sell_token = Address::repeat_byte(1);
+        let buy_token = Address::repeat_byte(2);
+        // triggers once 1 sell token is worth at most 2 buy tokens
+        let stop_loss =
+            r#"{"metadata":{"trigger":{"kind":"stopLoss","sellAmount":"1","buyAmount":"2"}}}"#;
+        let order = |uid: u8, full_app_data: Option<&str>| Order {
+            data: OrderData {
+                sell_token,
+                buy_token,
+                ..Default::default()
+            },
+            metadata: OrderMetadata {
+                uid: OrderUid([uid; 56]),
+                full_app_data: full_app_data.map(str::to_string),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let orders = vec![order(1, None), order(2, Some(stop_loss))];
+        let uids = |orders: &[Order]| {
+            orders
+                .iter()
+                .map(|order| order.metadata.uid)
+                .collect::<Vec<_>>()
+        };
+
+        // 1 sell token is worth 3 buy tokens
+        let mut prices = btreemap! {
+            sell_token.into_legacy() => U256::from(300),
+            buy_token.into_legacy() => U256::from(100),
+        };
+        let mut triggered = HashSet::new();
+        let (filtered, newly_triggered) =
+            filter_by_trigger(orders.clone(), &prices, &mut triggered);
+        assert_eq!(uids(&filtered), vec![OrderUid([1; 56])]);
+        assert!(newly_triggered.is_empty());
+
+        // 1 sell token is worth 2 buy tokens
+        prices.insert(sell_token.into_legacy(), U256::from(200));
+        let (filtered, newly_triggered) =
+            filter_by_trigger(orders.clone(), &prices, &mut triggered);
+        assert_eq!(uids(&filtered), uids(&orders));
+        assert_eq!(newly_triggered, vec![OrderUid([2; 56])]);
+
+        // Triggered orders stay solvable when the price moves back.
+        prices.insert(sell_token.into_legacy(), U256::from(300));
+        let (filtered, newly_triggered) =
+            filter_by_trigger(orders.clone(), &prices, &mut triggered);
+        assert_eq!(uids(&filtered), uids(&orders));
+        assert!(newly_triggered.is_empty());
+
+        // Untriggered orders without prices are filtered.
+        let (filtered, _) = filter_by_trigger(orders, &Default::default(), &mut HashSet::new());
+        assert_eq!(uids(&filtered), vec![OrderUid([1; 56])]);
+    }
+
     #[test]
     fn eip1271_orders_can_skip_balance_filtering() {
         let settlement_contract = Address::repeat_byte(1);
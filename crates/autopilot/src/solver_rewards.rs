@@ -0,0 +1,121 @@
+//! Background task accounting solver rewards and penalties for every
+//! completed accounting period.
+
+use {
+    crate::{
+        boundary,
+        database::Postgres,
+        domain::rewards::{Formula, Period, Periods},
+        infra,
+    },
+    anyhow::{Context, Result},
+    std::time::Duration,
+    tokio::time,
+};
+
+pub struct SolverRewardsConfig {
+    pub periods: Periods,
+    pub formula: Formula,
+    pub update_interval: Duration,
+}
+
+pub struct SolverRewards {
+    config: SolverRewardsConfig,
+    persistence: infra::Persistence,
+    db: Postgres,
+}
+
+impl SolverRewards {
+    pub fn new(config: SolverRewardsConfig, persistence: infra::Persistence, db: Postgres) -> Self {
+        Self {
+            config,
+            persistence,
+            db,
+        }
+    }
+
+    pub async fn run_forever(self) -> ! {
+        let mut interval = time::interval(self.config.update_interval);
+        loop {
+            interval.tick().await;
+
+            // Catch up on all periods that completed since the last update.
+            loop {
+                match self.account_next_period().await {
+                    Ok(true) => continue,
+                    Ok(false) => break,
+                    Err(err) => {
+                        tracing::warn!(?err, "failed to account solver rewards");
+                        Metrics::get().failures.inc();
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Accounts the period following the last accounted one. Returns `false`
+    /// if that period is not complete yet.
+    async fn account_next_period(&self) -> Result<bool> {
+        let id = match self.persistence.last_accounting_period().await? {
+            Some(id) => id + 1,
+            None => 0,
+        };
+        let period = self.config.periods.get(id);
+        if !self.is_complete(&period).await? {
+            return Ok(false);
+        }
+
+        let solutions = self.persistence.fetch_winning_solutions(&period).await?;
+        let accounting = self.config.formula.account(&solutions);
+        let stored = self
+            .persistence
+            .save_solver_rewards(&period, &self.config.formula, &accounting)
+            .await?;
+        if stored {
+            tracing::info!(
+                period = period.id,
+                start_block = period.start_block,
+                end_block = period.end_block,
+                solvers = accounting.payouts.len(),
+                "accounted solver rewards"
+            );
+            Metrics::get().last_period.set(period.id);
+        }
+        Ok(true)
+    }
+
+    /// A period is complete once all of its blocks passed and all settlements
+    /// of its auctions, which may still happen up to their deadline, are
+    /// indexed.
+    async fn is_complete(&self, period: &Period) -> Result<bool> {
+        let indexed_block = boundary::events::read_last_block_from_db(
+            &self.db.pool,
+            boundary::events::settlement::INDEX_NAME,
+        )
+        .await
+        .context("failed to read last indexed settlement block")?;
+        let deadline = self
+            .persistence
+            .latest_auction_deadline(period)
+            .await?
+            .unwrap_or_default();
+        Ok(indexed_block >= period.end_block && indexed_block >= deadline)
+    }
+}
+
+#[derive(prometheus_metric_storage::MetricStorage)]
+#[metric(subsystem = "solver_rewards")]
+struct Metrics {
+    /// Id of the last accounting period for which solver rewards were stored.
+    last_period: prometheus::IntGauge,
+
+    /// Number of failed attempts to account solver rewards.
+    failures: prometheus::IntCounter,
+}
+
+impl Metrics {
+    fn get() -> &'static Self {
+        Metrics::instance(observe::metrics::get_storage_registry()).unwrap()
+    }
+}
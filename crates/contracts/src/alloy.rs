@@ -569,6 +569,17 @@ crate::bindings!(
     }
 );
 crate::bindings!(CoWSwapOnchainOrders);
+crate::bindings!(
+    ComposableCoW,
+    // <https://github.com/cowprotocol/composable-cow/blob/main/networks.json>
+    crate::deployments! {
+        MAINNET => address!("0xfdaFc9d1902f4e0b84f65F49f244b32b31013b74"),
+        GNOSIS => address!("0xfdaFc9d1902f4e0b84f65F49f244b32b31013b74"),
+        SEPOLIA => address!("0xfdaFc9d1902f4e0b84f65F49f244b32b31013b74"),
+        ARBITRUM_ONE => address!("0xfdaFc9d1902f4e0b84f65F49f244b32b31013b74"),
+        BASE => address!("0xfdaFc9d1902f4e0b84f65F49f244b32b31013b74"),
+    }
+);
 crate::bindings!(ERC1271SignatureValidator);
 
 // Used in the gnosis/solvers repo for the balancer solver
@@ -723,6 +734,7 @@ crate::bindings!(
 );
 
 crate::bindings!(ERC20);
+crate::bindings!(IERC20Permit);
 
 pub mod cow_amm {
     crate::bindings!(CowAmm);
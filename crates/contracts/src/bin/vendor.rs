@@ -177,10 +177,18 @@ fn run() -> Result<()> {
             "ERC20",
             "@openzeppelin/contracts@3.3.0/build/contracts/ERC20.json",
         )?
+        .manual(
+            "IERC20Permit",
+            "Manually vendored ABI for EIP-2612 permits",
+        )
         .manual(
             "ERC1271SignatureValidator",
             "Manually vendored ABI for ERC-1271 signature validation",
         )
+        .manual(
+            "ComposableCoW",
+            "Full ABI vendored from the cowprotocol/composable-cow build output",
+        )
         .npm(
             "IUniswapLikePair",
             "@uniswap/v2-periphery@1.1.0-beta.0/build/IUniswapV2Pair.json",
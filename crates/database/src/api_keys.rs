@@ -0,0 +1,88 @@
+use {crate::byte_array::ByteArray, sqlx::PgConnection, tracing::instrument};
+
+/// An API key that identifies an integrator of the orderbook API.
+#[derive(Clone, Debug, Default, Eq, PartialEq, sqlx::FromRow)]
+pub struct ApiKey {
+    pub key_hash: ByteArray<32>,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub requests_per_minute: Option<i32>,
+    pub revoked: bool,
+}
+
+#[instrument(skip_all)]
+pub async fn insert(ex: &mut PgConnection, key: &ApiKey) -> Result<(), sqlx::Error> {
+    const QUERY: &str = r#"
+INSERT INTO api_keys (key_hash, name, scopes, requests_per_minute, revoked)
+VALUES ($1, $2, $3, $4, $5)
+    "#;
+    sqlx::query(QUERY)
+        .bind(key.key_hash)
+        .bind(&key.name)
+        .bind(&key.scopes)
+        .bind(key.requests_per_minute)
+        .bind(key.revoked)
+        .execute(ex)
+        .await?;
+    Ok(())
+}
+
+/// Revokes the key. Returns whether the key exists.
+#[instrument(skip_all)]
+pub async fn revoke(ex: &mut PgConnection, key_hash: &ByteArray<32>) -> Result<bool, sqlx::Error> {
+    const QUERY: &str = "UPDATE api_keys SET revoked = true WHERE key_hash = $1";
+    let result = sqlx::query(QUERY).bind(key_hash).execute(ex).await?;
+    Ok(result.rows_affected() > 0)
+}
+
+#[instrument(skip_all)]
+pub async fn fetch(
+    ex: &mut PgConnection,
+    key_hash: &ByteArray<32>,
+) -> Result<Option<ApiKey>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT key_hash, name, scopes, requests_per_minute, revoked
+FROM api_keys
+WHERE key_hash = $1
+    "#;
+    sqlx::query_as(QUERY)
+        .bind(key_hash)
+        .fetch_optional(ex)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, sqlx::Connection};
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_api_keys() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let key = ApiKey {
+            key_hash: ByteArray([1; 32]),
+            name: "partner".to_string(),
+            scopes: vec!["quote".to_string(), "read".to_string()],
+            requests_per_minute: Some(60),
+            revoked: false,
+        };
+        assert_eq!(fetch(&mut db, &key.key_hash).await.unwrap(), None);
+
+        insert(&mut db, &key).await.unwrap();
+        assert_eq!(fetch(&mut db, &key.key_hash).await.unwrap(), Some(key));
+        assert_eq!(fetch(&mut db, &ByteArray([2; 32])).await.unwrap(), None);
+
+        assert!(revoke(&mut db, &key.key_hash).await.unwrap());
+        assert!(
+            fetch(&mut db, &key.key_hash)
+                .await
+                .unwrap()
+                .unwrap()
+                .revoked
+        );
+        assert!(!revoke(&mut db, &ByteArray([2; 32])).await.unwrap());
+    }
+}
@@ -1,5 +1,15 @@
 use {crate::AppId, sqlx::PgConnection};
 
+/// The `appCode` of the full app data as it gets stored in the `app_code`
+/// column. Documents without an `appCode` get an empty string so that they
+/// can be told apart from rows that weren't backfilled yet.
+pub fn app_code(full_app_data: &[u8]) -> String {
+    serde_json::from_slice::<serde_json::Value>(full_app_data)
+        .ok()
+        .and_then(|document| document.get("appCode")?.as_str().map(str::to_owned))
+        .unwrap_or_default()
+}
+
 /// Tries to associate the contract app data with the full app data.
 ///
 /// If this contract app data already existed then the existing full app data is
@@ -11,8 +21,8 @@ pub async fn insert(
 ) -> Result<Option<Vec<u8>>, sqlx::Error> {
     const QUERY: &str = r#"
 WITH inserted AS (
-    INSERT INTO app_data (contract_app_data, full_app_data)
-    VALUES ($1, $2)
+    INSERT INTO app_data (contract_app_data, full_app_data, app_code)
+    VALUES ($1, $2, $3)
     -- returns null on conflict
     ON CONFLICT DO NOTHING
     -- returns TRUE if the insertion succeeded
@@ -29,6 +39,7 @@ SELECT
     sqlx::query_scalar(QUERY)
         .bind(contract_app_data)
         .bind(full_app_data)
+        .bind(app_code(full_app_data))
         .fetch_one(ex)
         .await
 }
@@ -48,6 +59,53 @@ WHERE contract_app_data = $1
         .await
 }
 
+/// App data stored before the `app_code` column got added.
+#[derive(Clone, Debug, Default, sqlx::FromRow, Eq, PartialEq)]
+pub struct MissingAppCode {
+    pub contract_app_data: AppId,
+    pub full_app_data: Vec<u8>,
+}
+
+/// Returns the next `limit` app data documents after `after` that don't have
+/// their app code stored yet.
+pub async fn missing_app_codes(
+    ex: &mut PgConnection,
+    after: &AppId,
+    limit: i64,
+) -> Result<Vec<MissingAppCode>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT contract_app_data, full_app_data
+FROM app_data
+WHERE contract_app_data > $1 AND app_code IS NULL
+ORDER BY contract_app_data
+LIMIT $2
+;"#;
+    sqlx::query_as(QUERY)
+        .bind(after)
+        .bind(limit)
+        .fetch_all(ex)
+        .await
+}
+
+pub async fn set_app_codes(
+    ex: &mut PgConnection,
+    contract_app_data: &[AppId],
+    app_codes: &[String],
+) -> Result<(), sqlx::Error> {
+    const QUERY: &str = r#"
+UPDATE app_data
+SET app_code = codes.app_code
+FROM UNNEST($1::bytea[], $2::text[]) AS codes(contract_app_data, app_code)
+WHERE app_data.contract_app_data = codes.contract_app_data
+;"#;
+    sqlx::query(QUERY)
+        .bind(contract_app_data)
+        .bind(app_codes)
+        .execute(ex)
+        .await?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use {super::*, crate::byte_array::ByteArray, sqlx::Connection};
@@ -81,4 +139,58 @@ mod tests {
         let result = insert(&mut db, &contract, &[4, 2]).await.unwrap();
         assert_eq!(result, Some(full));
     }
+
+    #[test]
+    fn extracts_app_code() {
+        assert_eq!(app_code(br#"{"appCode":"CoW Swap"}"#), "CoW Swap");
+        assert_eq!(app_code(br#"{"metadata":{}}"#), "");
+        assert_eq!(app_code(br#"{"appCode":1}"#), "");
+        assert_eq!(app_code(&[1]), "");
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_backfill_app_codes() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let with_code = ByteArray([1u8; 32]);
+        let without_code = ByteArray([2u8; 32]);
+        insert(&mut db, &with_code, br#"{"appCode":"CoW Swap"}"#)
+            .await
+            .unwrap();
+        insert(&mut db, &without_code, b"{}").await.unwrap();
+        // nothing to backfill for app data stored with its app code
+        let missing = missing_app_codes(&mut db, &ByteArray([0u8; 32]), 10)
+            .await
+            .unwrap();
+        assert!(missing.is_empty());
+
+        // simulate app data stored before the column got added
+        sqlx::query("UPDATE app_data SET app_code = NULL")
+            .execute(&mut *db)
+            .await
+            .unwrap();
+        let missing = missing_app_codes(&mut db, &ByteArray([0u8; 32]), 1)
+            .await
+            .unwrap();
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].contract_app_data, with_code);
+        let missing = missing_app_codes(&mut db, &with_code, 10).await.unwrap();
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].contract_app_data, without_code);
+
+        set_app_codes(
+            &mut db,
+            &[with_code, without_code],
+            &["CoW Swap".to_string(), String::new()],
+        )
+        .await
+        .unwrap();
+        let missing = missing_app_codes(&mut db, &ByteArray([0u8; 32]), 10)
+            .await
+            .unwrap();
+        assert!(missing.is_empty());
+    }
 }
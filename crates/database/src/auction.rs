@@ -18,6 +18,16 @@ LIMIT 1
     sqlx::query_as(QUERY).fetch_optional(ex).await
 }
 
+pub async fn load_most_recent_id(ex: &mut PgConnection) -> Result<Option<AuctionId>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT id
+FROM auctions
+ORDER BY id DESC
+LIMIT 1
+    ;"#;
+    sqlx::query_scalar(QUERY).fetch_optional(ex).await
+}
+
 pub async fn replace_auction(
     ex: &mut PgConnection,
     data: &JsonValue,
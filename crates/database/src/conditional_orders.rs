@@ -0,0 +1,176 @@
+use {
+    crate::{Address, byte_array::ByteArray},
+    sqlx::{PgConnection, QueryBuilder},
+    std::collections::HashSet,
+    tracing::instrument,
+};
+
+/// A conditional order created through the ComposableCoW framework.
+#[derive(Clone, Debug, Default, Eq, PartialEq, sqlx::FromRow)]
+pub struct ConditionalOrder {
+    pub owner: Address,
+    pub params_hash: ByteArray<32>,
+    pub handler: Address,
+    pub salt: ByteArray<32>,
+    pub static_input: Vec<u8>,
+    pub block_number: i64,
+    pub log_index: i64,
+    pub active: bool,
+}
+
+/// Inserts the conditional orders. Orders that were already indexed get
+/// activated again with the position of the latest event that created them
+/// because owners can create a conditional order again after removing it.
+#[instrument(skip_all)]
+pub async fn insert(ex: &mut PgConnection, orders: &[ConditionalOrder]) -> Result<(), sqlx::Error> {
+    // A single statement can't update the same row twice so only the last
+    // creation of every conditional order gets inserted.
+    let mut created = HashSet::new();
+    let mut orders: Vec<_> = orders
+        .iter()
+        .rev()
+        .filter(|order| created.insert((order.owner, order.params_hash)))
+        .collect();
+    orders.reverse();
+    if orders.is_empty() {
+        return Ok(());
+    }
+
+    const QUERY: &str = "INSERT INTO conditional_orders (owner, params_hash, handler, salt, \
+                         static_input, block_number, log_index, active) ";
+    let mut query_builder = QueryBuilder::new(QUERY);
+    query_builder.push_values(orders, |mut builder, order| {
+        builder
+            .push_bind(order.owner)
+            .push_bind(order.params_hash)
+            .push_bind(order.handler)
+            .push_bind(order.salt)
+            .push_bind(order.static_input.clone())
+            .push_bind(order.block_number)
+            .push_bind(order.log_index)
+            .push_bind(order.active);
+    });
+    query_builder.push(
+        " ON CONFLICT (owner, params_hash) DO UPDATE SET active = true, block_number = \
+         EXCLUDED.block_number, log_index = EXCLUDED.log_index",
+    );
+    query_builder.build().execute(ex).await?;
+    Ok(())
+}
+
+/// Deletes all conditional orders created at or after the given block.
+#[instrument(skip_all)]
+pub async fn delete_from_block(
+    ex: &mut PgConnection,
+    block_number: i64,
+) -> Result<(), sqlx::Error> {
+    const QUERY: &str = "DELETE FROM conditional_orders WHERE block_number >= $1";
+    sqlx::query(QUERY).bind(block_number).execute(ex).await?;
+    Ok(())
+}
+
+/// Returns all conditional orders that may still produce tradeable orders.
+#[instrument(skip_all)]
+pub async fn active(ex: &mut PgConnection) -> Result<Vec<ConditionalOrder>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT * FROM conditional_orders
+WHERE active
+ORDER BY block_number, log_index
+    "#;
+    sqlx::query_as(QUERY).fetch_all(ex).await
+}
+
+/// Marks the conditional order as one that will never be tradeable again.
+#[instrument(skip_all)]
+pub async fn deactivate(
+    ex: &mut PgConnection,
+    owner: &Address,
+    params_hash: &ByteArray<32>,
+) -> Result<(), sqlx::Error> {
+    const QUERY: &str =
+        "UPDATE conditional_orders SET active = false WHERE owner = $1 AND params_hash = $2";
+    sqlx::query(QUERY)
+        .bind(owner)
+        .bind(params_hash)
+        .execute(ex)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, sqlx::Connection};
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_roundtrip() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let order = |i: u8, block_number| ConditionalOrder {
+            owner: ByteArray([i; 20]),
+            params_hash: ByteArray([i; 32]),
+            handler: ByteArray([2; 20]),
+            salt: ByteArray([3; 32]),
+            static_input: vec![i],
+            block_number,
+            log_index: 0,
+            active: true,
+        };
+        let orders = [order(1, 1), order(2, 2), order(3, 3)];
+        insert(&mut db, &orders).await.unwrap();
+        // duplicates get ignored
+        insert(&mut db, &orders[..1]).await.unwrap();
+        assert_eq!(active(&mut db).await.unwrap(), orders);
+
+        deactivate(&mut db, &orders[0].owner, &orders[0].params_hash)
+            .await
+            .unwrap();
+        assert_eq!(active(&mut db).await.unwrap(), orders[1..]);
+
+        delete_from_block(&mut db, 3).await.unwrap();
+        assert_eq!(active(&mut db).await.unwrap(), orders[1..2]);
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_recreate_after_deactivation() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let order = ConditionalOrder {
+            owner: ByteArray([1; 20]),
+            params_hash: ByteArray([1; 32]),
+            block_number: 1,
+            active: true,
+            ..Default::default()
+        };
+        insert(&mut db, std::slice::from_ref(&order)).await.unwrap();
+        // the owner removed the conditional order
+        deactivate(&mut db, &order.owner, &order.params_hash)
+            .await
+            .unwrap();
+        assert!(active(&mut db).await.unwrap().is_empty());
+
+        // and created it again later on
+        let recreated = ConditionalOrder {
+            block_number: 5,
+            log_index: 2,
+            ..order.clone()
+        };
+        insert(&mut db, std::slice::from_ref(&recreated))
+            .await
+            .unwrap();
+        assert_eq!(active(&mut db).await.unwrap(), [recreated.clone()]);
+
+        // only the last creation within a batch counts
+        let again = ConditionalOrder {
+            block_number: 7,
+            ..order
+        };
+        insert(&mut db, &[recreated, again.clone()]).await.unwrap();
+        assert_eq!(active(&mut db).await.unwrap(), [again]);
+    }
+}
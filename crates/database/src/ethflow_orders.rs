@@ -1,5 +1,6 @@
 use {
-    crate::{OrderUid, PgTransaction, TransactionHash},
+    crate::{Address, OrderUid, PgTransaction, TransactionHash},
+    bigdecimal::BigDecimal,
     sqlx::{Executor, PgConnection},
     tracing::instrument,
 };
@@ -8,6 +9,7 @@ use {
 pub struct EthOrderPlacement {
     pub uid: OrderUid,
     pub valid_to: i64,
+    pub placement_tx_hash: Option<TransactionHash>,
 }
 
 #[instrument(skip_all)]
@@ -27,11 +29,12 @@ pub async fn insert_or_overwrite_ethflow_order(
     event: &EthOrderPlacement,
 ) -> Result<(), sqlx::Error> {
     const QUERY: &str = "\
-        INSERT INTO ethflow_orders (uid, valid_to) VALUES ($1, $2) ON CONFLICT (uid) DO UPDATE SET \
-                         valid_to = $2;";
+        INSERT INTO ethflow_orders (uid, valid_to, placement_tx_hash) VALUES ($1, $2, $3) ON \
+                         CONFLICT (uid) DO UPDATE SET valid_to = $2, placement_tx_hash = $3;";
     sqlx::query(QUERY)
         .bind(event.uid)
         .bind(event.valid_to)
+        .bind(event.placement_tx_hash)
         .execute(ex)
         .await?;
     Ok(())
@@ -57,6 +60,81 @@ pub async fn read_order(
     sqlx::query_as(QUERY).bind(id).fetch_optional(ex).await
 }
 
+/// An ethflow order without a placement tx hash together with the position
+/// of the event that placed it.
+#[derive(Clone, Debug, Default, sqlx::FromRow, Eq, PartialEq)]
+pub struct MissingPlacementTxHash {
+    pub uid: OrderUid,
+    pub block_number: i64,
+    pub log_index: i64,
+}
+
+/// Ethflow orders indexed before their placement tx hash got stored, ordered
+/// by the position of their placement event and starting after the given one.
+#[instrument(skip_all)]
+pub async fn missing_placement_tx_hashes(
+    ex: &mut PgConnection,
+    after: (i64, i64),
+    limit: i64,
+) -> Result<Vec<MissingPlacementTxHash>, sqlx::Error> {
+    const QUERY: &str = r#"
+        SELECT eo.uid, opo.block_number, opo.log_index
+        FROM ethflow_orders eo
+        JOIN onchain_placed_orders opo ON opo.uid = eo.uid
+        WHERE eo.placement_tx_hash IS NULL
+        AND NOT eo.placement_tx_hash_unavailable
+        AND NOT opo.is_reorged
+        AND (opo.block_number, opo.log_index) > ($1, $2)
+        ORDER BY opo.block_number, opo.log_index
+        LIMIT $3
+    "#;
+    sqlx::query_as(QUERY)
+        .bind(after.0)
+        .bind(after.1)
+        .bind(limit)
+        .fetch_all(ex)
+        .await
+}
+
+#[instrument(skip_all)]
+pub async fn set_placement_tx_hash(
+    ex: &mut PgConnection,
+    uid: &OrderUid,
+    tx_hash: &TransactionHash,
+) -> Result<(), sqlx::Error> {
+    const QUERY: &str = "UPDATE ethflow_orders SET placement_tx_hash = $2 WHERE uid = $1";
+    sqlx::query(QUERY)
+        .bind(uid)
+        .bind(tx_hash)
+        .execute(ex)
+        .await?;
+    Ok(())
+}
+
+/// Flags orders whose placement event couldn't be found so the backfill of
+/// their placement tx hash doesn't look them up again.
+#[instrument(skip_all)]
+pub async fn mark_placement_tx_hash_unavailable(
+    ex: &mut PgConnection,
+    uid: &OrderUid,
+) -> Result<(), sqlx::Error> {
+    const QUERY: &str =
+        "UPDATE ethflow_orders SET placement_tx_hash_unavailable = true WHERE uid = $1";
+    sqlx::query(QUERY).bind(uid).execute(ex).await?;
+    Ok(())
+}
+
+/// Every ethflow contract that placed an order. The owner of an ethflow order
+/// is always the ethflow contract that placed it.
+#[instrument(skip_all)]
+pub async fn ethflow_contracts(ex: &mut PgConnection) -> Result<Vec<Address>, sqlx::Error> {
+    const QUERY: &str = r#"
+        SELECT DISTINCT substring(uid FROM 33 FOR 20) AS contract FROM ethflow_orders
+        ORDER BY contract
+    "#;
+    sqlx::query_scalar(QUERY).fetch_all(ex).await
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct Refund {
     pub order_uid: OrderUid,
@@ -109,24 +187,29 @@ pub async fn insert_refund_tx_hash(
     Ok(())
 }
 
-#[instrument(skip_all)]
-pub async fn refundable_orders(
-    ex: &mut PgConnection,
-    since_valid_to: i64,
-    min_validity_duration: i64,
-    min_price_deviation: f64,
-) -> Result<Vec<EthOrderPlacement>, sqlx::Error> {
-    // condition (1.0 - o.buy_amount / GREATEST(oq.buy_amount,1)) >= $3 is added to
-    // skip refunding orders that have unrealistic slippage set. Those orders are
-    // unlikely to be filled so we don't want to be responsible for refunding them.
-    // Note that orders created with our UI should have realistic slippage in most
-    // cases.
-    //
-    // GREATEST(oq.buy_amount,1) added to avoid division by zero since
-    // table order_quotes contains entries with buy_amount = 0 (see
-    // https://github.com/cowprotocol/services/pull/1767#issuecomment-1680825756)
-    const QUERY: &str = r#"
-SELECT eo.uid, eo.valid_to from orders o
+#[derive(Clone, Debug, Default, sqlx::FromRow, Eq, PartialEq)]
+pub struct RefundableOrder {
+    pub uid: OrderUid,
+    pub valid_to: i64,
+    /// The amount of ETH the user gets back, i.e. the order's sell and fee
+    /// amount.
+    pub refund_amount: BigDecimal,
+}
+
+// Orders the refunder returns the ETH of, shared between the refunder and the
+// order status reported by the API.
+//
+// condition (1.0 - o.buy_amount / GREATEST(oq.buy_amount,1)) >= $3 is added to
+// skip refunding orders that have unrealistic slippage set. Those orders are
+// unlikely to be filled so we don't want to be responsible for refunding them.
+// Note that orders created with our UI should have realistic slippage in most
+// cases.
+//
+// GREATEST(oq.buy_amount,1) added to avoid division by zero since
+// table order_quotes contains entries with buy_amount = 0 (see
+// https://github.com/cowprotocol/services/pull/1767#issuecomment-1680825756)
+const REFUNDABLE_ORDERS: &str = r#"
+FROM orders o
 INNER JOIN ethflow_orders eo on eo.uid = o.uid 
 INNER JOIN order_quotes oq on o.uid = oq.order_uid
 LEFT JOIN trades t on o.uid = t.order_uid
@@ -141,7 +224,19 @@ AND eo.valid_to < $1
 AND o.sell_amount = oq.sell_amount
 AND (1.0 - o.buy_amount / GREATEST(oq.buy_amount,1)) >= $3
 AND eo.valid_to - extract(epoch from o.creation_timestamp)::int > $2
-    "#;
+"#;
+
+#[instrument(skip_all)]
+pub async fn refundable_orders(
+    ex: &mut PgConnection,
+    since_valid_to: i64,
+    min_validity_duration: i64,
+    min_price_deviation: f64,
+) -> Result<Vec<RefundableOrder>, sqlx::Error> {
+    const QUERY: &str = const_format::concatcp!(
+        "SELECT eo.uid, eo.valid_to, o.sell_amount + o.fee_amount AS refund_amount ",
+        REFUNDABLE_ORDERS,
+    );
     sqlx::query_as(QUERY)
         .bind(since_valid_to)
         .bind(min_validity_duration)
@@ -150,6 +245,27 @@ AND eo.valid_to - extract(epoch from o.creation_timestamp)::int > $2
         .await
 }
 
+/// Returns the subset of the given orders that are waiting for the refunder,
+/// according to the same criteria as [`refundable_orders`].
+#[instrument(skip_all)]
+pub async fn refundable_order_uids(
+    ex: &mut PgConnection,
+    uids: &[OrderUid],
+    since_valid_to: i64,
+    min_validity_duration: i64,
+    min_price_deviation: f64,
+) -> Result<Vec<OrderUid>, sqlx::Error> {
+    const QUERY: &str =
+        const_format::concatcp!("SELECT eo.uid ", REFUNDABLE_ORDERS, "AND eo.uid = ANY($4)");
+    sqlx::query_scalar(QUERY)
+        .bind(since_valid_to)
+        .bind(min_validity_duration)
+        .bind(min_price_deviation)
+        .bind(uids)
+        .fetch_all(ex)
+        .await
+}
+
 #[cfg(test)]
 mod tests {
     use {
@@ -157,10 +273,10 @@ mod tests {
         crate::{
             byte_array::ByteArray,
             events::{EventIndex, Trade, insert_trade},
+            onchain_broadcasted_orders::{OnchainOrderPlacement, insert_onchain_order},
             onchain_invalidations::insert_onchain_invalidation,
             orders::{Order, Quote, insert_order, insert_quote},
         },
-        bigdecimal::BigDecimal,
         chrono::{TimeZone, Utc},
         sqlx::Connection,
     };
@@ -202,6 +318,120 @@ mod tests {
         assert_eq!(order_2.valid_to, order_.valid_to);
     }
 
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_missing_placement_tx_hashes() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        for (id, block_number, placement_tx_hash) in
+            [(1, 2, None), (2, 1, None), (3, 1, Some(ByteArray([3; 32])))]
+        {
+            let uid = ByteArray([id; 56]);
+            insert_or_overwrite_ethflow_order(
+                &mut db,
+                &EthOrderPlacement {
+                    uid,
+                    placement_tx_hash,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+            insert_onchain_order(
+                &mut db,
+                &EventIndex {
+                    block_number,
+                    log_index: id.into(),
+                },
+                &OnchainOrderPlacement {
+                    order_uid: uid,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        let missing = missing_placement_tx_hashes(&mut db, (0, 0), 10)
+            .await
+            .unwrap();
+        assert_eq!(
+            missing,
+            vec![
+                MissingPlacementTxHash {
+                    uid: ByteArray([2; 56]),
+                    block_number: 1,
+                    log_index: 2,
+                },
+                MissingPlacementTxHash {
+                    uid: ByteArray([1; 56]),
+                    block_number: 2,
+                    log_index: 1,
+                },
+            ]
+        );
+        assert_eq!(
+            missing_placement_tx_hashes(&mut db, (1, 2), 10)
+                .await
+                .unwrap(),
+            missing[1..]
+        );
+
+        set_placement_tx_hash(&mut db, &ByteArray([1; 56]), &ByteArray([1; 32]))
+            .await
+            .unwrap();
+        assert_eq!(
+            missing_placement_tx_hashes(&mut db, (0, 0), 10)
+                .await
+                .unwrap(),
+            missing[..1]
+        );
+
+        mark_placement_tx_hash_unavailable(&mut db, &ByteArray([2; 56]))
+            .await
+            .unwrap();
+        assert!(
+            missing_placement_tx_hashes(&mut db, (0, 0), 10)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_ethflow_contracts() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        assert!(ethflow_contracts(&mut db).await.unwrap().is_empty());
+
+        let uid = |contract: u8, id: u8| {
+            let mut uid = [id; 56];
+            uid[32..52].fill(contract);
+            ByteArray(uid)
+        };
+        for uid in [uid(1, 1), uid(1, 2), uid(2, 3)] {
+            insert_or_overwrite_ethflow_order(
+                &mut db,
+                &EthOrderPlacement {
+                    uid,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(
+            ethflow_contracts(&mut db).await.unwrap(),
+            vec![ByteArray([1; 20]), ByteArray([2; 20])]
+        );
+    }
+
     fn refund(order_uid: OrderUid) -> Refund {
         Refund {
             order_uid,
@@ -219,10 +449,12 @@ mod tests {
         let order_1 = EthOrderPlacement {
             uid: ByteArray([1u8; 56]),
             valid_to: 1,
+            placement_tx_hash: None,
         };
         let order_2 = EthOrderPlacement {
             uid: ByteArray([2u8; 56]),
             valid_to: 2,
+            placement_tx_hash: None,
         };
 
         insert_or_overwrite_orders(&mut db, vec![order_1.clone(), order_2.clone()].as_slice())
@@ -248,10 +480,12 @@ mod tests {
         let order_1 = EthOrderPlacement {
             uid: ByteArray([1u8; 56]),
             valid_to: 1,
+            placement_tx_hash: None,
         };
         let order_2 = EthOrderPlacement {
             uid: ByteArray([2u8; 56]),
             valid_to: 2,
+            placement_tx_hash: None,
         };
 
         insert_or_overwrite_orders(&mut db, vec![order_1.clone(), order_2.clone()].as_slice())
@@ -287,6 +521,7 @@ mod tests {
             let eth_order = EthOrderPlacement {
                 uid: order_uid,
                 valid_to: 4,
+                placement_tx_hash: None,
             };
             let order = Order {
                 uid: order_uid,
@@ -308,6 +543,13 @@ mod tests {
                 refund: None,
             }
         }
+        fn refundable(order_parts: &EthflowOrderParts) -> RefundableOrder {
+            RefundableOrder {
+                uid: order_parts.eth_order.uid,
+                valid_to: order_parts.eth_order.valid_to,
+                refund_amount: &order_parts.order.sell_amount + &order_parts.order.fee_amount,
+            }
+        }
         async fn insert_order_parts_in_db(db: &mut PgConnection, order_parts: &EthflowOrderParts) {
             insert_order(db, &order_parts.order).await.unwrap();
             insert_or_overwrite_ethflow_order(db, &order_parts.eth_order)
@@ -325,7 +567,15 @@ mod tests {
         insert_order_parts_in_db(&mut db, &order_parts).await;
         // all criteria are fulfilled
         let orders = refundable_orders(&mut db, 5, 1, 0.01).await.unwrap();
-        assert_eq!(orders, vec![order_parts.eth_order.clone()]);
+        assert_eq!(orders, vec![refundable(&order_parts)]);
+        let uids = refundable_order_uids(&mut db, &[order_uid_1], 5, 1, 0.01)
+            .await
+            .unwrap();
+        assert_eq!(uids, vec![order_uid_1]);
+        let uids = refundable_order_uids(&mut db, &[order_uid_1], 5, 1, 0.53)
+            .await
+            .unwrap();
+        assert!(uids.is_empty());
         // slippage is not fulfilled
         let orders = refundable_orders(&mut db, 5, 1, 0.53).await.unwrap();
         assert_eq!(orders, Vec::new());
@@ -377,7 +627,7 @@ mod tests {
         insert_order_parts_in_db(&mut db, &order_parts).await;
         // the newly created order should be found
         let orders = refundable_orders(&mut db, 5, 1, 0.001).await.unwrap();
-        assert_eq!(orders, vec![order_parts.eth_order]);
+        assert_eq!(orders, vec![refundable(&order_parts)]);
         insert_onchain_invalidation(
             &mut db,
             &EventIndex {
@@ -424,6 +674,7 @@ mod tests {
             let ethflow_order = EthOrderPlacement {
                 uid: order_uid,
                 valid_to: i as i64,
+                placement_tx_hash: None,
             };
             insert_or_overwrite_ethflow_order(&mut db, &ethflow_order)
                 .await
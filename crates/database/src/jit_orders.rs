@@ -34,7 +34,9 @@ NULL AS onchain_user,
 NULL AS onchain_placement_error,
 COALESCE((SELECT SUM(executed_fee) FROM order_execution oe WHERE oe.order_uid = o.uid), 0) as executed_fee,
 COALESCE((SELECT executed_fee_token FROM order_execution oe WHERE oe.order_uid = o.uid LIMIT 1), o.sell_token) as executed_fee_token, -- TODO surplus token
-NULL AS full_app_data
+NULL AS full_app_data,
+NULL::bigint[] AS pre_hooks_gas,
+NULL::bigint[] AS post_hooks_gas
 "#;
 
 pub const FROM: &str = "jit_orders o";
@@ -1,7 +1,9 @@
+pub mod api_keys;
 pub mod app_data;
 pub mod auction;
 pub mod auction_prices;
 pub mod byte_array;
+pub mod conditional_orders;
 pub mod cow_amms;
 pub mod ethflow_orders;
 pub mod events;
@@ -11,9 +13,11 @@ pub mod last_indexed_blocks;
 pub mod leader_pg_lock;
 pub mod onchain_broadcasted_orders;
 pub mod onchain_invalidations;
+pub mod order_amendments;
 pub mod order_events;
 pub mod order_execution;
 pub mod order_history;
+pub mod order_hooks_gas;
 pub mod orders;
 pub mod quotes;
 pub mod reference_scores;
@@ -21,8 +25,10 @@ pub mod settlement_executions;
 pub mod settlements;
 pub mod solver_competition;
 pub mod solver_competition_v2;
+pub mod solver_rewards;
 pub mod surplus_capturing_jit_order_owners;
 pub mod trades;
+pub mod triggered_orders;
 
 use {
     byte_array::ByteArray,
@@ -51,17 +57,23 @@ pub type PgTransaction<'a> = sqlx::Transaction<'a, sqlx::Postgres>;
 
 /// The names of tables we use in the db.
 pub const TABLES: &[&str] = &[
+    "accounting_periods",
+    "api_keys",
     "app_data",
     "auctions",
+    "conditional_orders",
     "cow_amms",
     "ethflow_orders",
     "ethflow_refunds",
+    "fairness_baselines",
     "interactions",
     "invalidations",
     "jit_orders",
     "last_indexed_blocks",
     "onchain_order_invalidations",
     "onchain_placed_orders",
+    "order_amendments",
+    "order_hooks_gas",
     "presignature_events",
     "proposed_jit_orders",
     "quotes",
@@ -69,8 +81,11 @@ pub const TABLES: &[&str] = &[
     "settlement_executions",
     "settlements",
     "solver_competitions",
+    "solver_payouts",
+    "solver_rewards",
     "surplus_capturing_jit_order_owners",
     "trades",
+    "triggered_orders",
 ];
 
 /// The names of potentially big volume tables we use in the db.
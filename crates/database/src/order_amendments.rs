@@ -0,0 +1,122 @@
+use {
+    crate::OrderUid,
+    sqlx::{
+        PgConnection,
+        types::{
+            BigDecimal,
+            chrono::{DateTime, Utc},
+        },
+    },
+    tracing::instrument,
+};
+
+/// An order that got cancelled and replaced by a new one through the order
+/// amendment endpoint.
+#[derive(Clone, Debug, Default, Eq, PartialEq, sqlx::FromRow)]
+pub struct OrderAmendment {
+    pub new_order_uid: OrderUid,
+    pub old_order_uid: OrderUid,
+    /// Amounts the old order executed before it got amended.
+    pub executed_sell_amount: BigDecimal,
+    pub executed_buy_amount: BigDecimal,
+    pub executed_fee_amount: BigDecimal,
+    pub amendment_timestamp: DateTime<Utc>,
+}
+
+#[instrument(skip_all)]
+pub async fn insert(ex: &mut PgConnection, amendment: &OrderAmendment) -> Result<(), sqlx::Error> {
+    const QUERY: &str = r#"
+INSERT INTO order_amendments (
+    new_order_uid,
+    old_order_uid,
+    executed_sell_amount,
+    executed_buy_amount,
+    executed_fee_amount,
+    amendment_timestamp
+)
+VALUES ($1, $2, $3, $4, $5, $6)
+    "#;
+    sqlx::query(QUERY)
+        .bind(amendment.new_order_uid)
+        .bind(amendment.old_order_uid)
+        .bind(&amendment.executed_sell_amount)
+        .bind(&amendment.executed_buy_amount)
+        .bind(&amendment.executed_fee_amount)
+        .bind(amendment.amendment_timestamp)
+        .execute(ex)
+        .await?;
+    Ok(())
+}
+
+/// Returns all amendments of the lineage the order is part of, i.e. the ones
+/// that led to the order as well as the ones that replaced it, from the oldest
+/// to the newest.
+#[instrument(skip_all)]
+pub async fn lineage(
+    ex: &mut PgConnection,
+    order_uid: &OrderUid,
+) -> Result<Vec<OrderAmendment>, sqlx::Error> {
+    const QUERY: &str = r#"
+WITH RECURSIVE ancestors AS (
+    SELECT * FROM order_amendments WHERE new_order_uid = $1
+    UNION ALL
+    SELECT a.* FROM order_amendments a
+    JOIN ancestors ON a.new_order_uid = ancestors.old_order_uid
+),
+descendants AS (
+    SELECT * FROM order_amendments WHERE old_order_uid = $1
+    UNION ALL
+    SELECT a.* FROM order_amendments a
+    JOIN descendants ON a.old_order_uid = descendants.new_order_uid
+)
+SELECT * FROM ancestors
+UNION ALL
+SELECT * FROM descendants
+ORDER BY amendment_timestamp
+    "#;
+    sqlx::query_as(QUERY).bind(order_uid).fetch_all(ex).await
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::byte_array::ByteArray, sqlx::Connection};
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_order_amendments_lineage() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let amendment = |old: u8, new: u8, timestamp: i64| OrderAmendment {
+            new_order_uid: ByteArray([new; 56]),
+            old_order_uid: ByteArray([old; 56]),
+            executed_sell_amount: BigDecimal::from(old),
+            amendment_timestamp: DateTime::from_timestamp(timestamp, 0).unwrap(),
+            ..Default::default()
+        };
+        let first = amendment(1, 2, 1);
+        let second = amendment(2, 3, 2);
+        let unrelated = amendment(4, 5, 3);
+        for amendment in [&first, &second, &unrelated] {
+            insert(&mut db, amendment).await.unwrap();
+        }
+
+        let expected = vec![first.clone(), second.clone()];
+        for uid in [1, 2, 3] {
+            assert_eq!(
+                lineage(&mut db, &ByteArray([uid; 56])).await.unwrap(),
+                expected
+            );
+        }
+        assert!(
+            lineage(&mut db, &ByteArray([6; 56]))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // Every order can only be amended once.
+        assert!(insert(&mut db, &amendment(1, 6, 4)).await.is_err());
+    }
+}
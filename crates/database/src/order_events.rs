@@ -31,6 +31,9 @@ pub enum OrderEventLabel {
     Traded,
     /// Order was cancelled by the user.
     Cancelled,
+    /// The trigger condition of the order was met so it may now be included
+    /// in auctions.
+    Triggered,
 }
 
 /// Contains a single event of the life cycle of an order and when it was
@@ -0,0 +1,66 @@
+use {crate::OrderUid, sqlx::PgConnection, tracing::instrument};
+
+/// Gas used by the hooks of an order as measured by simulating them when the
+/// order got placed.
+#[derive(Clone, Debug, Default, Eq, PartialEq, sqlx::FromRow)]
+pub struct OrderHooksGas {
+    pub order_uid: OrderUid,
+    pub pre_hooks: Vec<i64>,
+    pub post_hooks: Vec<i64>,
+}
+
+#[instrument(skip_all)]
+pub async fn insert(ex: &mut PgConnection, hooks_gas: &OrderHooksGas) -> Result<(), sqlx::Error> {
+    const QUERY: &str = r#"
+INSERT INTO order_hooks_gas (order_uid, pre_hooks, post_hooks)
+VALUES ($1, $2, $3)
+    "#;
+    sqlx::query(QUERY)
+        .bind(hooks_gas.order_uid)
+        .bind(&hooks_gas.pre_hooks)
+        .bind(&hooks_gas.post_hooks)
+        .execute(ex)
+        .await?;
+    Ok(())
+}
+
+#[instrument(skip_all)]
+pub async fn fetch(
+    ex: &mut PgConnection,
+    order_uid: &OrderUid,
+) -> Result<Option<OrderHooksGas>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT order_uid, pre_hooks, post_hooks
+FROM order_hooks_gas
+WHERE order_uid = $1
+    "#;
+    sqlx::query_as(QUERY)
+        .bind(order_uid)
+        .fetch_optional(ex)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::byte_array::ByteArray, sqlx::Connection};
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_order_hooks_gas_roundtrip() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let hooks_gas = OrderHooksGas {
+            order_uid: ByteArray([1; 56]),
+            pre_hooks: vec![21_000, 50_000],
+            post_hooks: vec![],
+        };
+        insert(&mut db, &hooks_gas).await.unwrap();
+        assert_eq!(
+            fetch(&mut db, &ByteArray([1; 56])).await.unwrap(),
+            Some(hooks_gas)
+        );
+        assert_eq!(fetch(&mut db, &ByteArray([2; 56])).await.unwrap(), None);
+    }
+}
@@ -469,6 +469,58 @@ AND cancellation_timestamp IS NULL
         .map(|_| ())
 }
 
+/// Like [`cancel_order`] but returns whether the order got cancelled by this
+/// call, i.e. `false` if it was already cancelled or doesn't exist. This lets
+/// callers that replace orders detect concurrent cancellations.
+pub async fn try_cancel_order(
+    ex: &mut PgConnection,
+    order_uid: &OrderUid,
+    timestamp: DateTime<Utc>,
+) -> Result<bool, sqlx::Error> {
+    const QUERY: &str = r#"
+UPDATE orders
+SET cancellation_timestamp = $1
+WHERE uid = $2
+AND cancellation_timestamp IS NULL
+    "#;
+    let result = sqlx::query(QUERY)
+        .bind(timestamp)
+        .bind(order_uid.0.as_ref())
+        .execute(ex)
+        .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+/// Amounts an order executed summed over all of its trades.
+#[derive(Clone, Debug, Default, Eq, PartialEq, sqlx::FromRow)]
+pub struct ExecutedAmounts {
+    pub sell: BigDecimal,
+    pub buy: BigDecimal,
+    pub fee: BigDecimal,
+}
+
+/// Reads the amounts the order executed and locks its trades until the end
+/// of the transaction so the amounts can be relied upon while modifying the
+/// order (e.g. when amending it).
+pub async fn executed_amounts_for_update(
+    ex: &mut PgConnection,
+    order_uid: &OrderUid,
+) -> Result<ExecutedAmounts, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT
+    COALESCE(SUM(t.sell_amount), 0) AS sell,
+    COALESCE(SUM(t.buy_amount), 0) AS buy,
+    COALESCE(SUM(t.fee_amount), 0) AS fee
+FROM (
+    SELECT sell_amount, buy_amount, fee_amount
+    FROM trades
+    WHERE order_uid = $1
+    FOR UPDATE
+) t
+    "#;
+    sqlx::query_as(QUERY).bind(order_uid).fetch_one(ex).await
+}
+
 /// Interactions are read as arrays of their fields: target, value, data.
 /// This is done as sqlx does not support reading arrays of more complicated
 /// types than just one field. The pre_ and post_interaction's data of
@@ -511,6 +563,10 @@ pub struct FullOrder {
     pub executed_fee: BigDecimal,
     pub executed_fee_token: Address,
     pub full_app_data: Option<Vec<u8>>,
+    /// Gas used by the pre-hooks when they got simulated at order placement.
+    pub pre_hooks_gas: Option<Vec<i64>>,
+    /// Gas used by the post-hooks when they got simulated at order placement.
+    pub post_hooks_gas: Option<Vec<i64>>,
 }
 
 impl FullOrder {
@@ -626,7 +682,9 @@ array(Select (p.target, p.value, p.data) from interactions p where p.order_uid =
 (SELECT onchain_o.placement_error from onchain_placed_orders onchain_o where onchain_o.uid = o.uid limit 1) as onchain_placement_error,
 COALESCE((SELECT SUM(executed_fee) FROM order_execution oe WHERE oe.order_uid = o.uid), 0) as executed_fee,
 COALESCE((SELECT executed_fee_token FROM order_execution oe WHERE oe.order_uid = o.uid LIMIT 1), o.sell_token) as executed_fee_token, -- TODO surplus token
-(SELECT full_app_data FROM app_data ad WHERE o.app_data = ad.contract_app_data LIMIT 1) as full_app_data
+(SELECT full_app_data FROM app_data ad WHERE o.app_data = ad.contract_app_data LIMIT 1) as full_app_data,
+(SELECT h.pre_hooks FROM order_hooks_gas h WHERE h.order_uid = o.uid) as pre_hooks_gas,
+(SELECT h.post_hooks FROM order_hooks_gas h WHERE h.order_uid = o.uid) as post_hooks_gas
 "#;
 
 pub const FROM: &str = "orders o";
@@ -654,6 +712,32 @@ pub async fn single_full_order_with_quote(
     sqlx::query_as(QUERY).bind(uid).fetch_optional(ex).await
 }
 
+/// Ethflow orders placed by the given transaction.
+#[instrument(skip_all)]
+pub async fn full_orders_with_quote_by_placement_tx_hash(
+    ex: &mut PgConnection,
+    tx_hash: &TransactionHash,
+) -> Result<Vec<FullOrderWithQuote>, sqlx::Error> {
+    #[rustfmt::skip]
+    const QUERY: &str = const_format::concatcp!(
+        "SELECT ", SELECT,
+        ", o_quotes.sell_amount as quote_sell_amount",
+        ", o_quotes.buy_amount as quote_buy_amount",
+        ", o_quotes.gas_amount as quote_gas_amount",
+        ", o_quotes.gas_price as quote_gas_price",
+        ", o_quotes.sell_token_price as quote_sell_token_price",
+        ", o_quotes.verified as quote_verified",
+        ", o_quotes.metadata as quote_metadata",
+        ", o_quotes.solver as solver",
+        " FROM ", FROM,
+        " JOIN ethflow_orders eo ON eo.uid = o.uid",
+        " LEFT JOIN order_quotes o_quotes ON o.uid = o_quotes.order_uid",
+        " WHERE eo.placement_tx_hash = $1",
+        " ORDER BY o.uid",
+        );
+    sqlx::query_as(QUERY).bind(tx_hash).fetch_all(ex).await
+}
+
 // Partial query for getting the log indices of events of a single settlement.
 //
 // This will fail if we ever have multiple settlements in the same transaction
@@ -782,7 +866,9 @@ pub fn solvable_orders(
         NULL AS onchain_placement_error,
         COALESCE(fee_agg.executed_fee,0)        AS executed_fee,
         COALESCE(fee_agg.executed_fee_token, lo.sell_token) AS executed_fee_token,
-        ad.full_app_data
+        ad.full_app_data,
+        hg.pre_hooks AS pre_hooks_gas,
+        hg.post_hooks AS post_hooks_gas
     FROM live_orders lo
     LEFT JOIN LATERAL (
         SELECT NOT signed AS unsigned
@@ -811,6 +897,7 @@ pub fn solvable_orders(
         WHERE  order_uid = lo.uid
     ) fee_agg ON TRUE
     LEFT JOIN app_data ad ON ad.contract_app_data = lo.app_data
+    LEFT JOIN order_hooks_gas hg ON hg.order_uid = lo.uid
     LEFT JOIN trades_agg ta ON  ta.order_uid = lo.uid
     WHERE ((lo.kind = 'sell' AND COALESCE(ta.sum_sell,0) < lo.sell_amount) OR
            (lo.kind = 'buy'  AND COALESCE(ta.sum_buy ,0) < lo.buy_amount))
@@ -1099,6 +1186,7 @@ mod tests {
             &EthOrderPlacement {
                 uid: OrderUid::default(),
                 valid_to: user_valid_to,
+                placement_tx_hash: None,
             },
         )
         .await
@@ -1502,6 +1590,65 @@ mod tests {
         assert_eq!(quote, quote_);
     }
 
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_full_orders_with_quote_by_placement_tx_hash() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        for (id, tx) in [(2, Some(1)), (1, Some(1)), (3, Some(2)), (4, None)] {
+            let uid = ByteArray([id; 56]);
+            insert_order(
+                &mut db,
+                &Order {
+                    uid,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+            insert_or_overwrite_ethflow_order(
+                &mut db,
+                &EthOrderPlacement {
+                    uid,
+                    valid_to: 1,
+                    placement_tx_hash: tx.map(|tx| ByteArray([tx; 32])),
+                },
+            )
+            .await
+            .unwrap();
+        }
+        insert_quote(
+            &mut db,
+            &Quote {
+                order_uid: ByteArray([1; 56]),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let orders = full_orders_with_quote_by_placement_tx_hash(&mut db, &ByteArray([1; 32]))
+            .await
+            .unwrap();
+        assert_eq!(
+            orders
+                .iter()
+                .map(|order| order.full_order.uid)
+                .collect::<Vec<_>>(),
+            vec![ByteArray([1; 56]), ByteArray([2; 56])]
+        );
+        assert!(orders[0].quote_buy_amount.is_some());
+        assert!(orders[1].quote_buy_amount.is_none());
+        assert!(
+            full_orders_with_quote_by_placement_tx_hash(&mut db, &ByteArray([3; 32]))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
     #[tokio::test]
     #[ignore]
     async fn postgres_order_with_quote_roundtrip() {
@@ -1614,6 +1761,75 @@ mod tests {
         assert_eq!(time, order.cancellation_timestamp.unwrap());
     }
 
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_try_cancel_order() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let order = Order::default();
+        insert_order(&mut db, &order).await.unwrap();
+
+        let time = Utc.timestamp_opt(1234567890, 0).unwrap();
+        assert!(try_cancel_order(&mut db, &order.uid, time).await.unwrap());
+        assert!(!try_cancel_order(&mut db, &order.uid, time).await.unwrap());
+        assert!(
+            !try_cancel_order(&mut db, &ByteArray([1; 56]), time)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_executed_amounts_for_update() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let order = Order::default();
+        insert_order(&mut db, &order).await.unwrap();
+        assert_eq!(
+            executed_amounts_for_update(&mut db, &order.uid)
+                .await
+                .unwrap(),
+            ExecutedAmounts {
+                sell: 0.into(),
+                buy: 0.into(),
+                fee: 0.into(),
+            }
+        );
+
+        for block_number in [1, 2] {
+            crate::events::insert_trade(
+                &mut db,
+                &EventIndex {
+                    block_number,
+                    ..Default::default()
+                },
+                &Trade {
+                    order_uid: order.uid,
+                    sell_amount_including_fee: 10.into(),
+                    buy_amount: 100.into(),
+                    fee_amount: 1.into(),
+                },
+            )
+            .await
+            .unwrap();
+        }
+        assert_eq!(
+            executed_amounts_for_update(&mut db, &order.uid)
+                .await
+                .unwrap(),
+            ExecutedAmounts {
+                sell: 20.into(),
+                buy: 200.into(),
+                fee: 2.into(),
+            }
+        );
+    }
+
     // In the schema we set the type of executed amounts in individual events to a
     // 78 decimal digit number. Summing over multiple events could overflow this
     // because the smart contract only guarantees that the filled amount (which
@@ -1886,6 +2102,7 @@ mod tests {
         let ethflow_order = EthOrderPlacement {
             uid: order.uid,
             valid_to: 2,
+            placement_tx_hash: None,
         };
         insert_or_overwrite_ethflow_order(&mut db, &ethflow_order)
             .await
@@ -2185,6 +2402,54 @@ mod tests {
         assert_eq!(full_order.full_app_data, Some(full_app_data));
     }
 
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_order_hooks_gas() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let order = Order {
+            sell_amount: 10.into(),
+            buy_amount: 100.into(),
+            valid_to: 10,
+            ..Default::default()
+        };
+        insert_order(&mut db, &order).await.unwrap();
+        let full_order = single_full_order_with_quote(&mut db, &order.uid)
+            .await
+            .unwrap()
+            .unwrap()
+            .full_order;
+        assert_eq!(full_order.pre_hooks_gas, None);
+        assert_eq!(full_order.post_hooks_gas, None);
+
+        crate::order_hooks_gas::insert(
+            &mut db,
+            &crate::order_hooks_gas::OrderHooksGas {
+                order_uid: order.uid,
+                pre_hooks: vec![21_000],
+                post_hooks: vec![50_000, 60_000],
+            },
+        )
+        .await
+        .unwrap();
+        let full_order = single_full_order_with_quote(&mut db, &order.uid)
+            .await
+            .unwrap()
+            .unwrap()
+            .full_order;
+        assert_eq!(full_order.pre_hooks_gas, Some(vec![21_000]));
+        assert_eq!(full_order.post_hooks_gas, Some(vec![50_000, 60_000]));
+
+        let solvable = solvable_orders(&mut db, 0)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(solvable[0].pre_hooks_gas, Some(vec![21_000]));
+        assert_eq!(solvable[0].post_hooks_gas, Some(vec![50_000, 60_000]));
+    }
+
     #[tokio::test]
     #[ignore]
     async fn postgres_updated_order_uids_after() {
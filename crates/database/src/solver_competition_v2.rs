@@ -40,6 +40,7 @@ pub struct ProposedSolution {
     pub uid: i64,
     pub is_winner: bool,
     pub filtered_out: bool,
+    pub filter_reason: Option<FilterReason>,
     pub score: BigDecimal,
     pub price_tokens: Vec<Address>,
     pub price_values: Vec<BigDecimal>,
@@ -54,6 +55,8 @@ pub struct ProposedTrade {
     pub executed_buy: BigDecimal,
     pub sell_token: Address,
     pub buy_token: Address,
+    pub surplus_score: Option<BigDecimal>,
+    pub protocol_fee_score: Option<BigDecimal>,
 }
 
 #[derive(sqlx::FromRow)]
@@ -68,6 +71,7 @@ pub struct SolverCompetition {
     pub solutions: Vec<ProposedSolution>,
     pub trades: Vec<ProposedTrade>,
     pub reference_scores: Vec<ReferenceScore>,
+    pub fairness_baselines: Vec<FairnessBaseline>,
 }
 
 #[instrument(skip_all)]
@@ -140,7 +144,7 @@ pub async fn load_by_id(
     // we set `ranking to uid + 1` because uids get assigned from best to worst
     // solution starting at 0
     const FETCH_SOLUTIONS: &str = r#"
-        SELECT uid, uid + 1 as ranking, solver, is_winner, filtered_out, score, price_tokens, price_values
+        SELECT uid, uid + 1 as ranking, solver, is_winner, filtered_out, filter_reason, score, price_tokens, price_values
         FROM proposed_solutions
         WHERE auction_id = $1;
     "#;
@@ -152,7 +156,8 @@ pub async fn load_by_id(
     const FETCH_TRADES: &str = r#"
         SELECT pte.solution_uid, pte.order_uid, executed_sell, executed_buy, 
             COALESCE(o.sell_token, pjo.sell_token) AS sell_token,
-            COALESCE(o.buy_token, pjo.buy_token) AS buy_token
+            COALESCE(o.buy_token, pjo.buy_token) AS buy_token,
+            pte.surplus_score, pte.protocol_fee_score
         FROM proposed_trade_executions AS pte
         LEFT JOIN orders o ON
             pte.order_uid = o.uid
@@ -179,12 +184,23 @@ pub async fn load_by_id(
         .fetch_all(ex.deref_mut())
         .await?;
 
+    const FETCH_FAIRNESS_BASELINES: &str = r#"
+        SELECT sell_token, buy_token, score
+        FROM fairness_baselines
+        WHERE auction_id = $1;
+    "#;
+    let fairness_baselines: Vec<FairnessBaseline> = sqlx::query_as(FETCH_FAIRNESS_BASELINES)
+        .bind(id)
+        .fetch_all(ex.deref_mut())
+        .await?;
+
     Ok(Some(SolverCompetition {
         auction,
         settlements,
         solutions,
         trades,
         reference_scores,
+        fairness_baselines,
     }))
 }
 
@@ -327,6 +343,7 @@ pub struct Solution {
     pub solver: Address,
     pub is_winner: bool,
     pub filtered_out: bool,
+    pub filter_reason: Option<FilterReason>,
     pub score: BigDecimal,
     pub orders: Vec<Order>,
     // UCP prices
@@ -344,6 +361,30 @@ pub struct Order {
     pub executed_sell: BigDecimal,
     pub executed_buy: BigDecimal,
     pub side: OrderKind,
+    /// Surplus contributing to the score in native token. Missing for orders
+    /// that don't capture surplus.
+    pub surplus_score: Option<BigDecimal>,
+    /// Protocol fees contributing to the score in native token. Missing for
+    /// orders that don't capture surplus.
+    pub protocol_fee_score: Option<BigDecimal>,
+}
+
+/// Why a solution got filtered out by the winner selection.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, sqlx::Type)]
+#[sqlx(type_name = "SolutionFilterReason")]
+#[sqlx(rename_all = "lowercase")]
+pub enum FilterReason {
+    /// The solution executed a directed token pair at a lower score than the
+    /// fairness baseline of that pair.
+    Unfair,
+}
+
+/// Best score of a solution only trading a single directed token pair.
+#[derive(Clone, Debug, Default, PartialEq, sqlx::FromRow)]
+pub struct FairnessBaseline {
+    pub sell_token: Address,
+    pub buy_token: Address,
+    pub score: BigDecimal,
 }
 
 #[instrument(skip_all)]
@@ -351,6 +392,7 @@ pub async fn save(
     ex: &mut PgTransaction<'_>,
     auction_id: AuctionId,
     solutions: &[Solution],
+    fairness_baselines: &[FairnessBaseline],
 ) -> Result<(), sqlx::Error> {
     if solutions.is_empty() {
         return Ok(());
@@ -359,6 +401,7 @@ pub async fn save(
     save_solutions(ex, auction_id, solutions).await?;
     save_trade_executions(ex, auction_id, solutions).await?;
     save_jit_orders(ex, auction_id, solutions).await?;
+    save_fairness_baselines(ex, auction_id, fairness_baselines).await?;
 
     Ok(())
 }
@@ -371,7 +414,7 @@ async fn save_solutions(
 ) -> Result<(), sqlx::Error> {
     let mut builder = QueryBuilder::new(
         r#"INSERT INTO proposed_solutions 
-        (auction_id, uid, id, solver, is_winner, filtered_out, score, price_tokens, price_values)"#,
+        (auction_id, uid, id, solver, is_winner, filtered_out, filter_reason, score, price_tokens, price_values)"#,
     );
 
     builder.push_values(solutions.iter(), |mut b, solution| {
@@ -381,6 +424,7 @@ async fn save_solutions(
             .push_bind(solution.solver)
             .push_bind(solution.is_winner)
             .push_bind(solution.filtered_out)
+            .push_bind(solution.filter_reason)
             .push_bind(&solution.score)
             .push_bind(&solution.price_tokens)
             .push_bind(&solution.price_values);
@@ -399,7 +443,7 @@ async fn save_trade_executions(
 ) -> Result<(), sqlx::Error> {
     let mut builder = QueryBuilder::new(
         r#"INSERT INTO proposed_trade_executions 
-        (auction_id, solution_uid, order_uid, executed_sell, executed_buy)"#,
+        (auction_id, solution_uid, order_uid, executed_sell, executed_buy, surplus_score, protocol_fee_score)"#,
     );
 
     builder.push_values(
@@ -414,7 +458,9 @@ async fn save_trade_executions(
                 .push_bind(solution_uid)
                 .push_bind(order.uid)
                 .push_bind(order.executed_sell.clone())
-                .push_bind(order.executed_buy.clone());
+                .push_bind(order.executed_buy.clone())
+                .push_bind(order.surplus_score.clone())
+                .push_bind(order.protocol_fee_score.clone());
         },
     );
 
@@ -457,6 +503,30 @@ async fn save_jit_orders(
     Ok(())
 }
 
+#[instrument(skip_all)]
+async fn save_fairness_baselines(
+    ex: &mut PgTransaction<'_>,
+    auction_id: AuctionId,
+    fairness_baselines: &[FairnessBaseline],
+) -> Result<(), sqlx::Error> {
+    if fairness_baselines.is_empty() {
+        return Ok(());
+    }
+
+    let mut builder = QueryBuilder::new(
+        "INSERT INTO fairness_baselines (auction_id, sell_token, buy_token, score)",
+    );
+    builder.push_values(fairness_baselines, |mut b, baseline| {
+        b.push_bind(auction_id)
+            .push_bind(baseline.sell_token)
+            .push_bind(baseline.buy_token)
+            .push_bind(&baseline.score);
+    });
+    builder.push(" ON CONFLICT (auction_id, sell_token, buy_token) DO NOTHING;");
+    builder.build().execute(ex.deref_mut()).await?;
+    Ok(())
+}
+
 #[derive(sqlx::FromRow)]
 struct SolutionRow {
     uid: i64,
@@ -464,6 +534,7 @@ struct SolutionRow {
     solver: Address,
     is_winner: bool,
     filtered_out: bool,
+    filter_reason: Option<FilterReason>,
     score: BigDecimal,
     price_tokens: Vec<Address>,
     price_values: Vec<BigDecimal>,
@@ -475,13 +546,16 @@ struct SolutionRow {
     limit_sell: BigDecimal,
     limit_buy: BigDecimal,
     side: OrderKind,
+    surplus_score: Option<BigDecimal>,
+    protocol_fee_score: Option<BigDecimal>,
 }
 
 const BASE_SOLUTIONS_QUERY: &str = r#"
     SELECT
-        ps.uid, ps.id, ps.solver, ps.is_winner, ps.filtered_out,
+        ps.uid, ps.id, ps.solver, ps.is_winner, ps.filtered_out, ps.filter_reason,
         ps.score, ps.price_tokens, ps.price_values,
         pse.order_uid, pse.executed_sell, pse.executed_buy,
+        pse.surplus_score, pse.protocol_fee_score,
         COALESCE(pjo.sell_token, o.sell_token) AS sell_token,
         COALESCE(pjo.buy_token, o.buy_token) AS buy_token,
         COALESCE(pjo.limit_sell, o.sell_amount) AS limit_sell,
@@ -537,6 +611,8 @@ fn map_rows_to_solutions(rows: Vec<SolutionRow>) -> Result<Vec<Solution>, sqlx::
             executed_sell: row.executed_sell,
             executed_buy: row.executed_buy,
             side: row.side,
+            surplus_score: row.surplus_score,
+            protocol_fee_score: row.protocol_fee_score,
         };
 
         solutions_map
@@ -547,6 +623,7 @@ fn map_rows_to_solutions(rows: Vec<SolutionRow>) -> Result<Vec<Solution>, sqlx::
                 solver: row.solver,
                 is_winner: row.is_winner,
                 filtered_out: row.filtered_out,
+                filter_reason: row.filter_reason,
                 score: row.score,
                 orders: Vec::new(),
                 price_tokens: row.price_tokens,
@@ -633,7 +710,7 @@ mod tests {
             },
         ];
 
-        save(&mut db, 0, &solutions).await.unwrap();
+        save(&mut db, 0, &solutions, &[]).await.unwrap();
         let fetched_solutions = fetch(&mut db, 0).await.unwrap();
 
         // first two solutions should be identical
@@ -672,6 +749,60 @@ mod tests {
         assert!(fetched_solutions[2].orders.len() == 3);
     }
 
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_filtered_out_solution_roundtrip() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let solutions = vec![
+            Solution {
+                uid: 0,
+                id: 0.into(),
+                solver: ByteArray([1u8; 20]),
+                is_winner: true,
+                score: BigDecimal::from(100),
+                orders: vec![Order {
+                    uid: ByteArray([1u8; 56]),
+                    surplus_score: Some(BigDecimal::from(90)),
+                    protocol_fee_score: Some(BigDecimal::from(10)),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            Solution {
+                uid: 1,
+                id: 1.into(),
+                solver: ByteArray([2u8; 20]),
+                filtered_out: true,
+                filter_reason: Some(FilterReason::Unfair),
+                score: BigDecimal::from(150),
+                orders: vec![Order {
+                    uid: ByteArray([2u8; 56]),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        ];
+        let baselines = vec![FairnessBaseline {
+            sell_token: ByteArray([3u8; 20]),
+            buy_token: ByteArray([4u8; 20]),
+            score: BigDecimal::from(100),
+        }];
+
+        save(&mut db, 0, &solutions, &baselines).await.unwrap();
+        let fetched_solutions = fetch(&mut db, 0).await.unwrap();
+        assert_eq!(solutions, fetched_solutions);
+
+        let fairness_baselines: Vec<FairnessBaseline> =
+            sqlx::query_as("SELECT sell_token, buy_token, score FROM fairness_baselines")
+                .fetch_all(db.deref_mut())
+                .await
+                .unwrap();
+        assert_eq!(fairness_baselines, baselines);
+    }
+
     #[tokio::test]
     #[ignore]
     async fn postgres_non_settling_solvers_roundtrip() {
@@ -723,6 +854,7 @@ mod tests {
                 solver: non_settling_solver,
                 is_winner: true,
                 filtered_out: false,
+                filter_reason: None,
                 score: Default::default(),
                 orders: Default::default(),
                 price_tokens: Default::default(),
@@ -742,6 +874,7 @@ mod tests {
                 solver: ByteArray([2u8; 20]),
                 is_winner: auction_id != 2,
                 filtered_out: false,
+                filter_reason: None,
                 score: Default::default(),
                 orders: Default::default(),
                 price_tokens: Default::default(),
@@ -766,6 +899,7 @@ mod tests {
                 solver: ByteArray([3u8; 20]),
                 is_winner: true,
                 filtered_out: false,
+                filter_reason: None,
                 score: Default::default(),
                 orders: Default::default(),
                 price_tokens: Default::default(),
@@ -786,6 +920,7 @@ mod tests {
                 solver: ByteArray([4u8; 20]),
                 is_winner: true,
                 filtered_out: false,
+                filter_reason: None,
                 score: Default::default(),
                 orders: Default::default(),
                 price_tokens: Default::default(),
@@ -879,6 +1014,7 @@ mod tests {
                 solver: low_settling_solver,
                 is_winner: true,
                 filtered_out: false,
+                filter_reason: None,
                 score: Default::default(),
                 orders: Default::default(),
                 price_tokens: Default::default(),
@@ -913,6 +1049,7 @@ mod tests {
                 solver: non_settling_solver,
                 is_winner: true,
                 filtered_out: false,
+                filter_reason: None,
                 score: Default::default(),
                 orders: Default::default(),
                 price_tokens: Default::default(),
@@ -933,6 +1070,7 @@ mod tests {
                 solver: settling_solver,
                 is_winner: true,
                 filtered_out: false,
+                filter_reason: None,
                 score: Default::default(),
                 orders: Default::default(),
                 price_tokens: Default::default(),
@@ -1172,6 +1310,7 @@ mod tests {
             solver: ByteArray([1u8; 20]),
             is_winner: true,
             filtered_out: false,
+            filter_reason: None,
             score: BigDecimal::from(100),
             orders: vec![Order {
                 uid: order_uid,
@@ -1182,6 +1321,8 @@ mod tests {
                 executed_sell: order_executed_sell,
                 executed_buy: order_executed_buy,
                 side: order_side,
+                surplus_score: Some(BigDecimal::from(80)),
+                protocol_fee_score: Some(BigDecimal::from(20)),
             }],
             price_tokens: vec![ByteArray([1u8; 20])],
             price_values: vec![BigDecimal::from(100)],
@@ -1195,6 +1336,16 @@ mod tests {
             .await
             .unwrap();
 
+        // fairness_baselines
+        let baselines = vec![FairnessBaseline {
+            sell_token: order_sell_token,
+            buy_token: order_buy_token,
+            score: BigDecimal::from(90),
+        }];
+        save_fairness_baselines(&mut db, auction_id, &baselines)
+            .await
+            .unwrap();
+
         // reference_scores
         let scores = vec![reference_scores::Score {
             auction_id,
@@ -1230,6 +1381,11 @@ mod tests {
         assert_eq!(solver_competition.auction.id, 1);
         assert_eq!(solver_competition.trades.len(), 1);
         assert_eq!(solver_competition.trades.first().unwrap().solution_uid, 0);
+        assert_eq!(
+            solver_competition.trades.first().unwrap().surplus_score,
+            Some(BigDecimal::from(80))
+        );
+        assert_eq!(solver_competition.fairness_baselines, baselines);
         assert_eq!(solver_competition.reference_scores.len(), 1);
         assert_eq!(solver_competition.solutions.len(), 1);
         assert_eq!(solver_competition.solutions.first().unwrap().uid, 0);
@@ -0,0 +1,477 @@
+use {
+    crate::{Address, PgTransaction, auction::AuctionId},
+    bigdecimal::BigDecimal,
+    sqlx::{PgConnection, QueryBuilder},
+    std::ops::DerefMut,
+    tracing::instrument,
+};
+
+pub type PeriodId = i64;
+
+/// Range of blocks `[start_block, end_block)` for which solver rewards get
+/// accounted together. Auctions belong to the period their `block` falls into.
+#[derive(Clone, Debug, PartialEq, sqlx::FromRow)]
+pub struct AccountingPeriod {
+    pub id: PeriodId,
+    pub start_block: i64,
+    pub end_block: i64,
+    /// Parameters of the reward formula used for this period.
+    pub upper_cap: BigDecimal,
+    pub lower_cap: BigDecimal,
+    pub protocol_fee_cap_factor: f64,
+}
+
+/// A winning solution of an auction together with everything needed to
+/// compute the reward of its solver.
+#[derive(Clone, Debug, PartialEq, sqlx::FromRow)]
+pub struct WinningSolution {
+    pub auction_id: AuctionId,
+    pub solver: Address,
+    pub solution_uid: i64,
+    pub score: BigDecimal,
+    /// Missing reference scores are reported as 0.
+    pub reference_score: BigDecimal,
+    /// Protocol fees observed on-chain for the orders of the solution,
+    /// converted to native token with the auction's native prices.
+    pub protocol_fees: BigDecimal,
+    /// Whether the solution got settled on-chain.
+    pub settled: bool,
+}
+
+/// Reward (or penalty if negative) of a solver for a single auction.
+#[derive(Clone, Debug, PartialEq, sqlx::FromRow)]
+pub struct SolverReward {
+    pub auction_id: AuctionId,
+    pub solver: Address,
+    pub period_id: PeriodId,
+    /// Score of all winning solutions of the solver.
+    pub score: BigDecimal,
+    /// Score of the winning solutions of the solver that got settled.
+    pub observed_score: BigDecimal,
+    pub reference_score: BigDecimal,
+    pub protocol_fees: BigDecimal,
+    pub reward: BigDecimal,
+}
+
+/// Sum of all rewards of a solver in an accounting period.
+#[derive(Clone, Debug, PartialEq, sqlx::FromRow)]
+pub struct SolverPayout {
+    pub period_id: PeriodId,
+    pub solver: Address,
+    pub wins: i64,
+    pub settlements: i64,
+    pub protocol_fees: BigDecimal,
+    pub reward: BigDecimal,
+}
+
+/// Fetches all winning solutions of auctions created in the given block range.
+#[instrument(skip_all)]
+pub async fn fetch_winning_solutions(
+    ex: &mut PgConnection,
+    start_block: i64,
+    end_block: i64,
+) -> Result<Vec<WinningSolution>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT
+    ps.auction_id,
+    ps.solver,
+    ps.uid AS solution_uid,
+    ps.score,
+    COALESCE(rs.reference_score, 0) AS reference_score,
+    COALESCE((
+        SELECT FLOOR(SUM(fee.amount * price.value) / 1000000000000000000)
+        FROM proposed_trade_executions pte
+        JOIN order_execution oe ON oe.auction_id = pte.auction_id AND oe.order_uid = pte.order_uid
+        CROSS JOIN LATERAL UNNEST(oe.protocol_fee_tokens, oe.protocol_fee_amounts) AS fee(token, amount)
+        JOIN UNNEST(ca.price_tokens, ca.price_values) AS price(token, value) ON price.token = fee.token
+        WHERE pte.auction_id = ps.auction_id AND pte.solution_uid = ps.uid
+    ), 0) AS protocol_fees,
+    EXISTS (
+        SELECT 1
+        FROM settlements s
+        WHERE s.auction_id = ps.auction_id
+            AND s.solver = ps.solver
+            AND (s.solution_uid IS NULL OR s.solution_uid = ps.uid)
+    ) AS settled
+FROM competition_auctions ca
+JOIN proposed_solutions ps ON ps.auction_id = ca.id
+LEFT JOIN reference_scores rs ON rs.auction_id = ps.auction_id AND rs.solver = ps.solver
+WHERE ca.block >= $1 AND ca.block < $2 AND ps.is_winner = true
+ORDER BY ps.auction_id, ps.uid
+    "#;
+
+    sqlx::query_as(QUERY)
+        .bind(start_block)
+        .bind(end_block)
+        .fetch_all(ex)
+        .await
+}
+
+/// Returns the latest settlement deadline of all auctions created in the given
+/// block range.
+#[instrument(skip_all)]
+pub async fn latest_deadline(
+    ex: &mut PgConnection,
+    start_block: i64,
+    end_block: i64,
+) -> Result<Option<i64>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT MAX(deadline)
+FROM competition_auctions
+WHERE block >= $1 AND block < $2
+    "#;
+
+    sqlx::query_scalar(QUERY)
+        .bind(start_block)
+        .bind(end_block)
+        .fetch_one(ex)
+        .await
+}
+
+/// Stores the accounting period. Returns `false` if it was already accounted.
+#[instrument(skip_all)]
+pub async fn insert_period(
+    ex: &mut PgConnection,
+    period: &AccountingPeriod,
+) -> Result<bool, sqlx::Error> {
+    const QUERY: &str = r#"
+INSERT INTO accounting_periods (id, start_block, end_block, upper_cap, lower_cap, protocol_fee_cap_factor)
+VALUES ($1, $2, $3, $4, $5, $6)
+ON CONFLICT (id) DO NOTHING
+    "#;
+
+    let result = sqlx::query(QUERY)
+        .bind(period.id)
+        .bind(period.start_block)
+        .bind(period.end_block)
+        .bind(&period.upper_cap)
+        .bind(&period.lower_cap)
+        .bind(period.protocol_fee_cap_factor)
+        .execute(ex)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+#[instrument(skip_all)]
+pub async fn insert_rewards(
+    ex: &mut PgTransaction<'_>,
+    rewards: &[SolverReward],
+) -> Result<(), sqlx::Error> {
+    const QUERY: &str = "INSERT INTO solver_rewards (auction_id, solver, period_id, score, \
+                         observed_score, reference_score, protocol_fees, reward) ";
+
+    if rewards.is_empty() {
+        return Ok(());
+    }
+
+    let mut query_builder = QueryBuilder::new(QUERY);
+    query_builder.push_values(rewards, |mut builder, reward| {
+        builder
+            .push_bind(reward.auction_id)
+            .push_bind(reward.solver)
+            .push_bind(reward.period_id)
+            .push_bind(reward.score.clone())
+            .push_bind(reward.observed_score.clone())
+            .push_bind(reward.reference_score.clone())
+            .push_bind(reward.protocol_fees.clone())
+            .push_bind(reward.reward.clone());
+    });
+
+    query_builder.build().execute(ex.deref_mut()).await?;
+
+    Ok(())
+}
+
+#[instrument(skip_all)]
+pub async fn insert_payouts(
+    ex: &mut PgTransaction<'_>,
+    payouts: &[SolverPayout],
+) -> Result<(), sqlx::Error> {
+    const QUERY: &str =
+        "INSERT INTO solver_payouts (period_id, solver, wins, settlements, protocol_fees, reward) ";
+
+    if payouts.is_empty() {
+        return Ok(());
+    }
+
+    let mut query_builder = QueryBuilder::new(QUERY);
+    query_builder.push_values(payouts, |mut builder, payout| {
+        builder
+            .push_bind(payout.period_id)
+            .push_bind(payout.solver)
+            .push_bind(payout.wins)
+            .push_bind(payout.settlements)
+            .push_bind(payout.protocol_fees.clone())
+            .push_bind(payout.reward.clone());
+    });
+
+    query_builder.build().execute(ex.deref_mut()).await?;
+
+    Ok(())
+}
+
+#[instrument(skip_all)]
+pub async fn fetch_latest_period(
+    ex: &mut PgConnection,
+) -> Result<Option<AccountingPeriod>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT id, start_block, end_block, upper_cap, lower_cap, protocol_fee_cap_factor
+FROM accounting_periods
+ORDER BY id DESC
+LIMIT 1
+    "#;
+    sqlx::query_as(QUERY).fetch_optional(ex).await
+}
+
+#[instrument(skip_all)]
+pub async fn fetch_period(
+    ex: &mut PgConnection,
+    id: PeriodId,
+) -> Result<Option<AccountingPeriod>, sqlx::Error> {
+    const QUERY: &str = r#"
+SELECT id, start_block, end_block, upper_cap, lower_cap, protocol_fee_cap_factor
+FROM accounting_periods
+WHERE id = $1
+    "#;
+    sqlx::query_as(QUERY).bind(id).fetch_optional(ex).await
+}
+
+#[instrument(skip_all)]
+pub async fn fetch_payouts(
+    ex: &mut PgConnection,
+    period_id: PeriodId,
+) -> Result<Vec<SolverPayout>, sqlx::Error> {
+    const QUERY: &str = r#"SELECT * FROM solver_payouts WHERE period_id = $1 ORDER BY solver"#;
+    sqlx::query_as(QUERY).bind(period_id).fetch_all(ex).await
+}
+
+#[instrument(skip_all)]
+pub async fn fetch_rewards(
+    ex: &mut PgConnection,
+    period_id: PeriodId,
+) -> Result<Vec<SolverReward>, sqlx::Error> {
+    const QUERY: &str =
+        r#"SELECT * FROM solver_rewards WHERE period_id = $1 ORDER BY auction_id, solver"#;
+    sqlx::query_as(QUERY).bind(period_id).fetch_all(ex).await
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            auction,
+            byte_array::ByteArray,
+            events::{self, EventIndex, Settlement},
+            order_execution::{self, Asset},
+            reference_scores,
+            settlements,
+            solver_competition_v2::{self, Solution},
+        },
+        sqlx::Connection,
+    };
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_winning_solutions() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let settling_solver = ByteArray([1; 20]);
+        let failing_solver = ByteArray([2; 20]);
+        let losing_solver = ByteArray([3; 20]);
+        let fee_token = ByteArray([4; 20]);
+        let settled_order = ByteArray([5; 56]);
+        for (auction_id, block) in [(1, 10), (2, 20)] {
+            let auction = auction::Auction {
+                id: auction_id,
+                block,
+                deadline: block + 5,
+                order_uids: Default::default(),
+                // 1 fee token is worth 0.5 native token.
+                price_tokens: vec![fee_token],
+                price_values: vec![500_000_000_000_000_000_u64.into()],
+                surplus_capturing_jit_order_owners: Default::default(),
+            };
+            auction::save(&mut db, auction).await.unwrap();
+        }
+        let solution = |uid, solver, is_winner, score: u32, orders| Solution {
+            uid,
+            id: uid.into(),
+            solver,
+            is_winner,
+            filtered_out: false,
+            filter_reason: None,
+            score: score.into(),
+            orders,
+            price_tokens: Default::default(),
+            price_values: Default::default(),
+        };
+        solver_competition_v2::save(
+            &mut db,
+            1,
+            &[
+                solution(
+                    0,
+                    settling_solver,
+                    true,
+                    10,
+                    vec![solver_competition_v2::Order {
+                        uid: settled_order,
+                        // Promised protocol fees are ignored in favor of the
+                        // observed ones.
+                        protocol_fee_score: Some(100.into()),
+                        ..Default::default()
+                    }],
+                ),
+                solution(1, failing_solver, true, 8, vec![]),
+                solution(2, losing_solver, false, 5, vec![]),
+            ],
+            &[],
+        )
+        .await
+        .unwrap();
+        reference_scores::insert(
+            &mut db,
+            &[reference_scores::Score {
+                auction_id: 1,
+                solver: settling_solver,
+                reference_score: 13.into(),
+            }],
+        )
+        .await
+        .unwrap();
+        events::insert_settlement(
+            &mut db,
+            &EventIndex {
+                block_number: 12,
+                log_index: 0,
+            },
+            &Settlement {
+                solver: settling_solver,
+                transaction_hash: ByteArray([0; 32]),
+            },
+        )
+        .await
+        .unwrap();
+        settlements::update_settlement_auction(&mut db, 12, 0, 1)
+            .await
+            .unwrap();
+        order_execution::save(
+            &mut db,
+            &settled_order,
+            1,
+            12,
+            Asset {
+                amount: 0.into(),
+                token: fee_token,
+            },
+            &[
+                Asset {
+                    amount: 4.into(),
+                    token: fee_token,
+                },
+                Asset {
+                    amount: 3.into(),
+                    token: fee_token,
+                },
+            ],
+        )
+        .await
+        .unwrap();
+
+        let solutions = fetch_winning_solutions(&mut db, 0, 20).await.unwrap();
+        assert_eq!(
+            solutions,
+            vec![
+                WinningSolution {
+                    auction_id: 1,
+                    solver: settling_solver,
+                    solution_uid: 0,
+                    score: 10.into(),
+                    reference_score: 13.into(),
+                    // (4 + 3) * 0.5 rounded down
+                    protocol_fees: 3.into(),
+                    settled: true,
+                },
+                WinningSolution {
+                    auction_id: 1,
+                    solver: failing_solver,
+                    solution_uid: 1,
+                    score: 8.into(),
+                    reference_score: 0.into(),
+                    protocol_fees: 0.into(),
+                    settled: false,
+                },
+            ]
+        );
+        assert_eq!(latest_deadline(&mut db, 0, 20).await.unwrap(), Some(15));
+        assert_eq!(latest_deadline(&mut db, 0, 30).await.unwrap(), Some(25));
+        assert_eq!(latest_deadline(&mut db, 30, 40).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_rewards_roundtrip() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        assert_eq!(fetch_latest_period(&mut db).await.unwrap(), None);
+
+        let period = AccountingPeriod {
+            id: 3,
+            start_block: 300,
+            end_block: 400,
+            upper_cap: 12.into(),
+            lower_cap: 10.into(),
+            protocol_fee_cap_factor: 0.5,
+        };
+        assert!(insert_period(&mut db, &period).await.unwrap());
+        assert!(!insert_period(&mut db, &period).await.unwrap());
+
+        let rewards = vec![
+            SolverReward {
+                auction_id: 1,
+                solver: ByteArray([1; 20]),
+                period_id: 3,
+                score: 10.into(),
+                observed_score: 10.into(),
+                reference_score: 8.into(),
+                protocol_fees: 1.into(),
+                reward: 2.into(),
+            },
+            SolverReward {
+                auction_id: 2,
+                solver: ByteArray([1; 20]),
+                period_id: 3,
+                score: 10.into(),
+                observed_score: 0.into(),
+                reference_score: 8.into(),
+                protocol_fees: 0.into(),
+                reward: (-8).into(),
+            },
+        ];
+        insert_rewards(&mut db, &rewards).await.unwrap();
+        let payouts = vec![SolverPayout {
+            period_id: 3,
+            solver: ByteArray([1; 20]),
+            wins: 2,
+            settlements: 1,
+            protocol_fees: 1.into(),
+            reward: (-6).into(),
+        }];
+        insert_payouts(&mut db, &payouts).await.unwrap();
+
+        assert_eq!(
+            fetch_latest_period(&mut db).await.unwrap(),
+            Some(period.clone())
+        );
+        assert_eq!(fetch_period(&mut db, 3).await.unwrap(), Some(period));
+        assert_eq!(fetch_period(&mut db, 2).await.unwrap(), None);
+        assert_eq!(fetch_rewards(&mut db, 3).await.unwrap(), rewards);
+        assert_eq!(fetch_payouts(&mut db, 3).await.unwrap(), payouts);
+        assert!(fetch_payouts(&mut db, 2).await.unwrap().is_empty());
+    }
+}
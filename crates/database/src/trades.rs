@@ -1,6 +1,7 @@
 use {
     crate::{Address, OrderUid, TransactionHash, auction::AuctionId, events::EventIndex},
     bigdecimal::BigDecimal,
+    chrono::{DateTime, Utc},
     futures::stream::BoxStream,
     sqlx::PgConnection,
     tracing::{Instrument, info_span, instrument},
@@ -135,6 +136,216 @@ FROM (
     Ok(block_number)
 }
 
+#[derive(Clone, Debug, Default, Eq, PartialEq, sqlx::FromRow)]
+pub struct TokenTradeStats {
+    /// Number of trades buying or selling the token.
+    pub trades: i64,
+    /// Total amount of the token that was sold or bought in these trades.
+    pub volume: BigDecimal,
+    /// Number of distinct owners of the traded orders.
+    pub unique_traders: i64,
+    /// Same statistics restricted to the trades in recent blocks.
+    pub recent_trades: i64,
+    pub recent_volume: BigDecimal,
+    pub recent_unique_traders: i64,
+}
+
+/// Aggregates the trades of the given token in blocks at or after `min_block`
+/// in a single scan. The `recent_*` statistics only include trades in blocks
+/// at or after `recent_min_block` which should not be smaller than
+/// `min_block`.
+pub async fn token_trade_stats(
+    ex: &mut PgConnection,
+    token: Address,
+    min_block: i64,
+    recent_min_block: i64,
+) -> Result<TokenTradeStats, sqlx::Error> {
+    const QUERY: &str = r#"
+WITH token_trades AS (
+    SELECT
+        o.owner,
+        t.block_number,
+        CASE WHEN o.sell_token = $1 THEN t.sell_amount ELSE 0 END +
+        CASE WHEN o.buy_token = $1 THEN t.buy_amount ELSE 0 END AS amount
+    FROM trades t
+    JOIN orders o ON t.order_uid = o.uid
+    WHERE (o.sell_token = $1 OR o.buy_token = $1) AND t.block_number >= $2
+
+    UNION ALL
+
+    SELECT
+        j.owner,
+        t.block_number,
+        CASE WHEN j.sell_token = $1 THEN t.sell_amount ELSE 0 END +
+        CASE WHEN j.buy_token = $1 THEN t.buy_amount ELSE 0 END AS amount
+    FROM trades t
+    JOIN jit_orders j ON t.order_uid = j.uid
+    WHERE (j.sell_token = $1 OR j.buy_token = $1) AND t.block_number >= $2
+)
+SELECT
+    COUNT(*) AS trades,
+    COALESCE(SUM(amount), 0) AS volume,
+    COUNT(DISTINCT owner) AS unique_traders,
+    COUNT(*) FILTER (WHERE block_number >= $3) AS recent_trades,
+    COALESCE(SUM(amount) FILTER (WHERE block_number >= $3), 0) AS recent_volume,
+    COUNT(DISTINCT owner) FILTER (WHERE block_number >= $3) AS recent_unique_traders
+FROM token_trades
+"#;
+
+    sqlx::query_as(QUERY)
+        .bind(token)
+        .bind(min_block)
+        .bind(recent_min_block)
+        .fetch_one(ex)
+        .await
+}
+
+/// A settled trade together with everything needed to break down the value it
+/// generated into surplus and fees.
+#[derive(Clone, Debug, Default, PartialEq, sqlx::FromRow)]
+pub struct TradeFeesRow {
+    pub order_uid: OrderUid,
+    pub owner: Address,
+    pub sell_token: Address,
+    pub buy_token: Address,
+    /// When the settlement containing the trade finished executing.
+    pub timestamp: DateTime<Utc>,
+    /// Surplus over the limit price denominated in wei of the native token.
+    pub surplus_in_wei: Option<BigDecimal>,
+    /// Network costs charged for executing the trade.
+    pub executed_fee: Option<BigDecimal>,
+    pub executed_fee_token: Option<Address>,
+    /// Protocol fees (including partner fees) in the order of the applied fee
+    /// policies.
+    pub protocol_fee_tokens: Option<Vec<Address>>,
+    pub protocol_fee_amounts: Option<Vec<BigDecimal>>,
+    /// Kinds of the fee policies that were applied in the auction the trade
+    /// was settled in. Same order as the protocol fees.
+    pub fee_policy_kinds: Vec<String>,
+    pub app_code: Option<String>,
+    pub full_app_data: Option<Vec<u8>>,
+}
+
+/// Returns up to `limit` trades of regular orders whose settlement finished
+/// executing within `[from, to)`. Trades can optionally be filtered by owner or
+/// on-chain sender, by the `appCode` of the order's app data and by a recipient
+/// of the order's partner fees.
+///
+/// Trades whose settlement execution was not recorded don't have a timestamp
+/// and are therefore not returned.
+#[instrument(skip_all)]
+pub async fn trade_fees(
+    ex: &mut PgConnection,
+    owner: Option<&Address>,
+    app_code: Option<&str>,
+    partner_recipient: Option<&Address>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    limit: i64,
+) -> Result<Vec<TradeFeesRow>, sqlx::Error> {
+    const QUERY: &str = r#"
+WITH trade_components AS (
+    SELECT
+        o.uid AS order_uid,
+        o.owner,
+        o.sell_token,
+        o.buy_token,
+        o.kind,
+        se.end_timestamp AS timestamp,
+        CASE o.kind
+            WHEN 'sell' THEN t.buy_amount
+            WHEN 'buy' THEN t.sell_amount - t.fee_amount
+        END AS trade_amount,
+        CASE o.kind
+            WHEN 'sell' THEN (t.sell_amount - t.fee_amount) * o.buy_amount / NULLIF(o.sell_amount, 0)
+            WHEN 'buy' THEN t.buy_amount * o.sell_amount / NULLIF(o.buy_amount, 0)
+        END AS limit_amount,
+        CASE o.kind
+            WHEN 'sell' THEN (SELECT price FROM auction_prices ap WHERE ap.token = o.buy_token AND ap.auction_id = settlement.auction_id)
+            WHEN 'buy' THEN (SELECT price FROM auction_prices ap WHERE ap.token = o.sell_token AND ap.auction_id = settlement.auction_id)
+        END AS surplus_token_native_price,
+        oe.executed_fee,
+        oe.executed_fee_token,
+        oe.protocol_fee_tokens,
+        oe.protocol_fee_amounts,
+        ARRAY(
+            SELECT fp.kind::text FROM fee_policies fp
+            WHERE fp.auction_id = settlement.auction_id AND fp.order_uid = o.uid
+            ORDER BY fp.application_order
+        ) AS fee_policy_kinds,
+        NULLIF(ad.app_code, '') AS app_code,
+        ad.full_app_data
+    FROM trades t
+    JOIN orders o ON o.uid = t.order_uid
+    JOIN LATERAL (
+        SELECT auction_id, solver, solution_uid FROM settlements s
+        WHERE s.block_number = t.block_number
+        AND   s.log_index > t.log_index
+        ORDER BY s.log_index ASC
+        LIMIT 1
+    ) AS settlement ON true
+    JOIN settlement_executions se
+        ON se.auction_id = settlement.auction_id
+        AND se.solver = settlement.solver
+        AND se.solution_uid = settlement.solution_uid
+    LEFT OUTER JOIN order_execution oe
+        ON oe.order_uid = t.order_uid AND oe.auction_id = settlement.auction_id
+    LEFT OUTER JOIN app_data ad ON ad.contract_app_data = o.app_data
+    WHERE se.end_timestamp >= $2 AND se.end_timestamp < $3
+    AND (
+        $1 IS NULL
+        OR o.owner = $1
+        OR EXISTS (SELECT 1 FROM onchain_placed_orders op WHERE op.uid = o.uid AND op.sender = $1)
+    )
+    AND ($4::text IS NULL OR ad.app_code = $4)
+    -- full app data is validated to be a JSON document before it gets stored
+    AND (
+        $5::bytea IS NULL
+        OR EXISTS (
+            SELECT 1
+            FROM jsonb_path_query(
+                convert_from(ad.full_app_data, 'UTF8')::jsonb,
+                'lax $.metadata.partnerFee[*]'
+            ) AS fee
+            WHERE lower(fee ->> 'recipient') = '0x' || encode($5, 'hex')
+        )
+    )
+)
+SELECT
+    order_uid,
+    owner,
+    sell_token,
+    buy_token,
+    timestamp,
+    CASE kind
+        -- amounts refer to tokens bought; more is better
+        WHEN 'sell' THEN (trade_amount - limit_amount) * surplus_token_native_price
+        -- amounts refer to tokens sold; less is better
+        WHEN 'buy' THEN (limit_amount - trade_amount) * surplus_token_native_price
+    END / POWER(10, 18) AS surplus_in_wei,
+    executed_fee,
+    executed_fee_token,
+    protocol_fee_tokens,
+    protocol_fee_amounts,
+    fee_policy_kinds,
+    app_code,
+    full_app_data
+FROM trade_components
+ORDER BY timestamp ASC
+LIMIT $6
+"#;
+
+    sqlx::query_as(QUERY)
+        .bind(owner)
+        .bind(from)
+        .bind(to)
+        .bind(app_code)
+        .bind(partner_recipient)
+        .bind(limit)
+        .fetch_all(ex)
+        .await
+}
+
 #[cfg(test)]
 mod tests {
     use {
@@ -655,4 +866,250 @@ mod tests {
             Some(123)
         );
     }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_token_trade_stats() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let token = Default::default();
+        assert_eq!(
+            token_trade_stats(&mut db, token, 0, 0).await.unwrap(),
+            TokenTradeStats::default()
+        );
+
+        let (owners, order_ids) = generate_owners_and_order_ids(2, 3).await;
+        let event_index = |block_number| EventIndex {
+            block_number,
+            log_index: 0,
+        };
+        add_order_and_trade(&mut db, owners[0], order_ids[0], event_index(1), None, None).await;
+        add_order_and_trade(&mut db, owners[1], order_ids[1], event_index(2), None, None).await;
+        add_order_and_trade(&mut db, owners[1], order_ids[2], event_index(3), None, None).await;
+
+        let stats = token_trade_stats(&mut db, token, 0, 2).await.unwrap();
+        assert_eq!(stats.trades, 3);
+        assert_eq!(stats.unique_traders, 2);
+        assert_eq!(stats.recent_trades, 2);
+        assert_eq!(stats.recent_unique_traders, 1);
+
+        let stats = token_trade_stats(&mut db, token, 2, 3).await.unwrap();
+        assert_eq!(stats.trades, 2);
+        assert_eq!(stats.unique_traders, 1);
+        assert_eq!(stats.recent_trades, 1);
+        assert_eq!(stats.recent_unique_traders, 1);
+
+        let stats = token_trade_stats(&mut db, ByteArray([1; 20]), 0, 0)
+            .await
+            .unwrap();
+        assert_eq!(stats, TokenTradeStats::default());
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_trade_fees() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let (owners, order_ids) = generate_owners_and_order_ids(2, 3).await;
+        let solver = ByteArray([9; 20]);
+        let start = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let hour = chrono::Duration::hours(1);
+
+        // One settlement per trade, each finishing an hour after the previous one.
+        for (i, (owner, order_uid)) in [owners[0], owners[0], owners[1]]
+            .into_iter()
+            .zip(&order_ids)
+            .enumerate()
+        {
+            let block_number = i as i64;
+            let auction_id = i as i64;
+            add_order_and_trade(
+                &mut db,
+                owner,
+                *order_uid,
+                EventIndex {
+                    block_number,
+                    log_index: 0,
+                },
+                None,
+                None,
+            )
+            .await;
+            let settlement_index = EventIndex {
+                block_number,
+                log_index: 1,
+            };
+            add_settlement(
+                &mut db,
+                settlement_index,
+                solver,
+                Default::default(),
+                auction_id,
+            )
+            .await;
+            crate::settlements::update_settlement_solver(&mut db, block_number, 1, solver, 0)
+                .await
+                .unwrap();
+            crate::settlement_executions::insert(&mut db, auction_id, solver, 0, start, 0, 0)
+                .await
+                .unwrap();
+            crate::settlement_executions::update(
+                &mut db,
+                auction_id,
+                solver,
+                0,
+                start + hour * i as i32,
+                0,
+                "success".to_string(),
+            )
+            .await
+            .unwrap();
+        }
+        crate::fee_policies::insert_batch(
+            &mut db,
+            [crate::fee_policies::FeePolicy {
+                auction_id: 0,
+                order_uid: order_ids[0],
+                kind: crate::fee_policies::FeePolicyKind::Volume,
+                surplus_factor: None,
+                surplus_max_volume_factor: None,
+                volume_factor: Some(0.1),
+                price_improvement_factor: None,
+                price_improvement_max_volume_factor: None,
+            }],
+        )
+        .await
+        .unwrap();
+        crate::order_execution::save(
+            &mut db,
+            &order_ids[0],
+            0,
+            0,
+            crate::order_execution::Asset {
+                amount: 3.into(),
+                token: Default::default(),
+            },
+            &[crate::order_execution::Asset {
+                amount: 1.into(),
+                token: Default::default(),
+            }],
+        )
+        .await
+        .unwrap();
+
+        let rows = trade_fees(&mut db, None, None, None, start, start + hour * 3, 10)
+            .await
+            .unwrap();
+        assert_eq!(
+            rows.iter().map(|row| row.order_uid).collect::<Vec<_>>(),
+            order_ids
+        );
+        assert_eq!(rows[0].executed_fee, Some(3.into()));
+        assert_eq!(rows[0].protocol_fee_amounts, Some(vec![1.into()]));
+        assert_eq!(rows[0].fee_policy_kinds, vec!["volume".to_string()]);
+        assert_eq!(rows[1].executed_fee, None);
+        assert!(rows[1].fee_policy_kinds.is_empty());
+
+        let rows = trade_fees(&mut db, None, None, None, start, start + hour * 3, 1)
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].order_uid, order_ids[0]);
+
+        let rows = trade_fees(
+            &mut db,
+            Some(&owners[0]),
+            None,
+            None,
+            start + hour,
+            start + hour * 3,
+            10,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            rows.iter().map(|row| row.order_uid).collect::<Vec<_>>(),
+            vec![order_ids[1]]
+        );
+
+        let app_id = ByteArray([1; 32]);
+        let partner = ByteArray([0xab; 20]);
+        let app_data = serde_json::json!({
+            "appCode": "CoW Swap",
+            "metadata": {
+                "partnerFee": [{
+                    "volumeBps": 10,
+                    "recipient": const_hex::encode_prefixed(partner.0),
+                }],
+            },
+        });
+        crate::app_data::insert(&mut db, &app_id, app_data.to_string().as_bytes())
+            .await
+            .unwrap();
+        sqlx::query("UPDATE orders SET app_data = $1 WHERE uid = $2")
+            .bind(app_id)
+            .bind(order_ids[2])
+            .execute(&mut *db)
+            .await
+            .unwrap();
+        let uids = |rows: Vec<TradeFeesRow>| {
+            rows.into_iter()
+                .map(|row| row.order_uid)
+                .collect::<Vec<_>>()
+        };
+
+        let rows = trade_fees(
+            &mut db,
+            None,
+            Some("CoW Swap"),
+            None,
+            start,
+            start + hour * 3,
+            10,
+        )
+        .await
+        .unwrap();
+        assert_eq!(uids(rows.clone()), vec![order_ids[2]]);
+        assert_eq!(rows[0].app_code.as_deref(), Some("CoW Swap"));
+        let rows = trade_fees(
+            &mut db,
+            None,
+            Some("other"),
+            None,
+            start,
+            start + hour * 3,
+            10,
+        )
+        .await
+        .unwrap();
+        assert!(rows.is_empty());
+        let rows = trade_fees(
+            &mut db,
+            None,
+            None,
+            Some(&partner),
+            start,
+            start + hour * 3,
+            10,
+        )
+        .await
+        .unwrap();
+        assert_eq!(uids(rows), vec![order_ids[2]]);
+        let rows = trade_fees(
+            &mut db,
+            None,
+            None,
+            Some(&ByteArray([0xcd; 20])),
+            start,
+            start + hour * 3,
+            10,
+        )
+        .await
+        .unwrap();
+        assert!(rows.is_empty());
+    }
 }
@@ -0,0 +1,76 @@
+use {
+    crate::OrderUid,
+    chrono::{DateTime, Utc},
+    sqlx::{PgConnection, QueryBuilder},
+    tracing::instrument,
+};
+
+/// Marks the given orders as triggered. Orders that were triggered before keep
+/// their original trigger time.
+#[instrument(skip_all)]
+pub async fn insert(
+    ex: &mut PgConnection,
+    orders: &[OrderUid],
+    triggered_at: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    if orders.is_empty() {
+        return Ok(());
+    }
+
+    const QUERY: &str = "INSERT INTO triggered_orders (order_uid, triggered_at) ";
+    let mut query_builder = QueryBuilder::new(QUERY);
+    query_builder.push_values(orders, |mut builder, uid| {
+        builder.push_bind(uid).push_bind(triggered_at);
+    });
+    query_builder.push(" ON CONFLICT (order_uid) DO NOTHING");
+    query_builder.build().execute(ex).await?;
+    Ok(())
+}
+
+/// Returns the subset of the given orders that have been triggered.
+#[instrument(skip_all)]
+pub async fn triggered(
+    ex: &mut PgConnection,
+    orders: &[OrderUid],
+) -> Result<Vec<OrderUid>, sqlx::Error> {
+    const QUERY: &str = r#"
+        SELECT order_uid
+        FROM triggered_orders
+        WHERE order_uid = ANY($1)
+    "#;
+    sqlx::query_scalar(QUERY).bind(orders).fetch_all(ex).await
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::byte_array::ByteArray, sqlx::Connection};
+
+    #[tokio::test]
+    #[ignore]
+    async fn postgres_triggered_orders() {
+        let mut db = PgConnection::connect("postgresql://").await.unwrap();
+        let mut db = db.begin().await.unwrap();
+        crate::clear_DANGER_(&mut db).await.unwrap();
+
+        let now = Utc::now();
+        insert(&mut db, &[ByteArray([1; 56]), ByteArray([3; 56])], now)
+            .await
+            .unwrap();
+        // Triggering an order again is a no-op.
+        insert(
+            &mut db,
+            &[ByteArray([1; 56])],
+            now + chrono::Duration::seconds(1),
+        )
+        .await
+        .unwrap();
+
+        let triggered = triggered(
+            &mut db,
+            &[ByteArray([1; 56]), ByteArray([2; 56]), ByteArray([4; 56])],
+        )
+        .await
+        .unwrap();
+        assert_eq!(triggered, vec![ByteArray([1; 56])]);
+    }
+}
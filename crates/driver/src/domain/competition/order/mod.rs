@@ -38,6 +38,9 @@ pub struct Order {
     /// contract to the user.
     /// These are set by the user and included in the settlement transaction.
     pub post_interactions: Vec<eth::Interaction>,
+    /// Gas used by the pre- and post-interactions when the orderbook simulated
+    /// them at order placement.
+    pub hooks_gas: Option<HooksGas>,
     pub sell_token_balance: SellTokenBalance,
     pub buy_token_balance: BuyTokenBalance,
     pub signature: Signature,
@@ -49,6 +52,14 @@ pub struct Order {
     pub quote: Option<Quote>,
 }
 
+/// Gas used by every pre- and post-interaction of an [`Order`] in execution
+/// order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HooksGas {
+    pub pre: Vec<u64>,
+    pub post: Vec<u64>,
+}
+
 /// An amount denominated in the sell token of an [`Order`].
 #[derive(Clone, Copy, Debug, Default, Eq, Ord, PartialEq, PartialOrd, From, Into)]
 pub struct SellAmount(pub eth::U256);
@@ -447,6 +458,7 @@ mod tests {
                 .unwrap_or(Partial::No),
             pre_interactions: Default::default(),
             post_interactions: Default::default(),
+            hooks_gas: None,
             sell_token_balance: SellTokenBalance::Erc20,
             buy_token_balance: BuyTokenBalance::Erc20,
             signature: Signature {
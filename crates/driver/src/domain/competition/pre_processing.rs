@@ -489,6 +489,7 @@ impl Utilities {
                         .into_iter()
                         .map(Into::into)
                         .collect(),
+                    hooks_gas: None,
                     signature: match template.signature {
                         Signature::Eip1271(bytes) => order::Signature {
                             scheme: order::signature::Scheme::Eip1271,
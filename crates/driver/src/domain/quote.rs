@@ -147,6 +147,7 @@ impl Order {
                 partial: competition::order::Partial::No,
                 pre_interactions: Default::default(),
                 post_interactions: Default::default(),
+                hooks_gas: None,
                 sell_token_balance: competition::order::SellTokenBalance::Erc20,
                 buy_token_balance: competition::order::BuyTokenBalance::Erc20,
                 signature: competition::order::Signature {
@@ -68,13 +68,15 @@ impl SolveRequest {
                         None => AppData::Hash(AppDataHash::from(order.app_data)),
                     },
                     partial: if order.partially_fillable {
+                        let remaining = match order.kind {
+                            Kind::Sell => order.sell_amount.saturating_sub(order.executed),
+                            Kind::Buy => order.buy_amount.saturating_sub(order.executed),
+                        };
                         competition::order::Partial::Yes {
-                            available: match order.kind {
-                                Kind::Sell => {
-                                    order.sell_amount.saturating_sub(order.executed).into()
-                                }
-                                Kind::Buy => order.buy_amount.saturating_sub(order.executed).into(),
-                            },
+                            available: order
+                                .available
+                                .map_or(remaining, |available| available.min(remaining))
+                                .into(),
                         }
                     } else {
                         competition::order::Partial::No
@@ -97,6 +99,10 @@ impl SolveRequest {
                             call_data: interaction.call_data.into(),
                         })
                         .collect(),
+                    hooks_gas: order.hooks_gas.map(|gas| competition::order::HooksGas {
+                        pre: gas.pre,
+                        post: gas.post,
+                    }),
                     sell_token_balance: match order.sell_token_balance {
                         SellTokenBalance::Erc20 => competition::order::SellTokenBalance::Erc20,
                         SellTokenBalance::Internal => {
@@ -258,9 +264,16 @@ struct Order {
     /// Always zero if the order is not partially fillable.
     #[serde_as(as = "serialize::U256")]
     executed: eth::U256,
+    /// Limits how much of a partially fillable order can be executed in this
+    /// auction if less than the remaining amount is executable right now.
+    #[serde_as(as = "Option<serialize::U256>")]
+    #[serde(default)]
+    available: Option<eth::U256>,
     pre_interactions: Vec<Interaction>,
     post_interactions: Vec<Interaction>,
     #[serde(default)]
+    hooks_gas: Option<HooksGas>,
+    #[serde(default)]
     sell_token_balance: SellTokenBalance,
     #[serde(default)]
     buy_token_balance: BuyTokenBalance,
@@ -291,6 +304,13 @@ struct Interaction {
     call_data: Vec<u8>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HooksGas {
+    pre: Vec<u64>,
+    post: Vec<u64>,
+}
+
 #[derive(Debug, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 enum SellTokenBalance {
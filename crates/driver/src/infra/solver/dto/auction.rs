@@ -142,6 +142,13 @@ pub fn new(
                         .cloned()
                         .map(interaction_from_domain)
                         .collect::<Vec<_>>(),
+                    hooks_gas: order
+                        .hooks_gas
+                        .as_ref()
+                        .map(|gas| solvers_dto::auction::HooksGas {
+                            pre: gas.pre.clone(),
+                            post: gas.post.clone(),
+                        }),
                     sell_token_source: sell_token_source_from_domain(
                         order.sell_token_balance.into(),
                     ),
@@ -36,6 +36,7 @@ use {
         order::{
             BuyTokenDestination,
             EthflowData,
+            EthflowOrderStatus,
             OnchainOrderData,
             Order,
             OrderBuilder,
@@ -605,6 +606,7 @@ async fn test_order_parameters(
         Some(EthflowData {
             user_valid_to: order.0.validTo as i64,
             refund_tx_hash: None,
+            status: EthflowOrderStatus::Created,
         })
     );
     assert_eq!(
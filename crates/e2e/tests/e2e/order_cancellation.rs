@@ -141,7 +141,6 @@ async fn order_cancellation(web3: Web3) {
                 .delete(format!("{API_HOST}{ORDERS_ENDPOINT}/{order_uid}"))
                 .json(&CancellationPayload {
                     signature: cancellation.signature,
-                    signing_scheme: cancellation.signing_scheme,
                 })
                 .send()
                 .await
@@ -154,7 +153,6 @@ async fn order_cancellation(web3: Web3) {
     let cancel_orders = |order_uids: Vec<OrderUid>| {
         let client = services.client();
         let cancellations = OrderCancellations { order_uids };
-        let signing_scheme = EcdsaSigningScheme::Eip712;
         let signature = EcdsaSignature::sign(
             EcdsaSigningScheme::Eip712,
             &onchain.contracts().domain_separator,
@@ -164,8 +162,7 @@ async fn order_cancellation(web3: Web3) {
 
         let signed_cancellations = SignedOrderCancellations {
             data: cancellations,
-            signature,
-            signing_scheme,
+            signature: signature.to_signature(EcdsaSigningScheme::Eip712),
         };
 
         async move {
@@ -8,10 +8,14 @@ use {
         alloy::conversions::{IntoAlloy, IntoLegacy, TryIntoAlloyAsync},
         block_stream::timestamp_of_current_block_in_seconds,
     },
-    model::quote::{OrderQuoteRequest, OrderQuoteSide, QuoteSigningScheme, Validity},
+    model::{
+        order::EthflowOrderStatus,
+        quote::{OrderQuoteRequest, OrderQuoteSide, QuoteSigningScheme, Validity},
+    },
     number::nonzero::U256 as NonZeroU256,
     refunder::refund_service::RefundService,
     sqlx::PgPool,
+    std::time::Duration,
 };
 
 #[tokio::test]
@@ -129,21 +133,25 @@ async fn refunder_tx(web3: Web3) {
 
     // Create the refund service and execute the refund tx
     let pg_pool = PgPool::connect_lazy("postgresql://").expect("failed to create database");
-    let refunder_signer = {
-        match refunder.account().clone().try_into_alloy().await.unwrap() {
-            ethrpc::alloy::Account::Signer(signer) => signer,
-            _ => panic!("Refunder account must be a signer"),
-        }
-    };
+    let refunder_account = refunder.account().clone().try_into_alloy().await.unwrap();
+    let chain_id = web3.eth().chain_id().await.unwrap().as_u64();
     let mut refunder = RefundService::new(
+        chain_id,
         pg_pool,
         web3,
         vec![ethflow_contract.clone(), ethflow_contract_2.clone()],
         validity_duration as i64 / 2,
         10i64,
-        refunder_signer,
+        vec![refunder_account],
         2_000_000_000_000, // max_gas_price: 2000 Gwei
         30_000_000_000,    // start_priority_fee_tip: 30 Gwei
+        refunder::planner::Config {
+            sla: Duration::from_secs(30 * 60),
+            gas_price_threshold: None,
+            batch_target_gas: 1_000_000,
+            gas_per_refund: 35_000,
+            loop_interval: Duration::from_secs(30),
+        },
     );
 
     assert_ne!(
@@ -189,5 +197,16 @@ async fn refunder_tx(web3: Web3) {
                 .is_some()
         };
         wait_for_condition(TIMEOUT, has_tx_hash).await.unwrap();
+        assert_eq!(
+            services
+                .get_order(order)
+                .await
+                .unwrap()
+                .metadata
+                .ethflow_data
+                .unwrap()
+                .status,
+            EthflowOrderStatus::Refunded
+        );
     }
 }
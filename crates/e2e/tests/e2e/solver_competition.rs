@@ -8,6 +8,7 @@ use {
     model::{
         order::{OrderCreation, OrderKind},
         signature::EcdsaSigningScheme,
+        solver_competition_v2::FilterReason,
     },
     secp256k1::SecretKey,
     shared::ethrpc::Web3,
@@ -546,11 +547,25 @@ async fn store_filtered_solutions(web3: Web3) {
     );
     assert!(bad_solution.tx_hash.is_none());
     assert!(bad_solution.reference_score.is_none());
+    // the bad solution executed a token pair worse than the fairness baseline
+    let Some(FilterReason::Unfair { token_pairs }) = &bad_solution.filter_reason else {
+        panic!("missing filter reason");
+    };
+    assert!(!token_pairs.is_empty());
+    assert!(token_pairs.iter().all(|pair| pair.score < pair.baseline));
+    assert!(!competition.fairness_baselines.is_empty());
 
     let good_solution = &competition.solutions[1];
     assert_eq!(good_solution.ranking, 1);
     assert!(!good_solution.filtered_out);
+    assert!(good_solution.filter_reason.is_none());
     assert!(good_solution.is_winner);
+    assert!(
+        good_solution
+            .orders
+            .iter()
+            .all(|order| order.score.is_some())
+    );
     assert_eq!(
         good_solution.solver_address,
         good_solver_account.address().into_alloy()
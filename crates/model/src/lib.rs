@@ -7,6 +7,7 @@ pub mod quote;
 pub mod signature;
 pub mod solver_competition;
 pub mod solver_competition_v2;
+pub mod solver_rewards;
 pub mod time;
 pub mod trade;
 
@@ -550,34 +550,25 @@ impl OrderCancellations {
 }
 
 /// Signed order cancellations.
+///
+/// Cancellations of orders owned by smart contracts are signed with the
+/// `eip1271` scheme. Safes can instead pre-sign the cancellation on-chain with
+/// `signMessage` and use the `presign` scheme. All cancelled orders need to
+/// have the same owner.
 #[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SignedOrderCancellations {
     #[serde(flatten)]
     pub data: OrderCancellations,
-    pub signature: EcdsaSignature,
-    pub signing_scheme: EcdsaSigningScheme,
-}
-
-impl SignedOrderCancellations {
-    pub fn validate(&self, domain_separator: &DomainSeparator) -> Result<H160> {
-        Ok(self
-            .signature
-            .recover(
-                self.signing_scheme,
-                domain_separator,
-                &self.data.hash_struct(),
-            )?
-            .signer)
-    }
+    #[serde(flatten)]
+    pub signature: Signature,
 }
 
 /// An order cancellation as provided to the orderbook by the frontend.
-#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[derive(Eq, PartialEq, Clone, Debug)]
 pub struct OrderCancellation {
     pub order_uid: OrderUid,
-    pub signature: EcdsaSignature,
-    pub signing_scheme: EcdsaSigningScheme,
+    pub signature: Signature,
 }
 
 impl Default for OrderCancellation {
@@ -606,41 +597,33 @@ impl OrderCancellation {
         domain_separator: &DomainSeparator,
         key: SecretKeyRef,
     ) -> Self {
-        let mut result = Self {
+        let signing_scheme = EcdsaSigningScheme::Eip712;
+        let hash_struct = Self::hash_struct_for(&order_uid);
+        Self {
             order_uid,
-            signature: Default::default(),
-            signing_scheme: EcdsaSigningScheme::Eip712,
-        };
-        result.signature = EcdsaSignature::sign(
-            result.signing_scheme,
-            domain_separator,
-            &result.hash_struct(),
-            key,
-        );
-        result
+            signature: EcdsaSignature::sign(signing_scheme, domain_separator, &hash_struct, key)
+                .to_signature(signing_scheme),
+        }
     }
 
     pub fn hash_struct(&self) -> [u8; 32] {
+        Self::hash_struct_for(&self.order_uid)
+    }
+
+    fn hash_struct_for(order_uid: &OrderUid) -> [u8; 32] {
         let mut hash_data = [0u8; 64];
         hash_data[0..32].copy_from_slice(&Self::TYPE_HASH);
-        hash_data[32..64].copy_from_slice(&signing::keccak256(&self.order_uid.0));
+        hash_data[32..64].copy_from_slice(&signing::keccak256(&order_uid.0));
         signing::keccak256(&hash_data)
     }
-
-    pub fn validate(&self, domain_separator: &DomainSeparator) -> Result<H160> {
-        Ok(self
-            .signature
-            .recover(self.signing_scheme, domain_separator, &self.hash_struct())?
-            .signer)
-    }
 }
 
 /// Order cancellation payload that is sent over the API.
 #[derive(Debug, Eq, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CancellationPayload {
-    pub signature: EcdsaSignature,
-    pub signing_scheme: EcdsaSigningScheme,
+    #[serde(flatten)]
+    pub signature: Signature,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Default, Deserialize)]
@@ -648,6 +631,55 @@ pub struct CancellationPayload {
 pub struct EthflowData {
     pub user_valid_to: i64,
     pub refund_tx_hash: Option<H256>,
+    #[serde(default)]
+    pub status: EthflowOrderStatus,
+}
+
+/// Lifecycle of an ethflow order including the refund of the user's ETH.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EthflowOrderStatus {
+    /// The order was placed and can still be executed.
+    #[default]
+    Created,
+    /// The order was executed.
+    Executed,
+    /// The order expired but doesn't get refunded automatically, e.g. because
+    /// it is partially fillable or its slippage is unrealistic. The user has to
+    /// invalidate it to get their ETH back.
+    Expired,
+    /// The order expired and waits for the refunder to return the ETH.
+    RefundPending,
+    /// The ETH was returned to the user, see `refundTxHash`.
+    Refunded,
+    /// The order was invalidated on-chain or rejected by the backend when it
+    /// got placed and will never be executed.
+    Invalidated,
+}
+
+impl EthflowOrderStatus {
+    /// `refundable` tells whether the refunder picks up the order once it
+    /// expired.
+    pub fn new(
+        status: OrderStatus,
+        refund_tx_hash: Option<H256>,
+        refundable: bool,
+        placement_error: bool,
+    ) -> Self {
+        if refund_tx_hash.is_some() {
+            return Self::Refunded;
+        }
+        match status {
+            OrderStatus::Fulfilled => Self::Executed,
+            OrderStatus::Cancelled => Self::Invalidated,
+            OrderStatus::Expired if refundable => Self::RefundPending,
+            OrderStatus::Expired => Self::Expired,
+            OrderStatus::Open | OrderStatus::PresignaturePending if placement_error => {
+                Self::Invalidated
+            }
+            OrderStatus::Open | OrderStatus::PresignaturePending => Self::Created,
+        }
+    }
 }
 
 // We still want to have the `is_refunded` field in the JSON response to stay
@@ -665,12 +697,14 @@ impl ::serde::Serialize for EthflowData {
             user_valid_to: i64,
             refund_tx_hash: Option<H256>,
             is_refunded: bool,
+            status: EthflowOrderStatus,
         }
 
         let ext = Extended {
             user_valid_to: self.user_valid_to,
             refund_tx_hash: self.refund_tx_hash,
             is_refunded: self.refund_tx_hash.is_some(),
+            status: self.status,
         };
 
         ext.serialize(serializer)
@@ -754,6 +788,19 @@ pub struct OrderMetadata {
     /// quote data for reference.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub quote: Option<OrderQuote>,
+    /// Gas used by the order's hooks when they got simulated at order
+    /// placement. Only available if the orderbook simulates hooks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hooks_gas: Option<HooksGas>,
+}
+
+/// Gas used by every pre- and post-hook of an order in the order they get
+/// executed.
+#[derive(Eq, PartialEq, Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HooksGas {
+    pub pre: Vec<u64>,
+    pub post: Vec<u64>,
 }
 
 // uid as 56 bytes: 32 for orderDigest, 20 for ownerAddress and 4 for validTo
@@ -1410,10 +1457,14 @@ mod tests {
                 order_uid: OrderUid(hex!(
                     "2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a"
                 )),
-                signature: EcdsaSignature::from_bytes(signature),
-                signing_scheme: *signing_scheme,
+                signature: EcdsaSignature::from_bytes(signature).to_signature(*signing_scheme),
             };
-            let owner = cancellation.validate(&domain_separator).unwrap();
+            let owner = cancellation
+                .signature
+                .recover(&domain_separator, &cancellation.hash_struct())
+                .unwrap()
+                .unwrap()
+                .signer;
             assert_eq!(owner, expected_owner);
         }
     }
@@ -1508,4 +1559,91 @@ mod tests {
             assert_eq!(cancellations.hash_struct(), struct_hash);
         }
     }
+
+    #[test]
+    fn ethflow_order_status() {
+        let status = |status, refunded: bool, refundable, placement_error| {
+            EthflowOrderStatus::new(
+                status,
+                refunded.then(H256::zero),
+                refundable,
+                placement_error,
+            )
+        };
+        assert_eq!(
+            status(OrderStatus::Open, false, false, false),
+            EthflowOrderStatus::Created
+        );
+        assert_eq!(
+            status(OrderStatus::Open, false, false, true),
+            EthflowOrderStatus::Invalidated
+        );
+        assert_eq!(
+            status(OrderStatus::Fulfilled, false, false, false),
+            EthflowOrderStatus::Executed
+        );
+        assert_eq!(
+            status(OrderStatus::Cancelled, false, false, false),
+            EthflowOrderStatus::Invalidated
+        );
+        assert_eq!(
+            status(OrderStatus::Expired, false, true, false),
+            EthflowOrderStatus::RefundPending
+        );
+        assert_eq!(
+            status(OrderStatus::Expired, false, false, true),
+            EthflowOrderStatus::Expired
+        );
+        // Users invalidating their orders get refunded as well.
+        assert_eq!(
+            status(OrderStatus::Cancelled, true, false, false),
+            EthflowOrderStatus::Refunded
+        );
+    }
+
+    #[test]
+    fn signed_order_cancellations_serialization() {
+        let uid = OrderUid([0x2a; 56]);
+        for (json, signature) in [
+            (
+                json!({
+                    "orderUids": [uid],
+                    "signature": format!("0x{}1b", "00".repeat(64)),
+                    "signingScheme": "eip712",
+                }),
+                Signature::Eip712(EcdsaSignature {
+                    v: 27,
+                    ..Default::default()
+                }),
+            ),
+            (
+                json!({
+                    "orderUids": [uid],
+                    "signature": "0x0102",
+                    "signingScheme": "eip1271",
+                }),
+                Signature::Eip1271(vec![1, 2]),
+            ),
+            (
+                json!({
+                    "orderUids": [uid],
+                    "signature": "0x",
+                    "signingScheme": "presign",
+                }),
+                Signature::PreSign,
+            ),
+        ] {
+            let cancellations = SignedOrderCancellations {
+                data: OrderCancellations {
+                    order_uids: vec![uid],
+                },
+                signature,
+            };
+            assert_eq!(
+                serde_json::from_value::<SignedOrderCancellations>(json.clone()).unwrap(),
+                cancellations
+            );
+            assert_eq!(serde_json::to_value(&cancellations).unwrap(), json);
+        }
+    }
 }
@@ -19,6 +19,10 @@ pub struct Response {
     pub reference_scores: BTreeMap<Address, U256>,
     pub auction: Auction,
     pub solutions: Vec<Solution>,
+    /// Best score of a solution only trading a single directed token pair.
+    /// Solutions trading the pair at a lower score got filtered out as unfair.
+    #[serde(default)]
+    pub fairness_baselines: Vec<FairnessBaseline>,
 }
 
 #[serde_as]
@@ -46,6 +50,42 @@ pub struct Solution {
     pub tx_hash: Option<B256>,
     #[serde_as(as = "Option<HexOrDecimalU256>")]
     pub reference_score: Option<U256>,
+    /// Why the solution got filtered out before picking the winners.
+    #[serde(default)]
+    pub filter_reason: Option<FilterReason>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum FilterReason {
+    /// Some directed token pairs were executed at a lower score than their
+    /// fairness baseline.
+    #[serde(rename_all = "camelCase")]
+    Unfair { token_pairs: Vec<UnfairTokenPair> },
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UnfairTokenPair {
+    pub sell_token: Address,
+    pub buy_token: Address,
+    /// Score the solution got for trading the pair.
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub score: U256,
+    /// Score of the best solution only trading the pair.
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub baseline: U256,
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FairnessBaseline {
+    pub sell_token: Address,
+    pub buy_token: Address,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub score: U256,
 }
 
 #[serde_as]
@@ -63,6 +103,24 @@ pub struct Order {
     pub buy_token: Address,
     /// The sell token address.
     pub sell_token: Address,
+    /// How the execution of the order contributed to the score of the
+    /// solution. Missing for orders that can't capture surplus.
+    #[serde(default)]
+    pub score: Option<OrderScore>,
+}
+
+/// Contribution of an order to the score of a solution. Denominated in the
+/// native token.
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderScore {
+    /// Surplus the user received over their limit price.
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub surplus: U256,
+    /// Protocol fees charged on the trade.
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub protocol_fees: U256,
 }
 
 #[cfg(test)]
@@ -102,13 +160,25 @@ mod tests {
                             "sellAmount": "12",
                             "buyAmount": "13",
                             "buyToken": "0x2222222222222222222222222222222222222222",
-                            "sellToken": "0x2222222222222222222222222222222222222222"
+                            "sellToken": "0x2222222222222222222222222222222222222222",
+                            "score": {
+                                "surplus": "4",
+                                "protocolFees": "1",
+                            },
                         },
                     ],
                     "referenceScore": "10",
                     "txHash": "0x3333333333333333333333333333333333333333333333333333333333333333",
                     "isWinner": true,
                     "filteredOut": false,
+                    "filterReason": null,
+                },
+            ],
+            "fairnessBaselines": [
+                {
+                    "sellToken": "0x2222222222222222222222222222222222222222",
+                    "buyToken": "0x2222222222222222222222222222222222222222",
+                    "score": "5",
                 },
             ],
         });
@@ -142,11 +212,21 @@ mod tests {
                     buy_amount: 13.into(),
                     buy_token: Address::new([0x22; 20]),
                     sell_token: Address::new([0x22; 20]),
+                    score: Some(OrderScore {
+                        surplus: 4.into(),
+                        protocol_fees: 1.into(),
+                    }),
                 }],
                 is_winner: true,
                 filtered_out: false,
                 tx_hash: Some(tx),
                 reference_score: Some(10.into()),
+                filter_reason: None,
+            }],
+            fairness_baselines: vec![FairnessBaseline {
+                sell_token: Address::new([0x22; 20]),
+                buy_token: Address::new([0x22; 20]),
+                score: 5.into(),
             }],
         };
 
@@ -155,4 +235,32 @@ mod tests {
         let deserialized: Response = serde_json::from_value(correct).unwrap();
         assert_eq!(orig, deserialized);
     }
+
+    #[test]
+    fn serialize_filter_reason() {
+        let reason = FilterReason::Unfair {
+            token_pairs: vec![UnfairTokenPair {
+                sell_token: Address::new([0x11; 20]),
+                buy_token: Address::new([0x22; 20]),
+                score: 3.into(),
+                baseline: 5.into(),
+            }],
+        };
+        let correct = serde_json::json!({
+            "kind": "unfair",
+            "tokenPairs": [
+                {
+                    "sellToken": "0x1111111111111111111111111111111111111111",
+                    "buyToken": "0x2222222222222222222222222222222222222222",
+                    "score": "3",
+                    "baseline": "5",
+                },
+            ],
+        });
+        assert_json_matches!(correct, serde_json::to_value(&reason).unwrap());
+        assert_eq!(
+            serde_json::from_value::<FilterReason>(correct).unwrap(),
+            reason
+        );
+    }
 }
@@ -0,0 +1,132 @@
+use {
+    alloy::primitives::Address,
+    num::BigInt,
+    number::serialization::HexOrDecimalU256,
+    primitive_types::U256,
+    serde::{Deserialize, Serialize},
+    serde_with::{DisplayFromStr, serde_as},
+};
+
+/// Solver rewards and penalties of an accounting period.
+#[serde_as]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Response {
+    pub period_id: i64,
+    /// First block of the period.
+    pub start_block: i64,
+    /// First block after the period.
+    pub end_block: i64,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub upper_cap: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub lower_cap: U256,
+    pub protocol_fee_cap_factor: f64,
+    pub payouts: Vec<Payout>,
+    /// Breakdown of the payouts per auction and solver.
+    pub rewards: Vec<Reward>,
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Payout {
+    pub solver: Address,
+    /// Number of winning solutions of the solver.
+    pub wins: i64,
+    /// Number of winning solutions of the solver that got settled.
+    pub settlements: i64,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub protocol_fees: U256,
+    /// Sum of the rewards of the solver in wei. Negative if the penalties
+    /// outweigh the rewards.
+    #[serde_as(as = "DisplayFromStr")]
+    pub reward: BigInt,
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Reward {
+    pub auction_id: i64,
+    pub solver: Address,
+    /// Score of all winning solutions of the solver.
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub score: U256,
+    /// Score of the winning solutions of the solver that got settled.
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub observed_score: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub reference_score: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub protocol_fees: U256,
+    /// Reward of the solver for the auction in wei. Negative for penalties.
+    #[serde_as(as = "DisplayFromStr")]
+    pub reward: BigInt,
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, testlib::assert_json_matches};
+
+    #[test]
+    fn serialize() {
+        let correct = serde_json::json!({
+            "periodId": 3,
+            "startBlock": 100,
+            "endBlock": 200,
+            "upperCap": "12",
+            "lowerCap": "10",
+            "protocolFeeCapFactor": 0.5,
+            "payouts": [
+                {
+                    "solver": "0x2222222222222222222222222222222222222222",
+                    "wins": 4,
+                    "settlements": 3,
+                    "protocolFees": "7",
+                    "reward": "-5",
+                },
+            ],
+            "rewards": [
+                {
+                    "auctionId": 1,
+                    "solver": "0x2222222222222222222222222222222222222222",
+                    "score": "10",
+                    "observedScore": "0",
+                    "referenceScore": "8",
+                    "protocolFees": "0",
+                    "reward": "-5",
+                },
+            ],
+        });
+        let orig = Response {
+            period_id: 3,
+            start_block: 100,
+            end_block: 200,
+            upper_cap: 12.into(),
+            lower_cap: 10.into(),
+            protocol_fee_cap_factor: 0.5,
+            payouts: vec![Payout {
+                solver: Address::new([0x22; 20]),
+                wins: 4,
+                settlements: 3,
+                protocol_fees: 7.into(),
+                reward: BigInt::from(-5),
+            }],
+            rewards: vec![Reward {
+                auction_id: 1,
+                solver: Address::new([0x22; 20]),
+                score: 10.into(),
+                observed_score: 0.into(),
+                reference_score: 8.into(),
+                protocol_fees: 0.into(),
+                reward: BigInt::from(-5),
+            }],
+        };
+
+        let serialized = serde_json::to_value(&orig).unwrap();
+        assert_json_matches!(correct, serialized);
+        let deserialized: Response = serde_json::from_value(correct).unwrap();
+        assert_eq!(orig, deserialized);
+    }
+}
@@ -0,0 +1,182 @@
+//! Lists of banned addresses that are maintained outside of the services and
+//! get reloaded periodically so that they can be changed without a redeploy.
+
+use {
+    super::{Error, Provider},
+    alloy::primitives::Address,
+    futures::{FutureExt, future::BoxFuture},
+    reqwest::Url,
+    std::{
+        collections::HashSet,
+        path::PathBuf,
+        sync::{Arc, RwLock},
+        time::Duration,
+    },
+};
+
+/// Where a list of banned addresses gets loaded from.
+///
+/// Lists contain one address per line. Empty lines and lines starting with `#`
+/// are ignored.
+#[derive(Clone, Debug)]
+pub enum Source {
+    File(PathBuf),
+    Http(reqwest::Client, Url),
+}
+
+impl Source {
+    async fn load(&self) -> Result<HashSet<Address>, Error> {
+        let list = match self {
+            Self::File(path) => tokio::fs::read_to_string(path).await?,
+            Self::Http(client, url) => {
+                client
+                    .get(url.clone())
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .text()
+                    .await?
+            }
+        };
+        parse(&list)
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::File(_) => "file",
+            Self::Http(..) => "http",
+        }
+    }
+}
+
+fn parse(list: &str) -> Result<HashSet<Address>, Error> {
+    list.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            line.parse()
+                .map_err(|_| Error::InvalidAddress(line.to_string()))
+        })
+        .collect()
+}
+
+/// A list of banned addresses that gets reloaded from its [`Source`] in a
+/// background task. If reloading fails the previous list stays in use.
+pub struct ReloadingList {
+    name: &'static str,
+    list: Arc<RwLock<HashSet<Address>>>,
+}
+
+impl ReloadingList {
+    /// Loads the initial list and spawns the task reloading it every
+    /// `interval`. Fails if the initial list can't be loaded so that banned
+    /// users don't get let through because of a misconfigured source.
+    pub async fn new(source: Source, interval: Duration) -> Result<Self, Error> {
+        let initial = source.load().await?;
+        tracing::info!(
+            source = source.name(),
+            len = initial.len(),
+            "loaded banned users list"
+        );
+        let list = Arc::new(RwLock::new(initial));
+
+        let name = source.name();
+        let background = Arc::downgrade(&list);
+        tokio::task::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                // Stop reloading once the provider got dropped.
+                let Some(list) = background.upgrade() else {
+                    break;
+                };
+                reload(&source, &list).await;
+            }
+        });
+
+        Ok(Self { name, list })
+    }
+}
+
+async fn reload(source: &Source, list: &RwLock<HashSet<Address>>) {
+    match source.load().await {
+        Ok(new) => {
+            let mut list = list.write().unwrap();
+            if *list != new {
+                tracing::info!(
+                    source = source.name(),
+                    len = new.len(),
+                    "updated banned users list"
+                );
+                *list = new;
+            }
+        }
+        Err(err) => {
+            tracing::warn!(?err, ?source, "failed to reload banned users list");
+        }
+    }
+}
+
+impl Provider for ReloadingList {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn banned<'a>(&'a self, addresses: &'a HashSet<Address>) -> BoxFuture<'a, HashSet<Address>> {
+        let list = self.list.read().unwrap();
+        let banned = list.intersection(addresses).copied().collect();
+        futures::future::ready(banned).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, alloy::primitives::address, std::io::Write};
+
+    #[test]
+    fn parses_list() {
+        let list = parse(
+            "# sanctioned addresses\n0x1111111111111111111111111111111111111111\n\n  \
+             0x2222222222222222222222222222222222222222  \n",
+        )
+        .unwrap();
+        assert_eq!(
+            list,
+            HashSet::from([
+                address!("1111111111111111111111111111111111111111"),
+                address!("2222222222222222222222222222222222222222"),
+            ])
+        );
+        assert!(matches!(parse("0x12"), Err(Error::InvalidAddress(_))));
+    }
+
+    #[tokio::test]
+    async fn reloads_file() {
+        let a = address!("1111111111111111111111111111111111111111");
+        let b = address!("2222222222222222222222222222222222222222");
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "{a}").unwrap();
+
+        let list = ReloadingList::new(
+            Source::File(file.path().to_owned()),
+            Duration::from_millis(10),
+        )
+        .await
+        .unwrap();
+        let addresses = HashSet::from([a, b]);
+        assert_eq!(list.banned(&addresses).await, HashSet::from([a]));
+
+        std::fs::write(file.path(), format!("{b}\n")).unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(list.banned(&addresses).await, HashSet::from([b]));
+    }
+
+    #[tokio::test]
+    async fn fails_if_initial_load_fails() {
+        let list = ReloadingList::new(
+            Source::File("/nonexistent/banned_users".into()),
+            Duration::from_secs(60),
+        )
+        .await;
+        assert!(matches!(list, Err(Error::File(_))));
+    }
+}
@@ -0,0 +1,154 @@
+//! Banned user detection for order validation.
+//!
+//! Addresses are checked against a chain of [`Provider`]s: a hardcoded list,
+//! lists that get periodically reloaded from a file or an HTTP endpoint and the
+//! Chainalysis Oracle on-chain registry. Addresses on the allow-list are never
+//! considered banned regardless of what the providers report.
+
+use {
+    alloy::primitives::Address,
+    contracts::alloy::ChainalysisOracle,
+    futures::{FutureExt, future::BoxFuture},
+    std::collections::{HashMap, HashSet},
+};
+
+mod list;
+mod onchain;
+
+pub use list::{ReloadingList, Source};
+
+/// A source of information about which addresses are banned.
+pub trait Provider: Send + Sync {
+    /// Name of the provider used to attribute bans in the logs.
+    fn name(&self) -> &str;
+
+    /// Returns the subset of `addresses` which are banned.
+    fn banned<'a>(&'a self, addresses: &'a HashSet<Address>) -> BoxFuture<'a, HashSet<Address>>;
+}
+
+/// A fixed list of banned addresses.
+impl Provider for HashSet<Address> {
+    fn name(&self) -> &str {
+        "list"
+    }
+
+    fn banned<'a>(&'a self, addresses: &'a HashSet<Address>) -> BoxFuture<'a, HashSet<Address>> {
+        let banned = self.intersection(addresses).copied().collect();
+        futures::future::ready(banned).boxed()
+    }
+}
+
+/// Combines multiple providers of banned users with an allow-list.
+pub struct Users {
+    providers: Vec<Box<dyn Provider>>,
+    allowed: HashSet<Address>,
+}
+
+impl Users {
+    /// Creates a new `Users` instance that checks the hardcoded list and, if
+    /// the Chainalysis oracle is deployed on the network, its onchain registry
+    /// of banned addresses.
+    pub fn new(
+        contract: Option<ChainalysisOracle::Instance>,
+        banned_users: Vec<Address>,
+        cache_max_size: u64,
+    ) -> Self {
+        let users = Self::from_set(HashSet::from_iter(banned_users));
+        match contract {
+            Some(instance) => users.with_provider(onchain::Onchain::new(instance, cache_max_size)),
+            None => users,
+        }
+    }
+
+    /// Creates a new `Users` instance that passes all addresses.
+    pub fn none() -> Self {
+        Self {
+            providers: Vec::new(),
+            allowed: HashSet::new(),
+        }
+    }
+
+    /// Creates a new `Users` instance that passes all addresses except for the
+    /// ones in `list`.
+    pub fn from_set(list: HashSet<Address>) -> Self {
+        Self::none().with_provider(list)
+    }
+
+    /// Additionally checks addresses against the provider. Providers are
+    /// queried in the order they were added.
+    pub fn with_provider(mut self, provider: impl Provider + 'static) -> Self {
+        self.providers.push(Box::new(provider));
+        self
+    }
+
+    /// Never considers the addresses banned even if a provider reports them.
+    pub fn with_allowed(mut self, allowed: impl IntoIterator<Item = Address>) -> Self {
+        self.allowed.extend(allowed);
+        self
+    }
+
+    /// Returns the addresses from the input iterator which are banned together
+    /// with the name of the provider that reported them.
+    ///
+    /// Bans don't get logged here because the same addresses get checked over
+    /// and over again. Callers rejecting user requests should log them.
+    pub async fn banned(
+        &self,
+        addresses: impl IntoIterator<Item = Address>,
+    ) -> HashMap<Address, &str> {
+        let mut remaining: HashSet<_> = addresses
+            .into_iter()
+            .filter(|address| !self.allowed.contains(address))
+            .collect();
+
+        let mut banned = HashMap::new();
+        for provider in &self.providers {
+            if remaining.is_empty() {
+                break;
+            }
+            // Addresses banned by one provider don't need to be checked by the
+            // following (potentially more expensive) ones.
+            let newly_banned = provider.banned(&remaining).await;
+            for address in newly_banned {
+                remaining.remove(&address);
+                banned.insert(address, provider.name());
+            }
+        }
+        banned
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("failed to fetch banned users from onchain")]
+    Onchain(#[from] alloy::contract::Error),
+    #[error("failed to read banned users file")]
+    File(#[from] std::io::Error),
+    #[error("failed to fetch banned users list")]
+    Http(#[from] reqwest::Error),
+    #[error("invalid address {0:?} in banned users list")]
+    InvalidAddress(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, alloy::primitives::address};
+
+    #[tokio::test]
+    async fn combines_providers_with_allow_list() {
+        let a = address!("1111111111111111111111111111111111111111");
+        let b = address!("2222222222222222222222222222222222222222");
+        let c = address!("3333333333333333333333333333333333333333");
+        let d = address!("4444444444444444444444444444444444444444");
+
+        let users = Users::from_set([a, b].into())
+            .with_provider(HashSet::from([b, c]))
+            .with_allowed([b]);
+
+        assert_eq!(
+            users.banned([a, b, c, d]).await,
+            HashMap::from([(a, "list"), (c, "list")])
+        );
+        assert!(Users::none().banned([a, b, c, d]).await.is_empty());
+    }
+}
@@ -1,13 +1,15 @@
-//! Banned user detection for order validation.
-//!
-//! Checks if addresses are banned using a hardcoded list and optionally the
-//! Chainalysis Oracle on-chain registry. On-chain results are cached (1-hour
-//! expiry, LRU eviction) with background refresh every 60 seconds.
+//! Provider checking addresses against the Chainalysis Oracle on-chain
+//! registry. Results are cached (1-hour expiry, LRU eviction) with background
+//! refresh every 60 seconds.
 
 use {
+    super::{Error, Provider},
     alloy::primitives::Address,
     contracts::alloy::ChainalysisOracle,
-    futures::future::join_all,
+    futures::{
+        FutureExt,
+        future::{BoxFuture, join_all},
+    },
     moka::sync::Cache,
     std::{
         collections::HashSet,
@@ -16,12 +18,6 @@ use {
     },
 };
 
-/// A list of banned users and an optional registry that can be checked onchain.
-pub struct Users {
-    list: HashSet<Address>,
-    onchain: Option<Arc<Onchain>>,
-}
-
 #[derive(Clone)]
 struct UserMetadata {
     is_banned: bool,
@@ -31,7 +27,7 @@ struct UserMetadata {
 /// Onchain banned user checker using Chainalysis Oracle with caching and
 /// background refresh. Maintains a size-bounded LRU cache with periodic
 /// maintenance to refresh expired entries.
-struct Onchain {
+pub struct Onchain {
     contract: ChainalysisOracle::Instance,
     cache: Cache<Address, UserMetadata>,
 }
@@ -122,112 +118,63 @@ impl Onchain {
     }
 }
 
-impl Users {
-    /// Creates a new `Users` instance that checks the hardcoded list and uses
-    /// the given `web3` instance to determine whether an onchain registry of
-    /// banned addresses is available.
-    pub fn new(
-        contract: Option<ChainalysisOracle::Instance>,
-        banned_users: Vec<Address>,
-        cache_max_size: u64,
-    ) -> Self {
-        Self {
-            list: HashSet::from_iter(banned_users),
-            onchain: contract.map(|instance| Onchain::new(instance, cache_max_size)),
-        }
-    }
-
-    /// Creates a new `Users` instance that passes all addresses.
-    pub fn none() -> Self {
-        Self {
-            list: HashSet::new(),
-            onchain: None,
-        }
+impl Onchain {
+    async fn fetch(&self, address: Address) -> Result<bool, Error> {
+        Ok(self.contract.isSanctioned(address).call().await?)
     }
+}
 
-    /// Creates a new `Users` instance that passes all addresses except for the
-    /// ones in `list`.
-    pub fn from_set(list: HashSet<Address>) -> Self {
-        Self {
-            list,
-            onchain: None,
-        }
+impl Provider for Arc<Onchain> {
+    fn name(&self) -> &str {
+        "chainalysis"
     }
 
-    /// Returns a subset of addresses from the input iterator which are banned.
-    ///
-    /// On cache-misses, it will use the Chainalysis oracle to fetch the users.
-    pub async fn banned(&self, addresses: impl IntoIterator<Item = Address>) -> HashSet<Address> {
-        let mut banned = HashSet::new();
-
-        let need_lookup = addresses
-            .into_iter()
-            .filter(|address| {
-                if self.list.contains(address) {
-                    banned.insert(*address);
-                    false
-                } else {
-                    true
+    fn banned<'a>(&'a self, addresses: &'a HashSet<Address>) -> BoxFuture<'a, HashSet<Address>> {
+        async move {
+            let mut banned = HashSet::new();
+            // On cache-misses, the Chainalysis oracle is used to fetch the users.
+            let need_lookup: Vec<_> = {
+                let mut filtered = Vec::new();
+                for address in addresses {
+                    match self.cache.get(address) {
+                        Some(metadata) => {
+                            metadata.is_banned.then(|| banned.insert(*address));
+                        }
+                        None => {
+                            filtered.push(*address);
+                        }
+                    }
                 }
-            })
-            // Need to collect here to make sure filter gets executed and we insert addresses
-            .collect::<HashSet<_>>();
-
-        let Some(onchain) = &self.onchain else {
-            return banned;
-        };
-        let need_lookup: Vec<_> = {
-            let mut filtered = Vec::new();
-            for address in need_lookup {
-                match onchain.cache.get(&address) {
-                    Some(metadata) => {
-                        metadata.is_banned.then(|| banned.insert(address));
+                filtered
+            };
+
+            let to_cache = join_all(
+                need_lookup
+                    .into_iter()
+                    .map(|address| async move { (address, self.fetch(address).await) }),
+            )
+            .await;
+
+            let now = Instant::now();
+            for (address, result) in to_cache {
+                match result {
+                    Ok(is_banned) => {
+                        self.cache.insert(
+                            address,
+                            UserMetadata {
+                                is_banned,
+                                last_updated: now,
+                            },
+                        );
+                        is_banned.then(|| banned.insert(address));
                     }
-                    None => {
-                        filtered.push(address);
+                    Err(err) => {
+                        tracing::warn!(?err, ?address, "failed to fetch banned status");
                     }
                 }
             }
-            filtered
-        };
-
-        let to_cache = join_all(
-            need_lookup
-                .into_iter()
-                .map(|address| async move { (address, onchain.fetch(address).await) }),
-        )
-        .await;
-
-        let now = Instant::now();
-        for (address, result) in to_cache {
-            match result {
-                Ok(is_banned) => {
-                    onchain.cache.insert(
-                        address,
-                        UserMetadata {
-                            is_banned,
-                            last_updated: now,
-                        },
-                    );
-                    is_banned.then(|| banned.insert(address));
-                }
-                Err(err) => {
-                    tracing::warn!(?err, ?address, "failed to fetch banned status");
-                }
-            }
+            banned
         }
-        banned
+        .boxed()
     }
 }
-
-impl Onchain {
-    async fn fetch(&self, address: Address) -> Result<bool, Error> {
-        Ok(self.contract.isSanctioned(address).call().await?)
-    }
-}
-
-#[derive(Debug, thiserror::Error)]
-pub enum Error {
-    #[error("failed to fetch banned users from onchain")]
-    Onchain(#[from] alloy::contract::Error),
-}
@@ -0,0 +1,323 @@
+//! Configurable limits on how many orders, quotes and cancellations clients
+//! can create.
+//!
+//! Every client is subject to the default limits unless it belongs to one of
+//! the configured tiers. Clients are assigned to tiers by their owner address,
+//! the `appCode` of their app data or the API key they use. This allows
+//! raising the limits for integration partners as well as tightening them for
+//! addresses that spam the API.
+//!
+//! API keys are referenced by their keccak256 hash, the same one that gets
+//! stored in the `api_keys` table, so the configuration contains no secrets.
+
+use {
+    alloy::primitives::{Address, B256, keccak256},
+    moka::sync::Cache,
+    serde::Deserialize,
+    std::{
+        collections::{HashSet, VecDeque},
+        hash::Hash,
+        net::IpAddr,
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    },
+};
+
+/// The limits that apply to a single client.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Limits {
+    /// Maximum number of open out-of-market limit orders per owner.
+    pub max_limit_orders: u64,
+    /// Maximum number of orders an owner can place per minute.
+    #[serde(default)]
+    pub orders_per_minute: Option<u32>,
+    /// Maximum number of quotes an owner can request per minute.
+    #[serde(default)]
+    pub quotes_per_minute: Option<u32>,
+    /// Maximum number of cancellations whose signature has to be checked
+    /// on-chain (e.g. of Safes) a requester can make per minute.
+    #[serde(default)]
+    pub cancellation_checks_per_minute: Option<u32>,
+}
+
+/// A named set of limits together with the clients it applies to.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Tier {
+    pub name: String,
+    #[serde(flatten)]
+    pub limits: Limits,
+    #[serde(default)]
+    pub owners: HashSet<Address>,
+    #[serde(default)]
+    pub app_codes: HashSet<String>,
+    /// keccak256 hashes of the API keys that belong to the tier.
+    #[serde(default)]
+    pub api_key_hashes: HashSet<B256>,
+}
+
+/// Everything known about the client a request is made on behalf of that can
+/// be used to assign it to a tier.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Client<'a> {
+    pub owner: Address,
+    pub app_code: Option<&'a str>,
+    pub api_key: Option<&'a str>,
+}
+
+/// Identifies who made a request that isn't signed by the owner it is made
+/// for (e.g. a quote) so it can't be used to exhaust the limits of that owner.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Requester {
+    ApiKey(String),
+    Ip(IpAddr),
+}
+
+impl Requester {
+    /// The authenticated API key the request was made with, if any.
+    pub fn api_key(&self) -> Option<&str> {
+        match self {
+            Self::ApiKey(key) => Some(key),
+            Self::Ip(_) => None,
+        }
+    }
+}
+
+/// The default limits and all configured tiers.
+#[derive(Debug, Default)]
+pub struct Tiers {
+    default: Limits,
+    tiers: Vec<Tier>,
+}
+
+impl Tiers {
+    pub fn new(default: Limits, tiers: Vec<Tier>) -> Self {
+        Self { default, tiers }
+    }
+
+    /// Tiers that apply the same limits to every client.
+    pub fn uniform(default: Limits) -> Self {
+        Self::new(default, Vec::new())
+    }
+
+    /// Returns the limits that apply to the client.
+    ///
+    /// Owner addresses take precedence over API keys which take precedence
+    /// over app codes. This way an address that is known to spam the API can
+    /// be restricted even if it goes through a partner integration.
+    pub fn limits(&self, client: Client) -> &Limits {
+        let by_owner = || self.find(|tier| tier.owners.contains(&client.owner));
+        let by_api_key = || self.find_by_api_key(client.api_key?);
+        let by_app_code = || {
+            let app_code = client.app_code?;
+            self.find(|tier| tier.app_codes.contains(app_code))
+        };
+        by_owner()
+            .or_else(by_api_key)
+            .or_else(by_app_code)
+            .map(|tier| &tier.limits)
+            .unwrap_or(&self.default)
+    }
+
+    /// Returns the limits that apply to requests which aren't signed by the
+    /// owner they are made for (e.g. quotes). Only the API key of the requester
+    /// is authenticated so owners and app codes of such requests can't be
+    /// trusted to select a tier.
+    pub fn requester_limits(&self, requester: &Requester) -> &Limits {
+        requester
+            .api_key()
+            .and_then(|key| self.find_by_api_key(key))
+            .map(|tier| &tier.limits)
+            .unwrap_or(&self.default)
+    }
+
+    fn find_by_api_key(&self, key: &str) -> Option<&Tier> {
+        let hash = keccak256(key.as_bytes());
+        self.find(|tier| tier.api_key_hashes.contains(&hash))
+    }
+
+    fn find(&self, predicate: impl Fn(&Tier) -> bool) -> Option<&Tier> {
+        self.tiers.iter().find(|tier| predicate(tier))
+    }
+}
+
+/// Counts the requests of every client (by default identified by its owner
+/// address) over a sliding window of one minute. The number of tracked
+/// clients is bounded so that requests from many distinct clients can't
+/// exhaust the memory.
+#[derive(Clone)]
+pub struct RateLimiter<K = Address> {
+    requests: Cache<K, Arc<Mutex<VecDeque<Instant>>>>,
+}
+
+impl<K> Default for RateLimiter<K>
+where
+    K: Hash + Eq + Send + Sync + 'static,
+{
+    fn default() -> Self {
+        Self {
+            // Clients that didn't make a request for a whole window can't be
+            // limited anymore so there is no need to remember them.
+            requests: Cache::builder()
+                .max_capacity(MAX_CLIENTS)
+                .time_to_idle(WINDOW)
+                .build(),
+        }
+    }
+}
+
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// Maximum number of clients whose requests are tracked. Once reached the
+/// least recently active clients are forgotten.
+const MAX_CLIENTS: u64 = 100_000;
+
+impl<K> RateLimiter<K>
+where
+    K: Hash + Eq + Send + Sync + 'static,
+{
+    /// Records a request of the client unless it already made `limit` requests
+    /// within the last minute. Returns whether the request is allowed.
+    pub fn try_acquire(&self, client: K, limit: Option<u32>) -> bool {
+        let Some(limit) = limit else {
+            return true;
+        };
+        let now = Instant::now();
+        let requests = self.requests.get_with(client, Default::default);
+        let mut requests = requests.lock().unwrap();
+        while requests
+            .front()
+            .is_some_and(|request| now.duration_since(*request) >= WINDOW)
+        {
+            requests.pop_front();
+        }
+        if requests.len() >= limit as usize {
+            return false;
+        }
+        requests.push_back(now);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, alloy::primitives::address};
+
+    #[test]
+    fn owner_takes_precedence_over_api_key_and_app_code() {
+        let spammer = address!("1111111111111111111111111111111111111111");
+        let limits = |max_limit_orders| Limits {
+            max_limit_orders,
+            ..Default::default()
+        };
+        let tiers = Tiers::new(
+            limits(10),
+            vec![
+                Tier {
+                    name: "app".to_string(),
+                    limits: limits(100),
+                    owners: Default::default(),
+                    app_codes: ["Partner".to_string()].into(),
+                    api_key_hashes: Default::default(),
+                },
+                Tier {
+                    name: "key".to_string(),
+                    limits: limits(1000),
+                    owners: Default::default(),
+                    app_codes: Default::default(),
+                    api_key_hashes: [keccak256("secret")].into(),
+                },
+                Tier {
+                    name: "spam".to_string(),
+                    limits: limits(1),
+                    owners: [spammer].into(),
+                    app_codes: Default::default(),
+                    api_key_hashes: Default::default(),
+                },
+            ],
+        );
+
+        let limit = |owner, app_code, api_key| {
+            tiers
+                .limits(Client {
+                    owner,
+                    app_code,
+                    api_key,
+                })
+                .max_limit_orders
+        };
+        assert_eq!(limit(Address::ZERO, None, None), 10);
+        assert_eq!(limit(Address::ZERO, Some("Other"), Some("unknown")), 10);
+        assert_eq!(limit(Address::ZERO, Some("Partner"), None), 100);
+        assert_eq!(limit(Address::ZERO, Some("Partner"), Some("secret")), 1000);
+        assert_eq!(limit(spammer, Some("Partner"), Some("secret")), 1);
+    }
+
+    #[test]
+    fn requester_limits_only_depend_on_api_key() {
+        let limits = |max_limit_orders| Limits {
+            max_limit_orders,
+            ..Default::default()
+        };
+        let tiers = Tiers::new(
+            limits(10),
+            vec![Tier {
+                name: "key".to_string(),
+                limits: limits(1000),
+                owners: Default::default(),
+                app_codes: Default::default(),
+                api_key_hashes: [keccak256("secret")].into(),
+            }],
+        );
+        assert_eq!(
+            tiers
+                .requester_limits(&Requester::ApiKey("secret".to_string()))
+                .max_limit_orders,
+            1000
+        );
+        assert_eq!(
+            tiers
+                .requester_limits(&Requester::Ip([127, 0, 0, 1].into()))
+                .max_limit_orders,
+            10
+        );
+    }
+
+    #[test]
+    fn parses_tiers() {
+        let tiers: Vec<Tier> = serde_json::from_str(
+            r#"[{
+                "name": "partner",
+                "maxLimitOrders": 100,
+                "ordersPerMinute": 60,
+                "appCodes": ["Partner"],
+                "apiKeyHashes": ["0x65462b0520ef7d3df61b9992ed3bea0c56ead753be7c8b3614e0ce01e4cac41b"]
+            }]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            tiers[0].limits,
+            Limits {
+                max_limit_orders: 100,
+                orders_per_minute: Some(60),
+                quotes_per_minute: None,
+                cancellation_checks_per_minute: None,
+            }
+        );
+        assert!(tiers[0].app_codes.contains("Partner"));
+        assert!(tiers[0].owners.is_empty());
+        assert!(tiers[0].api_key_hashes.contains(&keccak256("secret")));
+    }
+
+    #[test]
+    fn rate_limits_per_owner() {
+        let limiter = RateLimiter::default();
+        let other = address!("1111111111111111111111111111111111111111");
+        assert!(limiter.try_acquire(Address::ZERO, Some(2)));
+        assert!(limiter.try_acquire(Address::ZERO, Some(2)));
+        assert!(!limiter.try_acquire(Address::ZERO, Some(2)));
+        assert!(limiter.try_acquire(other, Some(2)));
+        assert!(limiter.try_acquire(Address::ZERO, None));
+    }
+}
@@ -1,12 +1,21 @@
 use {
-    crate::{app_data, database::Postgres, orderbook::Orderbook, quoter::QuoteHandler},
+    crate::{
+        api_keys::{ApiKeys, AuthError, Client, Scope},
+        app_data,
+        database::Postgres,
+        orderbook::Orderbook,
+        quoter::QuoteHandler,
+        token_metadata::TokenMetadataHandler,
+    },
     anyhow::Result,
     observe::distributed_tracing::tracing_warp::make_span,
+    order_validation::limits::Requester,
     serde::{Serialize, de::DeserializeOwned},
     shared::price_estimation::{PriceEstimationError, native::NativePriceEstimating},
     std::{
         convert::Infallible,
         fmt::Debug,
+        net::{IpAddr, SocketAddr},
         sync::Arc,
         time::{Duration, Instant},
     },
@@ -14,48 +23,73 @@ use {
         Filter,
         Rejection,
         Reply,
-        filters::BoxedFilter,
+        filters::{BoxedFilter, path::FullPath},
+        http::Method,
         hyper::StatusCode,
         reply::{Json, WithStatus, json, with_status},
     },
 };
 
+mod amend_order;
 mod cancel_order;
 mod cancel_orders;
+mod explain_order;
 mod get_app_data;
 mod get_auction;
+mod get_ethflow_orders_by_tx;
+mod get_market_depth;
 mod get_native_price;
+mod get_order_amendments;
 mod get_order_by_uid;
 mod get_order_status;
 mod get_orders_by_tx;
 mod get_solver_competition;
 mod get_solver_competition_v2;
+mod get_solver_rewards;
 mod get_token_metadata;
 mod get_total_surplus;
+mod get_trade_analytics;
 mod get_trades;
 mod get_user_orders;
 mod post_order;
 mod post_quote;
+mod post_quote_stream;
 mod put_app_data;
 mod version;
 
+#[expect(clippy::too_many_arguments)]
 pub fn handle_all_routes(
     database_write: Postgres,
     database_read: Postgres,
     orderbook: Arc<Orderbook>,
     quotes: Arc<QuoteHandler>,
     app_data: Arc<app_data::Registry>,
+    token_metadata: Arc<TokenMetadataHandler>,
     native_price_estimator: Arc<dyn NativePriceEstimating>,
     quote_timeout: Duration,
+    api_keys: Option<Arc<ApiKeys>>,
+    trusted_proxy_hops: usize,
 ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
     // Note that we add a string with endpoint's name to all responses.
     // This string will be used later to report metrics.
     // It is not used to form the actual server response.
 
-    let routes = vec![
+    let authenticated = api_keys.is_some();
+    let identification = Identification {
+        authenticated,
+        trusted_proxy_hops,
+    };
+    let mut routes = vec![
         (
             "v1/create_order",
-            box_filter(post_order::post_order(orderbook.clone())),
+            box_filter(post_order::post_order(orderbook.clone(), identification)),
+        ),
+        (
+            "v1/explain_order",
+            box_filter(explain_order::explain_order(
+                orderbook.clone(),
+                identification,
+            )),
         ),
         (
             "v1/get_order",
@@ -65,17 +99,30 @@ pub fn handle_all_routes(
             "v1/get_order_status",
             box_filter(get_order_status::get_status(orderbook.clone())),
         ),
+        (
+            "v1/get_order_amendments",
+            box_filter(get_order_amendments::get_order_amendments(
+                orderbook.clone(),
+            )),
+        ),
         (
             "v1/get_trades",
             box_filter(get_trades::get_trades(database_read.clone())),
         ),
         (
             "v1/cancel_order",
-            box_filter(cancel_order::cancel_order(orderbook.clone())),
+            box_filter(cancel_order::cancel_order(
+                orderbook.clone(),
+                identification,
+            )),
+        ),
+        (
+            "v1/amend_order",
+            box_filter(amend_order::amend_order(orderbook.clone(), identification)),
         ),
         (
             "v1/cancel_orders",
-            box_filter(cancel_orders::filter(orderbook.clone())),
+            box_filter(cancel_orders::filter(orderbook.clone(), identification)),
         ),
         (
             "v1/get_user_orders",
@@ -85,11 +132,28 @@ pub fn handle_all_routes(
             "v1/get_orders_by_tx",
             box_filter(get_orders_by_tx::get_orders_by_tx(orderbook.clone())),
         ),
-        ("v1/post_quote", box_filter(post_quote::post_quote(quotes))),
+        (
+            "v1/get_ethflow_orders_by_tx",
+            box_filter(get_ethflow_orders_by_tx::get_ethflow_orders_by_tx(
+                orderbook.clone(),
+            )),
+        ),
+        (
+            "v1/post_quote",
+            box_filter(post_quote::post_quote(quotes.clone(), identification)),
+        ),
+        (
+            "v1/post_quote_stream",
+            box_filter(post_quote_stream::post_quote_stream(quotes, identification)),
+        ),
         (
             "v1/auction",
             box_filter(get_auction::get_auction(orderbook.clone())),
         ),
+        (
+            "v1/get_market_depth",
+            box_filter(get_market_depth::get_market_depth(orderbook.clone())),
+        ),
         (
             "v1/solver_competition",
             box_filter(get_solver_competition::get(Arc::new(
@@ -112,6 +176,14 @@ pub fn handle_all_routes(
                 database_write.clone(),
             )),
         ),
+        (
+            "v1/solver_rewards",
+            box_filter(get_solver_rewards::get(database_read.clone())),
+        ),
+        (
+            "v1/solver_rewards/latest",
+            box_filter(get_solver_rewards::get_latest(database_read.clone())),
+        ),
         ("v1/version", box_filter(version::version())),
         (
             "v1/get_native_price",
@@ -134,11 +206,21 @@ pub fn handle_all_routes(
         ),
         (
             "v1/get_token_metadata",
-            box_filter(get_token_metadata::get_token_metadata(database_read)),
+            box_filter(get_token_metadata::get_token_metadata(token_metadata)),
         ),
     ];
+    // Analytics queries are expensive so they are only served to
+    // authenticated clients.
+    if authenticated {
+        routes.push((
+            "v1/get_trade_analytics",
+            box_filter(get_trade_analytics::get_trade_analytics(
+                database_read.clone(),
+            )),
+        ));
+    }
 
-    finalize_router(routes, "orderbook::api::request_summary")
+    finalize_router(routes, "orderbook::api::request_summary", api_keys)
 }
 
 pub type ApiReply = WithStatus<Json>;
@@ -146,7 +228,10 @@ pub type ApiReply = WithStatus<Json>;
 // We turn Rejection into Reply to workaround warp not setting CORS headers on
 // rejections.
 async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
-    let response = err.default_response();
+    let response = match err.find::<AuthError>() {
+        Some(err) => auth_error_reply(err).into_response(),
+        None => err.default_response(),
+    };
 
     let metrics = ApiMetrics::instance(observe::metrics::get_storage_registry()).unwrap();
     metrics
@@ -160,8 +245,9 @@ async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
 #[derive(prometheus_metric_storage::MetricStorage, Clone, Debug)]
 #[metric(subsystem = "api")]
 struct ApiMetrics {
-    /// Number of completed API requests.
-    #[metric(labels("method", "status_code"))]
+    /// Number of completed API requests. The client is the name of the API
+    /// key the request was made with.
+    #[metric(labels("method", "status_code", "client"))]
     requests_complete: prometheus::IntCounterVec,
 
     /// Number of rejected API requests.
@@ -174,6 +260,8 @@ struct ApiMetrics {
 }
 
 impl ApiMetrics {
+    /// Client label of requests made without an API key.
+    const ANONYMOUS: &'static str = "anonymous";
     // Status codes we care about in our application. Populated with:
     // `rg -oIN 'StatusCode::[A-Z_]+' | sort | uniq`.
     const INITIAL_STATUSES: &'static [StatusCode] = &[
@@ -183,6 +271,7 @@ impl ApiMetrics {
         StatusCode::UNAUTHORIZED,
         StatusCode::FORBIDDEN,
         StatusCode::NOT_FOUND,
+        StatusCode::TOO_MANY_REQUESTS,
         StatusCode::INTERNAL_SERVER_ERROR,
         StatusCode::SERVICE_UNAVAILABLE,
     ];
@@ -198,14 +287,21 @@ impl ApiMetrics {
     fn reset_requests_complete(&self, method: &str) {
         for status in Self::INITIAL_STATUSES {
             self.requests_complete
-                .with_label_values(&[method, status.as_str()])
+                .with_label_values(&[method, status.as_str(), Self::ANONYMOUS])
                 .reset();
         }
     }
 
-    fn on_request_completed(&self, method: &str, status: StatusCode, timer: Instant) {
+    fn on_request_completed(
+        &self,
+        method: &str,
+        status: StatusCode,
+        timer: Instant,
+        client: Option<&Client>,
+    ) {
+        let client = client.map_or(Self::ANONYMOUS, |client| client.name.as_str());
         self.requests_complete
-            .with_label_values(&[method, status.as_str()])
+            .with_label_values(&[method, status.as_str(), client])
             .inc();
         self.requests_duration_seconds
             .with_label_values(&[method])
@@ -292,6 +388,80 @@ pub fn extract_payload_with_max_size<T: DeserializeOwned + Send>(
     warp::body::content_length_limit(max_size).and(warp::body::json())
 }
 
+/// Configures how the clients that make requests get identified.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Identification {
+    /// Whether API keys get authenticated. Only then can requests be
+    /// identified by their API key.
+    pub authenticated: bool,
+    /// How many proxies in front of the API append the address of their peer
+    /// to the `X-Forwarded-For` header. If 0 the header is ignored and the
+    /// socket address of the request is used.
+    pub trusted_proxy_hops: usize,
+}
+
+/// Extracts the optional API key a request was made with. The key determines
+/// which limits apply to the request so it is only used if API keys get
+/// authenticated. Otherwise anybody could claim the limits of any key.
+fn extract_api_key(
+    authenticated: bool,
+) -> impl Filter<Extract = (Option<String>,), Error = Rejection> + Clone {
+    api_key_header().map(move |key: Option<String>| key.filter(|_| authenticated))
+}
+
+fn api_key_header() -> impl Filter<Extract = (Option<String>,), Error = Rejection> + Clone {
+    warp::header::optional(API_KEY_HEADER)
+}
+
+const API_KEY_HEADER: &str = "X-API-Key";
+
+/// Extracts who made the request so requests that aren't signed by an owner
+/// can be rate limited. Requests are identified by their authenticated API key
+/// if they have one and by the IP address of the client otherwise.
+pub fn extract_requester(
+    identification: Identification,
+) -> impl Filter<Extract = (Option<Requester>,), Error = Rejection> + Clone {
+    extract_api_key(identification.authenticated)
+        .and(warp::header::optional::<String>(FORWARDED_FOR_HEADER))
+        .and(warp::addr::remote())
+        .map(
+            move |api_key: Option<String>,
+                  forwarded_for: Option<String>,
+                  remote: Option<SocketAddr>| {
+                if let Some(api_key) = api_key {
+                    return Some(Requester::ApiKey(api_key));
+                }
+                client_ip(
+                    forwarded_for.as_deref(),
+                    remote,
+                    identification.trusted_proxy_hops,
+                )
+                .map(Requester::Ip)
+            },
+        )
+}
+
+/// Returns the IP address of the client given the `X-Forwarded-For` header and
+/// the socket address of a request.
+///
+/// Every trusted proxy appends the address of its peer to the header so the
+/// entry added by the outermost trusted proxy is the first one that can't be
+/// spoofed by the client. If the header has fewer entries than expected the
+/// socket address is used instead.
+fn client_ip(
+    forwarded_for: Option<&str>,
+    remote: Option<SocketAddr>,
+    trusted_proxy_hops: usize,
+) -> Option<IpAddr> {
+    let forwarded = trusted_proxy_hops
+        .checked_sub(1)
+        .and_then(|index| forwarded_for?.rsplit(',').nth(index))
+        .and_then(|ip| ip.trim().parse::<IpAddr>().ok());
+    forwarded.or(remote.map(|remote| remote.ip()))
+}
+
+const FORWARDED_FOR_HEADER: &str = "X-Forwarded-For";
+
 pub type BoxedRoute = BoxedFilter<(Box<dyn Reply>,)>;
 
 pub fn box_filter<Filter_, Reply_>(filter: Filter_) -> BoxedFilter<(Box<dyn Reply>,)>
@@ -302,7 +472,50 @@ where
     filter.map(|a| Box::new(a) as Box<dyn Reply>).boxed()
 }
 
-/// Sets up basic metrics, cors and proper log tracing for all routes.
+/// Authenticates the API key of the request if API keys are enabled.
+/// Anonymous requests are let through unless keys are required.
+fn authenticate(
+    api_keys: Option<Arc<ApiKeys>>,
+) -> impl Filter<Extract = (Option<Client>,), Error = Rejection> + Clone {
+    warp::method()
+        .and(warp::path::full())
+        .and(api_key_header())
+        .and_then(move |method: Method, path: FullPath, key: Option<String>| {
+            let api_keys = api_keys.clone();
+            async move {
+                let Some(api_keys) = api_keys else {
+                    return Ok(None);
+                };
+                api_keys
+                    .authenticate(key.as_deref(), Scope::of_request(&method, path.as_str()))
+                    .await
+                    .map_err(warp::reject::custom)
+            }
+        })
+}
+
+fn auth_error_reply(err: &AuthError) -> ApiReply {
+    match err {
+        AuthError::MissingKey | AuthError::InvalidKey => with_status(
+            error("Unauthorized", err.to_string()),
+            StatusCode::UNAUTHORIZED,
+        ),
+        AuthError::MissingScope(_) => {
+            with_status(error("Forbidden", err.to_string()), StatusCode::FORBIDDEN)
+        }
+        AuthError::RateLimited => with_status(
+            error("TooManyRequests", err.to_string()),
+            StatusCode::TOO_MANY_REQUESTS,
+        ),
+        AuthError::Other(err) => {
+            tracing::error!(?err, "failed to authenticate API key");
+            internal_error_reply()
+        }
+    }
+}
+
+/// Sets up basic metrics, cors, API key authentication and proper log tracing
+/// for all routes.
 ///
 /// # Panics
 ///
@@ -310,6 +523,7 @@ where
 pub fn finalize_router(
     routes: Vec<(&'static str, BoxedRoute)>,
     log_prefix: &'static str,
+    api_keys: Option<Arc<ApiKeys>>,
 ) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
     let metrics = ApiMetrics::instance(observe::metrics::get_storage_registry()).unwrap();
     metrics.reset_requests_rejected();
@@ -332,21 +546,29 @@ pub fn finalize_router(
         )
         .expect("routes cannot be empty");
 
-    let instrumented =
-        warp::any()
-            .map(Instant::now)
-            .and(router)
-            .map(|timer, method, reply: Box<dyn Reply>| {
+    let instrumented = warp::any()
+        .map(Instant::now)
+        .and(authenticate(api_keys))
+        .and(router)
+        .map(
+            |timer, client: Option<Client>, method, reply: Box<dyn Reply>| {
                 let response = reply.into_response();
-                metrics.on_request_completed(method, response.status(), timer);
+                metrics.on_request_completed(method, response.status(), timer, client.as_ref());
                 response
-            });
+            },
+        );
 
     // Final setup
     let cors = warp::cors()
         .allow_any_origin()
         .allow_methods(vec!["GET", "POST", "DELETE", "OPTIONS", "PUT", "PATCH"])
-        .allow_headers(vec!["Origin", "Content-Type", "X-Auth-Token", "X-AppId"]);
+        .allow_headers(vec![
+            "Origin",
+            "Content-Type",
+            "X-Auth-Token",
+            "X-AppId",
+            API_KEY_HEADER,
+        ]);
 
     warp::path!("api" / ..)
         .and(instrumented)
@@ -418,6 +640,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn client_ip_respects_trusted_proxy_hops() {
+        let remote = Some(SocketAddr::from(([10, 0, 0, 1], 80)));
+        let header = Some("1.1.1.1, 2.2.2.2, 3.3.3.3");
+        let ip = |ip: [u8; 4]| Some(IpAddr::from(ip));
+
+        assert_eq!(client_ip(header, remote, 0), ip([10, 0, 0, 1]));
+        assert_eq!(client_ip(header, remote, 1), ip([3, 3, 3, 3]));
+        assert_eq!(client_ip(header, remote, 2), ip([2, 2, 2, 2]));
+        assert_eq!(client_ip(header, remote, 4), ip([10, 0, 0, 1]));
+        assert_eq!(client_ip(None, remote, 1), ip([10, 0, 0, 1]));
+        assert_eq!(client_ip(Some("garbage"), remote, 1), ip([10, 0, 0, 1]));
+        assert_eq!(client_ip(header, None, 0), None);
+    }
+
     #[tokio::test]
     async fn rich_errors_handle_serialization_errors() {
         struct AlwaysErrors;
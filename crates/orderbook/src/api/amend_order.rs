@@ -0,0 +1,68 @@
+use {
+    crate::{
+        api::{
+            ApiReply,
+            Identification,
+            extract_payload,
+            extract_requester,
+            post_order::create_order_response,
+        },
+        orderbook::Orderbook,
+    },
+    model::order::{OrderCreation, OrderUid},
+    order_validation::limits::Requester,
+    std::{convert::Infallible, sync::Arc},
+    warp::{Filter, Rejection},
+};
+
+fn amend_order_request()
+-> impl Filter<Extract = (OrderUid, OrderCreation), Error = Rejection> + Clone {
+    warp::path!("v1" / "orders" / OrderUid)
+        .and(warp::patch())
+        .and(extract_payload())
+}
+
+pub fn amend_order(
+    orderbook: Arc<Orderbook>,
+    identification: Identification,
+) -> impl Filter<Extract = (ApiReply,), Error = Rejection> + Clone {
+    amend_order_request()
+        .and(extract_requester(identification))
+        .and_then(
+            move |old_order_uid: OrderUid, order: OrderCreation, requester: Option<Requester>| {
+                let orderbook = orderbook.clone();
+                async move {
+                    let result = orderbook
+                        .amend_order(&old_order_uid, order.clone(), requester)
+                        .await
+                        .map(|(order_uid, quote_metadata)| {
+                            (order_uid, quote_metadata.and_then(|quote| quote.id))
+                        })
+                        .inspect_err(|err| {
+                            tracing::debug!(%old_order_uid, ?order, ?err, "error amending order");
+                        });
+
+                    Result::<_, Infallible>::Ok(create_order_response(result))
+                }
+            },
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, warp::test::request};
+
+    #[tokio::test]
+    async fn amend_order_request_ok() {
+        let filter = amend_order_request();
+        let uid = OrderUid([1; 56]);
+        let order_payload = OrderCreation::default();
+        let request = request()
+            .path(&format!("/v1/orders/{uid}"))
+            .method("PATCH")
+            .header("content-type", "application/json")
+            .json(&order_payload);
+        let result = request.filter(&filter).await.unwrap();
+        assert_eq!(result, (uid, order_payload));
+    }
+}
@@ -1,10 +1,17 @@
 use {
     crate::{
-        api::{IntoWarpReply, convert_json_response, extract_payload},
+        api::{
+            Identification,
+            IntoWarpReply,
+            convert_json_response,
+            extract_payload,
+            extract_requester,
+        },
         orderbook::{OrderCancellationError, Orderbook},
     },
     anyhow::Result,
     model::order::{CancellationPayload, OrderCancellation, OrderUid},
+    order_validation::limits::Requester,
     std::{convert::Infallible, sync::Arc},
     warp::{Filter, Rejection, hyper::StatusCode, reply::with_status},
 };
@@ -17,7 +24,6 @@ pub fn cancel_order_request()
         .map(|uid, payload: CancellationPayload| OrderCancellation {
             order_uid: uid,
             signature: payload.signature,
-            signing_scheme: payload.signing_scheme,
         })
 }
 
@@ -55,6 +61,18 @@ impl IntoWarpReply for OrderCancellationError {
                 super::error("OnChainOrder", "On-chain orders must be cancelled on-chain"),
                 StatusCode::BAD_REQUEST,
             ),
+            Self::TooManyRequests => with_status(
+                super::error(
+                    "TooManyRequests",
+                    "Too many cancellations requiring on-chain signature checks within the last \
+                     minute",
+                ),
+                StatusCode::TOO_MANY_REQUESTS,
+            ),
+            Self::NoOrders => with_status(
+                super::error("NoOrders", "At least one order has to be cancelled"),
+                StatusCode::BAD_REQUEST,
+            ),
             Self::Other(err) => {
                 tracing::error!(?err, "cancel_order");
                 crate::api::internal_error_reply()
@@ -69,14 +87,17 @@ pub fn cancel_order_response(result: Result<(), OrderCancellationError>) -> supe
 
 pub fn cancel_order(
     orderbook: Arc<Orderbook>,
+    identification: Identification,
 ) -> impl Filter<Extract = (super::ApiReply,), Error = Rejection> + Clone {
-    cancel_order_request().and_then(move |order| {
-        let orderbook = orderbook.clone();
-        async move {
-            let result = orderbook.cancel_order(order).await;
-            Result::<_, Infallible>::Ok(cancel_order_response(result))
-        }
-    })
+    cancel_order_request()
+        .and(extract_requester(identification))
+        .and_then(move |order, requester: Option<Requester>| {
+            let orderbook = orderbook.clone();
+            async move {
+                let result = orderbook.cancel_order(order, requester).await;
+                Result::<_, Infallible>::Ok(cancel_order_response(result))
+            }
+        })
 }
 
 #[cfg(test)]
@@ -110,8 +131,8 @@ mod tests {
                         "202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f"
                     )),
                     v: 27,
-                },
-                signing_scheme: EcdsaSigningScheme::Eip712,
+                }
+                .to_signature(EcdsaSigningScheme::Eip712),
             },
         );
     }
@@ -126,8 +147,7 @@ mod tests {
             .method("DELETE")
             .header("content-type", "application/json")
             .json(&CancellationPayload {
-                signature: cancellation.signature,
-                signing_scheme: cancellation.signing_scheme,
+                signature: cancellation.signature.clone(),
             });
         let result = request.filter(&filter).await.unwrap();
         assert_eq!(result, cancellation);
@@ -165,6 +185,10 @@ mod tests {
             cancel_order_response(Err(OrderCancellationError::OrderNotFound)).into_response();
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
 
+        let response =
+            cancel_order_response(Err(OrderCancellationError::NoOrders)).into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
         let response = cancel_order_response(Err(OrderCancellationError::Other(
             anyhow::Error::msg("test error"),
         )))
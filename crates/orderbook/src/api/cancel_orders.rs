@@ -1,10 +1,11 @@
 use {
     crate::{
-        api::{convert_json_response, extract_payload},
+        api::{Identification, convert_json_response, extract_payload, extract_requester},
         orderbook::{OrderCancellationError, Orderbook},
     },
     anyhow::Result,
     model::order::SignedOrderCancellations,
+    order_validation::limits::Requester,
     std::{convert::Infallible, sync::Arc},
     warp::{Filter, Rejection},
 };
@@ -21,12 +22,15 @@ pub fn response(result: Result<(), OrderCancellationError>) -> super::ApiReply {
 
 pub fn filter(
     orderbook: Arc<Orderbook>,
+    identification: Identification,
 ) -> impl Filter<Extract = (super::ApiReply,), Error = Rejection> + Clone {
-    request().and_then(move |cancellations| {
-        let orderbook = orderbook.clone();
-        async move {
-            let result = orderbook.cancel_orders(cancellations).await;
-            Result::<_, Infallible>::Ok(response(result))
-        }
-    })
+    request().and(extract_requester(identification)).and_then(
+        move |cancellations, requester: Option<Requester>| {
+            let orderbook = orderbook.clone();
+            async move {
+                let result = orderbook.cancel_orders(cancellations, requester).await;
+                Result::<_, Infallible>::Ok(response(result))
+            }
+        },
+    )
 }
@@ -0,0 +1,200 @@
+use {
+    crate::{
+        api::{
+            ApiReply,
+            Identification,
+            IntoWarpReply,
+            extract_payload,
+            extract_requester,
+            post_order::ValidationErrorWrapper,
+            response_body,
+        },
+        orderbook::Orderbook,
+    },
+    model::order::{OrderCreation, OrderUid},
+    order_validation::limits::Requester,
+    primitive_types::H160,
+    serde::Serialize,
+    shared::order_validation::{CheckOutcome, Explanation},
+    std::{convert::Infallible, sync::Arc},
+    warp::{Filter, Rejection, Reply, hyper::StatusCode, reply},
+};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Response {
+    /// Whether the order would be accepted.
+    valid: bool,
+    owner: Option<H160>,
+    uid: Option<OrderUid>,
+    checks: Vec<Check>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Check {
+    name: String,
+    outcome: Outcome,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<String>,
+    /// The error the order would be rejected with if the check failed. Has
+    /// the same format as the errors returned when placing the order.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<serde_json::Value>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+enum Outcome {
+    Passed,
+    Failed,
+    Skipped,
+    NotApplicable,
+}
+
+impl Response {
+    async fn new(explanation: Explanation) -> Self {
+        let valid = explanation.is_valid();
+        let mut checks = Vec::new();
+        for result in explanation.checks {
+            let name = result.check.to_string();
+            checks.push(match result.outcome {
+                CheckOutcome::Passed(details) => Check {
+                    name,
+                    outcome: Outcome::Passed,
+                    details,
+                    error: None,
+                },
+                CheckOutcome::Failed(err) => Check {
+                    name,
+                    outcome: Outcome::Failed,
+                    details: None,
+                    error: Some(error_body(ValidationErrorWrapper(err)).await),
+                },
+                CheckOutcome::Skipped => Check {
+                    name,
+                    outcome: Outcome::Skipped,
+                    details: None,
+                    error: None,
+                },
+                CheckOutcome::NotApplicable => Check {
+                    name,
+                    outcome: Outcome::NotApplicable,
+                    details: None,
+                    error: None,
+                },
+            });
+        }
+        Self {
+            valid,
+            owner: explanation.owner,
+            uid: explanation.uid,
+            checks,
+        }
+    }
+}
+
+/// Renders the error exactly like the order placement endpoint would.
+async fn error_body(err: impl IntoWarpReply) -> serde_json::Value {
+    let body = response_body(err.into_warp_reply().into_response()).await;
+    serde_json::from_slice(&body).unwrap_or_default()
+}
+
+fn explain_order_request() -> impl Filter<Extract = (OrderCreation,), Error = Rejection> + Clone {
+    warp::path!("v1" / "orders" / "explain")
+        .and(warp::post())
+        .and(extract_payload())
+}
+
+pub fn explain_order(
+    orderbook: Arc<Orderbook>,
+    identification: Identification,
+) -> impl Filter<Extract = (ApiReply,), Error = Rejection> + Clone {
+    explain_order_request()
+        .and(extract_requester(identification))
+        .and_then(move |order: OrderCreation, requester: Option<Requester>| {
+            let orderbook = orderbook.clone();
+            async move {
+                let reply = match orderbook.explain_order(order, requester.as_ref()).await {
+                    Ok(explanation) => reply::with_status(
+                        reply::json(&Response::new(explanation).await),
+                        StatusCode::OK,
+                    ),
+                    Err(err) => err.into_warp_reply(),
+                };
+                Result::<_, Infallible>::Ok(reply)
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        shared::order_validation::{Check as CheckKind, CheckResult, ValidationError},
+        warp::test::request,
+    };
+
+    #[tokio::test]
+    async fn explain_order_request_ok() {
+        let filter = explain_order_request();
+        let order_payload = OrderCreation::default();
+        let request = request()
+            .path("/v1/orders/explain")
+            .method("POST")
+            .header("content-type", "application/json")
+            .json(&order_payload);
+        let result = request.filter(&filter).await.unwrap();
+        assert_eq!(result, order_payload);
+    }
+
+    #[tokio::test]
+    async fn serializes_explanation() {
+        let explanation = Explanation {
+            owner: Some(H160([1; 20])),
+            uid: None,
+            checks: vec![
+                CheckResult {
+                    check: CheckKind::Signature,
+                    outcome: CheckOutcome::Passed(Some("details".to_string())),
+                },
+                CheckResult {
+                    check: CheckKind::Amounts,
+                    outcome: CheckOutcome::Failed(ValidationError::ZeroAmount),
+                },
+                CheckResult {
+                    check: CheckKind::Twap,
+                    outcome: CheckOutcome::NotApplicable,
+                },
+            ],
+        };
+        let response = serde_json::to_value(Response::new(explanation).await).unwrap();
+        assert_eq!(
+            response,
+            serde_json::json!({
+                "valid": false,
+                "owner": "0x0101010101010101010101010101010101010101",
+                "uid": null,
+                "checks": [
+                    {
+                        "name": "signature",
+                        "outcome": "passed",
+                        "details": "details",
+                    },
+                    {
+                        "name": "amounts",
+                        "outcome": "failed",
+                        "error": {
+                            "errorType": "ZeroAmount",
+                            "description": "Buy or sell amount is zero.",
+                        },
+                    },
+                    {
+                        "name": "twap",
+                        "outcome": "notApplicable",
+                    },
+                ],
+            })
+        );
+    }
+}
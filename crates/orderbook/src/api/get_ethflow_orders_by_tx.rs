@@ -0,0 +1,48 @@
+use {
+    crate::{api::ApiReply, orderbook::Orderbook},
+    alloy::primitives::B256,
+    anyhow::Result,
+    reqwest::StatusCode,
+    std::{convert::Infallible, sync::Arc},
+    warp::{Filter, Rejection, reply::with_status},
+};
+
+pub fn get_ethflow_orders_by_tx_request()
+-> impl Filter<Extract = (B256,), Error = Rejection> + Clone {
+    warp::path!("v1" / "ethflow" / "transactions" / B256 / "orders").and(warp::get())
+}
+
+pub fn get_ethflow_orders_by_tx(
+    orderbook: Arc<Orderbook>,
+) -> impl Filter<Extract = (ApiReply,), Error = Rejection> + Clone {
+    get_ethflow_orders_by_tx_request().and_then(move |hash: B256| {
+        let orderbook = orderbook.clone();
+        async move {
+            let result = orderbook.get_ethflow_orders_for_placement_tx(&hash).await;
+            Result::<_, Infallible>::Ok(match result {
+                Ok(response) => with_status(warp::reply::json(&response), StatusCode::OK),
+                Err(err) => {
+                    tracing::error!(?err, "get_ethflow_orders_by_tx");
+                    crate::api::internal_error_reply()
+                }
+            })
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::str::FromStr};
+
+    #[tokio::test]
+    async fn request_ok() {
+        let hash_str = "0x0191dbb560e936bd3320d5a505c9c05580a0ebb7e12fe117551ac26e484f295e";
+        let result = warp::test::request()
+            .path(&format!("/v1/ethflow/transactions/{hash_str}/orders"))
+            .method("GET")
+            .filter(&get_ethflow_orders_by_tx_request())
+            .await
+            .unwrap();
+        assert_eq!(result, B256::from_str(hash_str).unwrap());
+    }
+}
@@ -0,0 +1,124 @@
+use {
+    crate::{api::ApiReply, market_depth::MarketDepth, orderbook::Orderbook},
+    alloy::primitives::Address,
+    anyhow::Result,
+    ethrpc::alloy::conversions::IntoLegacy,
+    reqwest::StatusCode,
+    std::{convert::Infallible, sync::Arc},
+    warp::{Filter, Rejection, reply::with_status},
+};
+
+fn get_market_depth_request() -> impl Filter<Extract = (Address, Address), Error = Rejection> + Clone
+{
+    warp::path!("v1" / "markets" / Address / Address / "depth").and(warp::get())
+}
+
+pub fn get_market_depth(
+    orderbook: Arc<Orderbook>,
+) -> impl Filter<Extract = (ApiReply,), Error = Rejection> + Clone {
+    get_market_depth_request().and_then(move |base: Address, quote: Address| {
+        let orderbook = orderbook.clone();
+        async move {
+            let result = orderbook
+                .get_market_depth(base.into_legacy(), quote.into_legacy())
+                .await;
+            if let Err(err) = &result {
+                tracing::error!(?err, ?base, ?quote, "get_market_depth");
+            }
+            Result::<_, Infallible>::Ok(get_market_depth_response(result))
+        }
+    })
+}
+
+fn get_market_depth_response(result: Result<Option<MarketDepth>>) -> ApiReply {
+    match result {
+        Ok(Some(depth)) => with_status(warp::reply::json(&depth), StatusCode::OK),
+        Ok(None) => with_status(
+            super::error("NotFound", "There is no active auction"),
+            StatusCode::NOT_FOUND,
+        ),
+        Err(_) => crate::api::internal_error_reply(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::api::response_body,
+        warp::{Reply, test::request},
+    };
+
+    #[tokio::test]
+    async fn get_market_depth_request_ok() {
+        let base = Address::repeat_byte(1);
+        let quote = Address::repeat_byte(2);
+        let path = format!("/v1/markets/{base:?}/{quote:?}/depth");
+        let result = request()
+            .path(&path)
+            .method("GET")
+            .filter(&get_market_depth_request())
+            .await
+            .unwrap();
+        assert_eq!(result, (base, quote));
+    }
+
+    #[tokio::test]
+    async fn get_market_depth_request_rejects_invalid_paths() {
+        let filter = get_market_depth_request();
+        for path in [
+            "/v1/markets/0x01/0x02/depth",
+            format!("/v1/markets/{:?}/depth", Address::repeat_byte(1)).as_str(),
+            format!(
+                "/v1/markets/{:?}/{:?}",
+                Address::repeat_byte(1),
+                Address::repeat_byte(2)
+            )
+            .as_str(),
+        ] {
+            let result = request().path(path).method("GET").filter(&filter).await;
+            assert!(result.is_err(), "{path}");
+        }
+
+        let path = format!(
+            "/v1/markets/{:?}/{:?}/depth",
+            Address::repeat_byte(1),
+            Address::repeat_byte(2)
+        );
+        let result = request().path(&path).method("POST").filter(&filter).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn get_market_depth_response_ok() {
+        let depth = MarketDepth {
+            auction_id: 1,
+            block: 2,
+            base_token: Default::default(),
+            quote_token: Default::default(),
+            asks: vec![],
+            bids: vec![],
+        };
+        let response = get_market_depth_response(Ok(Some(depth))).into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value =
+            serde_json::from_slice(&response_body(response).await).unwrap();
+        assert_eq!(body["auctionId"], 1);
+    }
+
+    #[tokio::test]
+    async fn get_market_depth_response_without_auction() {
+        let response = get_market_depth_response(Ok(None)).into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let body: serde_json::Value =
+            serde_json::from_slice(&response_body(response).await).unwrap();
+        assert_eq!(body["errorType"], "NotFound");
+    }
+
+    #[tokio::test]
+    async fn get_market_depth_response_error() {
+        let response =
+            get_market_depth_response(Err(anyhow::anyhow!("database down"))).into_response();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
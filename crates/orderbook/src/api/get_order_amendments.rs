@@ -0,0 +1,28 @@
+use {
+    crate::{api::ApiReply, orderbook::Orderbook},
+    model::order::OrderUid,
+    std::{convert::Infallible, sync::Arc},
+    warp::{Filter, Rejection, hyper::StatusCode, reply},
+};
+
+fn get_order_amendments_request() -> impl Filter<Extract = (OrderUid,), Error = Rejection> + Clone {
+    warp::path!("v1" / "orders" / OrderUid / "amendments").and(warp::get())
+}
+
+pub fn get_order_amendments(
+    orderbook: Arc<Orderbook>,
+) -> impl Filter<Extract = (ApiReply,), Error = Rejection> + Clone {
+    get_order_amendments_request().and_then(move |uid| {
+        let orderbook = orderbook.clone();
+        async move {
+            let result = orderbook.get_order_amendments(&uid).await;
+            Result::<_, Infallible>::Ok(match result {
+                Ok(amendments) => reply::with_status(reply::json(&amendments), StatusCode::OK),
+                Err(err) => {
+                    tracing::error!(?err, "get_order_amendments");
+                    crate::api::internal_error_reply()
+                }
+            })
+        }
+    })
+}
@@ -1,14 +1,24 @@
 use {
     crate::{
         api::ApiReply,
+        dto,
         orderbook::{OrderStatusError, Orderbook},
     },
     anyhow::Result,
     model::order::OrderUid,
+    serde::Serialize,
     std::{convert::Infallible, sync::Arc},
     warp::{Filter, Rejection, hyper::StatusCode, reply},
 };
 
+#[derive(Serialize)]
+struct Response {
+    #[serde(flatten)]
+    status: dto::order::Status,
+    #[serde(flatten)]
+    details: dto::order::StatusDetails,
+}
+
 fn get_status_request() -> impl Filter<Extract = (OrderUid,), Error = Rejection> + Clone {
     warp::path!("v1" / "orders" / OrderUid / "status").and(warp::get())
 }
@@ -19,8 +29,12 @@ pub fn get_status(
     get_status_request().and_then(move |uid| {
         let orderbook = orderbook.clone();
         async move {
-            let status = orderbook.get_order_status(&uid).await;
-            Result::<_, Infallible>::Ok(match status {
+            let status = async {
+                let status = orderbook.get_order_status(&uid).await?;
+                let details = orderbook.get_status_details(&uid).await?;
+                Ok::<_, OrderStatusError>(Response { status, details })
+            };
+            Result::<_, Infallible>::Ok(match status.await {
                 Ok(status) => warp::reply::with_status(warp::reply::json(&status), StatusCode::OK),
                 Err(OrderStatusError::NotFound) => reply::with_status(
                     super::error("NotFound", "Order status was not found"),
@@ -34,3 +48,84 @@ pub fn get_status(
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        model::order::{EthflowData, EthflowOrderStatus},
+        serde_json::json,
+    };
+
+    #[test]
+    fn serializes_twap_status_next_to_status() {
+        let response = Response {
+            status: dto::order::Status::Active,
+            details: Default::default(),
+        };
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            json!({ "type": "active" })
+        );
+
+        let response = Response {
+            status: dto::order::Status::Active,
+            details: dto::order::StatusDetails {
+                twap: Some(dto::order::TwapStatus {
+                    parts: 4,
+                    part_duration: 100,
+                    start_time: 1000,
+                    unlocked_parts: 2,
+                    filled_parts: 1,
+                    unlocked_amount: 500.into(),
+                    executed_amount: 300.into(),
+                    next_part_time: Some(1200),
+                }),
+                ethflow: None,
+            },
+        };
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            json!({
+                "type": "active",
+                "twap": {
+                    "parts": 4,
+                    "partDuration": 100,
+                    "startTime": 1000,
+                    "unlockedParts": 2,
+                    "filledParts": 1,
+                    "unlockedAmount": "500",
+                    "executedAmount": "300",
+                    "nextPartTime": 1200,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn serializes_ethflow_status_next_to_status() {
+        let response = Response {
+            status: dto::order::Status::Open,
+            details: dto::order::StatusDetails {
+                twap: None,
+                ethflow: Some(EthflowData {
+                    user_valid_to: 1000,
+                    refund_tx_hash: None,
+                    status: EthflowOrderStatus::RefundPending,
+                }),
+            },
+        };
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            json!({
+                "type": "open",
+                "ethflow": {
+                    "userValidTo": 1000,
+                    "refundTxHash": null,
+                    "isRefunded": false,
+                    "status": "refundPending",
+                },
+            })
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use {
+    crate::database::{Postgres, solver_rewards::LoadSolverRewardsError},
+    anyhow::Result,
+    database::solver_rewards::PeriodId,
+    model::solver_rewards::Response,
+    reqwest::StatusCode,
+    std::convert::Infallible,
+    warp::{
+        Filter,
+        Rejection,
+        reply::{Json, WithStatus, with_status},
+    },
+};
+
+fn request_id() -> impl Filter<Extract = (PeriodId,), Error = Rejection> + Clone {
+    warp::path!("v1" / "solver_rewards" / PeriodId).and(warp::get())
+}
+
+fn request_latest() -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::path!("v1" / "solver_rewards" / "latest").and(warp::get())
+}
+
+pub fn get(db: Postgres) -> impl Filter<Extract = (super::ApiReply,), Error = Rejection> + Clone {
+    request_id().and_then(move |period_id: PeriodId| {
+        let db = db.clone();
+        async move {
+            let result = db.load_solver_rewards(period_id).await;
+            Result::<_, Infallible>::Ok(response(result))
+        }
+    })
+}
+
+pub fn get_latest(
+    db: Postgres,
+) -> impl Filter<Extract = (super::ApiReply,), Error = Rejection> + Clone {
+    request_latest().and_then(move || {
+        let db = db.clone();
+        async move {
+            let result = db.load_latest_solver_rewards().await;
+            Result::<_, Infallible>::Ok(response(result))
+        }
+    })
+}
+
+fn response(result: Result<Response, LoadSolverRewardsError>) -> WithStatus<Json> {
+    match result {
+        Ok(response) => with_status(warp::reply::json(&response), StatusCode::OK),
+        Err(LoadSolverRewardsError::NotFound) => with_status(
+            super::error("NotFound", "no solver rewards found"),
+            StatusCode::NOT_FOUND,
+        ),
+        Err(LoadSolverRewardsError::Other(err)) => {
+            tracing::error!(?err, "load solver rewards");
+            crate::api::internal_error_reply()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, warp::test::request};
+
+    #[tokio::test]
+    async fn request_id_and_latest() {
+        let id = request()
+            .path("/v1/solver_rewards/3")
+            .method("GET")
+            .filter(&request_id())
+            .await
+            .unwrap();
+        assert_eq!(id, 3);
+
+        let latest = request()
+            .path("/v1/solver_rewards/latest")
+            .method("GET")
+            .filter(&request_id())
+            .await;
+        assert!(latest.is_err());
+        request()
+            .path("/v1/solver_rewards/latest")
+            .method("GET")
+            .filter(&request_latest())
+            .await
+            .unwrap();
+    }
+}
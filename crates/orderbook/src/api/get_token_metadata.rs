@@ -1,8 +1,8 @@
 use {
-    crate::database::Postgres,
+    crate::token_metadata::TokenMetadataHandler,
     alloy::primitives::Address,
     hyper::StatusCode,
-    std::convert::Infallible,
+    std::{convert::Infallible, sync::Arc},
     warp::{Filter, Rejection, reply},
 };
 
@@ -11,16 +11,16 @@ fn get_native_prices_request() -> impl Filter<Extract = (Address,), Error = Reje
 }
 
 pub fn get_token_metadata(
-    db: Postgres,
+    tokens: Arc<TokenMetadataHandler>,
 ) -> impl Filter<Extract = (super::ApiReply,), Error = Rejection> + Clone {
     get_native_prices_request().and_then(move |token: Address| {
-        let db = db.clone();
+        let tokens = tokens.clone();
         async move {
-            let result = db.token_metadata(&token).await;
+            let result = tokens.get(token).await;
             let response = match result {
                 Ok(metadata) => reply::with_status(reply::json(&metadata), StatusCode::OK),
                 Err(err) => {
-                    tracing::error!(?err, ?token, "Failed to fetch token's first trade block");
+                    tracing::error!(?err, ?token, "Failed to fetch token metadata");
                     crate::api::internal_error_reply()
                 }
             };
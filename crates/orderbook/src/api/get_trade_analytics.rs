@@ -0,0 +1,178 @@
+use {
+    crate::{
+        api::{ApiReply, error},
+        database::Postgres,
+        trade_analytics::{self, Filter, Interval},
+    },
+    alloy::primitives::Address,
+    chrono::{DateTime, TimeDelta, Utc},
+    serde::Deserialize,
+    std::convert::Infallible,
+    warp::{Filter as _, Rejection, hyper::StatusCode, reply::with_status},
+};
+
+/// Period that is analysed if the request doesn't specify one.
+const DEFAULT_PERIOD: TimeDelta = TimeDelta::days(30);
+/// Longest period that can be analysed in a single request.
+const MAX_PERIOD: TimeDelta = TimeDelta::days(366);
+/// Most trades that can be analysed in a single request. Requests matching
+/// more trades have to be split into shorter periods.
+const MAX_TRADES: usize = 100_000;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Query {
+    owner: Option<Address>,
+    app_code: Option<String>,
+    partner_recipient: Option<Address>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    #[serde(default)]
+    interval: Interval,
+}
+
+impl Query {
+    fn validate(self, now: DateTime<Utc>) -> Result<Filter, String> {
+        if self.owner.is_none() && self.app_code.is_none() && self.partner_recipient.is_none() {
+            return Err("Must specify at least one of owner, appCode or partnerRecipient.".into());
+        }
+        let to = self.to.unwrap_or(now);
+        let from = self.from.unwrap_or(to - DEFAULT_PERIOD);
+        if from >= to {
+            return Err("from must be before to.".into());
+        }
+        if to - from > MAX_PERIOD {
+            return Err(format!(
+                "Period must not be longer than {} days.",
+                MAX_PERIOD.num_days()
+            ));
+        }
+        Ok(Filter {
+            owner: self.owner,
+            app_code: self.app_code,
+            partner_recipient: self.partner_recipient,
+            from,
+            to,
+            interval: self.interval,
+        })
+    }
+}
+
+fn get_trade_analytics_request()
+-> impl warp::Filter<Extract = (Result<Filter, String>,), Error = Rejection> + Clone {
+    warp::path!("v1" / "analytics" / "trades")
+        .and(warp::get())
+        .and(warp::query::<Query>())
+        .map(|query: Query| query.validate(Utc::now()))
+}
+
+pub fn get_trade_analytics(
+    db: Postgres,
+) -> impl warp::Filter<Extract = (ApiReply,), Error = Rejection> + Clone {
+    get_trade_analytics_request().and_then(move |filter: Result<Filter, String>| {
+        let db = db.clone();
+        async move {
+            let filter = match filter {
+                Ok(filter) => filter,
+                Err(msg) => {
+                    let err = error("InvalidAnalyticsFilter", msg);
+                    return Result::<_, Infallible>::Ok(with_status(err, StatusCode::BAD_REQUEST));
+                }
+            };
+            let trades = db.trade_fees(&filter, MAX_TRADES + 1).await;
+            Ok(match trades {
+                Ok(trades) if trades.len() > MAX_TRADES => {
+                    let err = error(
+                        "TooManyTrades",
+                        format!(
+                            "Period contains more than {MAX_TRADES} trades, request a shorter one."
+                        ),
+                    );
+                    with_status(err, StatusCode::BAD_REQUEST)
+                }
+                Ok(trades) => with_status(
+                    warp::reply::json(&trade_analytics::aggregate(trades, &filter)),
+                    StatusCode::OK,
+                ),
+                Err(err) => {
+                    tracing::error!(?err, ?filter, "failed to compute trade analytics");
+                    crate::api::internal_error_reply()
+                }
+            })
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, warp::test::request};
+
+    #[tokio::test]
+    async fn get_trade_analytics_request_ok() {
+        let owner = Address::with_last_byte(1);
+        let from = "2024-01-01T00:00:00Z";
+        let to = "2024-02-01T00:00:00Z";
+        let path =
+            format!("/v1/analytics/trades?owner=0x{owner:x}&from={from}&to={to}&interval=week");
+        let filter = request()
+            .path(&path)
+            .method("GET")
+            .filter(&get_trade_analytics_request())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            filter,
+            Filter {
+                owner: Some(owner),
+                app_code: None,
+                partner_recipient: None,
+                from: from.parse().unwrap(),
+                to: to.parse().unwrap(),
+                interval: Interval::Week,
+            }
+        );
+    }
+
+    #[test]
+    fn query_validation() {
+        let now: DateTime<Utc> = "2024-06-01T00:00:00Z".parse().unwrap();
+        let query = || Query {
+            owner: None,
+            app_code: Some("CoW Swap".to_string()),
+            partner_recipient: None,
+            from: None,
+            to: None,
+            interval: Interval::Day,
+        };
+
+        let filter = query().validate(now).unwrap();
+        assert_eq!(filter.to, now);
+        assert_eq!(filter.from, now - DEFAULT_PERIOD);
+
+        assert!(
+            Query {
+                app_code: None,
+                ..query()
+            }
+            .validate(now)
+            .is_err()
+        );
+        assert!(
+            Query {
+                from: Some(now),
+                ..query()
+            }
+            .validate(now)
+            .is_err()
+        );
+        assert!(
+            Query {
+                from: Some(now - MAX_PERIOD - TimeDelta::days(1)),
+                ..query()
+            }
+            .validate(now)
+            .is_err()
+        );
+    }
+}
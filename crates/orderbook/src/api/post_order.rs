@@ -1,6 +1,6 @@
 use {
     crate::{
-        api::{ApiReply, IntoWarpReply, error, extract_payload},
+        api::{ApiReply, Identification, IntoWarpReply, error, extract_payload, extract_requester},
         orderbook::{AddOrderError, OrderReplacementError, Orderbook},
     },
     anyhow::Result,
@@ -9,6 +9,7 @@ use {
         quote::QuoteId,
         signature,
     },
+    order_validation::limits::Requester,
     shared::order_validation::{
         AppDataValidationError,
         OrderValidToError,
@@ -116,7 +117,7 @@ impl IntoWarpReply for AppDataValidationErrorWrapper {
     }
 }
 
-pub struct ValidationErrorWrapper(ValidationError);
+pub struct ValidationErrorWrapper(pub ValidationError);
 impl IntoWarpReply for ValidationErrorWrapper {
     fn into_warp_reply(self) -> ApiReply {
         match self.0 {
@@ -219,10 +220,29 @@ impl IntoWarpReply for ValidationErrorWrapper {
                 error("TooManyLimitOrders", "Too many limit orders"),
                 StatusCode::BAD_REQUEST,
             ),
+            ValidationError::TooManyOrders => with_status(
+                error(
+                    "TooManyOrders",
+                    "Too many orders placed within the last minute",
+                ),
+                StatusCode::TOO_MANY_REQUESTS,
+            ),
             ValidationError::TooMuchGas => with_status(
                 error("TooMuchGas", "Executing order requires too many gas units"),
                 StatusCode::BAD_REQUEST,
             ),
+            ValidationError::InvalidTwap(err) => with_status(
+                error("InvalidTwap", err.to_string()),
+                StatusCode::BAD_REQUEST,
+            ),
+            ValidationError::InvalidPermit(err) => with_status(
+                error("InvalidPermit", err.to_string()),
+                StatusCode::BAD_REQUEST,
+            ),
+            ValidationError::InvalidHooks(err) => with_status(
+                error("InvalidHooks", err.to_string()),
+                StatusCode::BAD_REQUEST,
+            ),
 
             ValidationError::Other(err) => {
                 tracing::error!(?err, "ValidationErrorWrapper");
@@ -285,6 +305,22 @@ impl IntoWarpReply for OrderReplacementError {
                 ),
                 StatusCode::BAD_REQUEST,
             ),
+            OrderReplacementError::IncompatibleAmendment => with_status(
+                super::error(
+                    "IncompatibleAmendment",
+                    "The amended order must trade the same tokens with the same kind and partial \
+                     fillability as the old order",
+                ),
+                StatusCode::BAD_REQUEST,
+            ),
+            OrderReplacementError::AmendmentExceedsRemaining => with_status(
+                super::error(
+                    "AmendmentExceedsRemaining",
+                    "The amended order must not trade more than the remaining amount of the old \
+                     order",
+                ),
+                StatusCode::BAD_REQUEST,
+            ),
             OrderReplacementError::Other(err) => {
                 tracing::error!(?err, "replace_order");
                 crate::api::internal_error_reply()
@@ -304,26 +340,29 @@ pub fn create_order_response(
 
 pub fn post_order(
     orderbook: Arc<Orderbook>,
+    identification: Identification,
 ) -> impl Filter<Extract = (ApiReply,), Error = Rejection> + Clone {
-    create_order_request().and_then(move |order: OrderCreation| {
-        let orderbook = orderbook.clone();
-        async move {
-            let result = orderbook
-                .add_order(order.clone())
-                .await
-                .map(|(order_uid, quote_metadata)| {
-                    let quote_id = quote_metadata.as_ref().and_then(|q| q.id);
-                    let quote_solver = quote_metadata.as_ref().map(|q| q.solver);
-                    tracing::debug!(%order_uid, ?quote_id, ?quote_solver, "order created");
-                    (order_uid, quote_metadata.and_then(|quote| quote.id))
-                })
-                .inspect_err(|err| {
-                    tracing::debug!(?order, ?err, "error creating order");
-                });
+    create_order_request()
+        .and(extract_requester(identification))
+        .and_then(move |order: OrderCreation, requester: Option<Requester>| {
+            let orderbook = orderbook.clone();
+            async move {
+                let result = orderbook
+                    .add_order(order.clone(), requester)
+                    .await
+                    .map(|(order_uid, quote_metadata)| {
+                        let quote_id = quote_metadata.as_ref().and_then(|q| q.id);
+                        let quote_solver = quote_metadata.as_ref().map(|q| q.solver);
+                        tracing::debug!(%order_uid, ?quote_id, ?quote_solver, "order created");
+                        (order_uid, quote_metadata.and_then(|quote| quote.id))
+                    })
+                    .inspect_err(|err| {
+                        tracing::debug!(?order, ?err, "error creating order");
+                    });
 
-            Result::<_, Infallible>::Ok(create_order_response(result))
-        }
-    })
+                Result::<_, Infallible>::Ok(create_order_response(result))
+            }
+        })
 }
 
 #[cfg(test)]
@@ -6,6 +6,7 @@ use {
     },
     anyhow::Result,
     model::quote::OrderQuoteRequest,
+    order_validation::limits::Requester,
     reqwest::StatusCode,
     shared::order_quoting::CalculateQuoteError,
     std::{convert::Infallible, sync::Arc},
@@ -21,20 +22,25 @@ fn post_quote_request() -> impl Filter<Extract = (OrderQuoteRequest,), Error = R
 
 pub fn post_quote(
     quotes: Arc<QuoteHandler>,
+    identification: api::Identification,
 ) -> impl Filter<Extract = (super::ApiReply,), Error = Rejection> + Clone {
-    post_quote_request().and_then(move |request: OrderQuoteRequest| {
-        let quotes = quotes.clone();
-        async move {
-            let result = quotes
-                .calculate_quote(&request)
-                .await
-                .map_err(OrderQuoteErrorWrapper);
-            if let Err(err) = &result {
-                tracing::warn!(%err, ?request, "post_quote error");
-            }
-            Result::<_, Infallible>::Ok(convert_json_response(result))
-        }
-    })
+    post_quote_request()
+        .and(api::extract_requester(identification))
+        .and_then(
+            move |request: OrderQuoteRequest, requester: Option<Requester>| {
+                let quotes = quotes.clone();
+                async move {
+                    let result = quotes
+                        .calculate_quote(&request, requester.as_ref())
+                        .await
+                        .map_err(OrderQuoteErrorWrapper);
+                    if let Err(err) = &result {
+                        tracing::warn!(%err, ?request, "post_quote error");
+                    }
+                    Result::<_, Infallible>::Ok(convert_json_response(result))
+                }
+            },
+        )
 }
 
 #[derive(Debug, Error)]
@@ -48,6 +54,13 @@ impl IntoWarpReply for OrderQuoteErrorWrapper {
             OrderQuoteError::CalculateQuote(err) => {
                 CalculateQuoteErrorWrapper(err).into_warp_reply()
             }
+            OrderQuoteError::TooManyQuotes => warp::reply::with_status(
+                error(
+                    "TooManyQuotes",
+                    "Too many quotes requested within the last minute",
+                ),
+                StatusCode::TOO_MANY_REQUESTS,
+            ),
         }
     }
 }
@@ -0,0 +1,116 @@
+use {
+    super::post_quote::OrderQuoteErrorWrapper,
+    crate::{
+        api::{self, IntoWarpReply},
+        quoter::{OrderQuoteError, QuoteHandler, QuoteUpdate},
+    },
+    futures::StreamExt,
+    model::quote::{OrderQuoteRequest, OrderQuoteResponse},
+    order_validation::limits::Requester,
+    std::{convert::Infallible, sync::Arc},
+    warp::{Filter, Rejection, Reply, sse::Event},
+};
+
+fn post_quote_stream_request()
+-> impl Filter<Extract = (OrderQuoteRequest,), Error = Rejection> + Clone {
+    warp::path!("v1" / "quote" / "stream")
+        .and(warp::post())
+        .and(api::extract_payload())
+}
+
+pub fn post_quote_stream(
+    quotes: Arc<QuoteHandler>,
+    identification: api::Identification,
+) -> impl Filter<Extract = (Box<dyn Reply>,), Error = Rejection> + Clone {
+    post_quote_stream_request()
+        .and(api::extract_requester(identification))
+        .and_then(
+            move |request: OrderQuoteRequest, requester: Option<Requester>| {
+                let quotes = quotes.clone();
+                async move {
+                    let reply: Box<dyn Reply> = match quotes
+                        .calculate_quote_stream(request.clone(), requester.as_ref())
+                        .await
+                    {
+                        Ok(updates) => {
+                            let events = updates.then(|update| async move {
+                                Result::<_, Infallible>::Ok(to_event(update).await)
+                            });
+                            Box::new(warp::sse::reply(warp::sse::keep_alive().stream(events)))
+                        }
+                        Err(err) => {
+                            tracing::warn!(%err, ?request, "post_quote_stream error");
+                            Box::new(OrderQuoteErrorWrapper(err).into_warp_reply())
+                        }
+                    };
+                    Result::<_, Infallible>::Ok(reply)
+                }
+            },
+        )
+}
+
+async fn to_event(update: Result<QuoteUpdate, OrderQuoteError>) -> Event {
+    match update {
+        Ok(QuoteUpdate::Preliminary(quote)) => quote_event("preliminary", &quote),
+        Ok(QuoteUpdate::Final(quote)) => quote_event("final", &quote),
+        Err(err) => {
+            tracing::warn!(%err, "post_quote_stream error");
+            // Reuse the error body of the regular quote endpoint so clients can
+            // handle both endpoints the same way.
+            let response = OrderQuoteErrorWrapper(err)
+                .into_warp_reply()
+                .into_response();
+            let body = api::response_body(response).await;
+            Event::default()
+                .event("error")
+                .data(String::from_utf8_lossy(&body))
+        }
+    }
+}
+
+fn quote_event(name: &str, quote: &OrderQuoteResponse) -> Event {
+    Event::default()
+        .event(name)
+        .json_data(quote)
+        .expect("quote responses are always serializable")
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, warp::test::request};
+
+    #[tokio::test]
+    async fn post_quote_stream_request_ok() {
+        let filter = post_quote_stream_request();
+        let request_payload = OrderQuoteRequest::default();
+        let request = request()
+            .path("/v1/quote/stream")
+            .method("POST")
+            .header("content-type", "application/json")
+            .json(&request_payload);
+        let result = request.filter(&filter).await.unwrap();
+        assert_eq!(result, request_payload);
+    }
+
+    #[tokio::test]
+    async fn post_quote_stream_request_does_not_match_regular_quote() {
+        let filter = post_quote_stream_request();
+        let request = request()
+            .path("/v1/quote")
+            .method("POST")
+            .header("content-type", "application/json")
+            .json(&OrderQuoteRequest::default());
+        assert!(request.filter(&filter).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn error_events_contain_api_error() {
+        let event = to_event(Err(OrderQuoteError::CalculateQuote(
+            shared::order_quoting::CalculateQuoteError::QuoteNotVerified,
+        )))
+        .await;
+        let event = event.to_string();
+        assert!(event.contains("event:error"));
+        assert!(event.contains(r#""errorType":"QuoteNotVerified""#));
+    }
+}
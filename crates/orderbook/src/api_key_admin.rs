@@ -0,0 +1,90 @@
+//! Command line tool to create and revoke the API keys the orderbook
+//! authenticates requests with.
+
+use {
+    crate::{
+        api_keys::{self, Scope},
+        database::Postgres,
+    },
+    anyhow::{Context, Result},
+    clap::Parser,
+    database::{api_keys::ApiKey, byte_array::ByteArray},
+    url::Url,
+};
+
+#[derive(Debug, Parser)]
+struct Arguments {
+    /// Url of the Postgres database the orderbook writes to.
+    #[clap(long, env, default_value = "postgresql://")]
+    db_write_url: Url,
+
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum Command {
+    /// Creates a new API key and prints it. Only a hash of the key gets stored
+    /// so it can't be looked up again later.
+    Create {
+        /// Human readable name of the integrator, used to label metrics.
+        #[clap(long)]
+        name: String,
+
+        /// Groups of endpoints the key can be used for.
+        #[clap(long, value_enum, use_value_delimiter = true, required = true)]
+        scopes: Vec<Scope>,
+
+        /// Maximum number of requests per minute. Unlimited if unset.
+        #[clap(long)]
+        requests_per_minute: Option<u32>,
+    },
+    /// Revokes an API key.
+    Revoke {
+        #[clap(long)]
+        key: String,
+    },
+}
+
+pub async fn start(args: impl Iterator<Item = String>) {
+    let args = Arguments::parse_from(args);
+    if let Err(err) = run(args).await {
+        eprintln!("{err:?}");
+        std::process::exit(1);
+    }
+}
+
+async fn run(args: Arguments) -> Result<()> {
+    let postgres = Postgres::try_new(args.db_write_url.as_str())?;
+    let mut ex = postgres.pool.acquire().await?;
+    match args.command {
+        Command::Create {
+            name,
+            scopes,
+            requests_per_minute,
+        } => {
+            let key = api_keys::generate();
+            database::api_keys::insert(
+                &mut ex,
+                &ApiKey {
+                    key_hash: ByteArray(api_keys::hash(&key)),
+                    name,
+                    scopes: scopes.iter().map(ToString::to_string).collect(),
+                    requests_per_minute: requests_per_minute
+                        .map(i32::try_from)
+                        .transpose()
+                        .context("requests per minute out of range")?,
+                    revoked: false,
+                },
+            )
+            .await?;
+            println!("{key}");
+        }
+        Command::Revoke { key } => {
+            let found =
+                database::api_keys::revoke(&mut ex, &ByteArray(api_keys::hash(&key))).await?;
+            anyhow::ensure!(found, "unknown API key");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,223 @@
+//! Optional authentication of API requests with keys stored in the database.
+//!
+//! Keys identify integrators so that they can be given their own rate limits
+//! and show up in the API metrics. Every key is restricted to a set of
+//! [`Scope`]s and can be revoked at any time.
+
+use {
+    crate::database::Postgres,
+    alloy::primitives::keccak256,
+    anyhow::Result,
+    cached::{Cached, TimedSizedCache},
+    database::api_keys::ApiKey,
+    order_validation::limits::RateLimiter,
+    std::sync::{Arc, Mutex},
+    thiserror::Error,
+    warp::http::Method,
+};
+
+/// Groups of endpoints an API key can be allowed to use.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, clap::ValueEnum, strum::AsRefStr, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum Scope {
+    /// Requesting quotes.
+    Quote,
+    /// Creating, replacing and cancelling orders and registering app data.
+    Order,
+    /// Reading any data.
+    Read,
+    /// Aggregated trade analytics. Unlike the other scopes these endpoints
+    /// can't be used anonymously because the queries are expensive.
+    Analytics,
+}
+
+impl Scope {
+    /// Returns the scope needed to make the request.
+    pub fn of_request(method: &Method, path: &str) -> Self {
+        if path.starts_with("/api/v1/quote") {
+            Self::Quote
+        } else if path.starts_with("/api/v1/analytics") {
+            Self::Analytics
+        } else if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+            Self::Read
+        } else {
+            Self::Order
+        }
+    }
+
+    /// Whether requests need an API key even if keys are optional otherwise.
+    pub fn requires_key(&self) -> bool {
+        matches!(self, Self::Analytics)
+    }
+}
+
+/// An integrator whose API key was successfully authenticated.
+#[derive(Clone, Debug)]
+pub struct Client {
+    pub name: String,
+}
+
+#[derive(Debug, Error)]
+pub enum AuthError {
+    #[error("missing API key")]
+    MissingKey,
+    #[error("unknown or revoked API key")]
+    InvalidKey,
+    #[error("API key is not allowed to access {0} endpoints")]
+    MissingScope(Scope),
+    #[error("API key exceeded its rate limit")]
+    RateLimited,
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+impl warp::reject::Reject for AuthError {}
+
+pub struct ApiKeys {
+    database: Postgres,
+    /// Whether requests without an API key get rejected.
+    required: bool,
+    /// Keys are cached for a short time only so that revocations take effect
+    /// quickly.
+    known: Mutex<TimedSizedCache<[u8; 32], Arc<ApiKey>>>,
+    /// Hashes of keys that don't exist. Cached separately so that requests
+    /// with made up keys neither evict known keys nor hit the database every
+    /// time.
+    unknown: Mutex<TimedSizedCache<[u8; 32], ()>>,
+    rate_limiter: RateLimiter<[u8; 32]>,
+}
+
+impl ApiKeys {
+    pub fn new(database: Postgres, required: bool) -> Self {
+        Self {
+            database,
+            required,
+            known: Mutex::new(TimedSizedCache::with_size_and_lifespan_and_refresh(
+                1000, 60, false,
+            )),
+            unknown: Mutex::new(TimedSizedCache::with_size_and_lifespan_and_refresh(
+                10_000, 30, false,
+            )),
+            rate_limiter: Default::default(),
+        }
+    }
+
+    /// Checks that the API key the request was made with may be used for
+    /// endpoints of the scope. Returns the authenticated client or `None` for
+    /// anonymous requests.
+    pub async fn authenticate(
+        &self,
+        key: Option<&str>,
+        scope: Scope,
+    ) -> Result<Option<Client>, AuthError> {
+        let Some(key) = key else {
+            return match self.required || scope.requires_key() {
+                true => Err(AuthError::MissingKey),
+                false => Ok(None),
+            };
+        };
+
+        let key_hash = hash(key);
+        let key = self
+            .find(key_hash)
+            .await
+            .map_err(AuthError::Other)?
+            .filter(|key| !key.revoked)
+            .ok_or(AuthError::InvalidKey)?;
+        if !key.scopes.iter().any(|allowed| allowed == scope.as_ref()) {
+            return Err(AuthError::MissingScope(scope));
+        }
+        // A negative limit can't be satisfied and blocks the key entirely.
+        let limit = key
+            .requests_per_minute
+            .map(|limit| u32::try_from(limit).unwrap_or_default());
+        if !self.rate_limiter.try_acquire(key_hash, limit) {
+            return Err(AuthError::RateLimited);
+        }
+
+        Ok(Some(Client {
+            name: key.name.clone(),
+        }))
+    }
+
+    async fn find(&self, key_hash: [u8; 32]) -> Result<Option<Arc<ApiKey>>> {
+        if let Some(key) = self.known.lock().unwrap().cache_get(&key_hash) {
+            return Ok(Some(key.clone()));
+        }
+        if self.unknown.lock().unwrap().cache_get(&key_hash).is_some() {
+            return Ok(None);
+        }
+        match self.database.api_key(key_hash).await? {
+            Some(key) => {
+                let key = Arc::new(key);
+                self.known.lock().unwrap().cache_set(key_hash, key.clone());
+                Ok(Some(key))
+            }
+            None => {
+                self.unknown.lock().unwrap().cache_set(key_hash, ());
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Generates a new random API key.
+pub fn generate() -> String {
+    const_hex::encode(rand::random::<[u8; 32]>())
+}
+
+/// Hash of the API key as it gets stored in the database.
+pub fn hash(key: &str) -> [u8; 32] {
+    keccak256(key.as_bytes()).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scope_of_request() {
+        assert_eq!(
+            Scope::of_request(&Method::POST, "/api/v1/quote"),
+            Scope::Quote
+        );
+        assert_eq!(
+            Scope::of_request(&Method::POST, "/api/v1/quote/stream"),
+            Scope::Quote
+        );
+        assert_eq!(
+            Scope::of_request(&Method::POST, "/api/v1/orders"),
+            Scope::Order
+        );
+        assert_eq!(
+            Scope::of_request(&Method::DELETE, "/api/v1/orders"),
+            Scope::Order
+        );
+        assert_eq!(
+            Scope::of_request(&Method::PUT, "/api/v1/app_data"),
+            Scope::Order
+        );
+        assert_eq!(
+            Scope::of_request(&Method::GET, "/api/v1/orders/0x01"),
+            Scope::Read
+        );
+        assert_eq!(
+            Scope::of_request(&Method::GET, "/api/v1/analytics/trades"),
+            Scope::Analytics
+        );
+    }
+
+    #[test]
+    fn generated_keys_are_unique() {
+        let key = generate();
+        assert_eq!(key.len(), 64);
+        assert_ne!(key, generate());
+        assert_eq!(hash(&key), keccak256(key.as_bytes()).0);
+    }
+
+    #[test]
+    fn scopes_are_lowercase() {
+        assert_eq!(Scope::Quote.as_ref(), "quote");
+        assert_eq!(Scope::Order.to_string(), "order");
+    }
+}
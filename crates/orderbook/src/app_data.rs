@@ -5,13 +5,15 @@ use {
     },
     anyhow::{Context, Result},
     app_data::AppDataHash,
+    model::order::Order,
+    std::sync::Arc,
 };
 
 /// CoW Protocol API app-data registry.
 pub struct Registry {
     validator: app_data::Validator,
     database: Postgres,
-    ipfs: Option<IpfsAppData>,
+    ipfs: Option<Arc<IpfsAppData>>,
 }
 
 impl Registry {
@@ -19,7 +21,7 @@ impl Registry {
     pub fn new(
         validator: app_data::Validator,
         database: Postgres,
-        ipfs: Option<IpfsAppData>,
+        ipfs: Option<Arc<IpfsAppData>>,
     ) -> Self {
         Self {
             validator,
@@ -38,7 +40,8 @@ impl Registry {
     /// app data hash with the document.
     ///
     /// Returns `New` if the app data was newly added or `AlreadyExisted` if an
-    /// exactly matching entry already existed.
+    /// exactly matching entry already existed. Newly added app data is pinned
+    /// to IPFS in the background.
     pub async fn register(
         &self,
         hash: Option<AppDataHash>,
@@ -60,13 +63,36 @@ impl Registry {
             .insert_full_app_data(&validated.hash, &validated.document)
             .await
         {
-            Ok(()) => Ok((Registered::New, validated.hash)),
+            Ok(()) => {
+                self.pin(validated.hash, validated.document);
+                Ok((Registered::New, validated.hash))
+            }
             Err(InsertError::Duplicate) => Ok((Registered::AlreadyExisted, validated.hash)),
             Err(InsertError::Mismatch(existing)) => Err(RegisterError::DataMismatch { existing }),
             Err(InsertError::Other(err)) => Err(RegisterError::Other(err)),
         }
     }
 
+    /// Pins the full app data an order was placed with. Only needed when
+    /// placing the order stored the app data for the first time, otherwise it
+    /// was already pinned when it got stored.
+    pub fn pin_order_app_data(&self, order: &Order) {
+        if let Some(full_app_data) = &order.metadata.full_app_data
+            && !order.data.app_data.is_zero()
+        {
+            self.pin(order.data.app_data, full_app_data.clone());
+        }
+    }
+
+    /// Pins the app data to IPFS in the background.
+    fn pin(&self, hash: AppDataHash, document: String) {
+        if let Some(ipfs) = &self.ipfs {
+            // Pinning can be slow, so it shouldn't delay the response.
+            let ipfs = ipfs.clone();
+            tokio::spawn(async move { ipfs.pin(&hash, document.as_bytes()).await });
+        }
+    }
+
     /// Finds full app data for an order that only has the contract app data
     /// hash.
     ///
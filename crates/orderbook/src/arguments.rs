@@ -1,6 +1,8 @@
 use {
     alloy::primitives::Address,
+    anyhow::Context,
     chrono::{DateTime, Utc},
+    order_validation::limits,
     reqwest::Url,
     shared::{
         arguments::{display_option, display_secret_option},
@@ -8,7 +10,7 @@ use {
         http_client,
         price_estimation::{self, NativePriceEstimators},
     },
-    std::{net::SocketAddr, num::NonZeroUsize, str::FromStr, time::Duration},
+    std::{net::SocketAddr, num::NonZeroUsize, path::PathBuf, str::FromStr, time::Duration},
 };
 
 #[derive(clap::Parser)]
@@ -19,6 +21,9 @@ pub struct Arguments {
     #[clap(flatten)]
     pub order_quoting: shared::arguments::OrderQuotingArguments,
 
+    #[clap(flatten)]
+    pub banned_users_sources: shared::arguments::BannedUsersArguments,
+
     #[clap(flatten)]
     pub http_client: http_client::Arguments,
 
@@ -121,15 +126,72 @@ pub struct Arguments {
     #[clap(long, env, default_value = "10")]
     pub max_limit_orders_per_user: u64,
 
-    /// If set, the orderbook will use this IPFS gateway to fetch full app data
-    /// for orders that only specify the contract app data hash.
+    /// Max number of orders a user can place per minute. Unlimited if unset.
+    #[clap(long, env)]
+    pub max_orders_per_minute: Option<u32>,
+
+    /// Max number of quotes a user can request per minute. Unlimited if unset.
+    #[clap(long, env)]
+    pub max_quotes_per_minute: Option<u32>,
+
+    /// Max number of cancellations whose signature has to be checked on-chain
+    /// (e.g. of Safes) an API key or IP address can request per minute.
+    #[clap(long, env, default_value = "10")]
+    pub max_cancellation_checks_per_minute: u32,
+
+    /// Path to a JSON file with a list of tiers that override the default
+    /// order and quote limits for specific owners, app codes or API keys.
+    /// API keys are referenced by the keccak256 hash of the key.
     #[clap(long, env)]
-    pub ipfs_gateway: Option<Url>,
+    pub order_limit_tiers: Option<LimitTiersFile>,
 
-    /// Authentication key for Pinata IPFS gateway.
+    /// Authenticate requests that come with an `X-API-Key` header against the
+    /// API keys stored in the database.
+    #[clap(long, env, action = clap::ArgAction::Set, default_value = "false")]
+    pub api_key_authentication: bool,
+
+    /// Reject requests without an API key. Only has an effect if API key
+    /// authentication is enabled.
+    #[clap(long, env, action = clap::ArgAction::Set, default_value = "false")]
+    pub require_api_key: bool,
+
+    /// How many trusted proxies in front of the API append the address of
+    /// their peer to the `X-Forwarded-For` header. Requests without an API
+    /// key are rate limited by the address the outermost trusted proxy saw.
+    /// If 0 the header is ignored and the socket address is used.
+    #[clap(long, env, default_value = "0")]
+    pub trusted_proxy_hops: usize,
+
+    /// If set, the orderbook will use these IPFS gateways to fetch full app
+    /// data for orders that only specify the contract app data hash. Gateways
+    /// are tried in the specified order.
+    #[clap(long, env, use_value_delimiter = true)]
+    pub ipfs_gateway: Vec<Url>,
+
+    /// Authentication key for Pinata IPFS gateway. Only sent to the first
+    /// configured gateway.
     #[clap(long, env)]
     pub ipfs_pinata_auth: Option<String>,
 
+    /// Kubo RPC API of an IPFS node that app data registered via the API gets
+    /// added to and pinned on.
+    #[clap(long, env)]
+    pub ipfs_node_api: Option<Url>,
+
+    /// Endpoint of an IPFS Pinning Service API compatible service that app
+    /// data registered via the API or placed with orders gets pinned to.
+    #[clap(long, env, requires = "ipfs_pinning_service_token")]
+    pub ipfs_pinning_service: Option<Url>,
+
+    /// Access token for the IPFS pinning service.
+    #[clap(long, env)]
+    pub ipfs_pinning_service_token: Option<String>,
+
+    /// Multiaddresses of peers the IPFS pinning service can fetch app data
+    /// from (e.g. the configured IPFS node).
+    #[clap(long, env, use_value_delimiter = true)]
+    pub ipfs_pinning_service_origins: Vec<String>,
+
     /// Set the maximum size in bytes of order app data.
     #[clap(long, env, default_value = "8192")]
     pub app_data_size_limit: usize,
@@ -138,11 +200,51 @@ pub struct Arguments {
     #[clap(long, env, default_value = "8000000")]
     pub max_gas_per_order: u64,
 
+    /// Simulate the pre- and post-hooks of orders on placement. Orders whose
+    /// hooks revert or use more gas than declared in their app data get
+    /// rejected and the gas the hooks used gets stored with the order.
+    #[clap(long, env, action = clap::ArgAction::Set, default_value = "false")]
+    pub simulate_hooks: bool,
+
     /// The number of past solver competitions to look back at to determine
     /// whether an order is actively being bid on.
     #[clap(long, env, default_value = "5")]
     pub active_order_competition_threshold: u32,
 
+    /// Minimum time an expired ethflow order must have been valid for to get
+    /// reported as waiting for a refund. Should match the refunder's
+    /// `--min-validity-duration`.
+    #[clap(
+        long,
+        env,
+        default_value = "2m",
+        value_parser = humantime::parse_duration,
+    )]
+    pub ethflow_refund_min_validity_duration: Duration,
+
+    /// Minimum price deviation from the quote (in basis points) an expired
+    /// ethflow order must have to get reported as waiting for a refund. Should
+    /// match the refunder's `--min-price-deviation-bps`.
+    #[clap(long, env, default_value = "190")]
+    pub ethflow_refund_min_price_deviation_bps: i64,
+
+    /// Run the built-in watch-tower which indexes ComposableCoW conditional
+    /// orders and places their discrete orders once they become tradeable.
+    /// Should only be enabled on a single orderbook instance.
+    #[clap(long, env, action = clap::ArgAction::Set, default_value = "false")]
+    pub composable_cow_watch_tower: bool,
+
+    /// Override the address of the ComposableCoW contract watched by the
+    /// watch-tower.
+    #[clap(long, env)]
+    pub composable_cow_address: Option<Address>,
+
+    /// The block the ComposableCoW contract was deployed at. Indexing of
+    /// conditional orders starts there. Required when the watch-tower is
+    /// enabled.
+    #[clap(long, env, required_if_eq("composable_cow_watch_tower", "true"))]
+    pub composable_cow_deployment_block: Option<u64>,
+
     #[clap(flatten)]
     pub volume_fee_config: Option<VolumeFeeConfig>,
 }
@@ -204,11 +306,31 @@ impl FromStr for FeeFactor {
     }
 }
 
+/// Order limit tiers loaded from a JSON file. The file gets read while parsing
+/// the arguments so a missing or malformed file is reported on startup.
+#[derive(Clone, Debug)]
+pub struct LimitTiersFile {
+    pub path: PathBuf,
+    pub tiers: Vec<limits::Tier>,
+}
+
+impl FromStr for LimitTiersFile {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let path = PathBuf::from(s);
+        let tiers = std::fs::read_to_string(&path).context("failed to read order limit tiers")?;
+        let tiers = serde_json::from_str(&tiers).context("failed to parse order limit tiers")?;
+        Ok(Self { path, tiers })
+    }
+}
+
 impl std::fmt::Display for Arguments {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         let Arguments {
             shared,
             order_quoting,
+            banned_users_sources,
             http_client,
             token_owner_finder,
             price_estimation,
@@ -226,18 +348,36 @@ impl std::fmt::Display for Arguments {
             native_price_estimators,
             fast_price_estimation_results_required,
             max_limit_orders_per_user,
+            max_orders_per_minute,
+            max_quotes_per_minute,
+            max_cancellation_checks_per_minute,
+            order_limit_tiers,
+            api_key_authentication,
+            require_api_key,
+            trusted_proxy_hops,
             ipfs_gateway,
             ipfs_pinata_auth,
+            ipfs_node_api,
+            ipfs_pinning_service,
+            ipfs_pinning_service_token,
+            ipfs_pinning_service_origins,
             app_data_size_limit,
             db_write_url: db_url,
             db_read_url,
             max_gas_per_order,
+            simulate_hooks,
             active_order_competition_threshold,
+            ethflow_refund_min_validity_duration,
+            ethflow_refund_min_price_deviation_bps,
+            composable_cow_watch_tower,
+            composable_cow_address,
+            composable_cow_deployment_block,
             volume_fee_config,
         } = self;
 
         write!(f, "{shared}")?;
         write!(f, "{order_quoting}")?;
+        write!(f, "{banned_users_sources}")?;
         write!(f, "{http_client}")?;
         write!(f, "{token_owner_finder}")?;
         write!(f, "{price_estimation}")?;
@@ -279,14 +419,58 @@ impl std::fmt::Display for Arguments {
             "fast_price_estimation_results_required: {fast_price_estimation_results_required}"
         )?;
         writeln!(f, "max_limit_orders_per_user: {max_limit_orders_per_user}")?;
+        display_option(f, "max_orders_per_minute", max_orders_per_minute)?;
+        display_option(f, "max_quotes_per_minute", max_quotes_per_minute)?;
+        writeln!(
+            f,
+            "max_cancellation_checks_per_minute: {max_cancellation_checks_per_minute}"
+        )?;
+        display_option(
+            f,
+            "order_limit_tiers",
+            &order_limit_tiers.as_ref().map(|file| file.path.display()),
+        )?;
+        writeln!(f, "api_key_authentication: {api_key_authentication}")?;
+        writeln!(f, "require_api_key: {require_api_key}")?;
+        writeln!(f, "trusted_proxy_hops: {trusted_proxy_hops}")?;
         writeln!(f, "ipfs_gateway: {ipfs_gateway:?}")?;
         display_secret_option(f, "ipfs_pinata_auth", ipfs_pinata_auth.as_ref())?;
+        display_option(f, "ipfs_node_api", ipfs_node_api)?;
+        display_option(f, "ipfs_pinning_service", ipfs_pinning_service)?;
+        display_secret_option(
+            f,
+            "ipfs_pinning_service_token",
+            ipfs_pinning_service_token.as_ref(),
+        )?;
+        writeln!(
+            f,
+            "ipfs_pinning_service_origins: {ipfs_pinning_service_origins:?}"
+        )?;
         writeln!(f, "app_data_size_limit: {app_data_size_limit}")?;
         writeln!(f, "max_gas_per_order: {max_gas_per_order}")?;
+        writeln!(f, "simulate_hooks: {simulate_hooks}")?;
         writeln!(
             f,
             "active_order_competition_threshold: {active_order_competition_threshold}"
         )?;
+        writeln!(
+            f,
+            "ethflow_refund_min_validity_duration: {ethflow_refund_min_validity_duration:?}"
+        )?;
+        writeln!(
+            f,
+            "ethflow_refund_min_price_deviation_bps: {ethflow_refund_min_price_deviation_bps}"
+        )?;
+        writeln!(
+            f,
+            "composable_cow_watch_tower: {composable_cow_watch_tower}"
+        )?;
+        display_option(f, "composable_cow_address", composable_cow_address)?;
+        display_option(
+            f,
+            "composable_cow_deployment_block",
+            composable_cow_deployment_block,
+        )?;
         writeln!(f, "volume_fee_config: {volume_fee_config:?}")?;
 
         Ok(())
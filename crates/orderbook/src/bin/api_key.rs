@@ -0,0 +1,4 @@
+#[tokio::main]
+async fn main() {
+    orderbook::api_key_admin::start(std::env::args()).await;
+}
@@ -0,0 +1,13 @@
+use {anyhow::Result, database::byte_array::ByteArray};
+
+impl super::Postgres {
+    pub async fn api_key(&self, key_hash: [u8; 32]) -> Result<Option<database::api_keys::ApiKey>> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["api_key"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await?;
+        Ok(database::api_keys::fetch(&mut ex, &ByteArray(key_hash)).await?)
+    }
+}
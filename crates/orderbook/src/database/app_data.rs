@@ -53,6 +53,51 @@ impl super::Postgres {
 
         Ok(())
     }
+
+    /// App data stored before the `app_code` column got added doesn't have
+    /// one. Backfills it in batches. Failures are only logged since the
+    /// backfill resumes on the next start.
+    pub async fn backfill_app_codes(self) {
+        let mut after = ByteArray([0; 32]);
+        loop {
+            match self.backfill_app_codes_batch(&after).await {
+                Ok(Some(last)) => after = last,
+                Ok(None) => break,
+                Err(err) => {
+                    tracing::warn!(?err, "failed to backfill app codes");
+                    return;
+                }
+            }
+        }
+        tracing::debug!("backfilled app codes");
+    }
+
+    /// Backfills the app codes of the next batch of app data after `after`.
+    /// Returns the last processed app data or `None` if there was nothing left
+    /// to backfill.
+    async fn backfill_app_codes_batch(
+        &self,
+        after: &database::AppId,
+    ) -> Result<Option<database::AppId>> {
+        const BATCH_SIZE: i64 = 1000;
+
+        let mut ex = self.pool.acquire().await?;
+        let missing = database::app_data::missing_app_codes(&mut ex, after, BATCH_SIZE).await?;
+        let Some(last) = missing.last().map(|row| row.contract_app_data) else {
+            return Ok(None);
+        };
+        let (contract_app_data, app_codes): (Vec<_>, Vec<_>) = missing
+            .iter()
+            .map(|row| {
+                (
+                    row.contract_app_data,
+                    database::app_data::app_code(&row.full_app_data),
+                )
+            })
+            .unzip();
+        database::app_data::set_app_codes(&mut ex, &contract_app_data, &app_codes).await?;
+        Ok(Some(last))
+    }
 }
 
 #[derive(Debug)]
@@ -16,4 +16,14 @@ impl super::Postgres {
         let auction = dto::AuctionWithId { id, auction };
         Ok(Some(auction))
     }
+
+    pub async fn most_recent_auction_id(&self) -> Result<Option<dto::AuctionId>> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["load_most_recent_auction_id"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await?;
+        Ok(database::auction::load_most_recent_id(&mut ex).await?)
+    }
 }
@@ -1,11 +1,14 @@
+pub mod api_keys;
 pub mod app_data;
 pub mod auction_prices;
 pub mod auctions;
 mod fee_policies;
+pub mod order_amendments;
 pub mod orders;
 pub mod quotes;
 pub mod solver_competition;
 pub mod solver_competition_v2;
+pub mod solver_rewards;
 pub mod total_surplus;
 pub mod trades;
 
@@ -37,21 +40,24 @@ impl Postgres {
         })
     }
 
+    /// Stores the full app data of the order and returns whether it wasn't
+    /// stored before.
     async fn insert_order_app_data(
         order: &Order,
         ex: &mut PgConnection,
-    ) -> Result<(), InsertionError> {
-        if let Some(full_app_data) = order.metadata.full_app_data.as_ref() {
-            let contract_app_data = &ByteArray(order.data.app_data.0);
-            let full_app_data = full_app_data.as_bytes();
-            if let Some(existing) =
-                database::app_data::insert(ex, contract_app_data, full_app_data).await?
-                && full_app_data != existing
-            {
-                return Err(InsertionError::AppDataMismatch(existing));
+    ) -> Result<bool, InsertionError> {
+        let Some(full_app_data) = order.metadata.full_app_data.as_ref() else {
+            return Ok(false);
+        };
+        let contract_app_data = &ByteArray(order.data.app_data.0);
+        let full_app_data = full_app_data.as_bytes();
+        match database::app_data::insert(ex, contract_app_data, full_app_data).await? {
+            None => Ok(true),
+            Some(existing) if full_app_data != existing => {
+                Err(InsertionError::AppDataMismatch(existing))
             }
+            Some(_) => Ok(false),
         }
-        Ok(())
     }
 }
 
@@ -0,0 +1,35 @@
+use {
+    crate::dto,
+    anyhow::{Context, Result},
+    database::byte_array::ByteArray,
+    model::order::OrderUid,
+    number::conversions::big_decimal_to_u256,
+};
+
+impl super::Postgres {
+    pub async fn order_amendments(&self, uid: &OrderUid) -> Result<Vec<dto::order::Amendment>> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["order_amendments"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await?;
+        database::order_amendments::lineage(&mut ex, &ByteArray(uid.0))
+            .await?
+            .into_iter()
+            .map(|amendment| {
+                Ok(dto::order::Amendment {
+                    old_order_uid: OrderUid(amendment.old_order_uid.0),
+                    new_order_uid: OrderUid(amendment.new_order_uid.0),
+                    executed_sell_amount: big_decimal_to_u256(&amendment.executed_sell_amount)
+                        .context("executed sell amount is not a valid u256")?,
+                    executed_buy_amount: big_decimal_to_u256(&amendment.executed_buy_amount)
+                        .context("executed buy amount is not a valid u256")?,
+                    executed_fee_amount: big_decimal_to_u256(&amendment.executed_fee_amount)
+                        .context("executed fee amount is not a valid u256")?,
+                    timestamp: amendment.amendment_timestamp,
+                })
+            })
+            .collect()
+    }
+}
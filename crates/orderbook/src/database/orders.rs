@@ -1,6 +1,6 @@
 use {
     super::Postgres,
-    crate::dto::TokenMetadata,
+    crate::dto::{TokenMetadata, TradeStats},
     alloy::primitives::{Address, B256},
     anyhow::{Context as _, Result},
     app_data::AppDataHash,
@@ -18,11 +18,13 @@ use {
     model::{
         order::{
             EthflowData,
+            EthflowOrderStatus,
             Interactions,
             OnchainOrderData,
             Order,
             OrderClass,
             OrderData,
+            OrderKind,
             OrderMetadata,
             OrderStatus,
             OrderUid,
@@ -46,6 +48,7 @@ use {
             buy_token_destination_from,
             buy_token_destination_into,
             extract_interactions,
+            hooks_gas_from,
             onchain_order_placement_error_from,
             order_class_from,
             order_class_into,
@@ -67,15 +70,31 @@ use {
 #[cfg_attr(test, mockall::automock)]
 #[async_trait::async_trait]
 pub trait OrderStoring: Send + Sync {
-    async fn insert_order(&self, order: &Order) -> Result<(), InsertionError>;
+    /// Inserts the order together with its full app data. Returns whether the
+    /// full app data wasn't stored before. The same applies to
+    /// [`Self::replace_order`] and [`Self::amend_order`].
+    async fn insert_order(&self, order: &Order) -> Result<bool, InsertionError>;
     async fn cancel_orders(&self, order_uids: Vec<OrderUid>, now: DateTime<Utc>) -> Result<()>;
     async fn cancel_order(&self, order_uid: &OrderUid, now: DateTime<Utc>) -> Result<()>;
     async fn replace_order(
         &self,
         old_order: &OrderUid,
         new_order: &Order,
-    ) -> Result<(), InsertionError>;
+    ) -> Result<bool, InsertionError>;
+    /// Atomically cancels `old_order` and inserts `new_order` recording that
+    /// the new order is an amendment of the old one. Fails with
+    /// [`InsertionError::AlreadyCancelled`] if the old order got cancelled (or
+    /// amended) concurrently and with
+    /// [`InsertionError::AmendmentExceedsRemaining`] if the old order traded
+    /// in the meantime and the new order would exceed what remains.
+    async fn amend_order(
+        &self,
+        old_order: &Order,
+        new_order: &Order,
+    ) -> Result<bool, InsertionError>;
     async fn orders_for_tx(&self, tx_hash: &B256) -> Result<Vec<Order>>;
+    /// All ethflow orders placed by the given transaction.
+    async fn ethflow_orders_for_placement_tx(&self, tx_hash: &B256) -> Result<Vec<Order>>;
     /// All orders of a single user ordered by creation date descending (newest
     /// orders first).
     async fn user_orders(
@@ -95,6 +114,10 @@ pub enum InsertionError {
     /// Full app data to be inserted doesn't match existing.
     AppDataMismatch(Vec<u8>),
     MetadataSerializationFailed(serde_json::Error),
+    /// The order that should be replaced is no longer open.
+    AlreadyCancelled,
+    /// The amended order would trade more than what remains of the old order.
+    AmendmentExceedsRemaining,
 }
 
 impl From<sqlx::Error> for InsertionError {
@@ -217,12 +240,30 @@ async fn insert_order(order: &Order, ex: &mut PgConnection) -> Result<(), Insert
             .map_err(InsertionError::DbError)?;
     }
 
+    if let Some(hooks_gas) = order.metadata.hooks_gas.as_ref() {
+        let to_db = |gas: &[u64]| -> Vec<i64> {
+            gas.iter()
+                .map(|gas| i64::try_from(*gas).unwrap_or(i64::MAX))
+                .collect()
+        };
+        database::order_hooks_gas::insert(
+            ex,
+            &database::order_hooks_gas::OrderHooksGas {
+                order_uid,
+                pre_hooks: to_db(&hooks_gas.pre),
+                post_hooks: to_db(&hooks_gas.post),
+            },
+        )
+        .await
+        .map_err(InsertionError::DbError)?;
+    }
+
     Ok(())
 }
 
 #[async_trait::async_trait]
 impl OrderStoring for Postgres {
-    async fn insert_order(&self, order: &Order) -> Result<(), InsertionError> {
+    async fn insert_order(&self, order: &Order) -> Result<bool, InsertionError> {
         let _timer = super::Metrics::get()
             .database_queries
             .with_label_values(&["insert_order"])
@@ -233,10 +274,10 @@ impl OrderStoring for Postgres {
         let mut ex = connection.begin().await?;
 
         insert_order(&order, &mut ex).await?;
-        Self::insert_order_app_data(&order, &mut ex).await?;
+        let new_app_data = Self::insert_order_app_data(&order, &mut ex).await?;
 
         ex.commit().await?;
-        Ok(())
+        Ok(new_app_data)
     }
 
     async fn cancel_orders(&self, order_uids: Vec<OrderUid>, now: DateTime<Utc>) -> Result<()> {
@@ -270,7 +311,7 @@ impl OrderStoring for Postgres {
         &self,
         old_order: &model::order::OrderUid,
         new_order: &model::order::Order,
-    ) -> anyhow::Result<(), super::orders::InsertionError> {
+    ) -> anyhow::Result<bool, super::orders::InsertionError> {
         let _timer = super::Metrics::get()
             .database_queries
             .with_label_values(&["replace_order"])
@@ -289,9 +330,7 @@ impl OrderStoring for Postgres {
                     )
                     .await?;
                     insert_order(&new_order, ex).await?;
-                    Self::insert_order_app_data(&new_order, ex).await?;
-
-                    Ok(())
+                    Self::insert_order_app_data(&new_order, ex).await
                 }
                 .boxed()
             })
@@ -324,6 +363,92 @@ impl OrderStoring for Postgres {
         .transpose()
     }
 
+    async fn amend_order(
+        &self,
+        old_order: &Order,
+        new_order: &Order,
+    ) -> Result<bool, InsertionError> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["amend_order"])
+            .start_timer();
+
+        let mut amendment = database::order_amendments::OrderAmendment {
+            new_order_uid: ByteArray(new_order.metadata.uid.0),
+            old_order_uid: ByteArray(old_order.metadata.uid.0),
+            executed_sell_amount: BigDecimal::new(
+                old_order.metadata.executed_sell_amount.clone().into(),
+                0,
+            ),
+            executed_buy_amount: BigDecimal::new(
+                old_order.metadata.executed_buy_amount.clone().into(),
+                0,
+            ),
+            executed_fee_amount: u256_to_big_decimal(&old_order.metadata.executed_fee_amount),
+            amendment_timestamp: new_order.metadata.creation_date,
+        };
+        let (old_total, new_total) = match old_order.data.kind {
+            OrderKind::Buy => (old_order.data.buy_amount, new_order.data.buy_amount),
+            OrderKind::Sell => (old_order.data.sell_amount, new_order.data.sell_amount),
+        };
+        let kind = old_order.data.kind;
+        let new_order = new_order.clone();
+        let mut connection = self.pool.acquire().await?;
+        connection
+            .transaction(move |ex| {
+                async move {
+                    // Only the amendment that actually cancels the old order
+                    // may insert its replacement. Concurrent amendments block
+                    // on the row lock and then see the order as cancelled.
+                    if !database::orders::try_cancel_order(
+                        ex,
+                        &amendment.old_order_uid,
+                        amendment.amendment_timestamp,
+                    )
+                    .await?
+                    {
+                        return Err(InsertionError::AlreadyCancelled);
+                    }
+                    // The old order might have traded since it was read.
+                    // Re-read what it executed while holding the locks so
+                    // no amount gets traded twice. Orders that didn't trade
+                    // at all can be amended to any size.
+                    let executed =
+                        database::orders::executed_amounts_for_update(ex, &amendment.old_order_uid)
+                            .await?;
+                    let executed_amount = match kind {
+                        OrderKind::Buy => executed.buy.clone(),
+                        OrderKind::Sell => &executed.sell - &executed.fee,
+                    };
+                    if !executed_amount.is_zero()
+                        && alloy_u256_to_big_decimal(&new_total) + executed_amount
+                            > alloy_u256_to_big_decimal(&old_total)
+                    {
+                        return Err(InsertionError::AmendmentExceedsRemaining);
+                    }
+                    amendment.executed_sell_amount = executed.sell;
+                    amendment.executed_buy_amount = executed.buy;
+                    amendment.executed_fee_amount = executed.fee;
+                    insert_order_event(
+                        ex,
+                        &OrderEvent {
+                            order_uid: amendment.old_order_uid,
+                            timestamp: amendment.amendment_timestamp,
+                            label: OrderEventLabel::Cancelled,
+                        },
+                    )
+                    .await?;
+                    insert_order(&new_order, ex).await?;
+                    let new_app_data = Self::insert_order_app_data(&new_order, ex).await?;
+                    database::order_amendments::insert(ex, &amendment).await?;
+
+                    Ok(new_app_data)
+                }
+                .boxed()
+            })
+            .await
+    }
+
     async fn orders_for_tx(&self, tx_hash: &B256) -> Result<Vec<Order>> {
         tokio::try_join!(
             self.user_order_for_tx(tx_hash),
@@ -335,6 +460,23 @@ impl OrderStoring for Postgres {
         })
     }
 
+    async fn ethflow_orders_for_placement_tx(&self, tx_hash: &B256) -> Result<Vec<Order>> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["ethflow_orders_for_placement_tx"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await?;
+        orders::full_orders_with_quote_by_placement_tx_hash(&mut ex, &ByteArray(tx_hash.0))
+            .await?
+            .into_iter()
+            .map(|order_with_quote| {
+                let (order, quote) = order_with_quote.into_order_and_quote();
+                full_order_with_quote_into_model_order(order, quote.as_ref())
+            })
+            .collect()
+    }
+
     async fn user_orders(
         &self,
         owner: &Address,
@@ -374,7 +516,62 @@ impl OrderStoring for Postgres {
     }
 }
 
+/// Criteria of the refunder for returning the ETH of expired ethflow orders.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EthflowRefundCriteria {
+    /// Minimum time in seconds the order must have been valid for.
+    pub min_validity_duration: i64,
+    /// Minimum deviation of the order's price from its quote.
+    pub min_price_deviation: f64,
+}
+
 impl Postgres {
+    /// Updates the ethflow status of the expired orders the refunder will
+    /// return the ETH of to [`EthflowOrderStatus::RefundPending`].
+    pub async fn mark_pending_ethflow_refunds(
+        &self,
+        orders: &mut [Order],
+        criteria: &EthflowRefundCriteria,
+    ) -> Result<()> {
+        let expired = orders
+            .iter()
+            .filter(|order| {
+                order
+                    .metadata
+                    .ethflow_data
+                    .as_ref()
+                    .is_some_and(|data| data.status == EthflowOrderStatus::Expired)
+            })
+            .map(|order| ByteArray(order.metadata.uid.0))
+            .collect::<Vec<_>>();
+        if expired.is_empty() {
+            return Ok(());
+        }
+
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["mark_pending_ethflow_refunds"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await?;
+        let refundable = database::ethflow_orders::refundable_order_uids(
+            &mut ex,
+            &expired,
+            now_in_epoch_seconds().into(),
+            criteria.min_validity_duration,
+            criteria.min_price_deviation,
+        )
+        .await?;
+        for order in orders {
+            if let Some(data) = order.metadata.ethflow_data.as_mut()
+                && refundable.contains(&ByteArray(order.metadata.uid.0))
+            {
+                data.status = EthflowOrderStatus::RefundPending;
+            }
+        }
+        Ok(())
+    }
+
     /// Retrieve all user posted orders for a given transaction.
     pub async fn user_order_for_tx(&self, tx_hash: &B256) -> Result<Vec<Order>> {
         let _timer = super::Metrics::get()
@@ -407,8 +604,17 @@ impl Postgres {
             .collect::<Result<Vec<_>>>()
     }
 
-    pub async fn token_metadata(&self, token: &Address) -> Result<TokenMetadata> {
-        let (first_trade_block, native_price): (Option<u32>, Option<U256>) = tokio::try_join!(
+    /// Fetches the token metadata that can be derived from the database.
+    /// Trade statistics for the last day and week are computed from trades
+    /// in blocks at or after `last_24h_block` and `last_7d_block`
+    /// respectively.
+    pub async fn token_metadata(
+        &self,
+        token: &Address,
+        last_24h_block: u64,
+        last_7d_block: u64,
+    ) -> Result<TokenMetadata> {
+        let (first_trade_block, native_price, stats): (Option<u32>, Option<U256>, _) = tokio::try_join!(
             self.execute_instrumented("token_first_trade_block", async {
                 let mut ex = self.pool.acquire().await?;
                 database::trades::token_first_trade_block(&mut ex, ByteArray(token.0.0))
@@ -427,12 +633,35 @@ impl Postgres {
                 .await
                 .map_err(anyhow::Error::from)?
                 .and_then(|price| big_decimal_to_u256(&price)))
-            })
+            }),
+            self.execute_instrumented("token_trade_stats", async {
+                let mut ex = self.pool.acquire().await?;
+                Ok(database::trades::token_trade_stats(
+                    &mut ex,
+                    ByteArray(token.0.0),
+                    i64::try_from(last_7d_block)?,
+                    i64::try_from(last_24h_block.max(last_7d_block))?,
+                )
+                .await?)
+            }),
         )?;
 
         Ok(TokenMetadata {
             first_trade_block,
             native_price,
+            last_24h: TradeStats {
+                trades: u64::try_from(stats.recent_trades)?,
+                volume: big_decimal_to_u256(&stats.recent_volume)
+                    .context("token volume is not a valid U256")?,
+                unique_traders: u64::try_from(stats.recent_unique_traders)?,
+            },
+            last_7d: TradeStats {
+                trades: u64::try_from(stats.trades)?,
+                volume: big_decimal_to_u256(&stats.volume)
+                    .context("token volume is not a valid U256")?,
+                unique_traders: u64::try_from(stats.unique_traders)?,
+            },
+            ..Default::default()
         })
     }
 
@@ -535,10 +764,20 @@ fn full_order_with_quote_into_model_order(
     let status = calculate_status(&order);
     let pre_interactions = extract_interactions(&order, database::orders::ExecutionTime::Pre)?;
     let post_interactions = extract_interactions(&order, database::orders::ExecutionTime::Post)?;
+    let onchain_placement_error = onchain_order_placement_error_from(&order);
     let ethflow_data = if let Some((refund_tx, user_valid_to)) = order.ethflow_data {
+        let refund_tx_hash = refund_tx.map(|hash| H256(hash.0));
         Some(EthflowData {
             user_valid_to,
-            refund_tx_hash: refund_tx.map(|hash| H256(hash.0)),
+            refund_tx_hash,
+            // Whether the refunder picks up the order gets checked separately,
+            // see `Postgres::mark_pending_ethflow_refunds`.
+            status: EthflowOrderStatus::new(
+                status,
+                refund_tx_hash,
+                false,
+                onchain_placement_error.is_some(),
+            ),
         })
     } else {
         None
@@ -547,7 +786,6 @@ fn full_order_with_quote_into_model_order(
         .onchain_user
         .map(|onchain_user| Address::new(onchain_user.0));
     let class = order_class_from(&order);
-    let onchain_placement_error = onchain_order_placement_error_from(&order);
     let onchain_order_data = onchain_user.map(|onchain_user| OnchainOrderData {
         sender: onchain_user,
         placement_error: onchain_placement_error,
@@ -590,6 +828,7 @@ fn full_order_with_quote_into_model_order(
         quote: quote
             .map(|q| order_quote_into_model(q, status))
             .transpose()?,
+        hooks_gas: hooks_gas_from(&order)?,
     };
     let data = OrderData {
         sell_token: Address::new(order.sell_token.0),
@@ -701,6 +940,8 @@ mod tests {
             executed_fee: Default::default(),
             executed_fee_token: ByteArray([1; 20]), // TODO surplus token
             full_app_data: Default::default(),
+            pre_hooks_gas: None,
+            post_hooks_gas: None,
         };
 
         // Open - sell (filled - 0%)
@@ -3,12 +3,25 @@ use {
     crate::solver_competition::LoadSolverCompetitionError,
     alloy::primitives::{Address, B256},
     anyhow::{Context, Result},
-    database::{byte_array::ByteArray, solver_competition_v2::SolverCompetition as DbResponse},
+    database::{
+        byte_array::ByteArray,
+        solver_competition_v2::{FilterReason as DbFilterReason, SolverCompetition as DbResponse},
+    },
     model::{
         order::OrderUid,
-        solver_competition_v2::{Auction, Order, Response as ApiResponse, Solution},
+        solver_competition_v2::{
+            Auction,
+            FairnessBaseline,
+            FilterReason,
+            Order,
+            OrderScore,
+            Response as ApiResponse,
+            Solution,
+            UnfairTokenPair,
+        },
     },
     number::conversions::big_decimal_to_u256,
+    primitive_types::U256,
     std::collections::{BTreeMap, HashMap},
 };
 
@@ -94,9 +107,31 @@ fn try_into_dto(value: DbResponse) -> Result<ApiResponse, LoadSolverCompetitionE
         })
         .collect::<Result<_>>()?;
 
+    let fairness_baselines = value
+        .fairness_baselines
+        .into_iter()
+        .map(|row| {
+            Ok(FairnessBaseline {
+                sell_token: Address::new(row.sell_token.0),
+                buy_token: Address::new(row.buy_token.0),
+                score: big_decimal_to_u256(&row.score)
+                    .context("could not convert fairness baseline to U256")?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
     let mut trades: HashMap<i64, Vec<Order>> = {
         let mut grouped_trades = HashMap::<i64, Vec<Order>>::default();
         for trade in value.trades {
+            let score = match (&trade.surplus_score, &trade.protocol_fee_score) {
+                (Some(surplus), Some(protocol_fees)) => Some(OrderScore {
+                    surplus: big_decimal_to_u256(surplus)
+                        .context("could not convert surplus score to U256")?,
+                    protocol_fees: big_decimal_to_u256(protocol_fees)
+                        .context("could not convert protocol fee score to U256")?,
+                }),
+                _ => None,
+            };
             grouped_trades
                 .entry(trade.solution_uid)
                 .or_default()
@@ -108,6 +143,7 @@ fn try_into_dto(value: DbResponse) -> Result<ApiResponse, LoadSolverCompetitionE
                         .context("could not convert buy amount to U256")?,
                     sell_token: Address::new(trade.sell_token.0),
                     buy_token: Address::new(trade.buy_token.0),
+                    score,
                 });
         }
         grouped_trades
@@ -129,6 +165,12 @@ fn try_into_dto(value: DbResponse) -> Result<ApiResponse, LoadSolverCompetitionE
                     ))
                 })
                 .collect::<Result<_>>()?;
+            let orders = trades.remove(&solution.uid).unwrap_or_default();
+            let filter_reason = solution.filter_reason.map(|reason| match reason {
+                DbFilterReason::Unfair => FilterReason::Unfair {
+                    token_pairs: unfair_token_pairs(&orders, &fairness_baselines),
+                },
+            });
 
             Ok(Solution {
                 solver_address: Address::new(solution.solver.0),
@@ -136,13 +178,14 @@ fn try_into_dto(value: DbResponse) -> Result<ApiResponse, LoadSolverCompetitionE
                     .context("could not convert score to U256")?,
                 ranking: solution.ranking,
                 clearing_prices,
-                orders: trades.remove(&solution.uid).unwrap_or_default(),
+                orders,
                 is_winner: solution.is_winner,
                 filtered_out: solution.filtered_out,
                 tx_hash: settlements.get(&solution.uid).cloned(),
                 reference_score: reference_scores
                     .get(&Address::new(solution.solver.0))
                     .copied(),
+                filter_reason,
             })
         })
         .collect::<Result<_>>()?;
@@ -165,5 +208,96 @@ fn try_into_dto(value: DbResponse) -> Result<ApiResponse, LoadSolverCompetitionE
                 .collect(),
         },
         solutions,
+        fairness_baselines,
     })
 }
+
+/// Directed token pairs the solution executed at a lower score than their
+/// fairness baseline.
+fn unfair_token_pairs(orders: &[Order], baselines: &[FairnessBaseline]) -> Vec<UnfairTokenPair> {
+    let mut scores = BTreeMap::<(Address, Address), U256>::new();
+    for order in orders {
+        let Some(score) = &order.score else {
+            continue;
+        };
+        let pair_score = scores
+            .entry((order.sell_token, order.buy_token))
+            .or_default();
+        *pair_score = pair_score
+            .saturating_add(score.surplus)
+            .saturating_add(score.protocol_fees);
+    }
+    baselines
+        .iter()
+        .filter_map(|baseline| {
+            let score = *scores.get(&(baseline.sell_token, baseline.buy_token))?;
+            (score < baseline.score).then_some(UnfairTokenPair {
+                sell_token: baseline.sell_token,
+                buy_token: baseline.buy_token,
+                score,
+                baseline: baseline.score,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_unfair_token_pairs() {
+        let (a, b, c) = (
+            Address::new([1; 20]),
+            Address::new([2; 20]),
+            Address::new([3; 20]),
+        );
+        let order = |sell_token, buy_token, surplus: u64| Order {
+            id: OrderUid::default(),
+            sell_amount: U256::zero(),
+            buy_amount: U256::zero(),
+            sell_token,
+            buy_token,
+            score: Some(OrderScore {
+                surplus: surplus.into(),
+                protocol_fees: 1.into(),
+            }),
+        };
+        let orders = [
+            order(a, b, 3),
+            order(a, b, 4),
+            order(b, c, 4),
+            // orders without a score don't contribute
+            Order {
+                score: None,
+                ..order(b, c, 0)
+            },
+        ];
+        let baselines = [
+            FairnessBaseline {
+                sell_token: a,
+                buy_token: b,
+                score: 10.into(),
+            },
+            FairnessBaseline {
+                sell_token: b,
+                buy_token: c,
+                score: 5.into(),
+            },
+            FairnessBaseline {
+                sell_token: c,
+                buy_token: a,
+                score: 100.into(),
+            },
+        ];
+        assert_eq!(
+            unfair_token_pairs(&orders, &baselines),
+            vec![UnfairTokenPair {
+                sell_token: a,
+                buy_token: b,
+                score: 9.into(),
+                baseline: 10.into(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use {
+    super::Postgres,
+    alloy::primitives::Address,
+    anyhow::{Context, Result},
+    bigdecimal::num_bigint::ToBigInt,
+    database::solver_rewards::{AccountingPeriod, PeriodId, SolverPayout, SolverReward},
+    model::solver_rewards::{Payout, Response, Reward},
+    number::conversions::big_decimal_to_u256,
+    sqlx::PgConnection,
+    thiserror::Error,
+};
+
+/// Possible errors when loading the solver rewards of an accounting period.
+#[derive(Debug, Error)]
+pub enum LoadSolverRewardsError {
+    #[error("accounting period not found")]
+    NotFound,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl Postgres {
+    pub async fn load_solver_rewards(
+        &self,
+        period_id: PeriodId,
+    ) -> Result<Response, LoadSolverRewardsError> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["load_solver_rewards"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await.map_err(anyhow::Error::from)?;
+        let period = database::solver_rewards::fetch_period(&mut ex, period_id)
+            .await
+            .context("solver_rewards::fetch_period")?
+            .ok_or(LoadSolverRewardsError::NotFound)?;
+        load_payouts(&mut ex, period).await
+    }
+
+    pub async fn load_latest_solver_rewards(&self) -> Result<Response, LoadSolverRewardsError> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["load_latest_solver_rewards"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await.map_err(anyhow::Error::from)?;
+        let period = database::solver_rewards::fetch_latest_period(&mut ex)
+            .await
+            .context("solver_rewards::fetch_latest_period")?
+            .ok_or(LoadSolverRewardsError::NotFound)?;
+        load_payouts(&mut ex, period).await
+    }
+}
+
+async fn load_payouts(
+    ex: &mut PgConnection,
+    period: AccountingPeriod,
+) -> Result<Response, LoadSolverRewardsError> {
+    let payouts = database::solver_rewards::fetch_payouts(ex, period.id)
+        .await
+        .context("solver_rewards::fetch_payouts")?;
+    let rewards = database::solver_rewards::fetch_rewards(ex, period.id)
+        .await
+        .context("solver_rewards::fetch_rewards")?;
+    Ok(Response {
+        period_id: period.id,
+        start_block: period.start_block,
+        end_block: period.end_block,
+        upper_cap: big_decimal_to_u256(&period.upper_cap).context("upper cap is not u256")?,
+        lower_cap: big_decimal_to_u256(&period.lower_cap).context("lower cap is not u256")?,
+        protocol_fee_cap_factor: period.protocol_fee_cap_factor,
+        payouts: payouts
+            .into_iter()
+            .map(try_payout_into_dto)
+            .collect::<Result<_>>()?,
+        rewards: rewards
+            .into_iter()
+            .map(try_reward_into_dto)
+            .collect::<Result<_>>()?,
+    })
+}
+
+fn try_payout_into_dto(payout: SolverPayout) -> Result<Payout> {
+    anyhow::ensure!(payout.reward.is_integer(), "reward is not an integer");
+    Ok(Payout {
+        solver: Address::new(payout.solver.0),
+        wins: payout.wins,
+        settlements: payout.settlements,
+        protocol_fees: big_decimal_to_u256(&payout.protocol_fees)
+            .context("protocol fees are not u256")?,
+        reward: payout
+            .reward
+            .to_bigint()
+            .context("reward is not an integer")?,
+    })
+}
+
+fn try_reward_into_dto(reward: SolverReward) -> Result<Reward> {
+    anyhow::ensure!(reward.reward.is_integer(), "reward is not an integer");
+    Ok(Reward {
+        auction_id: reward.auction_id,
+        solver: Address::new(reward.solver.0),
+        score: big_decimal_to_u256(&reward.score).context("score is not u256")?,
+        observed_score: big_decimal_to_u256(&reward.observed_score)
+            .context("observed score is not u256")?,
+        reference_score: big_decimal_to_u256(&reward.reference_score)
+            .context("reference score is not u256")?,
+        protocol_fees: big_decimal_to_u256(&reward.protocol_fees)
+            .context("protocol fees are not u256")?,
+        reward: reward
+            .reward
+            .to_bigint()
+            .context("reward is not an integer")?,
+    })
+}
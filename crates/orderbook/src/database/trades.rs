@@ -1,11 +1,15 @@
 use {
-    crate::database::Postgres,
+    crate::{database::Postgres, trade_analytics},
     alloy::primitives::{Address, B256},
     anyhow::{Context, Result},
-    database::{byte_array::ByteArray, trades::TradesQueryRow},
+    bigdecimal::BigDecimal,
+    database::{
+        byte_array::ByteArray,
+        trades::{TradeFeesRow, TradesQueryRow},
+    },
     futures::stream::TryStreamExt,
     model::{fee_policy::ExecutedProtocolFee, order::OrderUid, trade::Trade},
-    number::conversions::big_decimal_to_big_uint,
+    number::conversions::{alloy::big_decimal_to_u256, big_decimal_to_big_uint},
     std::convert::TryInto,
 };
 
@@ -109,6 +113,98 @@ fn trade_from(
     })
 }
 
+impl Postgres {
+    /// Returns at most `limit` trades matching the filter.
+    pub async fn trade_fees(
+        &self,
+        filter: &trade_analytics::Filter,
+        limit: usize,
+    ) -> Result<Vec<trade_analytics::Trade>> {
+        let _timer = super::Metrics::get()
+            .database_queries
+            .with_label_values(&["trade_fees"])
+            .start_timer();
+
+        let mut ex = self.pool.acquire().await?;
+        let owner = filter.owner.map(|owner| ByteArray(owner.0.0));
+        let partner_recipient = filter
+            .partner_recipient
+            .map(|recipient| ByteArray(recipient.0.0));
+        database::trades::trade_fees(
+            &mut ex,
+            owner.as_ref(),
+            filter.app_code.as_deref(),
+            partner_recipient.as_ref(),
+            filter.from,
+            filter.to,
+            i64::try_from(limit).unwrap_or(i64::MAX),
+        )
+        .await?
+        .into_iter()
+        .map(analytics_trade_from)
+        .collect()
+    }
+}
+
+fn analytics_trade_from(row: TradeFeesRow) -> Result<trade_analytics::Trade> {
+    let token_amount = |token: &ByteArray<20>, amount: &BigDecimal| {
+        Ok(trade_analytics::TokenAmount {
+            token: Address::from_slice(&token.0),
+            amount: big_decimal_to_u256(amount).context("fee is not an unsigned integer")?,
+        })
+    };
+    let network_cost = match (&row.executed_fee_token, &row.executed_fee) {
+        (Some(token), Some(amount)) => Some(token_amount(token, amount)?),
+        _ => None,
+    };
+    let protocol_fee_amounts = row
+        .protocol_fee_tokens
+        .unwrap_or_default()
+        .iter()
+        .zip(row.protocol_fee_amounts.unwrap_or_default().iter())
+        .map(|(token, amount)| token_amount(token, amount))
+        .collect::<Result<Vec<_>>>()?;
+    // Fees can only be matched with their policies if all of them were recorded.
+    let kinds = row
+        .fee_policy_kinds
+        .iter()
+        .map(|kind| policy_kind_from(kind))
+        .collect::<Option<Vec<_>>>()
+        .filter(|kinds| kinds.len() == protocol_fee_amounts.len());
+    let protocol_fees = protocol_fee_amounts
+        .into_iter()
+        .enumerate()
+        .map(|(i, amount)| trade_analytics::ProtocolFee {
+            kind: kinds.as_ref().map(|kinds| kinds[i]),
+            amount,
+        })
+        .collect();
+    // Surplus is computed from prices and may have a fractional part or even be
+    // slightly negative due to rounding.
+    let surplus = row
+        .surplus_in_wei
+        .and_then(|surplus| big_decimal_to_u256(&surplus.with_scale(0)))
+        .unwrap_or_default();
+    Ok(trade_analytics::Trade {
+        timestamp: row.timestamp,
+        surplus,
+        network_cost,
+        protocol_fees,
+        app_code: row.app_code,
+        full_app_data: row.full_app_data,
+    })
+}
+
+/// Parses the `PolicyKind` database enum.
+fn policy_kind_from(kind: &str) -> Option<trade_analytics::PolicyKind> {
+    match kind {
+        "surplus" => Some(trade_analytics::PolicyKind::Surplus),
+        "volume" => Some(trade_analytics::PolicyKind::Volume),
+        "priceimprovement" => Some(trade_analytics::PolicyKind::PriceImprovement),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -117,4 +213,36 @@ mod tests {
     fn convert_trade() {
         trade_from(TradesQueryRow::default(), vec![]).unwrap();
     }
+
+    #[test]
+    fn convert_analytics_trade() {
+        let trade = analytics_trade_from(TradeFeesRow {
+            surplus_in_wei: Some(BigDecimal::new(15.into(), 1)),
+            executed_fee: Some(2.into()),
+            executed_fee_token: Some(ByteArray([1; 20])),
+            protocol_fee_tokens: Some(vec![ByteArray([2; 20])]),
+            protocol_fee_amounts: Some(vec![3.into()]),
+            fee_policy_kinds: vec!["priceimprovement".to_string()],
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(trade.surplus, alloy::primitives::U256::from(1));
+        assert_eq!(
+            trade.network_cost,
+            Some(trade_analytics::TokenAmount {
+                token: Address::repeat_byte(1),
+                amount: alloy::primitives::U256::from(2),
+            })
+        );
+        assert_eq!(
+            trade.protocol_fees,
+            vec![trade_analytics::ProtocolFee {
+                kind: Some(trade_analytics::PolicyKind::PriceImprovement),
+                amount: trade_analytics::TokenAmount {
+                    token: Address::repeat_byte(2),
+                    amount: alloy::primitives::U256::from(3),
+                },
+            }]
+        );
+    }
 }
@@ -13,10 +13,30 @@ use {
 };
 
 #[serde_as]
-#[derive(Serialize)]
+#[derive(Clone, Debug, Default, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TokenMetadata {
     pub first_trade_block: Option<u32>,
     #[serde_as(as = "Option<HexOrDecimalU256>")]
     pub native_price: Option<U256>,
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub decimals: Option<u8>,
+    pub last_24h: TradeStats,
+    pub last_7d: TradeStats,
+    /// Whether the bad token detection flagged the token as unsupported.
+    pub unsupported: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unsupported_reason: Option<String>,
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TradeStats {
+    pub trades: u64,
+    /// Amount of the token that got bought or sold.
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub volume: U256,
+    pub unique_traders: u64,
 }
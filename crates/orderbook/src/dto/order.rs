@@ -1,8 +1,16 @@
 use {
     app_data::AppDataHash,
+    chrono::{DateTime, Utc},
     model::{
         interaction::InteractionData,
-        order::{BuyTokenDestination, OrderClass, OrderKind, OrderUid, SellTokenSource},
+        order::{
+            BuyTokenDestination,
+            EthflowData,
+            OrderClass,
+            OrderKind,
+            OrderUid,
+            SellTokenSource,
+        },
         signature::Signature,
     },
     number::serialization::HexOrDecimalU256,
@@ -119,3 +127,59 @@ pub enum Status {
     /// The user cancelled the order. It will no longer show up in any auctions.
     Cancelled,
 }
+
+/// Aggregated execution progress of a TWAP order. Amounts are denominated in
+/// the sell token for sell orders and in the buy token for buy orders.
+#[serde_as]
+#[derive(Serialize, PartialEq, Debug, Clone)]
+#[cfg_attr(any(test, feature = "e2e"), derive(serde::Deserialize))]
+#[serde(rename_all = "camelCase")]
+pub struct TwapStatus {
+    pub parts: u32,
+    pub part_duration: u32,
+    pub start_time: u32,
+    /// Number of slices that became tradeable so far.
+    pub unlocked_parts: u32,
+    /// Number of slices that were executed in full.
+    pub filled_parts: u32,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub unlocked_amount: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub executed_amount: U256,
+    /// When the next slice becomes tradeable. `None` once all slices are
+    /// tradeable.
+    pub next_part_time: Option<u32>,
+}
+
+/// Status details that only apply to some orders.
+#[derive(Serialize, PartialEq, Debug, Clone, Default)]
+#[cfg_attr(any(test, feature = "e2e"), derive(serde::Deserialize))]
+#[serde(rename_all = "camelCase")]
+pub struct StatusDetails {
+    /// Aggregated progress of all slices if the order is a TWAP order.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub twap: Option<TwapStatus>,
+    /// Lifecycle and refund of the order if it is an ethflow order.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ethflow: Option<EthflowData>,
+}
+
+/// An order that got replaced by a new one through the order amendment
+/// endpoint.
+#[serde_as]
+#[derive(Serialize, PartialEq, Debug, Clone)]
+#[cfg_attr(any(test, feature = "e2e"), derive(serde::Deserialize))]
+#[serde(rename_all = "camelCase")]
+pub struct Amendment {
+    pub old_order_uid: OrderUid,
+    pub new_order_uid: OrderUid,
+    /// Amounts the old order executed before it got replaced. The new order
+    /// starts without any executed amounts.
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub executed_sell_amount: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub executed_buy_amount: U256,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub executed_fee_amount: U256,
+    pub timestamp: DateTime<Utc>,
+}
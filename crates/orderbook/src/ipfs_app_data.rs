@@ -1,13 +1,16 @@
 use {
-    crate::ipfs::Ipfs,
+    crate::{ipfs::Ipfs, ipfs_pinning::Pinning},
     anyhow::Result,
-    app_data::{AppDataHash, create_ipfs_cid},
+    app_data::{AppDataHash, create_ipfs_cid, hash_full_app_data},
     cached::{Cached, TimedSizedCache},
     std::sync::Mutex,
 };
 
 pub struct IpfsAppData {
-    ipfs: Ipfs,
+    /// Gateways to fetch app data from in order of preference.
+    gateways: Vec<Ipfs>,
+    /// Backends newly registered app data gets pinned to.
+    pinning: Vec<Box<dyn Pinning>>,
     cache: Mutex<TimedSizedCache<AppDataHash, Option<String>>>,
     metrics: &'static Metrics,
 }
@@ -21,10 +24,14 @@ struct Metrics {
 
     /// Timing of IPFS app data fetches.
     fetches: prometheus::Histogram,
+
+    /// Number of app data documents pinned per backend.
+    #[metric(labels("outcome", "backend"))]
+    pins: prometheus::IntCounterVec,
 }
 
 impl IpfsAppData {
-    pub fn new(ipfs: Ipfs) -> Self {
+    pub fn new(gateways: Vec<Ipfs>, pinning: Vec<Box<dyn Pinning>>) -> Self {
         let metrics = Metrics::instance(observe::metrics::get_storage_registry()).unwrap();
         // Initialize metrics.
         for outcome in &["error", "found", "missing"] {
@@ -32,8 +39,14 @@ impl IpfsAppData {
                 metrics.app_data.with_label_values(&[outcome, source]);
             }
         }
+        for outcome in &["error", "success"] {
+            for backend in &pinning {
+                metrics.pins.with_label_values(&[outcome, backend.name()]);
+            }
+        }
         Self {
-            ipfs,
+            gateways,
+            pinning,
             cache: Mutex::new(TimedSizedCache::with_size_and_lifespan_and_refresh(
                 1000, 600, false,
             )),
@@ -42,21 +55,43 @@ impl IpfsAppData {
     }
 
     /// Tries to find full app data corresponding to the contract app data on
-    /// IPFS.
+    /// IPFS by asking the gateways in order until one of them has it.
     ///
     /// A return value of `Some` indicates that either the old or new CID format
-    /// was found on IPFS and points to valid utf-8.
+    /// was found on IPFS and points to valid utf-8. Documents found under the
+    /// new CID format are additionally verified to hash to the contract app
+    /// data.
     ///
     /// A return value of `None` indicates that neither CID was found. This
     /// might be a temporary condition as IPFS is a decentralized network.
     ///
-    /// A return value of `Err` indicates an error communication with the IPFS
-    /// gateway.
+    /// A return value of `Err` indicates that communication with all IPFS
+    /// gateways failed.
     async fn fetch_raw(&self, contract_app_data: &AppDataHash) -> Result<Option<String>> {
+        let mut error = None;
+        let mut reachable = false;
+        for gateway in &self.gateways {
+            match self.fetch_from_gateway(gateway, contract_app_data).await {
+                Ok(Some(app_data)) => return Ok(Some(app_data)),
+                Ok(None) => reachable = true,
+                Err(err) => error = Some(err),
+            }
+        }
+        match error {
+            Some(err) if !reachable => Err(err),
+            _ => Ok(None),
+        }
+    }
+
+    async fn fetch_from_gateway(
+        &self,
+        ipfs: &Ipfs,
+        contract_app_data: &AppDataHash,
+    ) -> Result<Option<String>> {
         let old = old_app_data_cid(contract_app_data);
         let new = new_app_data_cid(contract_app_data);
-        let fetch = |cid: String| async move {
-            let result = self.ipfs.fetch(&cid).await;
+        let fetch = |cid: String, verify: bool| async move {
+            let result = ipfs.fetch(&cid).await;
             let result = match result {
                 Ok(Some(result)) => {
                     tracing::debug!(?contract_app_data, %cid, "found full app data");
@@ -71,6 +106,12 @@ impl IpfsAppData {
                     return Err(err);
                 }
             };
+            // The new CID format commits to the keccak hash of the document
+            // itself, so gateways can't serve us wrong content.
+            if verify && hash_full_app_data(&result) != contract_app_data.0 {
+                tracing::warn!(?contract_app_data, %cid, "full app data doesn't match CID");
+                return Ok(None);
+            }
             match String::from_utf8(result) {
                 Ok(result) => Ok(Some(result)),
                 Err(err) => {
@@ -79,9 +120,37 @@ impl IpfsAppData {
                 }
             }
         };
-        futures::future::select_ok([std::pin::pin!(fetch(old)), std::pin::pin!(fetch(new))])
-            .await
-            .map(|(ok, _rest)| ok)
+        futures::future::select_ok([
+            std::pin::pin!(fetch(old, false)),
+            std::pin::pin!(fetch(new, true)),
+        ])
+        .await
+        .map(|(ok, _rest)| ok)
+    }
+
+    /// Makes the full app data available on IPFS under the new CID format by
+    /// pinning it to all configured backends. Failures are only logged since
+    /// the app data is already stored in the database.
+    pub async fn pin(&self, contract_app_data: &AppDataHash, full_app_data: &[u8]) {
+        let cid = new_app_data_cid(contract_app_data);
+        let pins = self.pinning.iter().map(|backend| {
+            let cid = &cid;
+            async move {
+                let name = backend.name();
+                let outcome = match backend.pin(cid, full_app_data).await {
+                    Ok(()) => {
+                        tracing::debug!(?contract_app_data, %cid, name, "pinned full app data");
+                        "success"
+                    }
+                    Err(err) => {
+                        tracing::warn!(?contract_app_data, %cid, name, ?err, "failed to pin");
+                        "error"
+                    }
+                };
+                self.metrics.pins.with_label_values(&[outcome, name]).inc();
+            }
+        });
+        futures::future::join_all(pins).await;
     }
 
     pub async fn fetch(&self, contract_app_data: &AppDataHash) -> Result<Option<String>> {
@@ -137,7 +206,7 @@ fn old_app_data_cid(contract_app_data: &AppDataHash) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use {super::*, std::sync::Arc};
 
     // Can be compared with CID explorer to make sure CIDs encode the right data.
     #[test]
@@ -155,11 +224,59 @@ mod tests {
         println!("{cid}");
     }
 
+    #[tokio::test]
+    async fn pins_to_all_backends() {
+        struct Backend {
+            pinned: Mutex<Vec<(String, Vec<u8>)>>,
+            fail: bool,
+        }
+
+        #[async_trait::async_trait]
+        impl Pinning for Arc<Backend> {
+            fn name(&self) -> &'static str {
+                "test"
+            }
+
+            async fn pin(&self, cid: &str, content: &[u8]) -> Result<()> {
+                self.pinned
+                    .lock()
+                    .unwrap()
+                    .push((cid.to_string(), content.to_vec()));
+                anyhow::ensure!(!self.fail, "failed");
+                Ok(())
+            }
+        }
+
+        let backends = [true, false].map(|fail| {
+            Arc::new(Backend {
+                pinned: Default::default(),
+                fail,
+            })
+        });
+        let ipfs = IpfsAppData::new(
+            vec![],
+            backends
+                .iter()
+                .map(|backend| Box::new(backend.clone()) as Box<dyn Pinning>)
+                .collect(),
+        );
+
+        let document = br#"{"appCode":"CoW Swap"}"#;
+        let hash = AppDataHash(hash_full_app_data(document));
+        ipfs.pin(&hash, document).await;
+        for backend in backends {
+            assert_eq!(
+                *backend.pinned.lock().unwrap(),
+                vec![(new_app_data_cid(&hash), document.to_vec())]
+            );
+        }
+    }
+
     #[ignore]
     #[tokio::test]
     async fn fetch() {
         let ipfs = Ipfs::new(Default::default(), "https://ipfs.io".parse().unwrap(), None);
-        let ipfs = IpfsAppData::new(ipfs);
+        let ipfs = IpfsAppData::new(vec![ipfs], vec![]);
         let hash = AppDataHash::default();
         let result = ipfs.fetch(&hash).await;
         let _ = dbg!(result);
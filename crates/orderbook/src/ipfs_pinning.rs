@@ -0,0 +1,153 @@
+use {
+    anyhow::{Context, Result, ensure},
+    reqwest::{
+        Client,
+        ClientBuilder,
+        multipart::{Form, Part},
+    },
+    serde::{Deserialize, Serialize},
+    std::time::Duration,
+    url::Url,
+};
+
+/// A backend that keeps documents available on the IPFS network.
+#[async_trait::async_trait]
+pub trait Pinning: Send + Sync {
+    /// Short name of the backend used in logs and metrics.
+    fn name(&self) -> &'static str;
+
+    /// Makes `content`, which is expected to be addressed by `cid`, available
+    /// on IPFS.
+    async fn pin(&self, cid: &str, content: &[u8]) -> Result<()>;
+}
+
+/// An IPFS node exposing the Kubo RPC API (usually on port 5001).
+///
+/// Documents are added to the node and pinned there, so the node has to be
+/// reachable by the rest of the IPFS network for them to be resolvable.
+pub struct Node {
+    client: Client,
+    api: Url,
+}
+
+impl Node {
+    pub fn new(client: ClientBuilder, api: Url) -> Self {
+        assert!(!api.cannot_be_a_base());
+        Self {
+            client: client.timeout(Duration::from_secs(10)).build().unwrap(),
+            api,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Pinning for Node {
+    fn name(&self) -> &'static str {
+        "node"
+    }
+
+    async fn pin(&self, cid: &str, content: &[u8]) -> Result<()> {
+        // Adding the document as a single raw leaf hashed with keccak-256
+        // results in the same CID as `app_data::create_ipfs_cid`.
+        let mut url = shared::url::join(&self.api, "api/v0/add");
+        url.query_pairs_mut()
+            .append_pair("cid-version", "1")
+            .append_pair("raw-leaves", "true")
+            .append_pair("hash", "keccak-256")
+            .append_pair("pin", "true");
+        let form = Form::new().part("file", Part::bytes(content.to_vec()));
+        let response = self
+            .client
+            .post(url)
+            .multipart(form)
+            .send()
+            .await
+            .context("send")?
+            .error_for_status()?;
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "PascalCase")]
+        struct Added {
+            hash: String,
+        }
+        let added: Added = response.json().await.context("body")?;
+        ensure!(
+            added.hash == cid,
+            "node added document as {} instead of {cid}",
+            added.hash
+        );
+        Ok(())
+    }
+}
+
+/// A service implementing the IPFS Pinning Service API.
+///
+/// The service only receives the CID and has to fetch the document itself, so
+/// it should be configured with `origins` that have the content (for example
+/// a [`Node`] that the documents are also added to).
+pub struct PinningService {
+    client: Client,
+    endpoint: Url,
+    token: String,
+    origins: Vec<String>,
+}
+
+impl PinningService {
+    pub fn new(client: ClientBuilder, endpoint: Url, token: String, origins: Vec<String>) -> Self {
+        assert!(!endpoint.cannot_be_a_base());
+        Self {
+            client: client.timeout(Duration::from_secs(10)).build().unwrap(),
+            endpoint,
+            token,
+            origins,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Pinning for PinningService {
+    fn name(&self) -> &'static str {
+        "pinning_service"
+    }
+
+    async fn pin(&self, cid: &str, _: &[u8]) -> Result<()> {
+        #[derive(Serialize)]
+        struct Pin<'a> {
+            cid: &'a str,
+            name: &'a str,
+            #[serde(skip_serializing_if = "<[_]>::is_empty")]
+            origins: &'a [String],
+        }
+        self.client
+            .post(shared::url::join(&self.endpoint, "pins"))
+            .bearer_auth(&self.token)
+            .json(&Pin {
+                cid,
+                name: "cow-app-data",
+                origins: &self.origins,
+            })
+            .send()
+            .await
+            .context("send")?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, app_data::AppDataHash};
+
+    #[tokio::test]
+    #[ignore]
+    async fn local_node() {
+        let node = Node::new(Default::default(), "http://localhost:5001".parse().unwrap());
+        let document = br#"{"appCode":"CoW Swap","metadata":{},"version":"1.1.0"}"#;
+        let hash = AppDataHash(app_data::hash_full_app_data(document));
+        let cid = multibase::encode(
+            multibase::Base::Base32Lower,
+            app_data::create_ipfs_cid(&hash.0),
+        );
+        node.pin(&cid, document).await.unwrap();
+    }
+}
@@ -1,13 +1,20 @@
 pub mod api;
+pub mod api_key_admin;
+pub mod api_keys;
 pub mod app_data;
 pub mod arguments;
 pub mod database;
 pub mod dto;
 mod ipfs;
 mod ipfs_app_data;
+mod ipfs_pinning;
+pub mod market_depth;
 pub mod orderbook;
 mod quoter;
 pub mod run;
 pub mod solver_competition;
+pub mod token_metadata;
+pub mod trade_analytics;
+mod watch_tower;
 
 pub use self::run::{run, start};
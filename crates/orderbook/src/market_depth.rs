@@ -0,0 +1,238 @@
+//! Aggregated view of the resting limit orders of a token pair.
+//!
+//! The snapshot is computed from the most recent auction which contains all
+//! solvable orders as determined by the autopilot's solvable orders cache.
+
+use {
+    crate::dto,
+    model::order::OrderClass,
+    num::{BigRational, ToPrimitive, Zero},
+    number::{conversions::u256_to_big_rational, serialization::HexOrDecimalU256},
+    primitive_types::{H160, U256},
+    serde::Serialize,
+    serde_with::serde_as,
+    shared::remaining_amounts,
+    std::collections::BTreeMap,
+};
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketDepth {
+    /// Auction the snapshot was computed from.
+    pub auction_id: dto::AuctionId,
+    pub block: u64,
+    pub base_token: H160,
+    pub quote_token: H160,
+    /// Orders selling the base token for the quote token. Best (lowest) price
+    /// first.
+    pub asks: Vec<PriceLevel>,
+    /// Orders selling the quote token for the base token. Best (highest)
+    /// price first.
+    pub bids: Vec<PriceLevel>,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceLevel {
+    /// Limit price in atoms of the quote token per atom of the base token.
+    pub price: f64,
+    pub partially_fillable: Liquidity,
+    pub fill_or_kill: Liquidity,
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Liquidity {
+    /// Remaining (not yet executed) amount of the base token.
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub base_amount: U256,
+    /// Number of orders contributing to the amount.
+    pub orders: usize,
+}
+
+impl MarketDepth {
+    pub fn new(auction: &dto::AuctionWithId, base_token: H160, quote_token: H160) -> Self {
+        let mut asks = BTreeMap::<BigRational, PriceLevel>::new();
+        let mut bids = BTreeMap::<BigRational, PriceLevel>::new();
+
+        // Market orders are expected to be executed right away and liquidity
+        // orders are not placed by regular users, so neither of them represents
+        // resting liquidity.
+        let orders = auction
+            .auction
+            .orders
+            .iter()
+            .filter(|order| order.class == OrderClass::Limit);
+        for order in orders {
+            let (levels, base_amount, quote_amount) =
+                if order.sell_token == base_token && order.buy_token == quote_token {
+                    (&mut asks, order.sell_amount, order.buy_amount)
+                } else if order.sell_token == quote_token && order.buy_token == base_token {
+                    (&mut bids, order.buy_amount, order.sell_amount)
+                } else {
+                    continue;
+                };
+            if base_amount.is_zero() {
+                continue;
+            }
+            let remaining = match remaining_amount(order, base_amount) {
+                Some(remaining) if !remaining.is_zero() => remaining,
+                _ => continue,
+            };
+
+            let price = u256_to_big_rational(&quote_amount) / u256_to_big_rational(&base_amount);
+            let level = levels.entry(price).or_insert_with_key(|price| PriceLevel {
+                price: price.to_f64().unwrap_or_default(),
+                ..Default::default()
+            });
+            let liquidity = if order.partially_fillable {
+                &mut level.partially_fillable
+            } else {
+                &mut level.fill_or_kill
+            };
+            liquidity.base_amount = liquidity.base_amount.saturating_add(remaining);
+            liquidity.orders += 1;
+        }
+
+        Self {
+            auction_id: auction.id,
+            block: auction.auction.block,
+            base_token,
+            quote_token,
+            asks: asks.into_values().collect(),
+            bids: bids.into_values().rev().collect(),
+        }
+    }
+}
+
+/// Scales `total` by the portion of the order that has not been executed yet.
+fn remaining_amount(order: &dto::Order, total: U256) -> Option<U256> {
+    let remaining = remaining_amounts::Remaining::from_order(&remaining_amounts::Order {
+        kind: order.kind,
+        buy_amount: order.buy_amount,
+        sell_amount: order.sell_amount,
+        fee_amount: U256::zero(),
+        executed_amount: order.executed,
+        partially_fillable: order.partially_fillable,
+    })
+    .ok()?;
+    remaining.remaining(total).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        model::order::{OrderKind, OrderUid},
+    };
+
+    const BASE: H160 = H160([1; 20]);
+    const QUOTE: H160 = H160([2; 20]);
+
+    fn order(sell_token: H160, buy_token: H160, sell_amount: u64, buy_amount: u64) -> dto::Order {
+        dto::Order {
+            uid: OrderUid::default(),
+            sell_token,
+            buy_token,
+            sell_amount: sell_amount.into(),
+            buy_amount: buy_amount.into(),
+            protocol_fees: vec![],
+            created: 0,
+            valid_to: u32::MAX,
+            kind: OrderKind::Sell,
+            receiver: None,
+            owner: H160::zero(),
+            partially_fillable: false,
+            executed: U256::zero(),
+            pre_interactions: vec![],
+            post_interactions: vec![],
+            sell_token_balance: Default::default(),
+            buy_token_balance: Default::default(),
+            class: OrderClass::Limit,
+            app_data: Default::default(),
+            signature: Default::default(),
+            quote: dto::order::Quote {
+                sell_amount: U256::zero(),
+                buy_amount: U256::zero(),
+                fee: U256::zero(),
+                solver: H160::zero(),
+            },
+        }
+    }
+
+    fn auction(orders: Vec<dto::Order>) -> dto::AuctionWithId {
+        dto::AuctionWithId {
+            id: 42,
+            auction: dto::Auction {
+                block: 100,
+                orders,
+                prices: Default::default(),
+                surplus_capturing_jit_order_owners: vec![],
+            },
+        }
+    }
+
+    #[test]
+    fn aggregates_orders_into_price_levels() {
+        let auction = auction(vec![
+            // asks at price 2
+            order(BASE, QUOTE, 10, 20),
+            order(BASE, QUOTE, 5, 10),
+            // ask at price 3, half executed
+            dto::Order {
+                partially_fillable: true,
+                executed: 50.into(),
+                ..order(BASE, QUOTE, 100, 300)
+            },
+            // bid at price 1 selling 10 QUOTE for 10 BASE
+            dto::Order {
+                kind: OrderKind::Buy,
+                ..order(QUOTE, BASE, 10, 10)
+            },
+            // bid at price 1.5
+            order(QUOTE, BASE, 15, 10),
+            // unrelated pair
+            order(BASE, H160([3; 20]), 10, 10),
+            // not a limit order
+            dto::Order {
+                class: OrderClass::Market,
+                ..order(BASE, QUOTE, 10, 10)
+            },
+            // fully executed
+            dto::Order {
+                executed: 1.into(),
+                ..order(BASE, QUOTE, 10, 10)
+            },
+        ]);
+
+        let depth = MarketDepth::new(&auction, BASE, QUOTE);
+        assert_eq!(depth.auction_id, 42);
+        assert_eq!(depth.block, 100);
+
+        assert_eq!(depth.asks.len(), 2);
+        assert_eq!(depth.asks[0].price, 2.);
+        assert_eq!(
+            depth.asks[0].fill_or_kill,
+            Liquidity {
+                base_amount: 15.into(),
+                orders: 2,
+            }
+        );
+        assert_eq!(depth.asks[0].partially_fillable, Liquidity::default());
+        assert_eq!(depth.asks[1].price, 3.);
+        assert_eq!(
+            depth.asks[1].partially_fillable,
+            Liquidity {
+                base_amount: 50.into(),
+                orders: 1,
+            }
+        );
+
+        assert_eq!(depth.bids.len(), 2);
+        assert_eq!(depth.bids[0].price, 1.5);
+        assert_eq!(depth.bids[0].fill_or_kill.base_amount, 10.into());
+        assert_eq!(depth.bids[1].price, 1.);
+        assert_eq!(depth.bids[1].fill_or_kill.base_amount, 10.into());
+    }
+}
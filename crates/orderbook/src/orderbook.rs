@@ -1,10 +1,11 @@
 use {
     crate::{
         database::{
-            orders::{InsertionError, OrderStoring},
+            orders::{EthflowRefundCriteria, InsertionError, OrderStoring},
             trades::{TradeFilter, TradeRetrieving},
         },
         dto,
+        market_depth::MarketDepth,
         solver_competition::{Identifier, LoadSolverCompetitionError, SolverCompetitionStoring},
     },
     alloy::primitives::{Address, B256},
@@ -21,24 +22,30 @@ use {
             OrderCancellation,
             OrderCreation,
             OrderCreationAppData,
+            OrderKind,
             OrderStatus,
             OrderUid,
             SignedOrderCancellations,
         },
         quote::QuoteId,
+        signature::{Signature, hashed_eip712_message},
         solver_competition::{self, SolverCompetitionAPI},
     },
     observe::metrics::LivenessChecking,
+    order_validation::limits::{RateLimiter, Requester, Tiers},
     primitive_types::H160,
     shared::{
         fee::FeeParameters,
         order_quoting::Quote,
         order_validation::{
             Amounts,
+            Explanation,
             OrderValidating,
             ValidationError,
             is_order_outside_market_price,
         },
+        remaining_amounts,
+        signature_validator::{SignatureCheck, SignatureValidating, SignatureValidationError},
     },
     std::{borrow::Cow, sync::Arc},
     strum::Display,
@@ -169,6 +176,12 @@ impl AddOrderError {
             InsertionError::MetadataSerializationFailed(err) => {
                 AddOrderError::MetadataSerializationFailed(err)
             }
+            InsertionError::AlreadyCancelled => {
+                AddOrderError::OrderNotFound(OrderCancellationError::AlreadyCancelled)
+            }
+            InsertionError::AmendmentExceedsRemaining => {
+                AddOrderError::InvalidReplacement(OrderReplacementError::AmendmentExceedsRemaining)
+            }
         }
     }
 }
@@ -198,6 +211,10 @@ pub enum OrderCancellationError {
     OrderExpired,
     #[error("on-chain orders cannot be cancelled with off-chain signature")]
     OnChainOrder,
+    #[error("too many cancellations requiring on-chain signature checks within the last minute")]
+    TooManyRequests,
+    #[error("no orders to cancel")]
+    NoOrders,
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -210,6 +227,10 @@ pub enum OrderReplacementError {
     WrongOwner,
     #[error("old order is actively being bid on")]
     OldOrderActivelyBidOn,
+    #[error("amended order must trade the same tokens with the same kind")]
+    IncompatibleAmendment,
+    #[error("amended order exceeds the remaining amount of the old order")]
+    AmendmentExceedsRemaining,
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -237,9 +258,20 @@ pub struct Orderbook {
     order_validator: Arc<dyn OrderValidating>,
     app_data: Arc<crate::app_data::Registry>,
     active_order_competition_threshold: u32,
+    signature_validator: Arc<dyn SignatureValidating>,
+    ethflow_refunds: EthflowRefundCriteria,
+    limits: Arc<Tiers>,
+    /// Verifying signatures of smart contract owners requires calls to the
+    /// node which anybody can trigger without a valid signature, so they are
+    /// charged to whoever requested the cancellation.
+    cancellation_rate_limiter: RateLimiter<Requester>,
+    /// Most recent auction used to compute market depths. Only gets reloaded
+    /// once there is a new auction because loading it is expensive.
+    market_depth_auction: tokio::sync::Mutex<Option<Arc<dto::AuctionWithId>>>,
 }
 
 impl Orderbook {
+    #[expect(clippy::too_many_arguments)]
     pub fn new(
         domain_separator: DomainSeparator,
         settlement_contract: H160,
@@ -248,6 +280,9 @@ impl Orderbook {
         order_validator: Arc<dyn OrderValidating>,
         app_data: Arc<crate::app_data::Registry>,
         active_order_competition_threshold: u32,
+        signature_validator: Arc<dyn SignatureValidating>,
+        ethflow_refunds: EthflowRefundCriteria,
+        limits: Arc<Tiers>,
     ) -> Self {
         Metrics::initialize();
         Self {
@@ -258,6 +293,11 @@ impl Orderbook {
             order_validator,
             app_data,
             active_order_competition_threshold,
+            signature_validator,
+            ethflow_refunds,
+            limits,
+            cancellation_rate_limiter: Default::default(),
+            market_depth_auction: Default::default(),
         }
     }
 
@@ -268,16 +308,18 @@ impl Orderbook {
     /// 3. Validates and constructs an order.
     /// 4. If the new order is to replace an old one, replaces it; otherwise,
     ///    the new order is simply inserted in the database
+    ///
+    /// `requester` identifies who submitted the order, if known.
     #[instrument(skip_all)]
     pub async fn add_order(
         &self,
         payload: OrderCreation,
+        requester: Option<Requester>,
     ) -> Result<(OrderUid, Option<QuoteMetadata>), AddOrderError> {
         let full_app_data_override = match payload.app_data {
             OrderCreationAppData::Hash { hash } => self.app_data.find(&hash).await?,
             _ => None,
         };
-
         let replaced_order = self
             .get_replaced_order(&payload, full_app_data_override.as_deref())
             .await?;
@@ -289,29 +331,128 @@ impl Orderbook {
                 &self.domain_separator,
                 self.settlement_contract,
                 full_app_data_override,
+                requester.as_ref(),
             )
             .await?;
 
         let order_uid = order.metadata.uid;
 
         // Check if it has to replace an existing order
-        if let Some(old_order) = replaced_order {
-            self.replace_order(order, old_order).await?
+        let new_app_data = if let Some(old_order) = replaced_order {
+            self.replace_order(&order, old_order).await?
         } else {
-            self.database
+            let new_app_data = self
+                .database
                 .insert_order(&order)
                 .await
                 .map_err(|err| AddOrderError::from_insertion(err, &order))?;
             Metrics::on_order_operation(&order, OrderOperation::Created);
+            new_app_data
+        };
+        if new_app_data {
+            self.app_data.pin_order_app_data(&order);
         }
 
         Ok((order_uid, quote.as_ref().map(QuoteMetadata::from)))
     }
 
+    /// Runs all checks performed when placing the order without actually
+    /// placing it and reports the outcome of each one.
+    #[instrument(skip_all)]
+    pub async fn explain_order(
+        &self,
+        payload: OrderCreation,
+        requester: Option<&Requester>,
+    ) -> Result<Explanation, AddOrderError> {
+        let full_app_data_override = match payload.app_data {
+            OrderCreationAppData::Hash { hash } => self.app_data.find(&hash).await?,
+            _ => None,
+        };
+        Ok(self
+            .order_validator
+            .explain_order(
+                payload,
+                &self.domain_separator,
+                full_app_data_override,
+                requester,
+            )
+            .await?)
+    }
+
+    /// Replaces the order `old_order_uid` with a newly signed order in a single
+    /// step.
+    ///
+    /// In contrast to [`Self::add_order`] with the `replacedOrder` app data
+    /// field the new order doesn't need to reference the old one, which makes
+    /// it cheap to adjust the limit price or validity of an order. The old
+    /// order only gets cancelled if the new one passes validation and it
+    /// wasn't cancelled or amended concurrently. The amounts the old order
+    /// already executed are recorded together with the amendment so the
+    /// progress of partially fillable orders can be tracked across amendments.
+    /// Since the new order starts without any executed amount it may not trade
+    /// more than what remained of the old order.
+    #[instrument(skip_all)]
+    pub async fn amend_order(
+        &self,
+        old_order_uid: &OrderUid,
+        payload: OrderCreation,
+        requester: Option<Requester>,
+    ) -> Result<(OrderUid, Option<QuoteMetadata>), AddOrderError> {
+        let old_order = self
+            .find_order_for_cancellation(old_order_uid)
+            .await
+            .map_err(AddOrderError::OrderNotFound)?;
+
+        let full_app_data_override = match payload.app_data {
+            OrderCreationAppData::Hash { hash } => self.app_data.find(&hash).await?,
+            _ => None,
+        };
+        let (order, quote) = self
+            .order_validator
+            .validate_and_construct_order(
+                payload,
+                &self.domain_separator,
+                self.settlement_contract,
+                full_app_data_override,
+                requester.as_ref(),
+            )
+            .await?;
+
+        if !is_compatible_amendment(&old_order, &order) {
+            return Err(AddOrderError::InvalidReplacement(
+                OrderReplacementError::IncompatibleAmendment,
+            ));
+        }
+        if !fits_remaining_amount(&old_order, &order) {
+            return Err(AddOrderError::InvalidReplacement(
+                OrderReplacementError::AmendmentExceedsRemaining,
+            ));
+        }
+        self.verify_replacement(&order, &old_order).await?;
+
+        let new_app_data = self
+            .database
+            .amend_order(&old_order, &order)
+            .await
+            .map_err(|err| AddOrderError::from_insertion(err, &order))?;
+        tracing::debug!(
+            old_order_uid =% old_order.metadata.uid,
+            new_order_uid =% order.metadata.uid,
+            "order amended"
+        );
+        Metrics::on_order_operation(&old_order, OrderOperation::Cancelled);
+        Metrics::on_order_operation(&order, OrderOperation::Created);
+        if new_app_data {
+            self.app_data.pin_order_app_data(&order);
+        }
+
+        Ok((order.metadata.uid, quote.as_ref().map(QuoteMetadata::from)))
+    }
+
     /// Finds an order for cancellation.
     ///
     /// Returns an error if the order cannot be found or cannot be cancelled
-    /// (for example, orders using PreSign).
+    /// (for example, orders placed on-chain).
     async fn find_order_for_cancellation(
         &self,
         order_uid: &OrderUid,
@@ -323,8 +464,9 @@ impl Orderbook {
             .ok_or(OrderCancellationError::OrderNotFound)?;
 
         match order.metadata.status {
-            OrderStatus::PresignaturePending => return Err(OrderCancellationError::OnChainOrder),
-            OrderStatus::Open if !order.signature.scheme().is_ecdsa_scheme() => {
+            OrderStatus::PresignaturePending | OrderStatus::Open
+                if order.metadata.onchain_order_data.is_some() =>
+            {
                 return Err(OrderCancellationError::OnChainOrder);
             }
             OrderStatus::Fulfilled => return Err(OrderCancellationError::OrderFullyExecuted),
@@ -336,25 +478,36 @@ impl Orderbook {
         Ok(order)
     }
 
+    /// `requester` identifies who requested the cancellation, if known.
     pub async fn cancel_orders(
         &self,
         cancellation: SignedOrderCancellations,
+        requester: Option<Requester>,
     ) -> Result<(), OrderCancellationError> {
+        // Without orders there is no owner to verify the signature against.
+        if cancellation.data.order_uids.is_empty() {
+            return Err(OrderCancellationError::NoOrders);
+        }
         let mut orders = Vec::new();
         for order_uid in &cancellation.data.order_uids {
             orders.push(self.find_order_for_cancellation(order_uid).await?);
         }
 
         // Verify the cancellation signer is the same as the order signers
-        let signer = cancellation
-            .validate(&self.domain_separator)
-            .map_err(|_| OrderCancellationError::InvalidSignature)?;
+        let owner = orders[0].metadata.owner.into_legacy();
         if orders
             .iter()
-            .any(|order| signer != order.metadata.owner.into_legacy())
+            .any(|order| owner != order.metadata.owner.into_legacy())
         {
             return Err(OrderCancellationError::WrongOwner);
         };
+        self.verify_cancellation_signature(
+            &cancellation.signature,
+            &cancellation.data.hash_struct(),
+            owner,
+            requester.as_ref(),
+        )
+        .await?;
 
         // orders are already known to exist in DB at this point, and signer is
         // known to be correct!
@@ -370,21 +523,24 @@ impl Orderbook {
         Ok(())
     }
 
+    /// `requester` identifies who requested the cancellation, if known.
     pub async fn cancel_order(
         &self,
         cancellation: OrderCancellation,
+        requester: Option<Requester>,
     ) -> Result<(), OrderCancellationError> {
         let order = self
             .find_order_for_cancellation(&cancellation.order_uid)
             .await?;
 
         // Verify the cancellation signer is the same as the order signer.
-        let signer = cancellation
-            .validate(&self.domain_separator)
-            .map_err(|_| OrderCancellationError::InvalidSignature)?;
-        if signer != order.metadata.owner.into_legacy() {
-            return Err(OrderCancellationError::WrongOwner);
-        };
+        self.verify_cancellation_signature(
+            &cancellation.signature,
+            &cancellation.hash_struct(),
+            order.metadata.owner.into_legacy(),
+            requester.as_ref(),
+        )
+        .await?;
 
         // order is already known to exist in DB at this point, and signer is
         // known to be correct!
@@ -398,6 +554,62 @@ impl Orderbook {
         Ok(())
     }
 
+    /// Checks that the cancellation was signed by the owner of the cancelled
+    /// orders. ECDSA signatures get recovered while EIP-1271 signatures are
+    /// validated by the owner contract. Pre-signed cancellations are valid if
+    /// the owner is a Safe that approved the message on-chain with
+    /// `signMessage`.
+    async fn verify_cancellation_signature(
+        &self,
+        signature: &Signature,
+        struct_hash: &[u8; 32],
+        owner: H160,
+        requester: Option<&Requester>,
+    ) -> Result<(), OrderCancellationError> {
+        let recovered = signature
+            .recover(&self.domain_separator, struct_hash)
+            .map_err(|_| OrderCancellationError::InvalidSignature)?;
+        if let Some(recovered) = recovered {
+            if recovered.signer != owner {
+                return Err(OrderCancellationError::WrongOwner);
+            }
+            return Ok(());
+        }
+        if let Some(requester) = requester
+            && !self.cancellation_rate_limiter.try_acquire(
+                requester.clone(),
+                self.limits
+                    .requester_limits(requester)
+                    .cancellation_checks_per_minute,
+            )
+        {
+            return Err(OrderCancellationError::TooManyRequests);
+        }
+        let hash = hashed_eip712_message(&self.domain_separator, struct_hash);
+        let result = match signature {
+            Signature::PreSign => {
+                self.signature_validator
+                    .validate_safe_signed_message(owner, hash)
+                    .await
+            }
+            _ => {
+                self.signature_validator
+                    .validate_signature(SignatureCheck {
+                        signer: owner,
+                        hash,
+                        signature: signature.to_bytes(),
+                        interactions: Vec::new(),
+                        balance_override: None,
+                    })
+                    .await
+            }
+        };
+        result.map_err(|err| match err {
+            SignatureValidationError::Invalid => OrderCancellationError::InvalidSignature,
+            SignatureValidationError::Other(err) => OrderCancellationError::Other(err),
+        })
+    }
+
     /// Using the provided app data, finds the order to be replaced.
     ///
     /// Validates the provided app data before searching for the order to be
@@ -438,10 +650,34 @@ impl Orderbook {
     ///    cancelling orders on someone else's behalf.
     /// 2. Old and new order MUST have the same signer.
     /// 3. The old order cannot be bid on (to prevent double spending).
+    ///
+    /// Returns whether the full app data of the new order wasn't stored
+    /// before.
     pub async fn replace_order(
         &self,
-        validated_new_order: Order,
+        validated_new_order: &Order,
         old_order: Order,
+    ) -> Result<bool, AddOrderError> {
+        self.verify_replacement(validated_new_order, &old_order)
+            .await?;
+
+        let new_app_data = self
+            .database
+            .replace_order(&old_order.metadata.uid, validated_new_order)
+            .await
+            .map_err(|err| AddOrderError::from_insertion(err, validated_new_order))?;
+        Metrics::on_order_operation(&old_order, OrderOperation::Cancelled);
+        Metrics::on_order_operation(validated_new_order, OrderOperation::Created);
+
+        Ok(new_app_data)
+    }
+
+    /// Checks that `validated_new_order` may replace `old_order`. See
+    /// [`Self::replace_order`] for the individual checks.
+    async fn verify_replacement(
+        &self,
+        validated_new_order: &Order,
+        old_order: &Order,
     ) -> Result<(), AddOrderError> {
         validated_new_order
             .signature
@@ -466,13 +702,6 @@ impl Orderbook {
             ));
         }
 
-        self.database
-            .replace_order(&old_order.metadata.uid, &validated_new_order)
-            .await
-            .map_err(|err| AddOrderError::from_insertion(err, &validated_new_order))?;
-        Metrics::on_order_operation(&old_order, OrderOperation::Cancelled);
-        Metrics::on_order_operation(&validated_new_order, OrderOperation::Created);
-
         Ok(())
     }
 
@@ -496,11 +725,16 @@ impl Orderbook {
     }
 
     pub async fn get_order(&self, uid: &OrderUid) -> Result<Option<Order>> {
-        self.database_replica.single_order(uid).await
+        let mut order = self.database_replica.single_order(uid).await?;
+        self.mark_pending_ethflow_refunds(order.as_mut_slice())
+            .await?;
+        Ok(order)
     }
 
     pub async fn get_orders_for_tx(&self, hash: &B256) -> Result<Vec<Order>> {
-        self.database_replica.orders_for_tx(hash).await
+        let mut orders = self.database_replica.orders_for_tx(hash).await?;
+        self.mark_pending_ethflow_refunds(&mut orders).await?;
+        Ok(orders)
     }
 
     pub async fn get_auction(&self) -> Result<Option<dto::AuctionWithId>> {
@@ -514,16 +748,49 @@ impl Orderbook {
         Ok(Some(auction))
     }
 
+    /// Aggregates the limit orders of the most recent auction trading the
+    /// given token pair into price levels.
+    pub async fn get_market_depth(
+        &self,
+        base_token: H160,
+        quote_token: H160,
+    ) -> Result<Option<MarketDepth>> {
+        let auction = self.market_depth_auction().await?;
+        Ok(auction.map(|auction| MarketDepth::new(&auction, base_token, quote_token)))
+    }
+
+    /// Returns the most recent auction, only loading it from the database if
+    /// it changed since the last call.
+    async fn market_depth_auction(&self) -> Result<Option<Arc<dto::AuctionWithId>>> {
+        let Some(id) = self.database.most_recent_auction_id().await? else {
+            return Ok(None);
+        };
+        // Held while loading so concurrent requests don't all load the new
+        // auction.
+        let mut cached = self.market_depth_auction.lock().await;
+        if let Some(auction) = cached.as_ref().filter(|auction| auction.id >= id) {
+            return Ok(Some(auction.clone()));
+        }
+        let auction = self.get_auction().await?.map(Arc::new);
+        if auction.is_some() {
+            cached.clone_from(&auction);
+        }
+        Ok(auction)
+    }
+
     pub async fn get_user_orders(
         &self,
         owner: &Address,
         offset: u64,
         limit: u64,
     ) -> Result<Vec<Order>> {
-        self.database_replica
+        let mut orders = self
+            .database_replica
             .user_orders(owner, offset, Some(limit))
             .await
-            .context("get_user_orders error")
+            .context("get_user_orders error")?;
+        self.mark_pending_ethflow_refunds(&mut orders).await?;
+        Ok(orders)
     }
 
     pub async fn get_order_status(
@@ -601,9 +868,105 @@ impl Orderbook {
             OrderEventLabel::Cancelled => dto::order::Status::Cancelled,
             OrderEventLabel::Filtered => dto::order::Status::Open,
             OrderEventLabel::Invalid => dto::order::Status::Open,
+            OrderEventLabel::Triggered => dto::order::Status::Scheduled,
         };
         Ok(status)
     }
+
+    /// Returns the amendments of the lineage the order is part of from the
+    /// oldest to the newest.
+    pub async fn get_order_amendments(&self, uid: &OrderUid) -> Result<Vec<dto::order::Amendment>> {
+        self.database_replica.order_amendments(uid).await
+    }
+
+    /// Returns the aggregated execution progress of the order if it is a TWAP
+    /// order and the lifecycle of the order including its refund if it is an
+    /// ethflow order.
+    pub async fn get_status_details(&self, uid: &OrderUid) -> Result<dto::order::StatusDetails> {
+        let Some(order) = self.get_order(uid).await? else {
+            return Ok(Default::default());
+        };
+        Ok(dto::order::StatusDetails {
+            twap: twap_status(&order, model::time::now_in_epoch_seconds()),
+            ethflow: order.metadata.ethflow_data,
+        })
+    }
+
+    pub async fn get_ethflow_orders_for_placement_tx(&self, hash: &B256) -> Result<Vec<Order>> {
+        let mut orders = self
+            .database_replica
+            .ethflow_orders_for_placement_tx(hash)
+            .await?;
+        self.mark_pending_ethflow_refunds(&mut orders).await?;
+        Ok(orders)
+    }
+
+    /// Reports expired ethflow orders as waiting for a refund if the refunder
+    /// will pick them up.
+    async fn mark_pending_ethflow_refunds(&self, orders: &mut [Order]) -> Result<()> {
+        self.database_replica
+            .mark_pending_ethflow_refunds(orders, &self.ethflow_refunds)
+            .await
+    }
+}
+
+/// An amendment may change the amounts and validity of an order but not what
+/// gets traded. Otherwise the executed amounts recorded for the lineage of the
+/// order wouldn't be meaningful.
+fn is_compatible_amendment(old: &Order, new: &Order) -> bool {
+    old.data.sell_token == new.data.sell_token
+        && old.data.buy_token == new.data.buy_token
+        && old.data.kind == new.data.kind
+        && old.data.partially_fillable == new.data.partially_fillable
+}
+
+/// The amended order starts out without any executed amount so it must not
+/// trade more than what is left of the old order. Otherwise amending a
+/// partially filled order would allow it to get filled beyond its signed
+/// amounts. Orders that didn't trade yet can be amended to any size.
+fn fits_remaining_amount(old: &Order, new: &Order) -> bool {
+    let executed = remaining_amounts::Order::from(old).executed_amount;
+    if executed.is_zero() {
+        return true;
+    }
+    let (old_total, new_total) = match old.data.kind {
+        OrderKind::Buy => (old.data.buy_amount, new.data.buy_amount),
+        OrderKind::Sell => (old.data.sell_amount, new.data.sell_amount),
+    };
+    old_total
+        .into_legacy()
+        .checked_sub(executed)
+        .is_some_and(|remaining| new_total.into_legacy() <= remaining)
+}
+
+fn twap_status(order: &Order, now: u32) -> Option<dto::order::TwapStatus> {
+    let twap = app_data::parse(order.metadata.full_app_data.as_ref()?.as_bytes())
+        .ok()?
+        .twap?;
+    let start_time = twap.start_time.unwrap_or_else(|| {
+        u32::try_from(order.metadata.creation_date.timestamp()).unwrap_or_default()
+    });
+    let total = match order.data.kind {
+        OrderKind::Buy => order.data.buy_amount,
+        OrderKind::Sell => order.data.sell_amount,
+    };
+    let executed_amount = remaining_amounts::Order::from(order).executed_amount;
+    let filled_parts = executed_amount
+        .full_mul(twap.parts.into())
+        .checked_div(total.into_legacy().into())
+        .and_then(|parts| u32::try_from(parts).ok())
+        .unwrap_or_default()
+        .min(twap.parts);
+    Some(dto::order::TwapStatus {
+        parts: twap.parts,
+        part_duration: twap.part_duration,
+        start_time,
+        unlocked_parts: twap.unlocked_parts(start_time, now),
+        filled_parts,
+        unlocked_amount: twap.unlocked_amount(total, start_time, now).into_legacy(),
+        executed_amount,
+        next_part_time: twap.next_part_time(start_time, now),
+    })
 }
 
 #[derive(Error, Debug)]
@@ -642,7 +1005,11 @@ mod tests {
             order::{OrderData, OrderMetadata},
             signature::Signature,
         },
-        shared::order_validation::MockOrderValidating,
+        order_validation::limits::Limits,
+        shared::{
+            order_validation::MockOrderValidating,
+            signature_validator::MockSignatureValidating,
+        },
     };
 
     #[tokio::test]
@@ -670,12 +1037,12 @@ mod tests {
                 let old_order = old_order.clone();
                 move |_| Ok(Some(old_order.clone()))
             });
-        database.expect_replace_order().returning(|_, _| Ok(()));
+        database.expect_replace_order().returning(|_, _| Ok(false));
 
         let mut order_validator = MockOrderValidating::new();
         order_validator
             .expect_validate_and_construct_order()
-            .returning(move |creation, _, _, _| {
+            .returning(move |creation, _, _, _, _| {
                 Ok((
                     Order {
                         metadata: OrderMetadata {
@@ -709,6 +1076,11 @@ mod tests {
             settlement_contract: H160([0xba; 20]),
             app_data,
             active_order_competition_threshold: Default::default(),
+            signature_validator: Arc::new(MockSignatureValidating::new()),
+            ethflow_refunds: Default::default(),
+            limits: Default::default(),
+            cancellation_rate_limiter: Default::default(),
+            market_depth_auction: Default::default(),
         };
 
         // Different owner
@@ -724,7 +1096,7 @@ mod tests {
                         )
                     },
                     ..Default::default()
-                },)
+                }, None)
                 .await,
             Err(AddOrderError::InvalidReplacement(
                 OrderReplacementError::WrongOwner
@@ -744,7 +1116,7 @@ mod tests {
                         )
                     },
                     ..Default::default()
-                },)
+                }, None)
                 .await,
             Err(AddOrderError::OrderNotFound(
                 OrderCancellationError::OrderNotFound
@@ -764,7 +1136,7 @@ mod tests {
                         )
                     },
                     ..Default::default()
-                },)
+                }, None)
                 .await,
             Err(AddOrderError::InvalidReplacement(
                 OrderReplacementError::InvalidSignature
@@ -783,9 +1155,242 @@ mod tests {
                     ),
                 },
                 ..Default::default()
-            })
+            }, None)
             .await
             .unwrap();
         assert_eq!(order_id, new_order_uid,);
     }
+
+    #[test]
+    fn twap_status_() {
+        let order = Order {
+            data: OrderData {
+                sell_amount: alloy::primitives::U256::from(1000),
+                buy_amount: alloy::primitives::U256::from(2000),
+                kind: OrderKind::Buy,
+                partially_fillable: true,
+                ..Default::default()
+            },
+            metadata: OrderMetadata {
+                creation_date: chrono::DateTime::from_timestamp(1000, 0).unwrap(),
+                full_app_data: Some(
+                    r#"{"metadata":{"twap":{"parts":4,"partDuration":100}}}"#.to_string(),
+                ),
+                executed_buy_amount: 700u32.into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            twap_status(&order, 1250),
+            Some(dto::order::TwapStatus {
+                parts: 4,
+                part_duration: 100,
+                start_time: 1000,
+                unlocked_parts: 3,
+                filled_parts: 1,
+                unlocked_amount: 1500.into(),
+                executed_amount: 700.into(),
+                next_part_time: Some(1300),
+            })
+        );
+
+        let order = Order {
+            metadata: OrderMetadata {
+                full_app_data: Some("{}".to_string()),
+                ..order.metadata
+            },
+            ..order
+        };
+        assert_eq!(twap_status(&order, 1250), None);
+    }
+
+    #[test]
+    fn amendment_must_trade_the_same_tokens() {
+        let old = Order {
+            data: OrderData {
+                sell_token: Address::new([1; 20]),
+                buy_token: Address::new([2; 20]),
+                sell_amount: alloy::primitives::U256::from(1000),
+                buy_amount: alloy::primitives::U256::from(2000),
+                kind: OrderKind::Sell,
+                partially_fillable: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let amend = |data: OrderData| Order {
+            data,
+            ..Default::default()
+        };
+
+        assert!(is_compatible_amendment(
+            &old,
+            &amend(OrderData {
+                buy_amount: alloy::primitives::U256::from(2100),
+                valid_to: 100,
+                ..old.data
+            })
+        ));
+        assert!(!is_compatible_amendment(
+            &old,
+            &amend(OrderData {
+                buy_token: Address::new([3; 20]),
+                ..old.data
+            })
+        ));
+        assert!(!is_compatible_amendment(
+            &old,
+            &amend(OrderData {
+                kind: OrderKind::Buy,
+                ..old.data
+            })
+        ));
+        assert!(!is_compatible_amendment(
+            &old,
+            &amend(OrderData {
+                partially_fillable: false,
+                ..old.data
+            })
+        ));
+    }
+
+    #[test]
+    fn amendment_of_partially_filled_order_must_fit_remaining_amount() {
+        let old = Order {
+            data: OrderData {
+                sell_amount: alloy::primitives::U256::from(1000),
+                buy_amount: alloy::primitives::U256::from(2000),
+                kind: OrderKind::Sell,
+                partially_fillable: true,
+                ..Default::default()
+            },
+            metadata: OrderMetadata {
+                executed_sell_amount_before_fees: 600.into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let amend = |sell_amount: u64, buy_amount: u64| Order {
+            data: OrderData {
+                sell_amount: alloy::primitives::U256::from(sell_amount),
+                buy_amount: alloy::primitives::U256::from(buy_amount),
+                ..old.data
+            },
+            ..Default::default()
+        };
+
+        assert!(fits_remaining_amount(&old, &amend(400, 900)));
+        assert!(fits_remaining_amount(&old, &amend(300, 500)));
+        assert!(!fits_remaining_amount(&old, &amend(401, 900)));
+        assert!(!fits_remaining_amount(&old, &amend(1000, 2000)));
+
+        let old = Order {
+            data: OrderData {
+                kind: OrderKind::Buy,
+                ..old.data
+            },
+            metadata: OrderMetadata {
+                executed_buy_amount: 1500u32.into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let amend = |sell_amount: u64, buy_amount: u64| Order {
+            data: OrderData {
+                sell_amount: alloy::primitives::U256::from(sell_amount),
+                buy_amount: alloy::primitives::U256::from(buy_amount),
+                ..old.data
+            },
+            ..Default::default()
+        };
+
+        assert!(fits_remaining_amount(&old, &amend(300, 500)));
+        assert!(!fits_remaining_amount(&old, &amend(300, 501)));
+    }
+
+    #[test]
+    fn amendment_of_unfilled_order_can_increase_its_amount() {
+        let old = Order {
+            data: OrderData {
+                sell_amount: alloy::primitives::U256::from(1000),
+                buy_amount: alloy::primitives::U256::from(2000),
+                kind: OrderKind::Sell,
+                partially_fillable: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let amend = |sell_amount: u64, buy_amount: u64| Order {
+            data: OrderData {
+                sell_amount: alloy::primitives::U256::from(sell_amount),
+                buy_amount: alloy::primitives::U256::from(buy_amount),
+                ..old.data
+            },
+            ..Default::default()
+        };
+
+        assert!(fits_remaining_amount(&old, &amend(1500, 3000)));
+        assert!(fits_remaining_amount(&old, &amend(500, 1000)));
+    }
+
+    #[tokio::test]
+    async fn smart_contract_cancellations_are_rate_limited_per_requester() {
+        let owner = H160([1; 20]);
+        let mut signature_validator = MockSignatureValidating::new();
+        signature_validator
+            .expect_validate_signature()
+            .withf(move |check| check.signer == owner && check.signature == [1, 2])
+            .times(1)
+            .returning(|_| Ok(()));
+        signature_validator
+            .expect_validate_safe_signed_message()
+            .withf(move |safe, _| *safe == owner)
+            .times(2)
+            .returning(|_, _| Ok(()));
+
+        let database = crate::database::Postgres::try_new("postgresql://").unwrap();
+        let orderbook = Orderbook {
+            database: database.clone(),
+            database_replica: database.clone(),
+            order_validator: Arc::new(MockOrderValidating::new()),
+            domain_separator: Default::default(),
+            settlement_contract: H160([0xba; 20]),
+            app_data: Arc::new(crate::app_data::Registry::new(
+                Validator::new(8192),
+                database,
+                None,
+            )),
+            active_order_competition_threshold: Default::default(),
+            signature_validator: Arc::new(signature_validator),
+            ethflow_refunds: Default::default(),
+            limits: Arc::new(Tiers::uniform(Limits {
+                cancellation_checks_per_minute: Some(2),
+                ..Default::default()
+            })),
+            cancellation_rate_limiter: Default::default(),
+        };
+        let verify = |signature, requester| {
+            let orderbook = &orderbook;
+            async move {
+                orderbook
+                    .verify_cancellation_signature(&signature, &[0; 32], owner, Some(&requester))
+                    .await
+            }
+        };
+        let spammer = Requester::Ip([1, 1, 1, 1].into());
+        let other = Requester::Ip([2, 2, 2, 2].into());
+
+        assert!(
+            verify(Signature::Eip1271(vec![1, 2]), spammer.clone())
+                .await
+                .is_ok()
+        );
+        assert!(verify(Signature::PreSign, spammer.clone()).await.is_ok());
+        assert!(matches!(
+            verify(Signature::PreSign, spammer).await,
+            Err(OrderCancellationError::TooManyRequests)
+        ));
+        assert!(verify(Signature::PreSign, other).await.is_ok());
+    }
 }
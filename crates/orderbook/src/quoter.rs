@@ -4,10 +4,12 @@ use {
         arguments::{FeeFactor, VolumeFeeConfig},
     },
     chrono::{TimeZone, Utc},
+    futures::{FutureExt, StreamExt, channel::mpsc, stream::BoxStream},
     model::{
         order::OrderCreationAppData,
         quote::{OrderQuote, OrderQuoteRequest, OrderQuoteResponse, OrderQuoteSide, PriceQuality},
     },
+    order_validation::limits,
     primitive_types::U256,
     shared::{
         order_quoting::{CalculateQuoteError, OrderQuoting, Quote, QuoteParameters},
@@ -22,7 +24,7 @@ use {
     },
     std::sync::Arc,
     thiserror::Error,
-    tracing::instrument,
+    tracing::{Instrument, instrument},
 };
 
 /// Adjusted quote amounts after applying volume fee.
@@ -43,6 +45,8 @@ pub struct QuoteHandler {
     fast_quoter: Arc<dyn OrderQuoting>,
     app_data: Arc<app_data::Registry>,
     volume_fee: Option<VolumeFeeConfig>,
+    limits: Arc<limits::Tiers>,
+    rate_limiter: limits::RateLimiter<limits::Requester>,
 }
 
 impl QuoteHandler {
@@ -58,6 +62,8 @@ impl QuoteHandler {
             fast_quoter: quoter,
             app_data,
             volume_fee,
+            limits: Default::default(),
+            rate_limiter: Default::default(),
         }
     }
 
@@ -65,6 +71,12 @@ impl QuoteHandler {
         self.fast_quoter = fast_quoter;
         self
     }
+
+    /// Limits how many quotes clients can request per minute.
+    pub fn with_limits(mut self, limits: Arc<limits::Tiers>) -> Self {
+        self.limits = limits;
+        self
+    }
 }
 
 impl QuoteHandler {
@@ -72,18 +84,125 @@ impl QuoteHandler {
     pub async fn calculate_quote(
         &self,
         request: &OrderQuoteRequest,
+        requester: Option<&limits::Requester>,
     ) -> Result<OrderQuoteResponse, OrderQuoteError> {
         tracing::debug!(?request, "calculating quote");
 
+        let (params, valid_to) = self.prepare(request, requester).await?;
+        let quote = match request.price_quality {
+            PriceQuality::Optimal | PriceQuality::Verified => self.optimal_quote(params).await?,
+            PriceQuality::Fast => self.fast_quote(params).await?,
+        };
+        let response = self.build_response(request, valid_to, quote)?;
+
+        tracing::debug!(?response, "finished computing quote");
+        Ok(response)
+    }
+
+    /// Computes a quote like [`Self::calculate_quote`] but additionally
+    /// reports a preliminary quote for the estimate of every price source as
+    /// soon as it arrives.
+    ///
+    /// Validation errors are returned before any update gets produced. After
+    /// that the stream yields any number of [`QuoteUpdate::Preliminary`]
+    /// quotes and ends with either a [`QuoteUpdate::Final`] quote or an error.
+    #[instrument(skip_all, fields(buy_token = ?request.buy_token, sell_token = ?request.sell_token, price_quality = ?request.price_quality))]
+    pub async fn calculate_quote_stream(
+        self: Arc<Self>,
+        request: OrderQuoteRequest,
+        requester: Option<&limits::Requester>,
+    ) -> Result<BoxStream<'static, Result<QuoteUpdate, OrderQuoteError>>, OrderQuoteError> {
+        tracing::debug!(?request, "streaming quote");
+
+        let (params, valid_to) = self.prepare(&request, requester).await?;
+        let span = tracing::Span::current();
+        let stream = async_stream::stream! {
+            let quoter = match request.price_quality {
+                PriceQuality::Optimal | PriceQuality::Verified => &self.optimal_quoter,
+                PriceQuality::Fast => &self.fast_quoter,
+            };
+            let (sender, mut preliminary) = mpsc::unbounded();
+            let final_quote = quoter
+                .calculate_quote_progressively(params, sender)
+                .instrument(span)
+                .fuse();
+            futures::pin_mut!(final_quote);
+            let result = loop {
+                let quote = futures::select! {
+                    quote = preliminary.select_next_some() => quote,
+                    result = final_quote => break result,
+                };
+                if let Some(response) = self.preliminary_response(&request, valid_to, quote) {
+                    yield Ok(QuoteUpdate::Preliminary(response));
+                }
+            };
+            // The quoter is done so the channel only contains the preliminary
+            // quotes that arrived together with the final one.
+            while let Some(quote) = preliminary.next().await {
+                if let Some(response) = self.preliminary_response(&request, valid_to, quote) {
+                    yield Ok(QuoteUpdate::Preliminary(response));
+                }
+            }
+
+            let quote = match (result, request.price_quality) {
+                (Ok(quote), PriceQuality::Optimal | PriceQuality::Verified) => self
+                    .optimal_quoter
+                    .store_quote(quote)
+                    .await
+                    .map_err(CalculateQuoteError::Other),
+                (Ok(quote), PriceQuality::Fast) => Ok(expire_immediately(quote)),
+                (Err(err), _) => Err(err),
+            };
+            yield quote
+                .map_err(OrderQuoteError::from)
+                .and_then(|quote| self.build_response(&request, valid_to, quote))
+                .map(QuoteUpdate::Final);
+        };
+        Ok(stream.boxed())
+    }
+
+    /// Converts a preliminary quote into a response. Preliminary quotes are
+    /// not stored and expire immediately so they can't be used to place an
+    /// order.
+    fn preliminary_response(
+        &self,
+        request: &OrderQuoteRequest,
+        valid_to: u32,
+        quote: Quote,
+    ) -> Option<OrderQuoteResponse> {
+        self.build_response(request, valid_to, expire_immediately(quote))
+            .inspect_err(|err| tracing::debug!(?err, "failed to build preliminary quote"))
+            .ok()
+    }
+
+    /// Validates the request and converts it into the parameters for the
+    /// quoters. Also returns the `valid_to` of the resulting quote.
+    async fn prepare(
+        &self,
+        request: &OrderQuoteRequest,
+        requester: Option<&limits::Requester>,
+    ) -> Result<(QuoteParameters, u32), OrderQuoteError> {
         let full_app_data_override = match request.app_data {
             OrderCreationAppData::Hash { hash } => self.app_data.find(&hash).await.unwrap_or(None),
             _ => None,
         };
 
-        let app_data = self
-            .order_validator
+        self.order_validator
             .validate_app_data(&request.app_data, &full_app_data_override)?;
 
+        // Quotes aren't signed so anybody can request them on behalf of any
+        // owner or app code. Rate limit the requester instead to not let others
+        // exhaust the limit of an owner, and only grant the higher tier of an
+        // authenticated API key.
+        if let Some(requester) = requester
+            && !self.rate_limiter.try_acquire(
+                requester.clone(),
+                self.limits.requester_limits(requester).quotes_per_minute,
+            )
+        {
+            return Err(OrderQuoteError::TooManyQuotes);
+        }
+
         let order = PreOrderData::from(request);
         let valid_to = order.valid_to;
         self.order_validator.partial_validate(order).await?;
@@ -105,28 +224,32 @@ impl QuoteHandler {
             timeout: request.timeout,
         };
 
-        let quote = match request.price_quality {
-            PriceQuality::Optimal | PriceQuality::Verified => {
-                let quote = self.optimal_quoter.calculate_quote(params).await?;
-                self.optimal_quoter
-                    .store_quote(quote)
-                    .await
-                    .map_err(CalculateQuoteError::Other)?
-            }
-            PriceQuality::Fast => {
-                let mut quote = self.fast_quoter.calculate_quote(params).await?;
-                // We maintain an API guarantee that fast quotes always have an expiry of zero,
-                // because they're not very accurate and can be considered to
-                // expire immediately.
-                quote.data.expiration = Utc.timestamp_millis_opt(0).unwrap();
-                quote
-            }
-        };
+        Ok((params, valid_to))
+    }
+
+    async fn optimal_quote(&self, params: QuoteParameters) -> Result<Quote, CalculateQuoteError> {
+        let quote = self.optimal_quoter.calculate_quote(params).await?;
+        self.optimal_quoter
+            .store_quote(quote)
+            .await
+            .map_err(CalculateQuoteError::Other)
+    }
+
+    async fn fast_quote(&self, params: QuoteParameters) -> Result<Quote, CalculateQuoteError> {
+        let quote = self.fast_quoter.calculate_quote(params).await?;
+        Ok(expire_immediately(quote))
+    }
 
+    fn build_response(
+        &self,
+        request: &OrderQuoteRequest,
+        valid_to: u32,
+        quote: Quote,
+    ) -> Result<OrderQuoteResponse, OrderQuoteError> {
         let adjusted_quote =
             get_adjusted_quote_data(&quote, self.volume_fee.as_ref(), &request.side)
                 .map_err(|err| OrderQuoteError::CalculateQuote(err.into()))?;
-        let response = OrderQuoteResponse {
+        Ok(OrderQuoteResponse {
             quote: OrderQuote {
                 sell_token: request.sell_token,
                 buy_token: request.buy_token,
@@ -153,13 +276,29 @@ impl QuoteHandler {
             id: quote.id,
             verified: quote.data.verified,
             protocol_fee_bps: adjusted_quote.protocol_fee_bps,
-        };
-
-        tracing::debug!(?response, "finished computing quote");
-        Ok(response)
+        })
     }
 }
 
+/// We maintain an API guarantee that fast quotes always have an expiry of zero,
+/// because they're not very accurate and can be considered to expire
+/// immediately.
+fn expire_immediately(mut quote: Quote) -> Quote {
+    quote.data.expiration = Utc.timestamp_millis_opt(0).unwrap();
+    quote
+}
+
+/// Update produced while streaming a quote.
+#[derive(Debug)]
+pub enum QuoteUpdate {
+    /// Quote based on the estimate of a single price source. It is not stored
+    /// in the database and expires immediately so it can't be used to place an
+    /// order.
+    Preliminary(OrderQuoteResponse),
+    /// The quote that would have been returned by the regular quote endpoint.
+    Final(OrderQuoteResponse),
+}
+
 /// Calculates the protocol fee based on volume fee and adjusts quote amounts.
 fn get_adjusted_quote_data(
     quote: &Quote,
@@ -232,6 +371,9 @@ pub enum OrderQuoteError {
 
     #[error("error calculating quote: {0}")]
     CalculateQuote(#[from] CalculateQuoteError),
+
+    #[error("too many quotes requested within the last minute")]
+    TooManyQuotes,
 }
 
 impl From<AppDataValidationError> for OrderQuoteError {
@@ -1,12 +1,15 @@
 use {
     crate::{
         api,
+        api_keys::ApiKeys,
         arguments::Arguments,
-        database::Postgres,
+        database::{Postgres, orders::EthflowRefundCriteria},
         ipfs::Ipfs,
         ipfs_app_data::IpfsAppData,
+        ipfs_pinning::{self, Pinning},
         orderbook::Orderbook,
         quoter::QuoteHandler,
+        token_metadata::TokenMetadataHandler,
     },
     alloy::providers::Provider,
     anyhow::{Context, Result, anyhow},
@@ -16,6 +19,7 @@ use {
     contracts::alloy::{
         BalancerV2Vault,
         ChainalysisOracle,
+        ComposableCoW,
         GPv2Settlement,
         HooksTrampoline,
         IUniswapV3Factory,
@@ -42,6 +46,7 @@ use {
         baseline_solver::BaseTokens,
         code_fetching::CachedCodeFetcher,
         gas_price::InstrumentedGasEstimator,
+        hook_simulation,
         http_client::HttpClientFactory,
         order_quoting::{self, OrderQuoter},
         order_validation::{OrderValidPeriodConfiguration, OrderValidator},
@@ -57,6 +62,7 @@ use {
     },
     std::{convert::Infallible, future::Future, net::SocketAddr, sync::Arc, time::Duration},
     tokio::task::{self, JoinHandle},
+    tracing::Instrument,
     warp::Filter,
 };
 
@@ -193,7 +199,7 @@ pub async fn run(args: Arguments) {
             balances_contract.clone(),
             vault_relayer.into_legacy(),
             vault_address.map(IntoLegacy::into_legacy),
-            balance_overrider,
+            balance_overrider.clone(),
         ),
     );
 
@@ -409,37 +415,95 @@ pub async fn run(args: Arguments) {
     let chainalysis_oracle = ChainalysisOracle::Instance::deployed(&web3.alloy)
         .await
         .ok();
-    let order_validator = Arc::new(OrderValidator::new(
+    let banned_users = args
+        .banned_users_sources
+        .apply(
+            order_validation::banned::Users::new(
+                chainalysis_oracle,
+                args.banned_users,
+                args.banned_users_max_cache_size.get().to_u64().unwrap(),
+            ),
+            http_factory.create(),
+        )
+        .await
+        .expect("failed to load banned users");
+    let limits = Arc::new(order_validation::limits::Tiers::new(
+        order_validation::limits::Limits {
+            max_limit_orders: args.max_limit_orders_per_user,
+            orders_per_minute: args.max_orders_per_minute,
+            quotes_per_minute: args.max_quotes_per_minute,
+            cancellation_checks_per_minute: Some(args.max_cancellation_checks_per_minute),
+        },
+        args.order_limit_tiers
+            .as_ref()
+            .map(|file| file.tiers.clone())
+            .unwrap_or_default(),
+    ));
+    let hook_simulator = args.simulate_hooks.then(|| {
+        Arc::new(hook_simulation::Simulator::new(
+            &web3,
+            *hooks_contract.address(),
+            *settlement_contract.address(),
+            vault_relayer,
+            balance_overrider,
+        ))
+    });
+    let mut order_validator = OrderValidator::new(
         native_token.clone(),
-        Arc::new(order_validation::banned::Users::new(
-            chainalysis_oracle,
-            args.banned_users,
-            args.banned_users_max_cache_size.get().to_u64().unwrap(),
-        )),
+        Arc::new(banned_users),
         validity_configuration,
         args.eip1271_skip_creation_validation,
         bad_token_detector.clone(),
         hooks_contract,
+        vault_relayer.into_legacy(),
         optimal_quoter.clone(),
         balance_fetcher,
-        signature_validator,
+        signature_validator.clone(),
         Arc::new(postgres_write.clone()),
-        args.max_limit_orders_per_user,
+        limits.clone(),
         code_fetcher,
         app_data_validator.clone(),
         args.max_gas_per_order,
-    ));
-    let ipfs = args
+    );
+    if let Some(hook_simulator) = hook_simulator {
+        order_validator = order_validator.with_hook_simulator(hook_simulator);
+    }
+    let order_validator = Arc::new(order_validator);
+    let gateways = args
         .ipfs_gateway
-        .map(|url| {
-            Ipfs::new(
-                http_factory.builder(),
-                url,
-                args.ipfs_pinata_auth
+        .into_iter()
+        .enumerate()
+        .map(|(i, url)| {
+            // The Pinata token is specific to the primary gateway and shouldn't
+            // leak to any of the fallbacks.
+            let query = match i {
+                0 => args
+                    .ipfs_pinata_auth
+                    .as_ref()
                     .map(|auth| format!("pinataGatewayToken={auth}")),
-            )
+                _ => None,
+            };
+            Ipfs::new(http_factory.builder(), url, query)
         })
-        .map(IpfsAppData::new);
+        .collect::<Vec<_>>();
+    let mut pinning: Vec<Box<dyn Pinning>> = Vec::new();
+    if let Some(api) = args.ipfs_node_api {
+        pinning.push(Box::new(ipfs_pinning::Node::new(
+            http_factory.builder(),
+            api,
+        )));
+    }
+    if let Some(endpoint) = args.ipfs_pinning_service {
+        pinning.push(Box::new(ipfs_pinning::PinningService::new(
+            http_factory.builder(),
+            endpoint,
+            args.ipfs_pinning_service_token
+                .expect("required by the argument parser"),
+            args.ipfs_pinning_service_origins,
+        )));
+    }
+    let ipfs = (!gateways.is_empty() || !pinning.is_empty())
+        .then(|| Arc::new(IpfsAppData::new(gateways, pinning)));
     let app_data = Arc::new(crate::app_data::Registry::new(
         app_data_validator,
         postgres_write.clone(),
@@ -453,9 +517,43 @@ pub async fn run(args: Arguments) {
         order_validator.clone(),
         app_data.clone(),
         args.active_order_competition_threshold,
+        signature_validator,
+        EthflowRefundCriteria {
+            min_validity_duration: i64::try_from(
+                args.ethflow_refund_min_validity_duration.as_secs(),
+            )
+            .unwrap_or(i64::MAX),
+            min_price_deviation: args.ethflow_refund_min_price_deviation_bps as f64 / 10000f64,
+        },
+        limits.clone(),
     ));
 
     check_database_connection(orderbook.as_ref()).await;
+
+    tokio::task::spawn(
+        postgres_write
+            .clone()
+            .backfill_app_codes()
+            .instrument(tracing::info_span!("app_code_backfill")),
+    );
+
+    if args.composable_cow_watch_tower {
+        let address = args
+            .composable_cow_address
+            .or_else(|| ComposableCoW::deployment_address(&chain_id))
+            .expect("no ComposableCoW deployment on this network");
+        let deployment_block = args
+            .composable_cow_deployment_block
+            .expect("required by the argument parser");
+        crate::watch_tower::spawn(
+            web3.clone(),
+            address,
+            deployment_block,
+            postgres_write.clone(),
+            orderbook.clone(),
+            current_block_stream.clone(),
+        );
+    }
     let quotes = Arc::new(
         QuoteHandler::new(
             order_validator,
@@ -463,9 +561,23 @@ pub async fn run(args: Arguments) {
             app_data.clone(),
             args.volume_fee_config,
         )
-        .with_fast_quoter(fast_quoter),
+        .with_fast_quoter(fast_quoter)
+        .with_limits(limits),
     );
 
+    let token_metadata = Arc::new(TokenMetadataHandler::new(
+        postgres_read.clone(),
+        token_info_fetcher,
+        web3.clone(),
+        bad_token_detector,
+        current_block_stream,
+        chain,
+    ));
+
+    let api_keys = args
+        .api_key_authentication
+        .then(|| Arc::new(ApiKeys::new(postgres_read.clone(), args.require_api_key)));
+
     let (shutdown_sender, shutdown_receiver) = tokio::sync::oneshot::channel();
     let serve_api = serve_api(
         postgres_write,
@@ -473,12 +585,15 @@ pub async fn run(args: Arguments) {
         orderbook.clone(),
         quotes,
         app_data,
+        token_metadata,
         args.bind_address,
         async {
             let _ = shutdown_receiver.await;
         },
         native_price_estimator,
         args.price_estimation.quote_timeout,
+        api_keys,
+        args.trusted_proxy_hops,
     );
 
     let mut metrics_address = args.bind_address;
@@ -547,10 +662,13 @@ fn serve_api(
     orderbook: Arc<Orderbook>,
     quotes: Arc<QuoteHandler>,
     app_data: Arc<crate::app_data::Registry>,
+    token_metadata: Arc<TokenMetadataHandler>,
     address: SocketAddr,
     shutdown_receiver: impl Future<Output = ()> + Send + 'static,
     native_price_estimator: Arc<dyn NativePriceEstimating>,
     quote_timeout: Duration,
+    api_keys: Option<Arc<ApiKeys>>,
+    trusted_proxy_hops: usize,
 ) -> JoinHandle<()> {
     let filter = api::handle_all_routes(
         database,
@@ -558,8 +676,11 @@ fn serve_api(
         orderbook,
         quotes,
         app_data,
+        token_metadata,
         native_price_estimator,
         quote_timeout,
+        api_keys,
+        trusted_proxy_hops,
     )
     .boxed();
     tracing::info!(%address, "serving order book");
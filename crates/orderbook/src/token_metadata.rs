@@ -0,0 +1,131 @@
+use {
+    crate::{database::Postgres, dto::TokenMetadata},
+    alloy::primitives::Address,
+    anyhow::Result,
+    cached::{Cached, TimedSizedCache},
+    chain::Chain,
+    contracts::alloy::ERC20,
+    ethrpc::{
+        Web3,
+        alloy::conversions::{IntoAlloy, IntoLegacy},
+        block_stream::CurrentBlockWatcher,
+    },
+    model::order::BUY_ETH_ADDRESS,
+    shared::{
+        bad_token::{BadTokenDetecting, TokenQuality},
+        token_info::TokenInfoFetching,
+    },
+    std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    },
+};
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+const WEEK: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Combines the token metadata stored in the database with on-chain ERC20
+/// information and the result of the bad token detection.
+pub struct TokenMetadataHandler {
+    database: Postgres,
+    token_info: Arc<dyn TokenInfoFetching>,
+    web3: Web3,
+    bad_token_detector: Arc<dyn BadTokenDetecting>,
+    current_block: CurrentBlockWatcher,
+    chain: Chain,
+    /// Computing the metadata aggregates all trades of the token, so results
+    /// are cached for a short time to keep repeated requests cheap.
+    cache: Mutex<TimedSizedCache<Address, TokenMetadata>>,
+}
+
+impl TokenMetadataHandler {
+    pub fn new(
+        database: Postgres,
+        token_info: Arc<dyn TokenInfoFetching>,
+        web3: Web3,
+        bad_token_detector: Arc<dyn BadTokenDetecting>,
+        current_block: CurrentBlockWatcher,
+        chain: Chain,
+    ) -> Self {
+        Self {
+            database,
+            token_info,
+            web3,
+            bad_token_detector,
+            current_block,
+            chain,
+            cache: Mutex::new(TimedSizedCache::with_size_and_lifespan_and_refresh(
+                1000, 60, false,
+            )),
+        }
+    }
+
+    pub async fn get(&self, token: Address) -> Result<TokenMetadata> {
+        if let Some(metadata) = self.cache.lock().unwrap().cache_get(&token) {
+            return Ok(metadata.clone());
+        }
+        let metadata = self.fetch(token).await?;
+        self.cache
+            .lock()
+            .unwrap()
+            .cache_set(token, metadata.clone());
+        Ok(metadata)
+    }
+
+    async fn fetch(&self, token: Address) -> Result<TokenMetadata> {
+        let (metadata, info, name, quality) = futures::join!(
+            self.database.token_metadata(
+                &token,
+                self.first_block_within(DAY),
+                self.first_block_within(WEEK),
+            ),
+            self.token_info.get_token_info(token.into_legacy()),
+            self.fetch_name(token),
+            self.bad_token_detector.detect(token),
+        );
+
+        let mut metadata = metadata?;
+        // ERC20 information and token quality are best effort since they depend
+        // on the token contract and other services being available.
+        match info {
+            Ok(info) => {
+                metadata.symbol = info.symbol;
+                metadata.decimals = info.decimals;
+            }
+            Err(err) => tracing::debug!(?err, ?token, "failed to fetch token info"),
+        }
+        metadata.name = name;
+        match quality {
+            Ok(TokenQuality::Good) => (),
+            Ok(TokenQuality::Bad { reason }) => {
+                metadata.unsupported = true;
+                metadata.unsupported_reason = Some(reason);
+            }
+            Err(err) => tracing::warn!(?err, ?token, "failed to detect token quality"),
+        }
+        Ok(metadata)
+    }
+
+    /// Fetches the ERC20 name of the token. Only this endpoint needs the name
+    /// so it isn't part of the shared token info which gets fetched for every
+    /// token the services encounter.
+    async fn fetch_name(&self, token: Address) -> Option<String> {
+        if token == BUY_ETH_ADDRESS.into_alloy() {
+            return Some("NATIVE_ASSET".to_string());
+        }
+        ERC20::Instance::new(token, self.web3.alloy.clone())
+            .name()
+            .call()
+            .await
+            .inspect_err(|err| tracing::debug!(?err, ?token, "failed to fetch token name"))
+            .ok()
+    }
+
+    /// Returns the first block that was mined within the given period
+    /// according to the chain's block time.
+    fn first_block_within(&self, period: Duration) -> u64 {
+        let current_block = self.current_block.borrow().number;
+        let blocks = self.chain.blocks_in(period.as_millis() as u64) as u64;
+        current_block.saturating_sub(blocks)
+    }
+}
@@ -0,0 +1,415 @@
+//! Breakdown of the value settled trades generated into surplus, protocol
+//! fees, partner fees and network costs.
+//!
+//! Trades are grouped by time bucket and app code. Partner fees are attributed
+//! to the recipients specified in the order's app data by matching the kinds
+//! of the applied fee policies with the partner fee policies.
+
+use {
+    alloy::primitives::{Address, U256},
+    chrono::{DateTime, Datelike, Days, Utc},
+    number::serialization::HexOrDecimalU256,
+    serde::{Deserialize, Serialize},
+    serde_with::serde_as,
+    std::collections::BTreeMap,
+};
+
+/// Granularity of the time buckets trades are grouped in.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Interval {
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+impl Interval {
+    /// Returns the start of the bucket the timestamp falls into. Weeks start on
+    /// Monday and all buckets are aligned to UTC midnight.
+    fn bucket_start(&self, timestamp: DateTime<Utc>) -> DateTime<Utc> {
+        let date = timestamp.date_naive();
+        let date = match self {
+            Self::Day => date,
+            Self::Week => date - Days::new(date.weekday().num_days_from_monday().into()),
+            Self::Month => date.with_day(1).expect("every month has a first day"),
+        };
+        date.and_hms_opt(0, 0, 0)
+            .expect("midnight is a valid time")
+            .and_utc()
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Filter {
+    /// Only include trades of orders owned (or placed on-chain) by this
+    /// address.
+    pub owner: Option<Address>,
+    /// Only include trades of orders with this `appCode` in their app data.
+    pub app_code: Option<String>,
+    /// Only include trades of orders paying a partner fee to this address.
+    pub partner_recipient: Option<Address>,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub interval: Interval,
+}
+
+/// A settled trade as it is stored in the database.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Trade {
+    pub timestamp: DateTime<Utc>,
+    /// Surplus denominated in the native token.
+    pub surplus: U256,
+    pub network_cost: Option<TokenAmount>,
+    /// All protocol fees in the order of the applied fee policies. Partner
+    /// fees are applied after the policies of the protocol.
+    pub protocol_fees: Vec<ProtocolFee>,
+    pub app_code: Option<String>,
+    pub full_app_data: Option<Vec<u8>>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PolicyKind {
+    Surplus,
+    Volume,
+    PriceImprovement,
+}
+
+impl From<&app_data::FeePolicy> for PolicyKind {
+    fn from(policy: &app_data::FeePolicy) -> Self {
+        match policy {
+            app_data::FeePolicy::Surplus { .. } => Self::Surplus,
+            app_data::FeePolicy::Volume { .. } => Self::Volume,
+            app_data::FeePolicy::PriceImprovement { .. } => Self::PriceImprovement,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ProtocolFee {
+    /// Kind of the policy the fee was taken for. `None` if the applied
+    /// policies weren't recorded.
+    pub kind: Option<PolicyKind>,
+    pub amount: TokenAmount,
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenAmount {
+    pub token: Address,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub amount: U256,
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartnerFeeAmount {
+    pub recipient: Address,
+    pub token: Address,
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub amount: U256,
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bucket {
+    pub start: DateTime<Utc>,
+    pub app_code: Option<String>,
+    pub trades: u64,
+    /// Total surplus denominated in the native token.
+    #[serde_as(as = "HexOrDecimalU256")]
+    pub surplus: U256,
+    pub network_costs: Vec<TokenAmount>,
+    /// Fees collected by the protocol excluding partner fees.
+    pub protocol_fees: Vec<TokenAmount>,
+    pub partner_fees: Vec<PartnerFeeAmount>,
+}
+
+#[derive(Default)]
+struct Totals {
+    trades: u64,
+    surplus: U256,
+    network_costs: BTreeMap<Address, U256>,
+    protocol_fees: BTreeMap<Address, U256>,
+    partner_fees: BTreeMap<(Address, Address), U256>,
+}
+
+/// Groups the trades by time bucket and app code after applying the app data
+/// related parts of the filter. The database already narrows trades down by
+/// the same filter, this only guarantees the result is consistent with how the
+/// app data is interpreted here. Buckets are sorted by start time.
+pub fn aggregate(trades: impl IntoIterator<Item = Trade>, filter: &Filter) -> Vec<Bucket> {
+    let mut buckets = BTreeMap::<(DateTime<Utc>, Option<String>), Totals>::new();
+    for trade in trades {
+        let partner_fees = parse_partner_fees(trade.full_app_data.as_deref());
+        if filter
+            .app_code
+            .as_ref()
+            .is_some_and(|filter| trade.app_code.as_ref() != Some(filter))
+        {
+            continue;
+        }
+        if filter.partner_recipient.is_some_and(|filter| {
+            !partner_fees
+                .iter()
+                .any(|(recipient, _)| *recipient == filter)
+        }) {
+            continue;
+        }
+
+        let start = filter.interval.bucket_start(trade.timestamp);
+        let totals = buckets.entry((start, trade.app_code)).or_default();
+        totals.trades += 1;
+        totals.surplus = totals.surplus.saturating_add(trade.surplus);
+        if let Some(cost) = trade.network_cost {
+            add(&mut totals.network_costs, cost.token, cost.amount);
+        }
+
+        // Partner fee policies are applied after the protocol's policies. Fees
+        // are only attributed to partners as long as the kinds of the trailing
+        // policies match the partner fee policies so that protocol fees don't
+        // get misattributed if the partner fees weren't applied in the auction.
+        let mut partner_fees = partner_fees.iter().rev().peekable();
+        for fee in trade.protocol_fees.iter().rev() {
+            match partner_fees.next_if(|(_, kind)| fee.kind == Some(*kind)) {
+                Some((recipient, _)) => add(
+                    &mut totals.partner_fees,
+                    (*recipient, fee.amount.token),
+                    fee.amount.amount,
+                ),
+                None => {
+                    // Once a policy doesn't match all remaining ones belong to
+                    // the protocol.
+                    partner_fees.by_ref().for_each(drop);
+                    add(
+                        &mut totals.protocol_fees,
+                        fee.amount.token,
+                        fee.amount.amount,
+                    );
+                }
+            }
+        }
+    }
+
+    buckets
+        .into_iter()
+        .map(|((start, app_code), totals)| Bucket {
+            start,
+            app_code,
+            trades: totals.trades,
+            surplus: totals.surplus,
+            network_costs: token_amounts(totals.network_costs),
+            protocol_fees: token_amounts(totals.protocol_fees),
+            partner_fees: totals
+                .partner_fees
+                .into_iter()
+                .map(|((recipient, token), amount)| PartnerFeeAmount {
+                    recipient,
+                    token,
+                    amount,
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+fn add<K: Ord>(totals: &mut BTreeMap<K, U256>, key: K, amount: U256) {
+    let total = totals.entry(key).or_default();
+    *total = total.saturating_add(amount);
+}
+
+fn token_amounts(totals: BTreeMap<Address, U256>) -> Vec<TokenAmount> {
+    totals
+        .into_iter()
+        .map(|(token, amount)| TokenAmount { token, amount })
+        .collect()
+}
+
+/// Extracts the recipients and policy kinds of the partner fees (in the order
+/// the fee policies were applied) from the full app data.
+fn parse_partner_fees(full_app_data: Option<&[u8]>) -> Vec<(Address, PolicyKind)> {
+    full_app_data
+        .and_then(|full_app_data| app_data::parse(full_app_data).ok())
+        .map(|app_data| {
+            app_data
+                .partner_fee
+                .iter()
+                .map(|fee| (fee.recipient, PolicyKind::from(&fee.policy)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, serde_json::json};
+
+    fn timestamp(rfc3339: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(rfc3339).unwrap().into()
+    }
+
+    fn amount(token: u8, amount: u64) -> TokenAmount {
+        TokenAmount {
+            token: Address::repeat_byte(token),
+            amount: U256::from(amount),
+        }
+    }
+
+    fn fee(kind: PolicyKind, token: u8, value: u64) -> ProtocolFee {
+        ProtocolFee {
+            kind: Some(kind),
+            amount: amount(token, value),
+        }
+    }
+
+    fn filter(interval: Interval) -> Filter {
+        Filter {
+            owner: None,
+            app_code: None,
+            partner_recipient: None,
+            from: DateTime::<Utc>::MIN_UTC,
+            to: DateTime::<Utc>::MAX_UTC,
+            interval,
+        }
+    }
+
+    #[test]
+    fn bucket_start() {
+        // a Wednesday
+        let time = timestamp("2024-05-15T13:14:15Z");
+        assert_eq!(
+            Interval::Day.bucket_start(time),
+            timestamp("2024-05-15T00:00:00Z")
+        );
+        assert_eq!(
+            Interval::Week.bucket_start(time),
+            timestamp("2024-05-13T00:00:00Z")
+        );
+        assert_eq!(
+            Interval::Month.bucket_start(time),
+            timestamp("2024-05-01T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn aggregates_fees_by_bucket_and_app_code() {
+        let partner = Address::repeat_byte(0xaa);
+        let app_data = json!({
+            "appCode": "CoW Swap",
+            "metadata": {
+                "partnerFee": {
+                    "bps": 10,
+                    "recipient": partner,
+                },
+            },
+        })
+        .to_string()
+        .into_bytes();
+        let trades = vec![
+            Trade {
+                timestamp: timestamp("2024-05-15T01:00:00Z"),
+                surplus: U256::from(10),
+                network_cost: Some(amount(1, 5)),
+                protocol_fees: vec![
+                    fee(PolicyKind::Surplus, 2, 3),
+                    fee(PolicyKind::Volume, 2, 1),
+                ],
+                app_code: Some("CoW Swap".to_string()),
+                full_app_data: Some(app_data.clone()),
+            },
+            Trade {
+                timestamp: timestamp("2024-05-15T02:00:00Z"),
+                surplus: U256::from(20),
+                network_cost: Some(amount(1, 5)),
+                // only the partner fee was applied
+                protocol_fees: vec![fee(PolicyKind::Volume, 2, 2)],
+                app_code: Some("CoW Swap".to_string()),
+                full_app_data: Some(app_data.clone()),
+            },
+            Trade {
+                timestamp: timestamp("2024-05-15T02:30:00Z"),
+                // the partner fee wasn't applied so the trailing protocol
+                // policy must not be attributed to the partner
+                protocol_fees: vec![fee(PolicyKind::Surplus, 2, 4)],
+                app_code: Some("CoW Swap".to_string()),
+                full_app_data: Some(app_data),
+            },
+            Trade {
+                timestamp: timestamp("2024-05-15T03:00:00Z"),
+                surplus: U256::from(1),
+                network_cost: None,
+                protocol_fees: vec![fee(PolicyKind::PriceImprovement, 3, 7)],
+                app_code: None,
+                full_app_data: None,
+            },
+            Trade {
+                timestamp: timestamp("2024-05-16T01:00:00Z"),
+                surplus: U256::from(2),
+                ..Default::default()
+            },
+        ];
+
+        let buckets = aggregate(trades.clone(), &filter(Interval::Day));
+        assert_eq!(
+            buckets,
+            vec![
+                Bucket {
+                    start: timestamp("2024-05-15T00:00:00Z"),
+                    app_code: None,
+                    trades: 1,
+                    surplus: U256::from(1),
+                    network_costs: vec![],
+                    protocol_fees: vec![amount(3, 7)],
+                    partner_fees: vec![],
+                },
+                Bucket {
+                    start: timestamp("2024-05-15T00:00:00Z"),
+                    app_code: Some("CoW Swap".to_string()),
+                    trades: 3,
+                    surplus: U256::from(30),
+                    network_costs: vec![amount(1, 10)],
+                    protocol_fees: vec![amount(2, 7)],
+                    partner_fees: vec![PartnerFeeAmount {
+                        recipient: partner,
+                        token: Address::repeat_byte(2),
+                        amount: U256::from(3),
+                    }],
+                },
+                Bucket {
+                    start: timestamp("2024-05-16T00:00:00Z"),
+                    app_code: None,
+                    trades: 1,
+                    surplus: U256::from(2),
+                    ..Default::default()
+                },
+            ]
+        );
+
+        let buckets = aggregate(trades.clone(), &filter(Interval::Month));
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[0].trades, 2);
+        assert_eq!(buckets[1].trades, 3);
+
+        let buckets = aggregate(
+            trades.clone(),
+            &Filter {
+                app_code: Some("CoW Swap".to_string()),
+                ..filter(Interval::Week)
+            },
+        );
+        assert_eq!(buckets.len(), 1);
+        assert_eq!(buckets[0].trades, 3);
+
+        let buckets = aggregate(
+            trades,
+            &Filter {
+                partner_recipient: Some(Address::repeat_byte(0xbb)),
+                ..filter(Interval::Day)
+            },
+        );
+        assert!(buckets.is_empty());
+    }
+}
@@ -0,0 +1,174 @@
+use {
+    crate::database::Postgres,
+    alloy::{
+        primitives::{Address, keccak256},
+        providers::DynProvider,
+        rpc::types::{Filter, Log},
+        sol_types::{SolEvent, SolValue},
+    },
+    anyhow::{Context, Result},
+    contracts::alloy::ComposableCoW::ComposableCoW::{
+        ComposableCoWEvents,
+        ConditionalOrderCreated,
+    },
+    database::{byte_array::ByteArray, conditional_orders::ConditionalOrder},
+    ethrpc::{Web3, block_stream::RangeInclusive},
+    shared::event_handling::{AlloyEventRetrieving, EventStoring},
+};
+
+pub struct ComposableCoWContract {
+    pub web3: Web3,
+    pub address: Address,
+}
+
+impl AlloyEventRetrieving for ComposableCoWContract {
+    type Event = ComposableCoWEvents;
+
+    fn filter(&self) -> Filter {
+        Filter::new()
+            .address(self.address)
+            .event_signature(ConditionalOrderCreated::SIGNATURE_HASH)
+    }
+
+    fn provider(&self) -> &DynProvider {
+        &self.web3.alloy
+    }
+}
+
+/// Persists indexed conditional orders in the database.
+pub struct Storage {
+    database: Postgres,
+    address: Address,
+    /// The contract did not emit any events before this block.
+    start_of_index: u64,
+}
+
+impl Storage {
+    pub fn new(database: Postgres, address: Address, deployment_block: u64) -> Self {
+        Self {
+            database,
+            address,
+            // make sure to start 1 block **before** the deployment to get all the events
+            start_of_index: deployment_block.saturating_sub(1),
+        }
+    }
+
+    fn index_name(&self) -> String {
+        format!("{:#x}", self.address)
+    }
+}
+
+#[async_trait::async_trait]
+impl EventStoring<(ComposableCoWEvents, Log)> for Storage {
+    async fn replace_events(
+        &mut self,
+        events: Vec<(ComposableCoWEvents, Log)>,
+        range: RangeInclusive<u64>,
+    ) -> Result<()> {
+        let mut ex = self.database.pool.begin().await?;
+        let start_block = i64::try_from(*range.start()).context("start block is not i64")?;
+        database::conditional_orders::delete_from_block(&mut ex, start_block).await?;
+        database::conditional_orders::insert(&mut ex, &conditional_orders(events)).await?;
+        ex.commit().await?;
+        Ok(())
+    }
+
+    async fn append_events(&mut self, events: Vec<(ComposableCoWEvents, Log)>) -> Result<()> {
+        let mut ex = self.database.pool.acquire().await?;
+        database::conditional_orders::insert(&mut ex, &conditional_orders(events)).await?;
+        Ok(())
+    }
+
+    async fn last_event_block(&self) -> Result<u64> {
+        let mut ex = self.database.pool.acquire().await?;
+        database::last_indexed_blocks::fetch(&mut ex, &self.index_name())
+            .await?
+            .map(|block| block.try_into().context("last block is not u64"))
+            .unwrap_or(Ok(self.start_of_index))
+    }
+
+    async fn persist_last_indexed_block(&mut self, latest_block: u64) -> Result<()> {
+        let mut ex = self.database.pool.acquire().await?;
+        database::last_indexed_blocks::update(
+            &mut ex,
+            &self.index_name(),
+            i64::try_from(latest_block).context("latest block is not i64")?,
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+fn conditional_orders(events: Vec<(ComposableCoWEvents, Log)>) -> Vec<ConditionalOrder> {
+    events
+        .into_iter()
+        .filter_map(|(event, log)| {
+            let ComposableCoWEvents::ConditionalOrderCreated(event) = event;
+            let (Some(block_number), Some(log_index)) = (log.block_number, log.log_index) else {
+                tracing::warn!(?event, "event does not contain required meta data");
+                return None;
+            };
+            Some(ConditionalOrder {
+                owner: ByteArray(event.owner.0.0),
+                // Same as `ComposableCoW.hash(params)`.
+                params_hash: ByteArray(keccak256(event.params.abi_encode()).0),
+                handler: ByteArray(event.params.handler.0.0),
+                salt: ByteArray(event.params.salt.0),
+                static_input: event.params.staticInput.to_vec(),
+                block_number: block_number.try_into().ok()?,
+                log_index: log_index.try_into().ok()?,
+                active: true,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        alloy::primitives::{B256, Bytes},
+        contracts::alloy::ComposableCoW::IConditionalOrder::ConditionalOrderParams,
+    };
+
+    #[test]
+    fn converts_events() {
+        let event = ConditionalOrderCreated {
+            owner: Address::repeat_byte(1),
+            params: ConditionalOrderParams {
+                handler: Address::repeat_byte(2),
+                salt: B256::repeat_byte(3),
+                staticInput: Bytes::from(vec![4, 5]),
+            },
+        };
+        let log = Log {
+            block_number: Some(10),
+            log_index: Some(2),
+            ..Default::default()
+        };
+        let orders = conditional_orders(vec![
+            (
+                ComposableCoWEvents::ConditionalOrderCreated(event.clone()),
+                log,
+            ),
+            // missing meta data
+            (
+                ComposableCoWEvents::ConditionalOrderCreated(event.clone()),
+                Default::default(),
+            ),
+        ]);
+        assert_eq!(
+            orders,
+            vec![ConditionalOrder {
+                owner: ByteArray([1; 20]),
+                params_hash: ByteArray(keccak256(event.params.abi_encode()).0),
+                handler: ByteArray([2; 20]),
+                salt: ByteArray([3; 32]),
+                static_input: vec![4, 5],
+                block_number: 10,
+                log_index: 2,
+                active: true,
+            }]
+        );
+    }
+}
@@ -0,0 +1,85 @@
+//! Built-in watch-tower for conditional orders created through the
+//! [ComposableCoW](https://github.com/cowprotocol/composable-cow) framework.
+//!
+//! Smart contract wallets register conditional orders (e.g. TWAP or stop-loss
+//! orders) by emitting `ConditionalOrderCreated` events. Those get indexed into
+//! the database and on every new block the handlers of all active conditional
+//! orders are polled for a discrete order that is tradeable right now. These
+//! orders are then placed like any other EIP-1271 order.
+
+mod indexer;
+mod poller;
+
+use {
+    crate::{database::Postgres, orderbook::Orderbook},
+    alloy::primitives::Address,
+    contracts::alloy::ComposableCoW,
+    ethrpc::{Web3, block_stream::CurrentBlockWatcher},
+    shared::{
+        event_handling::{AlloyEventRetriever, EventHandler},
+        maintenance::{Maintaining, ServiceMaintenance},
+    },
+    std::sync::Arc,
+    tokio::sync::Mutex,
+};
+
+/// Starts indexing conditional orders of the ComposableCoW contract at
+/// `address` (starting at `deployment_block`) and posting their discrete
+/// orders to the orderbook.
+pub fn spawn(
+    web3: Web3,
+    address: Address,
+    deployment_block: u64,
+    database: Postgres,
+    orderbook: Arc<Orderbook>,
+    current_block: CurrentBlockWatcher,
+) {
+    let storage = indexer::Storage::new(database.clone(), address, deployment_block);
+    let event_handler = EventHandler::new(
+        Arc::new(web3.alloy.clone()),
+        AlloyEventRetriever(indexer::ComposableCoWContract {
+            web3: web3.clone(),
+            address,
+        }),
+        storage,
+        None,
+    );
+    let poller = poller::Poller::new(
+        ComposableCoW::Instance::new(address, web3.alloy.clone()),
+        database,
+        orderbook,
+        current_block.clone(),
+    );
+
+    // The poller runs after the indexer so that new conditional orders get
+    // polled in the block they were created. Known conditional orders still get
+    // polled if indexing fails.
+    let maintenance = ServiceMaintenance::new(vec![Arc::new(Sequential(vec![
+        Arc::new(Mutex::new(event_handler)),
+        Arc::new(poller),
+    ]))]);
+    tokio::task::spawn(maintenance.run_maintenance_on_new_block(current_block));
+}
+
+/// Runs the maintenance of all tasks one after the other. A failing task
+/// doesn't prevent the following ones from running.
+struct Sequential(Vec<Arc<dyn Maintaining>>);
+
+#[async_trait::async_trait]
+impl Maintaining for Sequential {
+    async fn run_maintenance(&self) -> anyhow::Result<()> {
+        let mut failed = Vec::new();
+        for task in &self.0 {
+            if let Err(err) = task.run_maintenance().await {
+                tracing::warn!(task = task.name(), ?err, "watch-tower task failed");
+                failed.push(task.name().to_owned());
+            }
+        }
+        anyhow::ensure!(failed.is_empty(), "failed tasks: {failed:?}");
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "WatchTower"
+    }
+}
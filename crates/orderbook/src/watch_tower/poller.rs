@@ -0,0 +1,448 @@
+use {
+    crate::{
+        database::Postgres,
+        orderbook::{AddOrderError, Orderbook},
+    },
+    alloy::primitives::{Address, B256, Bytes},
+    anyhow::{Context, Result},
+    app_data::AppDataHash,
+    contracts::alloy::ComposableCoW::{
+        self,
+        ComposableCoW::ComposableCoWErrors,
+        GPv2Order,
+        IConditionalOrder::ConditionalOrderParams,
+    },
+    database::conditional_orders::ConditionalOrder,
+    ethrpc::{
+        alloy::conversions::IntoLegacy,
+        block_stream::{BlockInfo, CurrentBlockWatcher},
+    },
+    futures::StreamExt,
+    model::{
+        order::{
+            BuyTokenDestination,
+            OrderCreation,
+            OrderCreationAppData,
+            OrderData,
+            OrderKind,
+            SellTokenSource,
+        },
+        signature::Signature,
+    },
+    shared::maintenance::Maintaining,
+    std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    },
+};
+
+/// How many conditional orders get polled at the same time.
+const MAX_CONCURRENT_POLLS: usize = 10;
+
+/// Upper bound of blocks to wait before placing the discrete order of a
+/// conditional order again after the orderbook rejected it.
+const MAX_PLACEMENT_BACKOFF_BLOCKS: u64 = 1024;
+
+/// Identifies a conditional order.
+type Key = (Address, B256);
+
+/// Polls the handlers of all active conditional orders and places the
+/// discrete orders they return.
+pub struct Poller {
+    contract: ComposableCoW::Instance,
+    database: Postgres,
+    orderbook: Arc<Orderbook>,
+    current_block: CurrentBlockWatcher,
+    schedules: Mutex<HashMap<Key, Schedule>>,
+}
+
+/// Polling state of a single conditional order.
+#[derive(Default)]
+struct Schedule {
+    /// When the conditional order should be polled next. `None` means it
+    /// should be polled on every block.
+    next: Option<Due>,
+    /// The last discrete order that got placed for the conditional order so it
+    /// doesn't get placed over and over again.
+    last_order: Option<OrderData>,
+    /// How often in a row placing a discrete order of the conditional order
+    /// failed. Used to back off exponentially.
+    failed_placements: u32,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Due {
+    Block(u64),
+    Epoch(u64),
+}
+
+impl Due {
+    fn is_reached(&self, block: &BlockInfo) -> bool {
+        match *self {
+            Due::Block(number) => block.number >= number,
+            Due::Epoch(timestamp) => block.timestamp >= timestamp,
+        }
+    }
+}
+
+/// The result of polling a conditional order.
+#[derive(Debug, Eq, PartialEq)]
+enum Poll {
+    /// The conditional order currently produces this discrete order.
+    Tradeable(OrderData, Vec<u8>),
+    /// The conditional order should be polled again at the given time.
+    TryAt(Option<Due>),
+    /// The conditional order will never produce a tradeable order again.
+    Never,
+}
+
+impl Poller {
+    pub fn new(
+        contract: ComposableCoW::Instance,
+        database: Postgres,
+        orderbook: Arc<Orderbook>,
+        current_block: CurrentBlockWatcher,
+    ) -> Self {
+        Self {
+            contract,
+            database,
+            orderbook,
+            current_block,
+            schedules: Default::default(),
+        }
+    }
+
+    async fn poll_all(&self) -> Result<()> {
+        let block = *self.current_block.borrow();
+        let conditional_orders = {
+            let mut ex = self.database.pool.acquire().await?;
+            database::conditional_orders::active(&mut ex).await?
+        };
+
+        let due: Vec<_> = {
+            let mut schedules = self.schedules.lock().unwrap();
+            // Forget about conditional orders that are no longer active.
+            schedules.retain(|key, _| conditional_orders.iter().any(|order| key_of(order) == *key));
+            conditional_orders
+                .into_iter()
+                .filter(|order| {
+                    schedules
+                        .get(&key_of(order))
+                        .and_then(|schedule| schedule.next)
+                        .is_none_or(|due| due.is_reached(&block))
+                })
+                .collect()
+        };
+
+        futures::stream::iter(due)
+            .for_each_concurrent(MAX_CONCURRENT_POLLS, |order| async move {
+                if let Err(err) = self.poll(&order, block.number).await {
+                    tracing::warn!(
+                        owner = ?order.owner,
+                        params_hash = ?order.params_hash,
+                        ?err,
+                        "failed to poll conditional order"
+                    );
+                }
+            })
+            .await;
+        Ok(())
+    }
+
+    async fn poll(&self, conditional_order: &ConditionalOrder, block: u64) -> Result<()> {
+        let key = key_of(conditional_order);
+        let params = ConditionalOrderParams {
+            handler: Address::new(conditional_order.handler.0),
+            salt: B256::new(conditional_order.salt.0),
+            staticInput: Bytes::from(conditional_order.static_input.clone()),
+        };
+        let result = self
+            .contract
+            .getTradeableOrderWithSignature(key.0, params, Bytes::new(), vec![])
+            .block(block.into())
+            .call()
+            .await;
+        let poll = match result {
+            Ok(tradeable) => {
+                Poll::Tradeable(order_data(&tradeable.order)?, tradeable.signature.to_vec())
+            }
+            Err(err) => match err.as_decoded_interface_error::<ComposableCoWErrors>() {
+                Some(err) => poll_from_error(err, block),
+                // Most likely a node issue so simply retry on the next block.
+                None => return Err(err).context("getTradeableOrderWithSignature"),
+            },
+        };
+
+        match poll {
+            Poll::Tradeable(order, signature) => {
+                let already_placed = self
+                    .schedules
+                    .lock()
+                    .unwrap()
+                    .get(&key)
+                    .is_some_and(|schedule| schedule.last_order.as_ref() == Some(&order));
+                if !already_placed && let Err(err) = self.place(key.0, &order, signature).await {
+                    let mut schedules = self.schedules.lock().unwrap();
+                    let schedule = schedules.entry(key).or_default();
+                    schedule.failed_placements = schedule.failed_placements.saturating_add(1);
+                    schedule.next = Some(Due::Block(
+                        block + placement_backoff(schedule.failed_placements),
+                    ));
+                    return Err(err);
+                }
+                self.schedules.lock().unwrap().insert(
+                    key,
+                    Schedule {
+                        next: None,
+                        last_order: Some(order),
+                        failed_placements: 0,
+                    },
+                );
+            }
+            Poll::TryAt(next) => {
+                self.schedules.lock().unwrap().entry(key).or_default().next = next;
+            }
+            Poll::Never => {
+                tracing::debug!(
+                    owner = ?key.0,
+                    params_hash = ?key.1,
+                    "deactivating conditional order"
+                );
+                let mut ex = self.database.pool.acquire().await?;
+                database::conditional_orders::deactivate(
+                    &mut ex,
+                    &conditional_order.owner,
+                    &conditional_order.params_hash,
+                )
+                .await?;
+                self.schedules.lock().unwrap().remove(&key);
+            }
+        }
+        Ok(())
+    }
+
+    async fn place(&self, owner: Address, order: &OrderData, signature: Vec<u8>) -> Result<()> {
+        let creation = OrderCreation {
+            sell_token: order.sell_token.into_legacy(),
+            buy_token: order.buy_token.into_legacy(),
+            receiver: order.receiver.map(IntoLegacy::into_legacy),
+            sell_amount: order.sell_amount.into_legacy(),
+            buy_amount: order.buy_amount.into_legacy(),
+            valid_to: order.valid_to,
+            fee_amount: order.fee_amount.into_legacy(),
+            kind: order.kind,
+            partially_fillable: order.partially_fillable,
+            sell_token_balance: order.sell_token_balance,
+            buy_token_balance: order.buy_token_balance,
+            from: Some(owner.into_legacy()),
+            signature: Signature::Eip1271(signature),
+            quote_id: None,
+            app_data: OrderCreationAppData::Hash {
+                hash: order.app_data,
+            },
+        };
+        match self.orderbook.add_order(creation, None).await {
+            Ok((uid, _)) => {
+                tracing::debug!(%uid, ?owner, "placed order of conditional order");
+                Ok(())
+            }
+            Err(AddOrderError::DuplicatedOrder) => Ok(()),
+            Err(err) => Err(err).context("add_order"),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Maintaining for Poller {
+    async fn run_maintenance(&self) -> Result<()> {
+        self.poll_all().await
+    }
+
+    fn name(&self) -> &str {
+        "ConditionalOrderPoller"
+    }
+}
+
+fn key_of(order: &ConditionalOrder) -> Key {
+    (Address::new(order.owner.0), B256::new(order.params_hash.0))
+}
+
+/// Number of blocks to wait before placing a discrete order again after it
+/// failed the given number of times in a row.
+fn placement_backoff(failed_placements: u32) -> u64 {
+    1u64.checked_shl(failed_placements)
+        .unwrap_or(u64::MAX)
+        .min(MAX_PLACEMENT_BACKOFF_BLOCKS)
+}
+
+/// Translates the custom errors handlers use to signal when they should be
+/// polled next and the errors ComposableCoW reverts with when the conditional
+/// order can't be used anymore.
+fn poll_from_error(err: ComposableCoWErrors, block: u64) -> Poll {
+    match err {
+        ComposableCoWErrors::OrderNotValid(_)
+        | ComposableCoWErrors::PollTryNextBlock(_)
+        // The owner can change these so the order may become valid again.
+        | ComposableCoWErrors::SwapGuardRestricted(_)
+        | ComposableCoWErrors::InvalidFallbackHandler(_) => {
+            Poll::TryAt(Some(Due::Block(block + 1)))
+        }
+        ComposableCoWErrors::PollTryAtBlock(err) => {
+            Poll::TryAt(Some(Due::Block(err.blockNumber.saturating_to())))
+        }
+        ComposableCoWErrors::PollTryAtEpoch(err) => {
+            Poll::TryAt(Some(Due::Epoch(err.timestamp.saturating_to())))
+        }
+        ComposableCoWErrors::PollNever(_)
+        // The owner removed the conditional order (or its merkle root).
+        | ComposableCoWErrors::SingleOrderNotAuthed(_)
+        | ComposableCoWErrors::ProofNotAuthed(_)
+        // The handler can never produce an order.
+        | ComposableCoWErrors::InterfaceNotSupported(_)
+        | ComposableCoWErrors::InvalidHandler(_) => Poll::Never,
+    }
+}
+
+fn order_data(order: &GPv2Order::Data) -> Result<OrderData> {
+    Ok(OrderData {
+        sell_token: order.sellToken,
+        buy_token: order.buyToken,
+        receiver: Some(order.receiver).filter(|receiver| !receiver.is_zero()),
+        sell_amount: order.sellAmount,
+        buy_amount: order.buyAmount,
+        valid_to: order.validTo,
+        app_data: AppDataHash(order.appData.0),
+        fee_amount: order.feeAmount,
+        kind: OrderKind::from_contract_bytes(order.kind.0)?,
+        partially_fillable: order.partiallyFillable,
+        sell_token_balance: SellTokenSource::from_contract_bytes(order.sellTokenBalance.0)?,
+        buy_token_balance: BuyTokenDestination::from_contract_bytes(order.buyTokenBalance.0)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        alloy::primitives::{U256, keccak256},
+        contracts::alloy::ComposableCoW::ComposableCoW::{
+            PollNever,
+            PollTryAtBlock,
+            PollTryAtEpoch,
+            SingleOrderNotAuthed,
+        },
+    };
+
+    #[test]
+    fn converts_contract_order() {
+        let order = GPv2Order::Data {
+            sellToken: Address::repeat_byte(1),
+            buyToken: Address::repeat_byte(2),
+            receiver: Address::ZERO,
+            sellAmount: U256::from(3),
+            buyAmount: U256::from(4),
+            validTo: 5,
+            appData: B256::repeat_byte(6),
+            feeAmount: U256::ZERO,
+            kind: B256::new(OrderKind::BUY),
+            partiallyFillable: true,
+            sellTokenBalance: B256::new(SellTokenSource::EXTERNAL),
+            buyTokenBalance: B256::new(BuyTokenDestination::INTERNAL),
+        };
+        assert_eq!(
+            order_data(&order).unwrap(),
+            OrderData {
+                sell_token: Address::repeat_byte(1),
+                buy_token: Address::repeat_byte(2),
+                receiver: None,
+                sell_amount: U256::from(3),
+                buy_amount: U256::from(4),
+                valid_to: 5,
+                app_data: AppDataHash([6; 32]),
+                fee_amount: U256::ZERO,
+                kind: OrderKind::Buy,
+                partially_fillable: true,
+                sell_token_balance: SellTokenSource::External,
+                buy_token_balance: BuyTokenDestination::Internal,
+            }
+        );
+
+        let order = GPv2Order::Data {
+            receiver: Address::repeat_byte(7),
+            ..order
+        };
+        assert_eq!(
+            order_data(&order).unwrap().receiver,
+            Some(Address::repeat_byte(7))
+        );
+
+        let order = GPv2Order::Data {
+            kind: keccak256("invalid"),
+            ..order
+        };
+        assert!(order_data(&order).is_err());
+    }
+
+    #[test]
+    fn schedules_from_errors() {
+        assert_eq!(
+            poll_from_error(
+                ComposableCoWErrors::PollTryNextBlock(Default::default()),
+                10
+            ),
+            Poll::TryAt(Some(Due::Block(11)))
+        );
+        assert_eq!(
+            poll_from_error(
+                ComposableCoWErrors::PollTryAtBlock(PollTryAtBlock {
+                    blockNumber: U256::from(20),
+                    message: Default::default(),
+                }),
+                10
+            ),
+            Poll::TryAt(Some(Due::Block(20)))
+        );
+        assert_eq!(
+            poll_from_error(
+                ComposableCoWErrors::PollTryAtEpoch(PollTryAtEpoch {
+                    timestamp: U256::from(1_700_000_000),
+                    message: Default::default(),
+                }),
+                10
+            ),
+            Poll::TryAt(Some(Due::Epoch(1_700_000_000)))
+        );
+        assert_eq!(
+            poll_from_error(ComposableCoWErrors::PollNever(PollNever::default()), 10),
+            Poll::Never
+        );
+        assert_eq!(
+            poll_from_error(
+                ComposableCoWErrors::SingleOrderNotAuthed(SingleOrderNotAuthed {}),
+                10
+            ),
+            Poll::Never
+        );
+    }
+
+    #[test]
+    fn backs_off_failed_placements() {
+        assert_eq!(placement_backoff(1), 2);
+        assert_eq!(placement_backoff(2), 4);
+        assert_eq!(placement_backoff(10), MAX_PLACEMENT_BACKOFF_BLOCKS);
+        assert_eq!(placement_backoff(64), MAX_PLACEMENT_BACKOFF_BLOCKS);
+    }
+
+    #[test]
+    fn due() {
+        let block = BlockInfo {
+            number: 10,
+            timestamp: 100,
+            ..Default::default()
+        };
+        assert!(Due::Block(10).is_reached(&block));
+        assert!(!Due::Block(11).is_reached(&block));
+        assert!(Due::Epoch(100).is_reached(&block));
+        assert!(!Due::Epoch(101).is_reached(&block));
+    }
+}
@@ -0,0 +1,136 @@
+//! Small HTTP API exposing the refund queue so users can find out when their
+//! expired ethflow orders get refunded.
+
+use {
+    crate::planner::{Queue, QueuedRefund},
+    serde::Serialize,
+    std::{
+        collections::BTreeMap,
+        convert::Infallible,
+        net::SocketAddr,
+        sync::{Arc, RwLock},
+    },
+    tokio::task::JoinHandle,
+    warp::{Filter, Rejection, Reply, http::StatusCode},
+};
+
+/// The refund queue of every chain by chain ID.
+pub type Queues = Arc<BTreeMap<u64, Arc<RwLock<Queue>>>>;
+
+pub fn serve(queues: BTreeMap<u64, Arc<RwLock<Queue>>>, address: SocketAddr) -> JoinHandle<()> {
+    tracing::info!(%address, "serving refund queue");
+    tokio::task::spawn(warp::serve(routes(Arc::new(queues))).bind(address))
+}
+
+fn routes(queues: Queues) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    get_queues(queues.clone()).or(get_refund(queues))
+}
+
+/// `GET /api/v1/refunds` returns all pending refunds by chain ID.
+fn get_queues(queues: Queues) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    warp::path!("api" / "v1" / "refunds")
+        .and(warp::get())
+        .map(move || {
+            let queues: BTreeMap<_, _> = queues
+                .iter()
+                .map(|(chain_id, queue)| (*chain_id, queue.read().unwrap().clone()))
+                .collect();
+            warp::reply::json(&queues)
+        })
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChainRefund<'a> {
+    chain_id: u64,
+    #[serde(flatten)]
+    refund: &'a QueuedRefund,
+}
+
+/// `GET /api/v1/refunds/{uid}` returns the pending refund of an order.
+fn get_refund(queues: Queues) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
+    warp::path!("api" / "v1" / "refunds" / String)
+        .and(warp::get())
+        .and_then(move |uid: String| {
+            let queues = queues.clone();
+            async move {
+                let queues: Vec<_> = queues
+                    .iter()
+                    .map(|(chain_id, queue)| (*chain_id, queue.read().unwrap()))
+                    .collect();
+                let refund = queues.iter().find_map(|(chain_id, queue)| {
+                    queue
+                        .refunds
+                        .iter()
+                        .find(|refund| refund.uid.eq_ignore_ascii_case(&uid))
+                        .map(|refund| ChainRefund {
+                            chain_id: *chain_id,
+                            refund,
+                        })
+                });
+                Result::<_, Infallible>::Ok(match refund {
+                    Some(refund) => {
+                        warp::reply::with_status(warp::reply::json(refund), StatusCode::OK)
+                    }
+                    None => warp::reply::with_status(
+                        warp::reply::json(&"order is not pending a refund"),
+                        StatusCode::NOT_FOUND,
+                    ),
+                })
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::planner::QueuedRefundStatus, alloy::primitives::Address};
+
+    fn queues() -> Queues {
+        let queue = Arc::new(RwLock::new(Queue {
+            updated_at: 100,
+            gas_price: 1e9,
+            refunds: vec![QueuedRefund {
+                uid: format!("0x{}", "ab".repeat(56)),
+                ethflow_contract: Address::repeat_byte(1),
+                refund_amount: "1000".into(),
+                valid_to: 50,
+                deadline: 150,
+                status: QueuedRefundStatus::WaitingForCheaperGas,
+                eta: 180,
+            }],
+        }));
+        Arc::new(BTreeMap::from([(1, queue), (100, Default::default())]))
+    }
+
+    #[tokio::test]
+    async fn serves_queue() {
+        let response = warp::test::request()
+            .path("/api/v1/refunds")
+            .reply(&routes(queues()))
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["1"]["updatedAt"], 100);
+        assert_eq!(body["1"]["refunds"][0]["status"], "waitingForCheaperGas");
+        assert_eq!(body["100"]["refunds"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn serves_single_refund() {
+        let response = warp::test::request()
+            .path(&format!("/api/v1/refunds/0x{}", "AB".repeat(56)))
+            .reply(&routes(queues()))
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(body["chainId"], 1);
+        assert_eq!(body["eta"], 180);
+        assert_eq!(body["refundAmount"], "1000");
+
+        let response = warp::test::request()
+            .path(&format!("/api/v1/refunds/0x{}", "cd".repeat(56)))
+            .reply(&routes(queues()))
+            .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -1,8 +1,19 @@
 use {
-    alloy::primitives::Address,
+    alloy::primitives::{Address, B256},
+    anyhow::{Context, anyhow, ensure},
     clap::Parser,
-    shared::{arguments::display_option, ethrpc, http_client, logging_args_with_default_filter},
-    std::time::Duration,
+    shared::{
+        arguments::{display_option, display_secret_option},
+        ethrpc,
+        http_client,
+        logging_args_with_default_filter,
+    },
+    std::{
+        fmt::{self, Display, Formatter},
+        path::PathBuf,
+        str::FromStr,
+        time::Duration,
+    },
     url::Url,
 };
 
@@ -60,8 +71,50 @@ pub struct Arguments {
     #[clap(long, env, use_value_delimiter = true)]
     pub ethflow_contracts: Vec<Address>,
 
+    /// Whether to also refund orders of the ethflow contract deployed on the
+    /// connected chains according to the known deployments and of every
+    /// ethflow contract (including older versions) that placed orders found
+    /// in the database of the chain.
+    #[clap(long, env, action = clap::ArgAction::Set, default_value = "false")]
+    pub ethflow_auto_discovery: bool,
+
+    /// Further chains to refund orders on in the same process, separated by
+    /// commas. Every chain is specified as
+    /// `<node url>|<database url>[|<ethflow contracts separated by ';'>]`.
+    /// All chains share the refunder accounts and refund settings.
+    #[clap(long, env, use_value_delimiter = true, hide_env_values = true)]
+    pub additional_chains: Vec<Chain>,
+
+    /// Comma separated accounts used to sign the refund transactions. Every
+    /// account can be one of:
+    ///   - an AWS KMS key ARN (`arn:aws:kms:...`)
+    ///   - an encrypted keystore file (`keystore:<path>`), decrypted with the
+    ///     password in `--refunder-keystore-password-file`
+    ///   - an address whose transactions get signed by the connected node or a
+    ///     remote signer behind it
+    ///   - a raw private key (only accepted with `--insecure-allow-raw-key`)
+    ///
+    /// Refunds are sent from the first account. Whenever a refund transaction
+    /// of an account gets stuck the next account takes over.
+    #[clap(long, env, use_value_delimiter = true, hide_env_values = true)]
+    pub refunder_accounts: Vec<RefunderAccount>,
+
+    /// Deprecated: private key of the refunder account. Use
+    /// `--refunder-accounts` instead. Only accepted with
+    /// `--insecure-allow-raw-key`.
     #[clap(long, env, hide_env_values = true)]
-    pub refunder_pk: String,
+    pub refunder_pk: Option<B256>,
+
+    /// Accept raw private keys as refunder accounts. Raw keys end up in plain
+    /// text in the environment or command line of the refunder so they are
+    /// rejected by default.
+    #[clap(long, env, action = clap::ArgAction::Set, default_value = "false")]
+    pub insecure_allow_raw_key: bool,
+
+    /// Path to a file (e.g. a mounted secret) that contains the password to
+    /// decrypt the keystore files of `--refunder-accounts`.
+    #[clap(long, env)]
+    pub refunder_keystore_password_file: Option<PathBuf>,
 
     /// The port at which we serve our metrics
     #[clap(long, env, default_value = "9590")]
@@ -76,6 +129,30 @@ pub struct Arguments {
     /// Default is 30 Gwei (30_000_000_000 wei)
     #[clap(long, env, default_value = "30000000000")]
     pub start_priority_fee_tip: u64,
+
+    /// Time after an order expired within which it gets refunded, even if the
+    /// gas price is above `--refund-gas-price-threshold`.
+    #[clap(long, env, default_value = "30m", value_parser = humantime::parse_duration)]
+    pub refund_sla: Duration,
+
+    /// Gas price (in wei) above which refunds are postponed until their SLA
+    /// deadline. Refunds are sent right away if unset.
+    #[clap(long, env)]
+    pub refund_gas_price_threshold: Option<u64>,
+
+    /// Gas a single refund transaction should stay below. Refunds that don't
+    /// fit get split into several transactions.
+    #[clap(long, env, default_value = "1000000")]
+    pub refund_batch_target_gas: u64,
+
+    /// Gas it takes to refund a single order. Used to split the refunds into
+    /// transactions that stay below `--refund-batch-target-gas`.
+    #[clap(long, env, default_value = "35000")]
+    pub refund_gas_per_order: u64,
+
+    /// The port at which the refund queue status API is served.
+    #[clap(long, env, default_value = "9591")]
+    pub api_port: u16,
 }
 
 impl std::fmt::Display for Arguments {
@@ -88,12 +165,22 @@ impl std::fmt::Display for Arguments {
             node_url,
             chain_id,
             ethflow_contracts,
+            ethflow_auto_discovery,
+            additional_chains,
             metrics_port,
             logging,
             db_url,
+            refunder_accounts,
             refunder_pk,
+            insecure_allow_raw_key,
+            refunder_keystore_password_file,
             max_gas_price,
             start_priority_fee_tip,
+            refund_sla,
+            refund_gas_price_threshold,
+            refund_batch_target_gas,
+            refund_gas_per_order,
+            api_port,
         } = self;
 
         write!(f, "{http_client}")?;
@@ -106,11 +193,298 @@ impl std::fmt::Display for Arguments {
         writeln!(f, "node_url: {node_url}")?;
         display_option(f, "chain_id", chain_id)?;
         writeln!(f, "ethflow_contracts: {ethflow_contracts:?}")?;
-        let _intentionally_ignored = refunder_pk;
-        writeln!(f, "refunder_pk: SECRET")?;
+        writeln!(f, "ethflow_auto_discovery: {ethflow_auto_discovery}")?;
+        writeln!(
+            f,
+            "additional_chains: [{}]",
+            additional_chains
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )?;
+        writeln!(
+            f,
+            "refunder_accounts: [{}]",
+            refunder_accounts
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )?;
+        display_secret_option(f, "refunder_pk", refunder_pk.as_ref())?;
+        writeln!(f, "insecure_allow_raw_key: {insecure_allow_raw_key}")?;
+        display_option(
+            f,
+            "refunder_keystore_password_file",
+            &refunder_keystore_password_file
+                .as_ref()
+                .map(|path| path.display()),
+        )?;
         writeln!(f, "metrics_port: {metrics_port}")?;
         writeln!(f, "max_gas_price: {max_gas_price}")?;
         writeln!(f, "start_priority_fee_tip: {start_priority_fee_tip}")?;
+        writeln!(f, "refund_sla: {refund_sla:?}")?;
+        display_option(f, "refund_gas_price_threshold", refund_gas_price_threshold)?;
+        writeln!(f, "refund_batch_target_gas: {refund_batch_target_gas}")?;
+        writeln!(f, "refund_gas_per_order: {refund_gas_per_order}")?;
+        writeln!(f, "api_port: {api_port}")?;
         Ok(())
     }
 }
+
+impl Arguments {
+    /// The chain configured with `--node-url` followed by the additional ones.
+    pub fn chains(&self) -> Vec<Chain> {
+        std::iter::once(Chain {
+            node_url: self.node_url.clone(),
+            db_url: self.db_url.clone(),
+            ethflow_contracts: self.ethflow_contracts.clone(),
+        })
+        .chain(self.additional_chains.iter().cloned())
+        .collect()
+    }
+
+    /// All configured refunder accounts, including the deprecated
+    /// `--refunder-pk`. Fails if raw private keys are configured without
+    /// `--insecure-allow-raw-key`.
+    pub fn refunder_accounts(&self) -> anyhow::Result<Vec<RefunderAccount>> {
+        let mut accounts = self.refunder_accounts.clone();
+        if let Some(pk) = self.refunder_pk {
+            tracing::warn!("--refunder-pk is deprecated, use --refunder-accounts instead");
+            accounts.push(RefunderAccount::PrivateKey(pk));
+        }
+        ensure!(
+            self.insecure_allow_raw_key
+                || !accounts
+                    .iter()
+                    .any(|account| matches!(account, RefunderAccount::PrivateKey(_))),
+            "raw private key refunder accounts require --insecure-allow-raw-key, use a KMS key, a \
+             keystore file or a node managed account instead"
+        );
+        Ok(accounts)
+    }
+
+    /// Reads the password of the keystore accounts from
+    /// `--refunder-keystore-password-file`.
+    pub fn refunder_keystore_password(&self) -> anyhow::Result<Option<String>> {
+        let Some(path) = &self.refunder_keystore_password_file else {
+            return Ok(None);
+        };
+        let password = std::fs::read_to_string(path)
+            .with_context(|| format!("unable to read keystore password {}", path.display()))?;
+        // Files usually end with a newline which isn't part of the password.
+        Ok(Some(password.trim_end_matches(['\r', '\n']).to_string()))
+    }
+}
+
+/// A chain to refund ethflow orders on.
+#[derive(Clone, Eq, PartialEq)]
+pub struct Chain {
+    pub node_url: Url,
+    pub db_url: Url,
+    /// Ethflow contracts to refund orders of in addition to the discovered
+    /// ones.
+    pub ethflow_contracts: Vec<Address>,
+}
+
+impl FromStr for Chain {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let parts: Vec<&str> = s.split('|').collect();
+        ensure!(
+            (2..=3).contains(&parts.len()),
+            "chain must be specified as <node url>|<database url>[|<ethflow contracts>]"
+        );
+        Ok(Self {
+            node_url: parts[0].parse().context("invalid node url")?,
+            // Don't echo the database url since it might contain credentials.
+            db_url: parts[1]
+                .parse()
+                .map_err(|_| anyhow!("invalid database url"))?,
+            ethflow_contracts: parts
+                .get(2)
+                .into_iter()
+                .flat_map(|contracts| contracts.split(';'))
+                .filter(|contract| !contract.is_empty())
+                .map(|contract| contract.parse().context("invalid ethflow contract"))
+                .collect::<anyhow::Result<_>>()?,
+        })
+    }
+}
+
+impl Display for Chain {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Chain(node_url: {}, db_url: SECRET, ethflow_contracts: {:?})",
+            self.node_url, self.ethflow_contracts
+        )
+    }
+}
+
+// Implemented by hand so database credentials never end up in logs.
+impl fmt::Debug for Chain {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+/// An account the refunder signs its transactions with.
+#[derive(Clone, Eq, PartialEq)]
+pub enum RefunderAccount {
+    /// A raw private key. Deprecated since the key ends up in plain text in the
+    /// environment of the refunder.
+    PrivateKey(B256),
+    /// A key held by AWS KMS, identified by its ARN.
+    Kms(String),
+    /// An encrypted JSON keystore file.
+    Keystore(PathBuf),
+    /// An account managed by the connected node, which signs the transactions
+    /// itself or forwards them to a remote signer.
+    Address(Address),
+}
+
+impl FromStr for RefunderAccount {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        if s.starts_with("arn:aws:kms:") {
+            return Ok(Self::Kms(s.to_string()));
+        }
+        if let Some(path) = s.strip_prefix("keystore:") {
+            return Ok(Self::Keystore(path.into()));
+        }
+        let bytes = const_hex::decode(s).context("invalid refunder account")?;
+        match bytes.len() {
+            20 => Ok(Self::Address(Address::from_slice(&bytes))),
+            32 => Ok(Self::PrivateKey(B256::from_slice(&bytes))),
+            // Don't echo the input since it might be a malformed private key.
+            _ => Err(anyhow!(
+                "refunder account must be a KMS ARN, a keystore file, an address or a private key"
+            )),
+        }
+    }
+}
+
+impl Display for RefunderAccount {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PrivateKey(_) => write!(f, "PrivateKey(SECRET)"),
+            Self::Kms(arn) => write!(f, "Kms({arn})"),
+            Self::Keystore(path) => write!(f, "Keystore({})", path.display()),
+            Self::Address(address) => write!(f, "Address({address})"),
+        }
+    }
+}
+
+// Implemented by hand so private keys never end up in logs.
+impl fmt::Debug for RefunderAccount {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, alloy::primitives::address};
+
+    #[test]
+    fn parses_refunder_accounts() {
+        assert_eq!(
+            "arn:aws:kms:eu-central-1:123456789012:key/key-id"
+                .parse::<RefunderAccount>()
+                .unwrap(),
+            RefunderAccount::Kms("arn:aws:kms:eu-central-1:123456789012:key/key-id".into()),
+        );
+        assert_eq!(
+            "keystore:/secrets/refunder.json"
+                .parse::<RefunderAccount>()
+                .unwrap(),
+            RefunderAccount::Keystore("/secrets/refunder.json".into()),
+        );
+        assert_eq!(
+            "0x0101010101010101010101010101010101010101"
+                .parse::<RefunderAccount>()
+                .unwrap(),
+            RefunderAccount::Address(address!("0x0101010101010101010101010101010101010101")),
+        );
+        assert_eq!(
+            "0x0202020202020202020202020202020202020202020202020202020202020202"
+                .parse::<RefunderAccount>()
+                .unwrap(),
+            RefunderAccount::PrivateKey(B256::repeat_byte(2)),
+        );
+        assert!("0x0303".parse::<RefunderAccount>().is_err());
+        assert!("not an account".parse::<RefunderAccount>().is_err());
+    }
+
+    #[test]
+    fn parses_chains() {
+        let chain: Chain = "http://node:8545|postgresql://user:password@db/gnosis"
+            .parse()
+            .unwrap();
+        assert_eq!(chain.node_url.as_str(), "http://node:8545/");
+        assert_eq!(
+            chain.db_url.as_str(),
+            "postgresql://user:password@db/gnosis"
+        );
+        assert!(chain.ethflow_contracts.is_empty());
+        assert!(!chain.to_string().contains("password"));
+
+        let chain: Chain = "http://node:8545|postgresql://|0x0101010101010101010101010101010101010101;0x0202020202020202020202020202020202020202"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            chain.ethflow_contracts,
+            vec![Address::repeat_byte(1), Address::repeat_byte(2)]
+        );
+
+        assert!("http://node:8545".parse::<Chain>().is_err());
+        assert!(
+            "http://node:8545|postgresql://|0x01"
+                .parse::<Chain>()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn rejects_raw_keys_by_default() {
+        let key = "0x0202020202020202020202020202020202020202020202020202020202020202";
+        let address = "0x0101010101010101010101010101010101010101";
+        let args =
+            |extra: &[&str]| Arguments::try_parse_from(["refunder"].iter().chain(extra)).unwrap();
+
+        assert!(
+            args(&["--refunder-accounts", key])
+                .refunder_accounts()
+                .is_err()
+        );
+        assert!(args(&["--refunder-pk", key]).refunder_accounts().is_err());
+        assert_eq!(
+            args(&["--refunder-accounts", address])
+                .refunder_accounts()
+                .unwrap(),
+            vec![RefunderAccount::Address(Address::repeat_byte(1))],
+        );
+        assert_eq!(
+            args(&[
+                "--refunder-accounts",
+                key,
+                "--insecure-allow-raw-key",
+                "true"
+            ])
+            .refunder_accounts()
+            .unwrap(),
+            vec![RefunderAccount::PrivateKey(B256::repeat_byte(2))],
+        );
+    }
+
+    #[test]
+    fn hides_private_keys() {
+        let account = RefunderAccount::PrivateKey(B256::repeat_byte(2));
+        assert_eq!(account.to_string(), "PrivateKey(SECRET)");
+        assert_eq!(format!("{account:?}"), "PrivateKey(SECRET)");
+    }
+}
@@ -1,17 +1,22 @@
+pub mod api;
 pub mod arguments;
+pub mod planner;
 pub mod refund_service;
 pub mod submitter;
 
 use {
-    crate::arguments::Arguments,
-    alloy::signers::local::PrivateKeySigner,
+    crate::arguments::{Arguments, RefunderAccount},
+    alloy::{primitives::Address, signers::local::PrivateKeySigner},
+    anyhow::{Context, Result},
     clap::Parser,
     contracts::alloy::CoWSwapEthFlow,
+    ethrpc::alloy::{Account, conversions::TryIntoAlloyAsync},
     observe::metrics::LivenessChecking,
     refund_service::RefundService,
     shared::http_client::HttpClientFactory,
     sqlx::PgPool,
     std::{
+        collections::HashMap,
         sync::{Arc, RwLock},
         time::{Duration, Instant},
     },
@@ -37,25 +42,102 @@ pub async fn start(args: impl Iterator<Item = String>) {
 
 pub async fn run(args: arguments::Arguments) {
     let http_factory = HttpClientFactory::new(&args.http_client);
-    let web3 = shared::ethrpc::web3(&args.ethrpc, &http_factory, &args.node_url, "base");
-    if let Some(expected_chain_id) = args.chain_id {
+    let refunder_accounts = args.refunder_accounts().expect("invalid refunder accounts");
+    let keystore_password = args
+        .refunder_keystore_password()
+        .expect("unable to read keystore password");
+    assert!(
+        !refunder_accounts.is_empty(),
+        "at least one refunder account is required"
+    );
+
+    let mut refunders = Vec::new();
+    for (index, chain) in args.chains().iter().enumerate() {
+        let label = match index {
+            0 => "base".to_string(),
+            _ => format!("base_{index}"),
+        };
+        let web3 = shared::ethrpc::web3(&args.ethrpc, &http_factory, &chain.node_url, label);
         let chain_id = web3
             .eth()
             .chain_id()
             .await
             .expect("Could not get chainId")
             .as_u64();
-        assert_eq!(
-            chain_id, expected_chain_id,
-            "connected to node with incorrect chain ID",
+        assert!(
+            refunders.iter().all(|(id, _)| *id != chain_id),
+            "chain {chain_id} is configured more than once"
         );
-    }
+        // The expected chain ID only refers to `--node-url`.
+        if index == 0
+            && let Some(expected_chain_id) = args.chain_id
+        {
+            assert_eq!(
+                chain_id, expected_chain_id,
+                "connected to node with incorrect chain ID",
+            );
+        }
 
-    let pg_pool = PgPool::connect_lazy(args.db_url.as_str()).expect("failed to create database");
+        let pg_pool =
+            PgPool::connect_lazy(chain.db_url.as_str()).expect("failed to create database");
+        let indexed = match args.ethflow_auto_discovery {
+            true => indexed_ethflow_contracts(&pg_pool)
+                .await
+                .expect("failed to read the ethflow contracts from the database"),
+            false => Vec::new(),
+        };
+        let ethflow_contracts = ethflow_contracts(
+            chain_id,
+            &chain.ethflow_contracts,
+            &indexed,
+            args.ethflow_auto_discovery,
+        );
+        assert!(
+            !ethflow_contracts.is_empty(),
+            "no ethflow contracts configured for chain {chain_id}, configure them or enable \
+             --ethflow-auto-discovery"
+        );
+        tracing::info!(chain_id, ?ethflow_contracts, "refunding ethflow orders");
+        let ethflow_contracts = ethflow_contracts
+            .into_iter()
+            .map(|contract| CoWSwapEthFlow::Instance::new(contract, web3.alloy.clone()))
+            .collect();
+        // Signers can't be shared between chains so they get loaded for every
+        // chain.
+        let mut accounts = Vec::with_capacity(refunder_accounts.len());
+        for account in &refunder_accounts {
+            let loaded = load_account(account, keystore_password.as_deref())
+                .await
+                .unwrap_or_else(|err| panic!("unable to load refunder account {account}: {err:?}"));
+            accounts.push(loaded);
+        }
+        let refunder = RefundService::new(
+            chain_id,
+            pg_pool,
+            web3,
+            ethflow_contracts,
+            i64::try_from(args.min_validity_duration.as_secs()).unwrap_or(i64::MAX),
+            args.min_price_deviation_bps,
+            accounts,
+            args.max_gas_price,
+            args.start_priority_fee_tip,
+            planner::Config {
+                sla: args.refund_sla,
+                gas_price_threshold: args.refund_gas_price_threshold,
+                batch_target_gas: args.refund_batch_target_gas,
+                gas_per_refund: args.refund_gas_per_order,
+                loop_interval: LOOP_INTERVAL,
+            },
+        );
+        refunders.push((chain_id, refunder));
+    }
 
     let liveness = Arc::new(Liveness {
         // Program will be healthy at the start even if no loop was ran yet.
-        last_successful_loop: RwLock::new(Instant::now()),
+        last_successful_loops: refunders
+            .iter()
+            .map(|(chain_id, _)| (*chain_id, RwLock::new(Instant::now())))
+            .collect(),
     });
     observe::metrics::serve_metrics(
         liveness.clone(),
@@ -63,52 +145,121 @@ pub async fn run(args: arguments::Arguments) {
         Default::default(),
         Default::default(),
     );
-
-    let ethflow_contracts = args
-        .ethflow_contracts
-        .iter()
-        .map(|contract| CoWSwapEthFlow::Instance::new(*contract, web3.alloy.clone()))
-        .collect();
-    let refunder_account = Box::new(
-        args.refunder_pk
-            .parse::<PrivateKeySigner>()
-            .expect("couldn't parse refunder private key"),
-    );
-    let mut refunder = RefundService::new(
-        pg_pool,
-        web3,
-        ethflow_contracts,
-        i64::try_from(args.min_validity_duration.as_secs()).unwrap_or(i64::MAX),
-        args.min_price_deviation_bps,
-        refunder_account,
-        args.max_gas_price,
-        args.start_priority_fee_tip,
+    api::serve(
+        refunders
+            .iter()
+            .map(|(chain_id, refunder)| (*chain_id, refunder.queue.clone()))
+            .collect(),
+        ([0, 0, 0, 0], args.api_port).into(),
     );
+
+    futures::future::join_all(
+        refunders
+            .into_iter()
+            .map(|(chain_id, refunder)| refund_loop(chain_id, refunder, liveness.clone())),
+    )
+    .await;
+}
+
+async fn refund_loop(chain_id: u64, mut refunder: RefundService, liveness: Arc<Liveness>) {
     loop {
-        tracing::info!("Staring a new refunding loop");
+        tracing::info!(chain_id, "Staring a new refunding loop");
         match refunder.try_to_refund_all_eligble_orders().await {
             Ok(_) => {
-                track_refunding_loop_result("success");
-                *liveness.last_successful_loop.write().unwrap() = Instant::now()
+                track_refunding_loop_result(chain_id, "success");
+                *liveness.last_successful_loops[&chain_id].write().unwrap() = Instant::now()
             }
             Err(err) => {
-                track_refunding_loop_result("error");
-                tracing::warn!("Error while refunding ethflow orders: {:?}", err)
+                track_refunding_loop_result(chain_id, "error");
+                tracing::warn!(chain_id, "Error while refunding ethflow orders: {:?}", err)
             }
         }
+        track_pending_refunds(chain_id, refunder.queue.read().unwrap().refunds.len());
         tokio::time::sleep(LOOP_INTERVAL).await;
     }
 }
 
+/// The configured ethflow contracts plus, if auto-discovery is enabled, the
+/// known deployment on the chain and all contracts that placed indexed orders.
+fn ethflow_contracts(
+    chain_id: u64,
+    configured: &[Address],
+    indexed: &[Address],
+    auto_discovery: bool,
+) -> Vec<Address> {
+    let mut contracts = configured.to_vec();
+    if !auto_discovery {
+        return contracts;
+    }
+    let discovered = CoWSwapEthFlow::deployment_address(&chain_id)
+        .into_iter()
+        .chain(indexed.iter().copied());
+    for contract in discovered {
+        if !contracts.contains(&contract) {
+            contracts.push(contract);
+        }
+    }
+    contracts
+}
+
+/// All ethflow contracts whose orders got indexed by the autopilot. This
+/// covers older deployments that are no longer known to the `contracts` crate.
+async fn indexed_ethflow_contracts(pool: &PgPool) -> Result<Vec<Address>> {
+    let mut ex = pool.acquire().await?;
+    let contracts = database::ethflow_orders::ethflow_contracts(&mut ex).await?;
+    Ok(contracts
+        .into_iter()
+        .map(|contract| Address::from(contract.0))
+        .collect())
+}
+
+async fn load_account(
+    account: &RefunderAccount,
+    keystore_password: Option<&str>,
+) -> Result<Account> {
+    match account {
+        RefunderAccount::PrivateKey(pk) => {
+            let signer = PrivateKeySigner::from_bytes(pk).context("invalid private key")?;
+            tracing::warn!(
+                account = ?signer.address(),
+                "using an insecure raw private key refunder account"
+            );
+            Ok(Account::Signer(Box::new(signer)))
+        }
+        RefunderAccount::Kms(arn) => {
+            let config = ethcontract::aws_config::load_from_env().await;
+            let account = ethcontract::transaction::kms::Account::new((&config).into(), arn)
+                .await
+                .context("unable to load KMS account")?;
+            ethcontract::Account::Kms(account, None)
+                .try_into_alloy()
+                .await
+        }
+        RefunderAccount::Keystore(path) => {
+            let password = keystore_password
+                .context("keystore accounts require --refunder-keystore-password-file")?;
+            let signer = PrivateKeySigner::decrypt_keystore(path, password)
+                .context("unable to decrypt keystore")?;
+            Ok(Account::Signer(Box::new(signer)))
+        }
+        RefunderAccount::Address(address) => Ok(Account::Address(*address)),
+    }
+}
+
 struct Liveness {
-    last_successful_loop: RwLock<Instant>,
+    /// Time of the last successful refunding loop of every chain.
+    last_successful_loops: HashMap<u64, RwLock<Instant>>,
 }
 
 #[async_trait::async_trait]
 impl LivenessChecking for Liveness {
     async fn is_alive(&self) -> bool {
-        Instant::now().duration_since(*self.last_successful_loop.read().unwrap())
-            < DELAY_FROM_LAST_LOOP_BEFORE_UNHEALTHY
+        self.last_successful_loops
+            .values()
+            .all(|last_successful_loop| {
+                Instant::now().duration_since(*last_successful_loop.read().unwrap())
+                    < DELAY_FROM_LAST_LOOP_BEFORE_UNHEALTHY
+            })
     }
 }
 
@@ -116,14 +267,61 @@ impl LivenessChecking for Liveness {
 #[metric(subsystem = "main")]
 struct Metrics {
     /// Tracks the result of every refunding loops.
-    #[metric(labels("result"))]
+    #[metric(labels("chain_id", "result"))]
     refunding_loops: prometheus::IntCounterVec,
+
+    /// Number of refunds waiting to be sent.
+    #[metric(labels("chain_id"))]
+    pending_refunds: prometheus::IntGaugeVec,
+}
+
+impl Metrics {
+    fn get() -> &'static Self {
+        Metrics::instance(observe::metrics::get_storage_registry())
+            .expect("unexpected error getting metrics instance")
+    }
 }
 
-fn track_refunding_loop_result(result: &str) {
-    Metrics::instance(observe::metrics::get_storage_registry())
-        .expect("unexpected error getting metrics instance")
+fn track_refunding_loop_result(chain_id: u64, result: &str) {
+    Metrics::get()
         .refunding_loops
-        .with_label_values(&[result])
+        .with_label_values(&[&chain_id.to_string(), result])
         .inc();
 }
+
+fn track_pending_refunds(chain_id: u64, pending: usize) {
+    Metrics::get()
+        .pending_refunds
+        .with_label_values(&[&chain_id.to_string()])
+        .set(i64::try_from(pending).unwrap_or(i64::MAX));
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, contracts::alloy::networks::MAINNET};
+
+    #[test]
+    fn discovers_ethflow_contracts() {
+        let deployed = CoWSwapEthFlow::deployment_address(&MAINNET).unwrap();
+        let configured = Address::repeat_byte(1);
+        let indexed = Address::repeat_byte(2);
+
+        assert_eq!(
+            ethflow_contracts(MAINNET, &[configured], &[], true),
+            vec![configured, deployed]
+        );
+        assert_eq!(
+            ethflow_contracts(MAINNET, &[deployed], &[deployed, indexed], true),
+            vec![deployed, indexed]
+        );
+        assert_eq!(
+            ethflow_contracts(MAINNET, &[configured], &[indexed], false),
+            vec![configured]
+        );
+        assert_eq!(ethflow_contracts(31337, &[], &[], true), vec![]);
+        assert_eq!(
+            ethflow_contracts(31337, &[], &[indexed], true),
+            vec![indexed]
+        );
+    }
+}
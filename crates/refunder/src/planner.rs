@@ -0,0 +1,346 @@
+//! Decides which refunds get sent when.
+//!
+//! Refunds are prioritised by whether they are overdue, then by their value and
+//! finally by their age. While the gas price is above the configured threshold
+//! refunds get postponed, but only until their SLA deadline has passed. The due
+//! refunds are split into batches whose estimated gas stays below the target.
+//! Every refunding loop sends the highest priority batch of each ethflow
+//! contract.
+
+use {
+    alloy::primitives::{Address, U256},
+    database::OrderUid,
+    serde::Serialize,
+    std::{cmp::Reverse, collections::HashMap, time::Duration},
+};
+
+/// Gas of a refund tx that doesn't depend on the number of refunded orders.
+const TX_BASE_GAS: u64 = 40_000;
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Time after an order expired within which it gets refunded regardless
+    /// of the gas price.
+    pub sla: Duration,
+    /// Gas price (in wei) above which refunds get postponed until their SLA
+    /// deadline. Refunds are never postponed if unset.
+    pub gas_price_threshold: Option<u64>,
+    /// Gas a single refund tx should stay below.
+    pub batch_target_gas: u64,
+    /// Gas it takes to refund a single order, i.e. decoding the order,
+    /// marking it as invalidated and sending back the ETH.
+    pub gas_per_refund: u64,
+    /// Time between two refunding loops, used for ETA estimates.
+    pub loop_interval: Duration,
+}
+
+impl Config {
+    fn deadline(&self, refund: &PendingRefund) -> i64 {
+        refund
+            .valid_to
+            .saturating_add(i64::try_from(self.sla.as_secs()).unwrap_or(i64::MAX))
+    }
+
+    fn max_refunds_per_batch(&self) -> usize {
+        let refunds = self
+            .batch_target_gas
+            .saturating_sub(TX_BASE_GAS)
+            .checked_div(self.gas_per_refund)
+            .unwrap_or(u64::MAX);
+        usize::try_from(refunds).unwrap_or(usize::MAX).max(1)
+    }
+
+    fn loop_interval(&self) -> i64 {
+        i64::try_from(self.loop_interval.as_secs()).unwrap_or(i64::MAX)
+    }
+}
+
+/// An expired order that has not been refunded yet.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingRefund {
+    pub uid: OrderUid,
+    pub ethflow_contract: Address,
+    pub valid_to: i64,
+    pub refund_amount: U256,
+}
+
+/// Refunds of a single ethflow contract that get sent in one tx.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Batch {
+    pub ethflow_contract: Address,
+    pub refunds: Vec<PendingRefund>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Plan {
+    /// Due refunds split into batches, ordered by priority.
+    pub batches: Vec<Batch>,
+    /// Refunds waiting for a cheaper gas price, ordered by priority.
+    pub postponed: Vec<PendingRefund>,
+}
+
+pub fn plan(config: &Config, mut refunds: Vec<PendingRefund>, now: i64, gas_price: f64) -> Plan {
+    refunds.sort_by_key(|refund| {
+        (
+            Reverse(config.deadline(refund) <= now),
+            Reverse(refund.refund_amount),
+            refund.valid_to,
+        )
+    });
+    let cheap_gas = config
+        .gas_price_threshold
+        .is_none_or(|threshold| gas_price <= threshold as f64);
+    let (due, postponed): (Vec<_>, Vec<_>) = refunds
+        .into_iter()
+        .partition(|refund| cheap_gas || config.deadline(refund) <= now);
+
+    let max_refunds = config.max_refunds_per_batch();
+    let mut batches: Vec<Batch> = Vec::new();
+    for refund in due {
+        // Refunds of the same contract fill up the same batch so every batch
+        // only starts after all higher priority batches.
+        match batches.iter_mut().rev().find(|batch| {
+            batch.ethflow_contract == refund.ethflow_contract && batch.refunds.len() < max_refunds
+        }) {
+            Some(batch) => batch.refunds.push(refund),
+            None => batches.push(Batch {
+                ethflow_contract: refund.ethflow_contract,
+                refunds: vec![refund],
+            }),
+        }
+    }
+
+    Plan { batches, postponed }
+}
+
+impl Plan {
+    /// The batches to send in the current refunding loop, i.e. the highest
+    /// priority batch of every ethflow contract.
+    pub fn current_batches(&self) -> impl Iterator<Item = &Batch> {
+        self.batches_with_loop()
+            .filter(|(loop_index, _)| *loop_index == 0)
+            .map(|(_, batch)| batch)
+    }
+
+    /// Pairs every batch with the index of the refunding loop sending it.
+    fn batches_with_loop(&self) -> impl Iterator<Item = (i64, &Batch)> {
+        let mut batches_per_contract = HashMap::<Address, i64>::new();
+        self.batches.iter().map(move |batch| {
+            let count = batches_per_contract
+                .entry(batch.ethflow_contract)
+                .or_default();
+            let loop_index = *count;
+            *count += 1;
+            (loop_index, batch)
+        })
+    }
+
+    /// Describes every refund of the plan with its expected refund time.
+    pub fn queue(&self, config: &Config, now: i64, gas_price: f64) -> Queue {
+        let scheduled = self.batches_with_loop().flat_map(|(loop_index, batch)| {
+            let eta = now.saturating_add((loop_index + 1).saturating_mul(config.loop_interval()));
+            batch
+                .refunds
+                .iter()
+                .map(move |refund| (refund, QueuedRefundStatus::Scheduled, eta))
+        });
+        let postponed = self.postponed.iter().map(|refund| {
+            (
+                refund,
+                QueuedRefundStatus::WaitingForCheaperGas,
+                config
+                    .deadline(refund)
+                    .saturating_add(config.loop_interval()),
+            )
+        });
+        Queue {
+            updated_at: now,
+            gas_price,
+            refunds: scheduled
+                .chain(postponed)
+                .map(|(refund, status, eta)| QueuedRefund {
+                    uid: const_hex::encode_prefixed(refund.uid.0),
+                    ethflow_contract: refund.ethflow_contract,
+                    refund_amount: refund.refund_amount.to_string(),
+                    valid_to: refund.valid_to,
+                    deadline: config.deadline(refund),
+                    status,
+                    eta,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The pending refunds as exposed by the status API.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Queue {
+    /// Block timestamp the queue was computed at.
+    pub updated_at: i64,
+    /// Effective gas price (in wei) the queue was computed with.
+    pub gas_price: f64,
+    pub refunds: Vec<QueuedRefund>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedRefund {
+    pub uid: String,
+    pub ethflow_contract: Address,
+    /// Refunded amount in wei as a decimal string.
+    pub refund_amount: String,
+    pub valid_to: i64,
+    /// Timestamp by which the refund gets sent regardless of the gas price.
+    pub deadline: i64,
+    pub status: QueuedRefundStatus,
+    /// Estimated timestamp of the refund.
+    pub eta: i64,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QueuedRefundStatus {
+    Scheduled,
+    WaitingForCheaperGas,
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, database::byte_array::ByteArray};
+
+    const CONTRACT_A: Address = Address::repeat_byte(0xa);
+    const CONTRACT_B: Address = Address::repeat_byte(0xb);
+
+    fn config() -> Config {
+        Config {
+            sla: Duration::from_secs(100),
+            gas_price_threshold: Some(10),
+            batch_target_gas: TX_BASE_GAS + 2 * 35_000,
+            gas_per_refund: 35_000,
+            loop_interval: Duration::from_secs(30),
+        }
+    }
+
+    fn refund(id: u8, ethflow_contract: Address, valid_to: i64, amount: u64) -> PendingRefund {
+        PendingRefund {
+            uid: ByteArray([id; 56]),
+            ethflow_contract,
+            valid_to,
+            refund_amount: U256::from(amount),
+        }
+    }
+
+    fn ids(refunds: &[PendingRefund]) -> Vec<u8> {
+        refunds.iter().map(|refund| refund.uid.0[0]).collect()
+    }
+
+    #[test]
+    fn prioritises_overdue_then_value_then_age() {
+        let refunds = vec![
+            refund(1, CONTRACT_A, 50, 1),
+            refund(2, CONTRACT_A, 0, 1),
+            refund(3, CONTRACT_A, 60, 5),
+            refund(4, CONTRACT_A, 40, 1),
+        ];
+        let plan = plan(&config(), refunds, 120, 1.);
+
+        assert!(plan.postponed.is_empty());
+        assert_eq!(
+            plan.batches
+                .iter()
+                .map(|batch| ids(&batch.refunds))
+                .collect::<Vec<_>>(),
+            vec![vec![2, 3], vec![4, 1]]
+        );
+    }
+
+    #[test]
+    fn postpones_refunds_until_deadline_on_expensive_gas() {
+        let refunds = vec![refund(1, CONTRACT_A, 0, 1), refund(2, CONTRACT_A, 50, 100)];
+        let plan = plan(&config(), refunds, 120, 11.);
+
+        assert_eq!(plan.batches.len(), 1);
+        assert_eq!(ids(&plan.batches[0].refunds), vec![1]);
+        assert_eq!(ids(&plan.postponed), vec![2]);
+    }
+
+    #[test]
+    fn never_postpones_without_threshold() {
+        let config = Config {
+            gas_price_threshold: None,
+            ..config()
+        };
+        let plan = plan(&config, vec![refund(1, CONTRACT_A, 50, 1)], 120, 1e12);
+
+        assert!(plan.postponed.is_empty());
+        assert_eq!(plan.batches.len(), 1);
+    }
+
+    #[test]
+    fn batches_per_contract() {
+        let refunds = vec![
+            refund(1, CONTRACT_A, 0, 4),
+            refund(2, CONTRACT_B, 0, 3),
+            refund(3, CONTRACT_A, 0, 2),
+            refund(4, CONTRACT_A, 0, 1),
+        ];
+        let plan = plan(&config(), refunds, 120, 1.);
+
+        assert_eq!(
+            plan.batches
+                .iter()
+                .map(|batch| (batch.ethflow_contract, ids(&batch.refunds)))
+                .collect::<Vec<_>>(),
+            vec![
+                (CONTRACT_A, vec![1, 3]),
+                (CONTRACT_B, vec![2]),
+                (CONTRACT_A, vec![4]),
+            ]
+        );
+    }
+
+    #[test]
+    fn estimates_eta() {
+        let refunds = vec![
+            refund(1, CONTRACT_A, 0, 3),
+            refund(2, CONTRACT_A, 0, 2),
+            refund(3, CONTRACT_A, 0, 1),
+            refund(4, CONTRACT_A, 50, 1),
+            refund(5, CONTRACT_B, 0, 1),
+        ];
+        let config = config();
+        let plan = plan(&config, refunds, 120, 11.);
+        let queue = plan.queue(&config, 120, 11.);
+
+        assert_eq!(
+            queue
+                .refunds
+                .iter()
+                .map(|refund| (refund.status, refund.eta))
+                .collect::<Vec<_>>(),
+            vec![
+                (QueuedRefundStatus::Scheduled, 150),
+                (QueuedRefundStatus::Scheduled, 150),
+                (QueuedRefundStatus::Scheduled, 180),
+                (QueuedRefundStatus::Scheduled, 150),
+                (QueuedRefundStatus::WaitingForCheaperGas, 180),
+            ]
+        );
+        assert_eq!(
+            plan.current_batches()
+                .map(|batch| ids(&batch.refunds))
+                .collect::<Vec<_>>(),
+            vec![vec![1, 2], vec![5]]
+        );
+    }
+
+    #[test]
+    fn keeps_at_least_one_refund_per_batch() {
+        let config = Config {
+            batch_target_gas: 0,
+            ..config()
+        };
+        assert_eq!(config.max_refunds_per_batch(), 1);
+    }
+}
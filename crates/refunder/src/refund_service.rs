@@ -1,28 +1,29 @@
 use {
-    crate::submitter::Submitter,
-    alloy::{
-        network::TxSigner,
-        primitives::{Address, B256, Signature, address},
+    crate::{
+        planner::{self, Batch, PendingRefund, Plan, Queue},
+        submitter::{SubmissionAccount, Submitter},
     },
+    alloy::primitives::{Address, B256, address},
     anyhow::{Context, Result, anyhow},
     contracts::alloy::CoWSwapEthFlow,
     database::{
         OrderUid,
-        ethflow_orders::{EthOrderPlacement, read_order, refundable_orders},
+        ethflow_orders::{RefundableOrder, read_order, refundable_orders},
         orders::read_order as read_db_order,
     },
-    ethrpc::{Web3, block_stream::timestamp_of_current_block_in_seconds},
+    ethrpc::{
+        Web3,
+        alloy::{Account, ProviderSignerExt},
+        block_stream::timestamp_of_current_block_in_seconds,
+    },
     futures::{StreamExt, stream},
     number::conversions::alloy::big_decimal_to_u256,
     sqlx::PgPool,
-    std::collections::HashMap,
+    std::sync::{Arc, RwLock},
 };
 
 pub const NO_OWNER: Address = Address::ZERO;
 pub const INVALIDATED_OWNER: Address = address!("0xffffffffffffffffffffffffffffffffffffffff");
-const MAX_NUMBER_OF_UIDS_PER_REFUND_TX: usize = 30;
-
-type CoWSwapEthFlowAddress = Address;
 
 pub struct RefundService {
     pub db: PgPool,
@@ -33,6 +34,9 @@ pub struct RefundService {
     pub submitter: Submitter,
     pub max_gas_price: u64,
     pub start_priority_fee_tip: u64,
+    pub planner: planner::Config,
+    /// Pending refunds as of the last refunding loop.
+    pub queue: Arc<RwLock<Queue>>,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -45,52 +49,89 @@ enum RefundStatus {
 impl RefundService {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
+        chain_id: u64,
         db: PgPool,
         web3: Web3,
         ethflow_contracts: Vec<CoWSwapEthFlow::Instance>,
         min_validity_duration: i64,
         min_price_deviation_bps: i64,
-        signer: Box<dyn TxSigner<Signature> + Send + Sync + 'static>,
+        accounts: Vec<Account>,
         max_gas_price: u64,
         start_priority_fee_tip: u64,
+        planner: planner::Config,
     ) -> Self {
-        let signer_address = signer.address();
         let gas_estimator = Box::new(web3.legacy.clone());
-        web3.wallet.register_signer(signer);
+        let accounts = accounts
+            .into_iter()
+            .map(|account| match account {
+                Account::Signer(signer) => {
+                    let address = signer.address();
+                    web3.wallet.register_signer(signer);
+                    SubmissionAccount::new(address, web3.alloy.clone())
+                }
+                // The node signs the tx, so alloy must not try to.
+                Account::Address(address) => {
+                    SubmissionAccount::new(address, web3.alloy.without_wallet())
+                }
+            })
+            .collect();
         RefundService {
             db,
-            web3: web3.clone(),
+            web3,
             ethflow_contracts,
             min_validity_duration,
             min_price_deviation: min_price_deviation_bps as f64 / 10000f64,
             max_gas_price,
             start_priority_fee_tip,
-            submitter: Submitter {
-                web3,
-                signer_address,
+            submitter: Submitter::new(
+                chain_id,
+                accounts,
                 gas_estimator,
-                gas_parameters_of_last_tx: None,
-                nonce_of_last_submission: None,
                 max_gas_price,
                 start_priority_fee_tip,
-            },
+            ),
+            planner,
+            queue: Default::default(),
         }
     }
 
     pub async fn try_to_refund_all_eligble_orders(&mut self) -> Result<()> {
-        let refundable_order_uids = self.get_refundable_ethflow_orders_from_db().await?;
+        let block_time = timestamp_of_current_block_in_seconds(&self.web3.alloy).await? as i64;
+        let refundable_orders = self
+            .get_refundable_ethflow_orders_from_db(block_time)
+            .await?;
 
-        let to_be_refunded_uids = self
-            .identify_uids_refunding_status_via_web3_calls(refundable_order_uids)
+        let mut pending_refunds = self
+            .identify_uids_refunding_status_via_web3_calls(refundable_orders)
             .await;
+        let in_flight = self.submitter.in_flight_uids().await;
+        self.submitter.track_balances().await;
+        pending_refunds.retain(|refund| !in_flight.contains(&refund.uid));
+
+        let gas_price = self
+            .submitter
+            .gas_estimator
+            .estimate()
+            .await?
+            .effective_gas_price();
+        let plan = planner::plan(&self.planner, pending_refunds, block_time, gas_price);
+        *self.queue.write().unwrap() = plan.queue(&self.planner, block_time, gas_price);
+        if !plan.postponed.is_empty() {
+            tracing::debug!(
+                postponed = plan.postponed.len(),
+                gas_price,
+                "postponing refunds until gas gets cheaper"
+            );
+        }
 
-        self.send_out_refunding_tx(to_be_refunded_uids).await?;
+        self.send_out_refunding_txs(&plan).await?;
         Ok(())
     }
 
-    pub async fn get_refundable_ethflow_orders_from_db(&self) -> Result<Vec<EthOrderPlacement>> {
-        let block_time = timestamp_of_current_block_in_seconds(&self.web3.alloy).await? as i64;
-
+    pub async fn get_refundable_ethflow_orders_from_db(
+        &self,
+        block_time: i64,
+    ) -> Result<Vec<RefundableOrder>> {
         let mut ex = self.db.acquire().await?;
         refundable_orders(
             &mut ex,
@@ -109,9 +150,9 @@ impl RefundService {
 
     async fn identify_uids_refunding_status_via_web3_calls(
         &self,
-        refundable_order_uids: Vec<EthOrderPlacement>,
-    ) -> HashMap<CoWSwapEthFlowAddress, Vec<OrderUid>> {
-        let futures = refundable_order_uids
+        refundable_orders: Vec<RefundableOrder>,
+    ) -> Vec<PendingRefund> {
+        let futures = refundable_orders
             .into_iter()
             .filter_map(|eth_order_placement| {
                 // Owner of the ethflow order is always the ethflow contract itself
@@ -152,24 +193,24 @@ impl RefundService {
                     // any other owner
                     _ => RefundStatus::NotYetRefunded,
                 };
-                Some((eth_order_placement.uid, refund_status, ethflow_contract))
+                Some((eth_order_placement, refund_status, ethflow_contract))
             });
 
         let uid_with_latest_refundablility = futures::future::join_all(futures).await;
-        let mut to_be_refunded_uids = HashMap::<_, Vec<_>>::new();
+        let mut pending_refunds = Vec::new();
         let mut invalid_uids = Vec::new();
-        for (uid, refund_status, ethflow_contract) in
+        for (order, refund_status, ethflow_contract) in
             uid_with_latest_refundablility.into_iter().flatten()
         {
             match refund_status {
                 RefundStatus::Refunded => (),
-                RefundStatus::Invalid => invalid_uids.push(uid),
-                RefundStatus::NotYetRefunded => {
-                    to_be_refunded_uids
-                        .entry(*ethflow_contract.address())
-                        .or_default()
-                        .push(uid);
-                }
+                RefundStatus::Invalid => invalid_uids.push(order.uid),
+                RefundStatus::NotYetRefunded => pending_refunds.push(PendingRefund {
+                    uid: order.uid,
+                    ethflow_contract: *ethflow_contract.address(),
+                    valid_to: order.valid_to,
+                    refund_amount: big_decimal_to_u256(&order.refund_amount).unwrap_or_default(),
+                }),
             }
         }
         if !invalid_uids.is_empty() {
@@ -181,7 +222,7 @@ impl RefundService {
                 invalid_uids
             );
         }
-        to_be_refunded_uids
+        pending_refunds
     }
 
     async fn get_ethflow_data_from_db(
@@ -212,20 +253,14 @@ impl RefundService {
         })
     }
 
-    async fn send_out_refunding_tx(
-        &mut self,
-        uids_by_contract: HashMap<CoWSwapEthFlowAddress, Vec<OrderUid>>,
-    ) -> Result<()> {
-        if uids_by_contract.is_empty() {
-            return Ok(());
-        }
-
+    async fn send_out_refunding_txs(&mut self, plan: &Plan) -> Result<()> {
         // For each ethflow contract, issue a separate tx to refund
-        for (contract, mut uids) in uids_by_contract.into_iter() {
-            // only try to refund MAX_NUMBER_OF_UIDS_PER_REFUND_TX uids, in order to fit
-            // into gas limit
-            uids.truncate(MAX_NUMBER_OF_UIDS_PER_REFUND_TX);
-
+        for Batch {
+            ethflow_contract,
+            refunds,
+        } in plan.current_batches()
+        {
+            let uids: Vec<_> = refunds.iter().map(|refund| refund.uid).collect();
             tracing::debug!("Trying to refund the following uids: {:?}", uids);
 
             let futures = uids.iter().map(|uid| {
@@ -247,7 +282,7 @@ impl RefundService {
                 .collect()
                 .await;
             self.submitter
-                .submit(uids, encoded_ethflow_orders, contract)
+                .submit(uids, encoded_ethflow_orders, *ethflow_contract)
                 .await?;
         }
 
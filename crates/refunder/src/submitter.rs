@@ -7,15 +7,25 @@
 // this submitter stores the last gas_price in order to submit the new tx with
 // a higher gas price, in order to avoid: ErrReplaceUnderpriced erros
 // In the re-newed attempt for submission the same nonce is used as before.
+//
+// Several accounts can be configured, each with their own nonce and gas price
+// tracking. Whenever the tx of an account is not mined in time, the next
+// account takes over, so a stuck tx doesn't block further refunds. Once it's
+// the stuck account's turn again, its pending tx gets replaced as described
+// above. Until then the orders of the stuck tx are considered in flight and
+// don't get refunded by the other accounts.
 
 use {
-    alloy::{primitives::Address, providers::Provider},
+    alloy::{
+        primitives::{Address, TxHash},
+        providers::Provider,
+    },
     anyhow::{Context, Result},
     contracts::alloy::CoWSwapEthFlow::{self, EthFlowOrder},
     database::OrderUid,
+    ethrpc::{AlloyProvider, alloy::conversions::IntoLegacy},
     gas_estimation::{GasPrice1559, GasPriceEstimating},
-    shared::ethrpc::Web3,
-    std::time::Duration,
+    std::{collections::HashSet, time::Duration},
 };
 
 // The gas price buffer determines the gas price buffer used to
@@ -33,30 +43,119 @@ const fn f64_to_u128(n: f64) -> u128 {
     n as u128
 }
 
-pub struct Submitter {
-    pub web3: Web3,
-    pub signer_address: Address,
-    pub gas_estimator: Box<dyn GasPriceEstimating>,
+/// An account refunds get sent from.
+pub struct SubmissionAccount {
+    pub address: Address,
+    /// Provider able to send txs from this account, either by signing them
+    /// locally or by letting the node sign them.
+    pub provider: AlloyProvider,
     pub gas_parameters_of_last_tx: Option<GasPrice1559>,
     pub nonce_of_last_submission: Option<u64>,
-    pub max_gas_price: u64,
-    pub start_priority_fee_tip: u64,
+    /// The last tx of this account if it wasn't mined in time.
+    pending_tx: Option<PendingTx>,
 }
 
-impl Submitter {
+/// A refund tx that was sent but not mined in time.
+struct PendingTx {
+    hash: TxHash,
+    uids: Vec<OrderUid>,
+}
+
+impl SubmissionAccount {
+    pub fn new(address: Address, provider: AlloyProvider) -> Self {
+        Self {
+            address,
+            provider,
+            gas_parameters_of_last_tx: None,
+            nonce_of_last_submission: None,
+            pending_tx: None,
+        }
+    }
+
+    /// Forgets the pending tx once it got mined or dropped from the mempool.
+    async fn update_pending_tx(&mut self) -> Result<()> {
+        let Some(pending) = &self.pending_tx else {
+            return Ok(());
+        };
+        let tx = self
+            .provider
+            .get_transaction_by_hash(pending.hash)
+            .await
+            .context("could not get pending refund tx")?;
+        if tx.is_none_or(|tx| tx.block_number.is_some()) {
+            self.pending_tx = None;
+        }
+        Ok(())
+    }
+
     async fn get_submission_nonce(&self) -> Result<u64> {
         // this command returns the tx count ever mined at the latest block
         // Mempool tx are not considered.
-        self.web3
-            .alloy
-            .get_transaction_count(self.signer_address)
+        self.provider
+            .get_transaction_count(self.address)
             .await
-            .with_context(|| {
-                format!(
-                    "could not get latest nonce for address {:?}",
-                    self.signer_address
-                )
-            })
+            .with_context(|| format!("could not get latest nonce for address {:?}", self.address))
+    }
+}
+
+pub struct Submitter {
+    /// Chain the refunds get sent on, used to label the metrics.
+    pub chain_id: u64,
+    pub accounts: Vec<SubmissionAccount>,
+    pub gas_estimator: Box<dyn GasPriceEstimating>,
+    pub max_gas_price: u64,
+    pub start_priority_fee_tip: u64,
+    /// Index of the account the next tx gets sent from.
+    active_account: usize,
+}
+
+impl Submitter {
+    pub fn new(
+        chain_id: u64,
+        accounts: Vec<SubmissionAccount>,
+        gas_estimator: Box<dyn GasPriceEstimating>,
+        max_gas_price: u64,
+        start_priority_fee_tip: u64,
+    ) -> Self {
+        assert!(
+            !accounts.is_empty(),
+            "at least one refunder account is required"
+        );
+        Self {
+            chain_id,
+            accounts,
+            gas_estimator,
+            max_gas_price,
+            start_priority_fee_tip,
+            active_account: 0,
+        }
+    }
+
+    /// The account the next tx gets sent from.
+    pub fn active_account(&self) -> &SubmissionAccount {
+        &self.accounts[self.active_account]
+    }
+
+    /// Returns the orders refunded by pending txs of inactive accounts. These
+    /// must not be refunded again until the pending tx gets replaced by its
+    /// account or dropped. The pending tx of the active account doesn't count
+    /// since it gets replaced by the next submission anyway.
+    pub async fn in_flight_uids(&mut self) -> HashSet<OrderUid> {
+        let mut uids = HashSet::new();
+        for (i, account) in self.accounts.iter_mut().enumerate() {
+            if i == self.active_account {
+                continue;
+            }
+            if let Err(err) = account.update_pending_tx().await {
+                // Stay on the safe side and keep considering the orders as
+                // in flight.
+                tracing::warn!(?err, account = ?account.address, "failed to check pending tx");
+            }
+            if let Some(pending) = &account.pending_tx {
+                uids.extend(pending.uids.iter().copied());
+            }
+        }
+        uids
     }
 
     pub async fn submit(
@@ -65,46 +164,140 @@ impl Submitter {
         encoded_ethflow_orders: Vec<EthFlowOrder::Data>,
         ethflow_contract: Address,
     ) -> Result<()> {
+        let result = self
+            .submit_from_active_account(uids, encoded_ethflow_orders, ethflow_contract)
+            .await;
+        let label = match result {
+            Ok(true) => "mined",
+            Ok(false) => "timeout",
+            Err(_) => "error",
+        };
+        Metrics::get()
+            .refund_transactions
+            .with_label_values(&[&self.chain_id.to_string(), label])
+            .inc();
+        if !matches!(result, Ok(true)) {
+            self.rotate_account();
+        }
+        result.map(|_| ())
+    }
+
+    /// Reports the ETH balance of every account.
+    pub async fn track_balances(&self) {
+        let chain_id = self.chain_id.to_string();
+        for account in &self.accounts {
+            match account.provider.get_balance(account.address).await {
+                Ok(balance) => Metrics::get()
+                    .account_balance
+                    .with_label_values(&[&chain_id, &account.address.to_string()])
+                    .set(balance.into_legacy().to_f64_lossy()),
+                Err(err) => {
+                    tracing::warn!(?err, account = ?account.address, "failed to fetch balance")
+                }
+            }
+        }
+    }
+
+    /// Returns whether the tx got mined in time.
+    async fn submit_from_active_account(
+        &mut self,
+        uids: Vec<OrderUid>,
+        encoded_ethflow_orders: Vec<EthFlowOrder::Data>,
+        ethflow_contract: Address,
+    ) -> Result<bool> {
         const TIMEOUT_5_BLOCKS: Duration = Duration::from_secs(60);
 
         let gas_price_estimation = self.gas_estimator.estimate().await?;
-        let nonce = self.get_submission_nonce().await?;
+        let account = &mut self.accounts[self.active_account];
+        let nonce = account.get_submission_nonce().await?;
         let gas_price = calculate_submission_gas_price(
-            self.gas_parameters_of_last_tx,
+            account.gas_parameters_of_last_tx,
             gas_price_estimation,
             nonce,
-            self.nonce_of_last_submission,
+            account.nonce_of_last_submission,
             self.max_gas_price,
             self.start_priority_fee_tip,
         )?;
 
-        self.gas_parameters_of_last_tx = Some(gas_price);
-        self.nonce_of_last_submission = Some(nonce);
+        account.gas_parameters_of_last_tx = Some(gas_price);
+        account.nonce_of_last_submission = Some(nonce);
 
         let ethflow_contract =
-            CoWSwapEthFlow::Instance::new(ethflow_contract, self.web3.alloy.clone());
-        let tx_result = ethflow_contract
+            CoWSwapEthFlow::Instance::new(ethflow_contract, account.provider.clone());
+        let pending_tx = ethflow_contract
             .invalidateOrdersIgnoringNotAllowed(encoded_ethflow_orders)
             // Gas conversions are lossy but technically the should not have decimal points even though they're floats
             .max_priority_fee_per_gas(f64_to_u128(gas_price.max_priority_fee_per_gas))
             .max_fee_per_gas(f64_to_u128(gas_price.max_fee_per_gas))
-            .from(self.signer_address)
+            .from(account.address)
             .nonce(nonce)
             .send()
-            .await?.with_timeout(Some(TIMEOUT_5_BLOCKS)).get_receipt().await;
+            .await?;
+        // Any previous tx of the account got replaced since the nonce is the
+        // same.
+        account.pending_tx = Some(PendingTx {
+            hash: *pending_tx.tx_hash(),
+            uids: uids.clone(),
+        });
+        let tx_result = pending_tx
+            .with_timeout(Some(TIMEOUT_5_BLOCKS))
+            .get_receipt()
+            .await;
 
         match tx_result {
             Ok(receipt) => {
+                account.pending_tx = None;
                 tracing::debug!(
                     "Tx to refund the orderuids {:?} yielded following result {:?}",
                     uids,
                     receipt
                 );
+                Ok(true)
+            }
+            Err(err) => {
+                tracing::debug!("transaction failed with: {err}");
+                Ok(false)
             }
-            Err(err) => tracing::debug!("transaction failed with: {err}"),
         }
-        Ok(())
     }
+
+    /// Hands over to the next account after a failed submission. The stuck
+    /// account keeps its nonce and gas price so its pending tx gets replaced
+    /// once it's used again.
+    fn rotate_account(&mut self) {
+        let stuck = self.accounts[self.active_account].address;
+        self.active_account = next_account(self.active_account, self.accounts.len());
+        if self.accounts.len() > 1 {
+            tracing::warn!(
+                ?stuck,
+                next = ?self.accounts[self.active_account].address,
+                "refund tx not mined, rotating refunder account"
+            );
+        }
+    }
+}
+
+#[derive(prometheus_metric_storage::MetricStorage, Debug)]
+#[metric(subsystem = "submitter")]
+struct Metrics {
+    /// Refund txs sent, by whether they got mined in time.
+    #[metric(labels("chain_id", "result"))]
+    refund_transactions: prometheus::IntCounterVec,
+
+    /// ETH balance (in wei) of the refunder accounts.
+    #[metric(labels("chain_id", "account"))]
+    account_balance: prometheus::GaugeVec,
+}
+
+impl Metrics {
+    fn get() -> &'static Self {
+        Metrics::instance(observe::metrics::get_storage_registry())
+            .expect("unexpected error getting metrics instance")
+    }
+}
+
+fn next_account(active_account: usize, accounts: usize) -> usize {
+    (active_account + 1) % accounts
 }
 
 fn calculate_submission_gas_price(
@@ -158,6 +351,14 @@ fn calculate_submission_gas_price(
 mod tests {
     use super::*;
 
+    #[test]
+    fn rotates_through_accounts() {
+        assert_eq!(next_account(0, 1), 0);
+        assert_eq!(next_account(0, 3), 1);
+        assert_eq!(next_account(1, 3), 2);
+        assert_eq!(next_account(2, 3), 0);
+    }
+
     #[test]
     fn test_calculate_submission_gas_price() {
         const TEST_MAX_GAS_PRICE: u64 = 800_000_000_000;
@@ -19,6 +19,7 @@ use {
     std::{
         fmt::{self, Display, Formatter},
         num::NonZeroU64,
+        path::PathBuf,
         str::FromStr,
         time::Duration,
     },
@@ -102,6 +103,63 @@ pub struct OrderQuotingArguments {
     pub standard_offchain_quote_validity: Duration,
 }
 
+// Additional sources of banned users on top of the hardcoded list and the
+// onchain registry. Shared between the orderbook which rejects new orders of
+// banned users and the autopilot which filters their existing orders.
+#[derive(clap::Parser)]
+pub struct BannedUsersArguments {
+    /// File with banned addresses, one per line. Gets reloaded periodically.
+    #[clap(long, env)]
+    pub banned_users_file: Option<PathBuf>,
+
+    /// URL serving banned addresses, one per line. Gets reloaded periodically.
+    #[clap(long, env)]
+    pub banned_users_url: Option<Url>,
+
+    /// How often the banned users file and URL get reloaded.
+    #[clap(
+        long,
+        env,
+        default_value = "1m",
+        value_parser = humantime::parse_duration,
+    )]
+    pub banned_users_reload_interval: Duration,
+
+    /// Addresses that are never considered banned, even if one of the banned
+    /// users sources reports them.
+    #[clap(long, env, use_value_delimiter = true)]
+    pub allowed_users: Vec<Address>,
+}
+
+impl BannedUsersArguments {
+    /// Adds the configured sources and allow-list to `users`. Fails if one of
+    /// the sources can't be loaded initially.
+    pub async fn apply(
+        self,
+        users: order_validation::banned::Users,
+        http_client: reqwest::Client,
+    ) -> Result<order_validation::banned::Users, order_validation::banned::Error> {
+        use order_validation::banned::{ReloadingList, Source};
+
+        let mut users = users.with_allowed(self.allowed_users);
+        if let Some(path) = self.banned_users_file {
+            users = users.with_provider(
+                ReloadingList::new(Source::File(path), self.banned_users_reload_interval).await?,
+            );
+        }
+        if let Some(url) = self.banned_users_url {
+            users = users.with_provider(
+                ReloadingList::new(
+                    Source::Http(http_client, url),
+                    self.banned_users_reload_interval,
+                )
+                .await?,
+            );
+        }
+        Ok(users)
+    }
+}
+
 logging_args_with_default_filter!(
     LoggingArguments,
     "info,autopilot=debug,driver=debug,observe=info,orderbook=debug,solver=debug,shared=debug,\
@@ -338,6 +396,30 @@ where
     Ok(())
 }
 
+impl Display for BannedUsersArguments {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let Self {
+            banned_users_file,
+            banned_users_url,
+            banned_users_reload_interval,
+            allowed_users,
+        } = self;
+
+        display_option(
+            f,
+            "banned_users_file",
+            &banned_users_file.as_ref().map(|path| path.display()),
+        )?;
+        display_secret_option(f, "banned_users_url", banned_users_url.as_ref())?;
+        writeln!(
+            f,
+            "banned_users_reload_interval: {banned_users_reload_interval:?}"
+        )?;
+        writeln!(f, "allowed_users: {allowed_users:?}")?;
+        Ok(())
+    }
+}
+
 impl Display for OrderQuotingArguments {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let Self {
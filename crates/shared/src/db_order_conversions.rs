@@ -22,6 +22,8 @@ use {
         order::{
             BuyTokenDestination,
             EthflowData,
+            EthflowOrderStatus,
+            HooksGas,
             Interactions,
             OnchainOrderData,
             OnchainOrderPlacementError,
@@ -54,9 +56,11 @@ pub fn full_order_into_model_order(order: database::orders::FullOrder) -> Result
     let pre_interactions = extract_interactions(&order, ExecutionTime::Pre)?;
     let post_interactions = extract_interactions(&order, ExecutionTime::Post)?;
     let ethflow_data = if let Some((refund_tx, user_valid_to)) = order.ethflow_data {
+        // Only open orders get converted here.
         Some(EthflowData {
             user_valid_to,
             refund_tx_hash: refund_tx.map(|hash| H256::from(hash.0)),
+            status: EthflowOrderStatus::Created,
         })
     } else {
         None
@@ -106,6 +110,7 @@ pub fn full_order_into_model_order(order: database::orders::FullOrder) -> Result
             .transpose()
             .context("full app data isn't utf-8")?,
         quote: None,
+        hooks_gas: hooks_gas_from(&order)?,
     };
     let data = OrderData {
         sell_token: Address::new(order.sell_token.0),
@@ -184,6 +189,21 @@ pub fn extract_interactions(
         .collect()
 }
 
+pub fn hooks_gas_from(order: &FullOrderDb) -> Result<Option<HooksGas>> {
+    let (Some(pre), Some(post)) = (&order.pre_hooks_gas, &order.post_hooks_gas) else {
+        return Ok(None);
+    };
+    let convert = |gas: &[i64]| -> Result<Vec<u64>> {
+        gas.iter()
+            .map(|gas| u64::try_from(*gas).context("hook gas is negative"))
+            .collect()
+    };
+    Ok(Some(HooksGas {
+        pre: convert(pre)?,
+        post: convert(post)?,
+    }))
+}
+
 pub fn order_kind_into(kind: OrderKind) -> DbOrderKind {
     match kind {
         OrderKind::Buy => DbOrderKind::Buy,
@@ -0,0 +1,365 @@
+//! Simulation of the pre- and post-hooks of an order at placement time.
+//!
+//! The hooks get called from the `HooksTrampoline` like they would be during a
+//! settlement, with the token transfers of the trade in between, all within a
+//! single `eth_simulateV1` request. This detects hooks that revert or need more
+//! gas than the order declared, which would otherwise make the order
+//! unsettleable and only show up when solvers simulate their solutions.
+
+use {
+    crate::price_estimation::trade_verifier::balance_overrides::{
+        BalanceOverrideRequest,
+        BalanceOverriding,
+    },
+    alloy::{
+        primitives::{Address, U256},
+        providers::Provider,
+        rpc::types::{
+            TransactionInput,
+            TransactionRequest,
+            simulate::{SimBlock, SimulatePayload},
+        },
+        sol_types::SolCall,
+    },
+    anyhow::{Context, anyhow},
+    app_data::{Hook, Hooks},
+    contracts::alloy::ERC20,
+    ethcontract::state_overrides::{StateOverride, StateOverrides},
+    ethrpc::{
+        Web3,
+        alloy::conversions::{IntoAlloy, IntoLegacy},
+    },
+    model::order::{BUY_ETH_ADDRESS, BuyTokenDestination, HooksGas, SellTokenSource},
+    std::sync::Arc,
+    thiserror::Error,
+    tracing::instrument,
+};
+
+/// The parts of an order needed to simulate its hooks.
+#[derive(Clone, Debug, Default)]
+pub struct HookSimulationRequest {
+    pub owner: Address,
+    pub receiver: Address,
+    pub sell_token: Address,
+    pub buy_token: Address,
+    pub sell_amount: U256,
+    pub buy_amount: U256,
+    pub sell_token_source: SellTokenSource,
+    pub buy_token_destination: BuyTokenDestination,
+    pub hooks: Hooks,
+}
+
+/// When a hook gets executed relative to the trade.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum Execution {
+    Pre,
+    Post,
+}
+
+#[derive(Debug, Error)]
+pub enum HookSimulationError {
+    #[error("{execution}-hook {index} reverted")]
+    Reverted { execution: Execution, index: usize },
+    #[error("{execution}-hook {index} ran out of its declared gas limit of {gas_limit}")]
+    GasLimitExceeded {
+        execution: Execution,
+        index: usize,
+        gas_limit: u64,
+    },
+    /// The simulation itself failed, which says nothing about the hooks.
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+#[cfg_attr(any(test, feature = "test-util"), mockall::automock)]
+#[async_trait::async_trait]
+pub trait HookSimulating: Send + Sync {
+    /// Simulates the hooks of the order and returns the gas every hook used.
+    async fn simulate(
+        &self,
+        request: &HookSimulationRequest,
+    ) -> Result<HooksGas, HookSimulationError>;
+}
+
+/// Simulates hooks with `eth_simulateV1` on the configured node.
+pub struct Simulator {
+    web3: Web3,
+    trampoline: Address,
+    settlement: Address,
+    vault_relayer: Address,
+    balance_overrider: Arc<dyn BalanceOverriding>,
+}
+
+impl Simulator {
+    pub fn new(
+        web3: &Web3,
+        trampoline: Address,
+        settlement: Address,
+        vault_relayer: Address,
+        balance_overrider: Arc<dyn BalanceOverriding>,
+    ) -> Self {
+        let web3 = ethrpc::instrumented::instrument_with_label(web3, "hookSimulation".into());
+        Self {
+            web3,
+            trampoline,
+            settlement,
+            vault_relayer,
+            balance_overrider,
+        }
+    }
+
+    /// Calls the hook with exactly the gas the trampoline forwards to it,
+    /// so hooks needing more gas at any point of their execution (e.g. before
+    /// getting refunds for cleared storage) fail like they would on-chain.
+    fn hook_call(&self, hook: &Hook) -> TransactionRequest {
+        TransactionRequest::default()
+            .from(self.trampoline)
+            .to(hook.target)
+            .gas_limit(intrinsic_gas(&hook.call_data).saturating_add(hook.gas_limit))
+            .input(TransactionInput::new(hook.call_data.clone().into()))
+    }
+
+    /// Calls moving the traded tokens like the settlement would. The sell
+    /// tokens get pulled from the owner and the buy tokens get paid out to the
+    /// receiver. Transfers through the Balancer vault are not simulated.
+    fn trade_calls(&self, request: &HookSimulationRequest) -> Vec<TransactionRequest> {
+        let mut calls = Vec::with_capacity(2);
+        if request.sell_token_source == SellTokenSource::Erc20 {
+            let call_data = ERC20::ERC20::transferFromCall {
+                sender: request.owner,
+                recipient: self.settlement,
+                amount: request.sell_amount,
+            }
+            .abi_encode();
+            calls.push(
+                TransactionRequest::default()
+                    .from(self.vault_relayer)
+                    .to(request.sell_token)
+                    .input(TransactionInput::new(call_data.into())),
+            );
+        }
+        if request.buy_token_destination == BuyTokenDestination::Erc20 {
+            let call = TransactionRequest::default().from(self.settlement);
+            calls.push(if request.buy_token == BUY_ETH_ADDRESS.into_alloy() {
+                call.to(request.receiver).value(request.buy_amount)
+            } else {
+                let call_data = ERC20::ERC20::transferCall {
+                    recipient: request.receiver,
+                    amount: request.buy_amount,
+                }
+                .abi_encode();
+                call.to(request.buy_token)
+                    .input(TransactionInput::new(call_data.into()))
+            });
+        }
+        calls
+    }
+
+    /// Funds the settlement contract with the buy amount so the payout to the
+    /// receiver doesn't depend on the current buffers.
+    async fn state_overrides(&self, request: &HookSimulationRequest) -> StateOverrides {
+        let mut overrides = StateOverrides::default();
+        if request.buy_token == BUY_ETH_ADDRESS.into_alloy() {
+            overrides.insert(
+                self.settlement.into_legacy(),
+                StateOverride {
+                    balance: Some(request.buy_amount.into_legacy()),
+                    ..Default::default()
+                },
+            );
+        } else if let Some((token, state_override)) = self
+            .balance_overrider
+            .state_override(BalanceOverrideRequest {
+                token: request.buy_token.into_legacy(),
+                holder: self.settlement.into_legacy(),
+                amount: request.buy_amount.into_legacy(),
+            })
+            .await
+        {
+            overrides.insert(token, state_override);
+        }
+        overrides
+    }
+}
+
+#[async_trait::async_trait]
+impl HookSimulating for Simulator {
+    #[instrument(skip_all)]
+    async fn simulate(
+        &self,
+        request: &HookSimulationRequest,
+    ) -> Result<HooksGas, HookSimulationError> {
+        let pre = request.hooks.pre.iter().map(|hook| self.hook_call(hook));
+        let trade = self.trade_calls(request);
+        let post = request.hooks.post.iter().map(|hook| self.hook_call(hook));
+        let trade_calls = trade.len();
+        let calls: Vec<_> = pre.chain(trade).chain(post).collect();
+        let expected_results = calls.len();
+
+        let payload = SimulatePayload {
+            block_state_calls: vec![SimBlock {
+                block_overrides: None,
+                state_overrides: Some(self.state_overrides(request).await.into_alloy()),
+                calls,
+            }],
+            trace_transfers: false,
+            validation: false,
+            return_full_transactions: false,
+        };
+        let block = self
+            .web3
+            .alloy
+            .simulate(&payload)
+            .await
+            .context("eth_simulateV1 failed")?
+            .into_iter()
+            .next()
+            .context("simulation returned no block")?;
+        if block.calls.len() != expected_results {
+            return Err(anyhow!(
+                "simulation returned {} results for {expected_results} calls",
+                block.calls.len()
+            )
+            .into());
+        }
+
+        let outcomes: Vec<_> = block
+            .calls
+            .iter()
+            .map(|call| Outcome {
+                success: call.status,
+                gas_used: call.gas_used,
+            })
+            .collect();
+        let (pre, rest) = outcomes.split_at(request.hooks.pre.len());
+        let (trade, post) = rest.split_at(trade_calls);
+        if trade.iter().any(|outcome| !outcome.success) {
+            // Transferability gets checked separately so this is not a reason
+            // to reject the order. Post-hooks relying on the proceeds might
+            // revert because of it though.
+            tracing::debug!(?request, "trade transfers failed in hook simulation");
+        }
+
+        let gas = HooksGas {
+            pre: measure(Execution::Pre, &request.hooks.pre, pre)?,
+            post: measure(Execution::Post, &request.hooks.post, post)?,
+        };
+        tracing::debug!(?gas, "simulated hooks");
+        Ok(gas)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Outcome {
+    success: bool,
+    gas_used: u64,
+}
+
+/// Checks the simulation outcome of every hook, which got simulated with its
+/// declared gas limit, and returns the gas the hooks need.
+fn measure(
+    execution: Execution,
+    hooks: &[Hook],
+    outcomes: &[Outcome],
+) -> Result<Vec<u64>, HookSimulationError> {
+    hooks
+        .iter()
+        .zip(outcomes)
+        .enumerate()
+        .map(|(index, (hook, outcome))| {
+            // The trampoline calls hooks from within the settlement, so they
+            // don't pay for the transaction overhead.
+            let intrinsic_gas = intrinsic_gas(&hook.call_data);
+            if !outcome.success {
+                // Running out of gas consumes all the gas of the call.
+                if outcome.gas_used >= intrinsic_gas.saturating_add(hook.gas_limit) {
+                    return Err(HookSimulationError::GasLimitExceeded {
+                        execution,
+                        index,
+                        gas_limit: hook.gas_limit,
+                    });
+                }
+                return Err(HookSimulationError::Reverted { execution, index });
+            }
+            // The reported gas is net of refunds which only get paid out at the
+            // end of the settlement. Refunds are capped at a fifth of the gas
+            // used before them (EIP-3529), which bounds the gas the hook needs.
+            let gas_used = outcome
+                .gas_used
+                .saturating_add(outcome.gas_used / 4)
+                .saturating_sub(intrinsic_gas)
+                .min(hook.gas_limit);
+            Ok(gas_used)
+        })
+        .collect()
+}
+
+/// Gas every transaction pays before executing any code.
+fn intrinsic_gas(call_data: &[u8]) -> u64 {
+    const TRANSACTION: u64 = 21_000;
+    const ZERO_BYTE: u64 = 4;
+    const NON_ZERO_BYTE: u64 = 16;
+
+    call_data.iter().fold(TRANSACTION, |gas, byte| {
+        gas + if *byte == 0 { ZERO_BYTE } else { NON_ZERO_BYTE }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hook(call_data: Vec<u8>, gas_limit: u64) -> Hook {
+        Hook {
+            target: Address::repeat_byte(1),
+            call_data,
+            gas_limit,
+        }
+    }
+
+    #[test]
+    fn computes_intrinsic_gas() {
+        assert_eq!(intrinsic_gas(&[]), 21_000);
+        assert_eq!(intrinsic_gas(&[0, 1, 0, 2]), 21_040);
+    }
+
+    #[test]
+    fn measures_hooks() {
+        let hooks = [hook(vec![], 50_000), hook(vec![1], 50_000)];
+        let outcome = |success, gas_used| Outcome { success, gas_used };
+
+        assert_eq!(
+            measure(
+                Execution::Pre,
+                &hooks,
+                &[outcome(true, 30_000), outcome(true, 60_000)]
+            )
+            .unwrap(),
+            vec![16_500, 50_000],
+        );
+        assert!(matches!(
+            measure(
+                Execution::Post,
+                &hooks,
+                &[outcome(true, 30_000), outcome(false, 30_000)]
+            ),
+            Err(HookSimulationError::Reverted {
+                execution: Execution::Post,
+                index: 1
+            })
+        ));
+        assert!(matches!(
+            measure(
+                Execution::Pre,
+                &hooks,
+                &[outcome(false, 71_000), outcome(true, 30_000)]
+            ),
+            Err(HookSimulationError::GasLimitExceeded {
+                execution: Execution::Pre,
+                index: 0,
+                gas_limit: 50_000,
+            })
+        ));
+    }
+}
@@ -19,6 +19,7 @@ pub mod external_prices;
 pub mod fee;
 pub mod gas_price;
 pub mod gas_price_estimation;
+pub mod hook_simulation;
 pub mod http_client;
 pub mod http_solver;
 pub mod interaction;
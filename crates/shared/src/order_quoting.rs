@@ -10,7 +10,7 @@ use {
         db_order_conversions::order_kind_from,
         fee::FeeParameters,
         order_validation::PreOrderData,
-        price_estimation::{Estimate, QuoteVerificationMode, Verification},
+        price_estimation::{Estimate, EstimateUpdate, QuoteVerificationMode, Verification},
         trade_finding::external::dto,
     },
     anyhow::{Context, Result},
@@ -18,7 +18,7 @@ use {
     database::quotes::{Quote as QuoteRow, QuoteKind},
     ethcontract::{H160, U256},
     ethrpc::alloy::conversions::IntoAlloy,
-    futures::TryFutureExt,
+    futures::{FutureExt, StreamExt, TryFutureExt, channel::mpsc},
     gas_estimation::GasPriceEstimating,
     model::{
         interaction::InteractionData,
@@ -243,6 +243,16 @@ pub trait OrderQuoting: Send + Sync {
         parameters: QuoteParameters,
     ) -> Result<Quote, CalculateQuoteError>;
 
+    /// Computes a quote like [`Self::calculate_quote`] but additionally sends
+    /// a preliminary quote to `progress` for the estimate of every price
+    /// source that arrives before the final quote is decided. Doesn't store
+    /// any quotes.
+    async fn calculate_quote_progressively(
+        &self,
+        parameters: QuoteParameters,
+        progress: mpsc::UnboundedSender<Quote>,
+    ) -> Result<Quote, CalculateQuoteError>;
+
     /// Stores a quote.
     async fn store_quote(&self, quote: Quote) -> Result<Quote>;
 
@@ -404,6 +414,12 @@ impl Default for Validity {
     }
 }
 
+/// Native prices needed to compute the fee of a quote.
+struct FeePrices {
+    gas_price: f64,
+    sell_token_price: f64,
+}
+
 /// An order quoter implementation that relies
 pub struct OrderQuoter {
     price_estimator: Arc<dyn PriceEstimating>,
@@ -445,6 +461,7 @@ impl OrderQuoter {
     async fn compute_quote_data(
         &self,
         parameters: &QuoteParameters,
+        progress: Option<&mpsc::UnboundedSender<Quote>>,
     ) -> Result<QuoteData, CalculateQuoteError> {
         let expiration = match parameters.signing_scheme {
             QuoteSigningScheme::Eip1271 {
@@ -458,27 +475,129 @@ impl OrderQuoter {
         };
 
         let trade_query = Arc::new(parameters.to_price_query(self.default_quote_timeout));
-        let (gas_estimate, trade_estimate, sell_token_price, _) = futures::try_join!(
+        let (fee_prices, trade_estimate) = match progress {
+            None => futures::try_join!(
+                self.fee_prices(parameters, trade_query.timeout),
+                self.price_estimator
+                    .estimate(trade_query.clone())
+                    .map_err(|err| (EstimatorKind::Regular, err).into()),
+            )?,
+            Some(progress) => {
+                self.estimate_with_progress(parameters, &trade_query, expiration, progress)
+                    .await?
+            }
+        };
+
+        let verified = trade_estimate.verified;
+        let quote = self.quote_data(
+            parameters,
+            &trade_query,
+            trade_estimate,
+            &fee_prices,
+            expiration,
+        );
+        self.verify_quote(verified, parameters, quote.quoted_sell_amount)
+            .await?;
+
+        Ok(quote)
+    }
+
+    /// Fetches the prices needed to compute the fee of a quote.
+    async fn fee_prices(
+        &self,
+        parameters: &QuoteParameters,
+        timeout: std::time::Duration,
+    ) -> Result<FeePrices, CalculateQuoteError> {
+        let (gas_estimate, sell_token_price, _) = futures::try_join!(
             self.gas_estimator
                 .estimate()
                 .map_err(|err| CalculateQuoteError::from((
                     EstimatorKind::Gas,
                     PriceEstimationError::ProtocolInternal(err)
                 ))),
-            self.price_estimator
-                .estimate(trade_query.clone())
-                .map_err(|err| (EstimatorKind::Regular, err).into()),
             self.native_price_estimator
-                .estimate_native_price(parameters.sell_token.into_alloy(), trade_query.timeout)
+                .estimate_native_price(parameters.sell_token.into_alloy(), timeout)
                 .map_err(|err| (EstimatorKind::NativeSell, err).into()),
             // We don't care about the native price of the buy_token for the quote but we need it
             // when we build the auction. To prevent creating orders which we can't settle later on
             // we make the native buy_token price a requirement here as well.
             self.native_price_estimator
-                .estimate_native_price(parameters.buy_token.into_alloy(), trade_query.timeout)
+                .estimate_native_price(parameters.buy_token.into_alloy(), timeout)
                 .map_err(|err| (EstimatorKind::NativeBuy, err).into()),
         )?;
+        Ok(FeePrices {
+            gas_price: gas_estimate.effective_gas_price(),
+            sell_token_price,
+        })
+    }
+
+    /// Estimates the price of the trade and sends a preliminary quote to
+    /// `progress` for every estimate of an individual price source that
+    /// arrives before the final estimate. Preliminary estimates that arrive
+    /// before the fee prices are known get reported once they are.
+    async fn estimate_with_progress(
+        &self,
+        parameters: &QuoteParameters,
+        trade_query: &Arc<price_estimation::Query>,
+        expiration: DateTime<Utc>,
+        progress: &mpsc::UnboundedSender<Quote>,
+    ) -> Result<(FeePrices, Estimate), CalculateQuoteError> {
+        let report = |estimate: Estimate, fee_prices: &FeePrices| {
+            let data = self.quote_data(parameters, trade_query, estimate, fee_prices, expiration);
+            match self.quote_from_data(parameters, data) {
+                Ok(quote) => {
+                    let _ = progress.unbounded_send(quote);
+                }
+                Err(err) => tracing::debug!(?err, "failed to compute preliminary quote"),
+            }
+        };
+
+        let fee_prices = self.fee_prices(parameters, trade_query.timeout).fuse();
+        let mut updates = self
+            .price_estimator
+            .estimate_progressively(trade_query.clone())
+            .fuse();
+        futures::pin_mut!(fee_prices);
+        let mut known_fee_prices = None;
+        let mut pending = Vec::new();
+        loop {
+            futures::select! {
+                prices = fee_prices => {
+                    let prices = prices?;
+                    for estimate in pending.drain(..) {
+                        report(estimate, &prices);
+                    }
+                    known_fee_prices = Some(prices);
+                }
+                update = updates.next() => match update {
+                    Some(EstimateUpdate::Preliminary(estimate)) => match &known_fee_prices {
+                        Some(prices) => report(estimate, prices),
+                        None => pending.push(estimate),
+                    },
+                    Some(EstimateUpdate::Final(result)) => {
+                        let estimate = result.map_err(|err| (EstimatorKind::Regular, err))?;
+                        let prices = match known_fee_prices {
+                            Some(prices) => prices,
+                            None => fee_prices.await?,
+                        };
+                        return Ok((prices, estimate));
+                    }
+                    None => {
+                        return Err(anyhow::anyhow!("price estimator reported no final estimate").into());
+                    }
+                },
+            }
+        }
+    }
 
+    fn quote_data(
+        &self,
+        parameters: &QuoteParameters,
+        trade_query: &price_estimation::Query,
+        trade_estimate: Estimate,
+        fee_prices: &FeePrices,
+        expiration: DateTime<Utc>,
+    ) -> QuoteData {
         let (quoted_sell_amount, quoted_buy_amount) = match &parameters.side {
             OrderQuoteSide::Sell {
                 sell_amount: SellAmount::BeforeFee { value: sell_amount },
@@ -492,15 +611,12 @@ impl OrderQuoter {
         };
         let fee_parameters = FeeParameters {
             gas_amount: trade_estimate.gas as _,
-            gas_price: gas_estimate.effective_gas_price(),
-            sell_token_price,
+            gas_price: fee_prices.gas_price,
+            sell_token_price: fee_prices.sell_token_price,
         };
 
-        self.verify_quote(&trade_estimate, parameters, quoted_sell_amount)
-            .await?;
-
         let quote_kind = quote_kind_from_signing_scheme(&parameters.signing_scheme);
-        let quote = QuoteData {
+        QuoteData {
             sell_token: parameters.sell_token,
             buy_token: parameters.buy_token,
             quoted_sell_amount,
@@ -517,7 +633,38 @@ impl OrderQuoter {
                 jit_orders: trade_estimate.execution.jit_orders,
             }
             .into(),
-        };
+        }
+    }
+
+    /// Builds the quote for the computed quote data.
+    fn quote_from_data(
+        &self,
+        parameters: &QuoteParameters,
+        data: QuoteData,
+    ) -> Result<Quote, CalculateQuoteError> {
+        let mut quote =
+            Quote::new(Default::default(), data).with_additional_cost(parameters.additional_cost());
+
+        // Make sure to scale the sell and buy amounts for quotes for sell
+        // amounts before fees.
+        if let OrderQuoteSide::Sell {
+            sell_amount:
+                SellAmount::BeforeFee {
+                    value: sell_amount_before_fee,
+                },
+        } = &parameters.side
+        {
+            let sell_amount =
+                Into::<U256>::into(*sell_amount_before_fee).saturating_sub(quote.fee_amount);
+            if sell_amount == U256::zero() {
+                // We want a sell_amount of at least 1!
+                return Err(CalculateQuoteError::SellAmountDoesNotCoverFee {
+                    fee_amount: quote.fee_amount,
+                });
+            }
+
+            quote = quote.with_scaled_sell_amount(sell_amount);
+        }
 
         Ok(quote)
     }
@@ -525,11 +672,11 @@ impl OrderQuoter {
     /// Makes sure a quote was verified according to the configured rule.
     async fn verify_quote(
         &self,
-        estimate: &Estimate,
+        verified: bool,
         parameters: &QuoteParameters,
         sell_amount: U256,
     ) -> Result<(), CalculateQuoteError> {
-        if estimate.verified
+        if verified
             || !matches!(
                 self.quote_verification,
                 QuoteVerificationMode::EnforceWhenPossible
@@ -588,31 +735,22 @@ impl OrderQuoting for OrderQuoter {
         &self,
         parameters: QuoteParameters,
     ) -> Result<Quote, CalculateQuoteError> {
-        let data = self.compute_quote_data(&parameters).await?;
-        let mut quote =
-            Quote::new(Default::default(), data).with_additional_cost(parameters.additional_cost());
-
-        // Make sure to scale the sell and buy amounts for quotes for sell
-        // amounts before fees.
-        if let OrderQuoteSide::Sell {
-            sell_amount:
-                SellAmount::BeforeFee {
-                    value: sell_amount_before_fee,
-                },
-        } = &parameters.side
-        {
-            let sell_amount =
-                Into::<U256>::into(*sell_amount_before_fee).saturating_sub(quote.fee_amount);
-            if sell_amount == U256::zero() {
-                // We want a sell_amount of at least 1!
-                return Err(CalculateQuoteError::SellAmountDoesNotCoverFee {
-                    fee_amount: quote.fee_amount,
-                });
-            }
-
-            quote = quote.with_scaled_sell_amount(sell_amount);
-        }
+        let data = self.compute_quote_data(&parameters, None).await?;
+        let quote = self.quote_from_data(&parameters, data)?;
+        tracing::debug!(?quote, "computed quote");
+        Ok(quote)
+    }
 
+    #[instrument(skip_all)]
+    async fn calculate_quote_progressively(
+        &self,
+        parameters: QuoteParameters,
+        progress: mpsc::UnboundedSender<Quote>,
+    ) -> Result<Quote, CalculateQuoteError> {
+        let data = self
+            .compute_quote_data(&parameters, Some(&progress))
+            .await?;
+        let quote = self.quote_from_data(&parameters, data)?;
         tracing::debug!(?quote, "computed quote");
         Ok(quote)
     }
@@ -3,6 +3,7 @@ use {
         account_balances::{self, BalanceFetching, TransferSimulationError},
         bad_token::{BadTokenDetecting, TokenQuality},
         code_fetching::CodeFetching,
+        hook_simulation::{HookSimulating, HookSimulationError, HookSimulationRequest},
         order_quoting::{
             CalculateQuoteError,
             OrderQuoting,
@@ -18,11 +19,11 @@ use {
         signature_validator::{SignatureCheck, SignatureValidating, SignatureValidationError},
         trade_finding,
     },
-    alloy::primitives::Address,
-    anyhow::{Result, anyhow},
-    app_data::{AppDataHash, Hook, Hooks, ValidatedAppData, Validator},
+    alloy::{primitives::Address, sol_types::SolCall},
+    anyhow::{Result, anyhow, ensure},
+    app_data::{AppDataHash, Hook, Hooks, Permit, ValidatedAppData, Validator},
     async_trait::async_trait,
-    contracts::alloy::{HooksTrampoline, WETH9},
+    contracts::alloy::{HooksTrampoline, IERC20Permit, WETH9},
     ethcontract::{H160, H256, U256},
     ethrpc::alloy::conversions::{IntoAlloy, IntoLegacy},
     model::{
@@ -32,6 +33,7 @@ use {
             AppdataFromMismatch,
             BUY_ETH_ADDRESS,
             BuyTokenDestination,
+            HooksGas,
             Interactions,
             Order,
             OrderClass,
@@ -40,13 +42,15 @@ use {
             OrderData,
             OrderKind,
             OrderMetadata,
+            OrderUid,
             SellTokenSource,
             VerificationError,
         },
-        quote::{OrderQuoteSide, QuoteSigningScheme, SellAmount},
+        quote::{OrderQuoteSide, QuoteId, QuoteSigningScheme, SellAmount},
         signature::{self, Signature, SigningScheme, hashed_eip712_message},
         time,
     },
+    order_validation::limits,
     std::{sync::Arc, time::Duration},
     tracing::instrument,
 };
@@ -95,13 +99,37 @@ pub trait OrderValidating: Send + Sync {
     /// `full_app_data_override` is used as the full app data and the contract
     /// app data hash is not validated against it (the hash doesn't have to
     /// match). The full app data is still otherwise validated.
+    ///
+    /// `requester` identifies who submitted the order. Its API key is used to
+    /// determine which limits apply to the order owner. Pre-signed orders and
+    /// EIP-1271 orders whose signature doesn't get verified aren't proven to
+    /// come from their owner so they are rate limited per requester instead
+    /// of per owner.
     async fn validate_and_construct_order(
         &self,
         order: OrderCreation,
         domain_separator: &DomainSeparator,
         settlement_contract: H160,
         full_app_data_override: Option<String>,
+        requester: Option<&limits::Requester>,
     ) -> Result<(Order, Option<Quote>), ValidationError>;
+
+    /// Runs the checks of [`Self::validate_and_construct_order`] without
+    /// placing the order or counting it towards the owner's rate limit.
+    /// Instead of stopping at the first failure every check is performed and
+    /// its outcome reported. Checks that depend on a failed check are skipped.
+    ///
+    /// Since explanations don't have to be validly signed they are rate
+    /// limited per `requester` instead of per owner and fail with
+    /// [`ValidationError::TooManyOrders`] once the requester exceeds the
+    /// `orders_per_minute` of its tier.
+    async fn explain_order(
+        &self,
+        order: OrderCreation,
+        domain_separator: &DomainSeparator,
+        full_app_data_override: Option<String>,
+        requester: Option<&limits::Requester>,
+    ) -> Result<Explanation, ValidationError>;
 }
 
 #[derive(Debug)]
@@ -159,8 +187,16 @@ pub enum ValidationError {
     ZeroAmount,
     IncompatibleSigningScheme,
     TooManyLimitOrders,
+    /// The owner placed more orders within the last minute than allowed.
+    TooManyOrders,
     TooMuchGas,
     QuoteNotVerified,
+    /// The TWAP parameters in the app data are invalid for the order.
+    InvalidTwap(anyhow::Error),
+    /// The permit in the app data can not be used to approve the order.
+    InvalidPermit(anyhow::Error),
+    /// A hook of the order reverts or uses more gas than it declared.
+    InvalidHooks(anyhow::Error),
     Other(anyhow::Error),
 }
 
@@ -209,6 +245,105 @@ impl From<CalculateQuoteError> for ValidationError {
     }
 }
 
+/// The outcome of every check performed by [`OrderValidating::explain_order`].
+#[derive(Debug, Default)]
+pub struct Explanation {
+    /// The owner recovered from the order's signature.
+    pub owner: Option<H160>,
+    pub uid: Option<OrderUid>,
+    /// Results of the checks in the order they were performed.
+    pub checks: Vec<CheckResult>,
+}
+
+impl Explanation {
+    /// Whether the order would have been accepted.
+    pub fn is_valid(&self) -> bool {
+        self.checks
+            .iter()
+            .all(|check| !matches!(check.outcome, CheckOutcome::Failed(_)))
+    }
+
+    fn record(&mut self, check: Check, outcome: impl Into<CheckOutcome>) {
+        self.checks.push(CheckResult {
+            check,
+            outcome: outcome.into(),
+        });
+    }
+
+    /// Marks all checks that were not performed yet as skipped.
+    fn skip_remaining(mut self) -> Self {
+        for check in Check::ALL {
+            if !self.checks.iter().any(|result| result.check == check) {
+                self.record(check, CheckOutcome::Skipped);
+            }
+        }
+        self
+    }
+}
+
+/// An individual check of the order validation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, strum::Display)]
+#[strum(serialize_all = "camelCase")]
+pub enum Check {
+    AppData,
+    Signature,
+    Permit,
+    Eip1271Signature,
+    Amounts,
+    Twap,
+    OrderParameters,
+    Transferability,
+    Hooks,
+    Quote,
+    LimitOrders,
+    Gas,
+}
+
+impl Check {
+    const ALL: [Self; 12] = [
+        Self::AppData,
+        Self::Signature,
+        Self::Permit,
+        Self::Eip1271Signature,
+        Self::Amounts,
+        Self::Twap,
+        Self::OrderParameters,
+        Self::Transferability,
+        Self::Hooks,
+        Self::Quote,
+        Self::LimitOrders,
+        Self::Gas,
+    ];
+}
+
+#[derive(Debug)]
+pub struct CheckResult {
+    pub check: Check,
+    pub outcome: CheckOutcome,
+}
+
+#[derive(Debug)]
+pub enum CheckOutcome {
+    /// The check passed. Might contain details about what the check found.
+    Passed(Option<String>),
+    /// The order would be rejected with this error.
+    Failed(ValidationError),
+    /// The check could not be performed because a check it depends on failed.
+    Skipped,
+    /// The check does not apply to the order, e.g. EIP-1271 signature
+    /// verification for an ECDSA signed order.
+    NotApplicable,
+}
+
+impl From<Result<(), ValidationError>> for CheckOutcome {
+    fn from(result: Result<(), ValidationError>) -> Self {
+        match result {
+            Ok(()) => Self::Passed(None),
+            Err(err) => Self::Failed(err),
+        }
+    }
+}
+
 #[cfg_attr(any(test, feature = "test-util"), mockall::automock)]
 #[async_trait]
 pub trait LimitOrderCounting: Send + Sync {
@@ -225,15 +360,20 @@ pub struct OrderValidator {
     eip1271_skip_creation_validation: bool,
     bad_token_detector: Arc<dyn BadTokenDetecting>,
     hooks: HooksTrampoline::Instance,
+    vault_relayer: H160,
     /// For Full-Validation: performed time of order placement
     quoter: Arc<dyn OrderQuoting>,
     balance_fetcher: Arc<dyn BalanceFetching>,
     signature_validator: Arc<dyn SignatureValidating>,
     limit_order_counter: Arc<dyn LimitOrderCounting>,
-    max_limit_orders_per_user: u64,
+    limits: Arc<limits::Tiers>,
+    order_rate_limiter: limits::RateLimiter,
+    presign_rate_limiter: limits::RateLimiter<limits::Requester>,
+    explain_rate_limiter: limits::RateLimiter<limits::Requester>,
     pub code_fetcher: Arc<dyn CodeFetching>,
     app_data_validator: Validator,
     max_gas_per_order: u64,
+    hook_simulator: Option<Arc<dyn HookSimulating>>,
 }
 
 #[derive(Debug, Eq, PartialEq, Default)]
@@ -297,11 +437,12 @@ impl OrderValidator {
         eip1271_skip_creation_validation: bool,
         bad_token_detector: Arc<dyn BadTokenDetecting>,
         hooks: HooksTrampoline::Instance,
+        vault_relayer: H160,
         quoter: Arc<dyn OrderQuoting>,
         balance_fetcher: Arc<dyn BalanceFetching>,
         signature_validator: Arc<dyn SignatureValidating>,
         limit_order_counter: Arc<dyn LimitOrderCounting>,
-        max_limit_orders_per_user: u64,
+        limits: Arc<limits::Tiers>,
         code_fetcher: Arc<dyn CodeFetching>,
         app_data_validator: Validator,
         max_gas_per_order: u64,
@@ -313,24 +454,39 @@ impl OrderValidator {
             eip1271_skip_creation_validation,
             bad_token_detector,
             hooks,
+            vault_relayer,
             quoter,
             balance_fetcher,
             signature_validator,
             limit_order_counter,
-            max_limit_orders_per_user,
+            limits,
+            order_rate_limiter: Default::default(),
+            presign_rate_limiter: Default::default(),
+            explain_rate_limiter: Default::default(),
             code_fetcher,
             app_data_validator,
             max_gas_per_order,
+            hook_simulator: None,
         }
     }
 
-    async fn check_max_limit_orders(&self, owner: H160) -> Result<(), ValidationError> {
+    /// Enables simulating the hooks of orders on placement.
+    pub fn with_hook_simulator(mut self, simulator: Arc<dyn HookSimulating>) -> Self {
+        self.hook_simulator = Some(simulator);
+        self
+    }
+
+    async fn check_max_limit_orders(
+        &self,
+        owner: H160,
+        limits: &limits::Limits,
+    ) -> Result<(), ValidationError> {
         let num_limit_orders = self
             .limit_order_counter
             .count(owner)
             .await
             .map_err(ValidationError::Other)?;
-        if num_limit_orders >= self.max_limit_orders_per_user {
+        if num_limit_orders >= limits.max_limit_orders {
             return Err(ValidationError::TooManyLimitOrders);
         }
         Ok(())
@@ -375,6 +531,77 @@ impl OrderValidator {
         }
     }
 
+    /// Builds the pre-hook executing the order's EIP-2612 permit which
+    /// approves the vault relayer to spend the sell token.
+    fn permit_hook(&self, permit: &Permit, order: &OrderData, owner: H160) -> Result<Hook> {
+        let (v, r, s) = permit.signature_parts()?;
+        ensure!(
+            order.sell_token_balance == SellTokenSource::Erc20,
+            "permits can only be used for ERC20 sell token balances"
+        );
+        ensure!(
+            permit.deadline >= alloy::primitives::U256::from(order.valid_to),
+            "permit expires before the order"
+        );
+        ensure!(
+            permit.value >= order.sell_amount.saturating_add(order.fee_amount),
+            "permit value does not cover the sell amount"
+        );
+        let call_data = IERC20Permit::IERC20Permit::permitCall {
+            owner: owner.into_alloy(),
+            spender: self.vault_relayer.into_alloy(),
+            value: permit.value,
+            deadline: permit.deadline,
+            v,
+            r,
+            s,
+        }
+        .abi_encode();
+        Ok(Hook {
+            target: order.sell_token,
+            call_data,
+            gas_limit: Permit::GAS_LIMIT,
+        })
+    }
+
+    /// Simulates all hooks of the order (i.e. including the hook executing the
+    /// permit as returned by [`Self::add_permit_hook`]) and returns the gas
+    /// they used. Returns `None` if there is nothing to simulate or the
+    /// simulation itself failed, since that is no reason to reject the order.
+    async fn simulate_hooks(
+        &self,
+        data: &OrderData,
+        owner: H160,
+        hooks: &Hooks,
+    ) -> Result<Option<HooksGas>, ValidationError> {
+        let Some(simulator) = &self.hook_simulator else {
+            return Ok(None);
+        };
+        if hooks.pre.is_empty() && hooks.post.is_empty() {
+            return Ok(None);
+        }
+
+        let request = HookSimulationRequest {
+            owner: owner.into_alloy(),
+            receiver: actual_receiver(owner, data).into_alloy(),
+            sell_token: data.sell_token,
+            buy_token: data.buy_token,
+            sell_amount: data.sell_amount,
+            buy_amount: data.buy_amount,
+            sell_token_source: data.sell_token_balance,
+            buy_token_destination: data.buy_token_balance,
+            hooks: hooks.clone(),
+        };
+        match simulator.simulate(&request).await {
+            Ok(gas) => Ok(Some(gas)),
+            Err(HookSimulationError::Other(err)) => {
+                tracing::warn!(?err, "failed to simulate hooks");
+                Ok(None)
+            }
+            Err(err) => Err(ValidationError::InvalidHooks(err.into())),
+        }
+    }
+
     /// Verifies that tokens can actually be transferred from the user account
     /// to the settlement contract (takes pre-hooks into account).
     async fn ensure_token_is_transferable(
@@ -451,18 +678,300 @@ impl OrderValidator {
 
         res
     }
+
+    /// Returns the limits that apply to the client placing the order.
+    fn client_limits(
+        &self,
+        owner: H160,
+        order: &OrderCreation,
+        app_data: &OrderAppData,
+        full_app_data_override: Option<&str>,
+        api_key: Option<&str>,
+    ) -> limits::Limits {
+        let full_app_data = match &order.app_data {
+            OrderCreationAppData::Hash { .. } => full_app_data_override,
+            _ => Some(app_data.inner.document.as_str()),
+        };
+        let app_code = full_app_data.and_then(|full| app_data::app_code(full.as_bytes()));
+        *self.limits.limits(limits::Client {
+            owner: owner.into_alloy(),
+            app_code: app_code.as_deref(),
+            api_key,
+        })
+    }
+
+    /// Adds the pre-hook executing the order's permit (if any) to the
+    /// interactions of the app data and returns all hooks of the order.
+    fn add_permit_hook(
+        &self,
+        app_data: &mut OrderAppData,
+        data: &OrderData,
+        owner: H160,
+    ) -> Result<Hooks, ValidationError> {
+        // The permit is executed before all other pre-hooks because they might
+        // already rely on the allowance. Since the hooks trampoline ignores
+        // reverting hooks an already used permit does not block the settlement
+        // and an invalid one surfaces as missing allowance in the transfer
+        // simulation.
+        let mut hooks = app_data.inner.protocol.hooks.clone();
+        if let Some(permit) = &app_data.inner.protocol.permit {
+            let hook = self
+                .permit_hook(permit, data, owner)
+                .map_err(ValidationError::InvalidPermit)?;
+            hooks.pre.insert(0, hook);
+            app_data.interactions = self.custom_interactions(&hooks);
+        }
+        Ok(hooks)
+    }
+
+    /// Verifies the signature of EIP-1271 orders and returns the gas needed
+    /// for the verification. Returns 0 for all other signing schemes.
+    async fn verify_eip1271_signature(
+        &self,
+        order: &OrderCreation,
+        data: &OrderData,
+        owner: H160,
+        app_data: &OrderAppData,
+        domain_separator: &DomainSeparator,
+    ) -> Result<u64, ValidationError> {
+        let Signature::Eip1271(signature) = &order.signature else {
+            // in any other case, just apply 0
+            return Ok(0);
+        };
+        if self.eip1271_skip_creation_validation {
+            tracing::debug!(?signature, "skipping EIP-1271 signature validation");
+            // We don't care! Because we are skipping validation anyway
+            return Ok(0);
+        }
+
+        let hash = hashed_eip712_message(domain_separator, &data.hash_struct());
+        self.signature_validator
+            .validate_signature_and_get_additional_gas(SignatureCheck {
+                signer: owner,
+                hash,
+                signature: signature.to_owned(),
+                interactions: app_data.interactions.pre.clone(),
+                balance_override: app_data.inner.protocol.flashloan.as_ref().map(|loan| {
+                    BalanceOverrideRequest {
+                        token: loan.token.into_legacy(),
+                        holder: loan.receiver.into_legacy(),
+                        amount: loan.amount.into_legacy(),
+                    }
+                }),
+            })
+            .await
+            .map_err(|err| match err {
+                SignatureValidationError::Invalid => {
+                    ValidationError::InvalidEip1271Signature(H256(hash))
+                }
+                SignatureValidationError::Other(err) => ValidationError::Other(err),
+            })
+    }
+
+    /// Fetches a quote for the order and uses it to classify the order.
+    /// Freshly calculated quotes are only stored if `persist` is set.
+    ///
+    /// Check if we need to re-classify the market order if it is outside the
+    /// market price. We consider out-of-price orders as liquidity orders. See
+    /// <https://github.com/cowprotocol/services/pull/301>.
+    async fn classify(
+        &self,
+        class: OrderClass,
+        data: &OrderData,
+        quote_id: Option<QuoteId>,
+        quote_parameters: &QuoteSearchParameters,
+        persist: bool,
+    ) -> Result<Classification, ValidationError> {
+        let is_outside_market_price = |quote: &Quote| {
+            is_order_outside_market_price(
+                &Amounts {
+                    sell: data.sell_amount.into_legacy(),
+                    buy: data.buy_amount.into_legacy(),
+                    fee: data.fee_amount.into_legacy(),
+                },
+                &Amounts {
+                    sell: quote.sell_amount,
+                    buy: quote.buy_amount,
+                    fee: quote.fee_amount,
+                },
+                data.kind,
+            )
+        };
+
+        let classification = match class {
+            // This has to be here in order to keep the previous behaviour
+            OrderClass::Market => {
+                let quote = get_quote_and_check_fee(
+                    &*self.quoter,
+                    quote_parameters,
+                    quote_id,
+                    Some(data.fee_amount.into_legacy()),
+                    persist,
+                )
+                .await?;
+                tracing::debug!(?data, ?quote, "checking if order is outside market price");
+                let class = if is_outside_market_price(&quote) {
+                    tracing::debug!(?class, "order being flagged as outside market price");
+                    OrderClass::Limit
+                } else {
+                    class
+                };
+                Classification {
+                    class,
+                    quote: Some(quote),
+                    counts_as_limit_order: false,
+                }
+            }
+            OrderClass::Limit => {
+                match get_quote_and_check_fee(
+                    &*self.quoter,
+                    quote_parameters,
+                    quote_id,
+                    None,
+                    persist,
+                )
+                .await
+                {
+                    // If the order is not "In-Market", check for the limit orders
+                    Ok(quote) => Classification {
+                        class,
+                        counts_as_limit_order: is_outside_market_price(&quote),
+                        quote: Some(quote),
+                    },
+                    // If there is not enough liquidity, it's still possible to place this order (as
+                    // an implicit out of market order)
+                    Err(ValidationError::PriceForQuote(PriceEstimationError::NoLiquidity)) => {
+                        tracing::debug!("placing order without quote");
+                        Classification {
+                            class,
+                            quote: None,
+                            counts_as_limit_order: false,
+                        }
+                    }
+                    Err(other) => return Err(other),
+                }
+            }
+            OrderClass::Liquidity => {
+                let quote = get_quote_and_check_fee(
+                    &*self.quoter,
+                    quote_parameters,
+                    quote_id,
+                    None,
+                    persist,
+                )
+                .await?;
+                // If the order is not "In-Market", check for the limit orders
+                Classification {
+                    class: OrderClass::Limit,
+                    quote: None,
+                    counts_as_limit_order: is_outside_market_price(&quote),
+                }
+            }
+        };
+        Ok(classification)
+    }
+
+    fn check_gas(
+        &self,
+        quote: Option<&Quote>,
+        quote_parameters: &QuoteSearchParameters,
+    ) -> Result<(), ValidationError> {
+        if quote.is_some_and(|quote| {
+            // Quoted gas does not include additional gas for hooks nor ERC1271 signatures
+            quote.data.fee_parameters.gas_amount as u64 + quote_parameters.additional_cost()
+                > self.max_gas_per_order
+        }) {
+            return Err(ValidationError::TooMuchGas);
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of classifying an order with its quote.
+struct Classification {
+    class: OrderClass,
+    quote: Option<Quote>,
+    /// Whether the order is outside the market price and therefore counts
+    /// towards the owner's limit of open limit orders.
+    counts_as_limit_order: bool,
+}
+
+fn check_amounts(data: &OrderData, app_data: &OrderAppData) -> Result<(), ValidationError> {
+    if data.buy_amount.is_zero() || data.sell_amount.is_zero() {
+        return Err(ValidationError::ZeroAmount);
+    }
+    if let Some(trigger) = &app_data.inner.protocol.trigger
+        && (trigger.sell_amount.is_zero() || trigger.buy_amount.is_zero())
+    {
+        return Err(ValidationError::ZeroAmount);
+    }
+    Ok(())
+}
+
+fn check_twap(data: &OrderData, app_data: &OrderAppData) -> Result<(), ValidationError> {
+    let Some(twap) = &app_data.inner.protocol.twap else {
+        return Ok(());
+    };
+    if !data.partially_fillable {
+        return Err(ValidationError::InvalidTwap(anyhow!(
+            "TWAP orders must be partially fillable"
+        )));
+    }
+    let start_time = twap.start_time.unwrap_or_else(time::now_in_epoch_seconds);
+    twap.validate(start_time, data.valid_to)
+        .map_err(ValidationError::InvalidTwap)
+}
+
+fn quote_parameters(
+    order: &OrderCreation,
+    data: &OrderData,
+    owner: H160,
+    app_data: &OrderAppData,
+    hooks: &Hooks,
+    verification_gas_limit: u64,
+) -> Result<QuoteSearchParameters, ValidationError> {
+    let verification = Verification {
+        from: owner,
+        receiver: order.receiver.unwrap_or(owner),
+        sell_token_source: order.sell_token_balance,
+        buy_token_destination: order.buy_token_balance,
+        pre_interactions: trade_finding::map_interactions(&app_data.interactions.pre),
+        post_interactions: trade_finding::map_interactions(&app_data.interactions.post),
+    };
+
+    Ok(QuoteSearchParameters {
+        sell_token: data.sell_token.into_legacy(),
+        buy_token: data.buy_token.into_legacy(),
+        sell_amount: data.sell_amount.into_legacy(),
+        buy_amount: data.buy_amount.into_legacy(),
+        fee_amount: data.fee_amount.into_legacy(),
+        kind: data.kind,
+        signing_scheme: convert_signing_scheme_into_quote_signing_scheme(
+            order.signature.scheme(),
+            true,
+            verification_gas_limit,
+        )
+        .map_err(|_| ValidationError::InvalidSignature)?,
+        additional_gas: hooks.gas_limit(),
+        verification,
+    })
 }
 
 #[async_trait::async_trait]
 impl OrderValidating for OrderValidator {
     #[instrument(skip_all)]
     async fn partial_validate(&self, order: PreOrderData) -> Result<(), PartialValidationError> {
-        if !self
+        let banned = self
             .banned_users
             .banned([order.receiver.into_alloy(), order.owner.into_alloy()])
-            .await
-            .is_empty()
-        {
+            .await;
+        if !banned.is_empty() {
+            tracing::info!(
+                owner = ?order.owner,
+                receiver = ?order.receiver,
+                ?banned,
+                "rejecting order of banned user"
+            );
             return Err(PartialValidationError::Forbidden);
         }
 
@@ -576,14 +1085,23 @@ impl OrderValidating for OrderValidator {
         domain_separator: &DomainSeparator,
         settlement_contract: H160,
         full_app_data_override: Option<String>,
+        requester: Option<&limits::Requester>,
     ) -> Result<(Order, Option<Quote>), ValidationError> {
         // Happens before signature verification because a miscalculated app data hash
         // by the API user would lead to being unable to validate the signature below.
-        let app_data = self.validate_app_data(&order.app_data, &full_app_data_override)?;
+        let mut app_data = self.validate_app_data(&order.app_data, &full_app_data_override)?;
         let app_data_signer = app_data.inner.protocol.signer.map(IntoLegacy::into_legacy);
 
         let owner = order.verify_owner(domain_separator, app_data_signer)?;
         tracing::debug!(?owner, "recovered owner from order and signature");
+
+        let limits = self.client_limits(
+            owner,
+            &order,
+            &app_data,
+            full_app_data_override.as_deref(),
+            requester.and_then(limits::Requester::api_key),
+        );
         let signing_scheme = order.signature.scheme();
         let data = OrderData {
             app_data: app_data.inner.hash,
@@ -591,43 +1109,36 @@ impl OrderValidating for OrderValidator {
         };
         let uid = data.uid(domain_separator, &owner);
 
-        let verification_gas_limit = if let Signature::Eip1271(signature) = &order.signature {
-            if self.eip1271_skip_creation_validation {
-                tracing::debug!(?signature, "skipping EIP-1271 signature validation");
-                // We don't care! Because we are skipping validation anyway
-                0u64
-            } else {
-                let hash = hashed_eip712_message(domain_separator, &data.hash_struct());
-                self.signature_validator
-                    .validate_signature_and_get_additional_gas(SignatureCheck {
-                        signer: owner,
-                        hash,
-                        signature: signature.to_owned(),
-                        interactions: app_data.interactions.pre.clone(),
-                        balance_override: app_data.inner.protocol.flashloan.as_ref().map(|loan| {
-                            BalanceOverrideRequest {
-                                token: loan.token.into_legacy(),
-                                holder: loan.receiver.into_legacy(),
-                                amount: loan.amount.into_legacy(),
-                            }
-                        }),
-                    })
-                    .await
-                    .map_err(|err| match err {
-                        SignatureValidationError::Invalid => {
-                            ValidationError::InvalidEip1271Signature(H256(hash))
-                        }
-                        SignatureValidationError::Other(err) => ValidationError::Other(err),
-                    })?
-            }
+        let hooks = self.add_permit_hook(&mut app_data, &data, owner)?;
+        let verification_gas_limit = self
+            .verify_eip1271_signature(&order, &data, owner, &app_data, domain_separator)
+            .await?;
+        // Only charge the owner once it is proven that the order was really
+        // signed by it. Otherwise anybody could exhaust the limit of an owner.
+        // Pre-signed orders only get approved by the owner on-chain after they
+        // were placed and EIP-1271 signatures might not get verified, so these
+        // orders are charged to whoever submitted them.
+        let signed_by_owner = match signing_scheme {
+            SigningScheme::PreSign => false,
+            SigningScheme::Eip1271 => !self.eip1271_skip_creation_validation,
+            SigningScheme::Eip712 | SigningScheme::EthSign => true,
+        };
+        let allowed = if signed_by_owner {
+            self.order_rate_limiter
+                .try_acquire(owner.into_alloy(), limits.orders_per_minute)
         } else {
-            // in any other case, just apply 0
-            0u64
+            requester.is_none_or(|requester| {
+                self.presign_rate_limiter.try_acquire(
+                    requester.clone(),
+                    self.limits.requester_limits(requester).orders_per_minute,
+                )
+            })
         };
-
-        if data.buy_amount.is_zero() || data.sell_amount.is_zero() {
-            return Err(ValidationError::ZeroAmount);
+        if !allowed {
+            return Err(ValidationError::TooManyOrders);
         }
+        check_amounts(&data, &app_data)?;
+        check_twap(&data, &app_data)?;
 
         let pre_order = PreOrderData::from_order_creation(owner, &data, signing_scheme);
         let class = pre_order.class;
@@ -635,141 +1146,28 @@ impl OrderValidating for OrderValidator {
             .await
             .map_err(ValidationError::Partial)?;
 
-        let verification = Verification {
-            from: owner,
-            receiver: order.receiver.unwrap_or(owner),
-            sell_token_source: order.sell_token_balance,
-            buy_token_destination: order.buy_token_balance,
-            pre_interactions: trade_finding::map_interactions(&app_data.interactions.pre),
-            post_interactions: trade_finding::map_interactions(&app_data.interactions.post),
-        };
-
-        let quote_parameters = QuoteSearchParameters {
-            sell_token: data.sell_token.into_legacy(),
-            buy_token: data.buy_token.into_legacy(),
-            sell_amount: data.sell_amount.into_legacy(),
-            buy_amount: data.buy_amount.into_legacy(),
-            fee_amount: data.fee_amount.into_legacy(),
-            kind: data.kind,
-            signing_scheme: convert_signing_scheme_into_quote_signing_scheme(
-                order.signature.scheme(),
-                true,
-                verification_gas_limit,
-            )
-            .map_err(|_| ValidationError::InvalidSignature)?,
-            additional_gas: app_data.inner.protocol.hooks.gas_limit(),
-            verification,
-        };
+        let quote_parameters = quote_parameters(
+            &order,
+            &data,
+            owner,
+            &app_data,
+            &hooks,
+            verification_gas_limit,
+        )?;
 
         self.ensure_token_is_transferable(&order, owner, &app_data)
             .await?;
+        let hooks_gas = self.simulate_hooks(&data, owner, &hooks).await?;
 
-        // Check if we need to re-classify the market order if it is outside the market
-        // price. We consider out-of-price orders as liquidity orders. See
-        // <https://github.com/cowprotocol/services/pull/301>.
-        let (class, quote) = match class {
-            // This has to be here in order to keep the previous behaviour
-            OrderClass::Market => {
-                let quote = get_quote_and_check_fee(
-                    &*self.quoter,
-                    &quote_parameters,
-                    order.quote_id,
-                    Some(data.fee_amount.into_legacy()),
-                )
-                .await?;
-                tracing::debug!(
-                    ?uid,
-                    ?order,
-                    ?quote,
-                    "checking if order is outside market price"
-                );
-                if is_order_outside_market_price(
-                    &Amounts {
-                        sell: data.sell_amount.into_legacy(),
-                        buy: data.buy_amount.into_legacy(),
-                        fee: data.fee_amount.into_legacy(),
-                    },
-                    &Amounts {
-                        sell: quote.sell_amount,
-                        buy: quote.buy_amount,
-                        fee: quote.fee_amount,
-                    },
-                    data.kind,
-                ) {
-                    tracing::debug!(%uid, ?owner, ?class, "order being flagged as outside market price");
-                    (OrderClass::Limit, Some(quote))
-                } else {
-                    (class, Some(quote))
-                }
-            }
-            OrderClass::Limit => {
-                match get_quote_and_check_fee(
-                    &*self.quoter,
-                    &quote_parameters,
-                    order.quote_id,
-                    None,
-                )
-                .await
-                {
-                    Ok(quote) => {
-                        // If the order is not "In-Market", check for the limit orders
-                        if is_order_outside_market_price(
-                            &Amounts {
-                                sell: data.sell_amount.into_legacy(),
-                                buy: data.buy_amount.into_legacy(),
-                                fee: data.fee_amount.into_legacy(),
-                            },
-                            &Amounts {
-                                sell: quote.sell_amount,
-                                buy: quote.buy_amount,
-                                fee: quote.fee_amount,
-                            },
-                            data.kind,
-                        ) {
-                            self.check_max_limit_orders(owner).await?;
-                        }
-                        (class, Some(quote))
-                    }
-                    // If there is not enough liquidity, it's still possible to place this order (as
-                    // an implicit out of market order)
-                    Err(ValidationError::PriceForQuote(PriceEstimationError::NoLiquidity)) => {
-                        tracing::debug!("placing order without quote");
-                        (class, None)
-                    }
-                    Err(other) => return Err(other),
-                }
-            }
-            OrderClass::Liquidity => {
-                let quote =
-                    get_quote_and_check_fee(&*self.quoter, &quote_parameters, order.quote_id, None)
-                        .await?;
-                // If the order is not "In-Market", check for the limit orders
-                if is_order_outside_market_price(
-                    &Amounts {
-                        sell: data.sell_amount.into_legacy(),
-                        buy: data.buy_amount.into_legacy(),
-                        fee: data.fee_amount.into_legacy(),
-                    },
-                    &Amounts {
-                        sell: quote.sell_amount,
-                        buy: quote.buy_amount,
-                        fee: quote.fee_amount,
-                    },
-                    data.kind,
-                ) {
-                    self.check_max_limit_orders(owner).await?;
-                }
-                (OrderClass::Limit, None)
-            }
-        };
-
-        if quote.as_ref().is_some_and(|quote| {
-            // Quoted gas does not include additional gas for hooks nor ERC1271 signatures
-            quote.data.fee_parameters.gas_amount as u64 + quote_parameters.additional_cost()
-                > self.max_gas_per_order
-        }) {
-            return Err(ValidationError::TooMuchGas);
+        let classification = self
+            .classify(class, &data, order.quote_id, &quote_parameters, true)
+            .await?;
+        if classification.counts_as_limit_order {
+            self.check_max_limit_orders(owner, &limits).await?;
         }
+        let (class, quote) = (classification.class, classification.quote);
+
+        self.check_gas(quote.as_ref(), &quote_parameters)?;
 
         let order = Order {
             metadata: OrderMetadata {
@@ -788,6 +1186,7 @@ impl OrderValidating for OrderValidator {
                     .map(|q| q.try_to_model_order_quote())
                     .transpose()
                     .map_err(ValidationError::Other)?,
+                hooks_gas,
                 ..Default::default()
             },
             signature: order.signature.clone(),
@@ -797,17 +1196,198 @@ impl OrderValidating for OrderValidator {
 
         Ok((order, quote))
     }
-}
 
-/// Order validity period configuration.
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub struct OrderValidPeriodConfiguration {
-    pub min: Duration,
-    pub max_market: Duration,
-    pub max_limit: Duration,
-}
+    #[instrument(skip_all)]
+    async fn explain_order(
+        &self,
+        order: OrderCreation,
+        domain_separator: &DomainSeparator,
+        full_app_data_override: Option<String>,
+        requester: Option<&limits::Requester>,
+    ) -> Result<Explanation, ValidationError> {
+        let mut explanation = Explanation::default();
+
+        let mut app_data = match self.validate_app_data(&order.app_data, &full_app_data_override) {
+            Ok(app_data) => {
+                explanation.record(Check::AppData, CheckOutcome::Passed(None));
+                app_data
+            }
+            Err(err) => {
+                explanation.record(Check::AppData, CheckOutcome::Failed(err.into()));
+                return Ok(explanation.skip_remaining());
+            }
+        };
+        let app_data_signer = app_data.inner.protocol.signer.map(IntoLegacy::into_legacy);
 
-impl OrderValidPeriodConfiguration {
+        let owner = match order.verify_owner(domain_separator, app_data_signer) {
+            Ok(owner) => {
+                explanation.record(
+                    Check::Signature,
+                    CheckOutcome::Passed(Some(format!("signed by {owner:?}"))),
+                );
+                owner
+            }
+            Err(err) => {
+                explanation.record(Check::Signature, CheckOutcome::Failed(err.into()));
+                return Ok(explanation.skip_remaining());
+            }
+        };
+        // The remaining checks are expensive (e.g. simulations and quotes).
+        let limits = self.client_limits(
+            owner,
+            &order,
+            &app_data,
+            full_app_data_override.as_deref(),
+            requester.and_then(limits::Requester::api_key),
+        );
+        // The owner and app data of an explanation aren't authenticated so only
+        // the requester determines how many explanations it may request.
+        if let Some(requester) = requester
+            && !self.explain_rate_limiter.try_acquire(
+                requester.clone(),
+                self.limits.requester_limits(requester).orders_per_minute,
+            )
+        {
+            return Err(ValidationError::TooManyOrders);
+        }
+        let data = OrderData {
+            app_data: app_data.inner.hash,
+            ..order.data()
+        };
+        explanation.owner = Some(owner);
+        explanation.uid = Some(data.uid(domain_separator, &owner));
+
+        let hooks = if app_data.inner.protocol.permit.is_some() {
+            match self.add_permit_hook(&mut app_data, &data, owner) {
+                Ok(hooks) => {
+                    explanation.record(Check::Permit, CheckOutcome::Passed(None));
+                    hooks
+                }
+                Err(err) => {
+                    explanation.record(Check::Permit, CheckOutcome::Failed(err));
+                    app_data.inner.protocol.hooks.clone()
+                }
+            }
+        } else {
+            explanation.record(Check::Permit, CheckOutcome::NotApplicable);
+            app_data.inner.protocol.hooks.clone()
+        };
+
+        let verification_gas_limit = if matches!(order.signature, Signature::Eip1271(_)) {
+            match self
+                .verify_eip1271_signature(&order, &data, owner, &app_data, domain_separator)
+                .await
+            {
+                Ok(gas) => {
+                    explanation.record(
+                        Check::Eip1271Signature,
+                        CheckOutcome::Passed(Some(format!("verification requires {gas} gas"))),
+                    );
+                    gas
+                }
+                Err(err) => {
+                    explanation.record(Check::Eip1271Signature, CheckOutcome::Failed(err));
+                    0
+                }
+            }
+        } else {
+            explanation.record(Check::Eip1271Signature, CheckOutcome::NotApplicable);
+            0
+        };
+
+        explanation.record(Check::Amounts, check_amounts(&data, &app_data));
+        if app_data.inner.protocol.twap.is_some() {
+            explanation.record(Check::Twap, check_twap(&data, &app_data));
+        } else {
+            explanation.record(Check::Twap, CheckOutcome::NotApplicable);
+        }
+
+        let pre_order = PreOrderData::from_order_creation(owner, &data, order.signature.scheme());
+        let class = pre_order.class;
+        explanation.record(
+            Check::OrderParameters,
+            self.partial_validate(pre_order)
+                .await
+                .map_err(ValidationError::Partial),
+        );
+
+        explanation.record(
+            Check::Transferability,
+            self.ensure_token_is_transferable(&order, owner, &app_data)
+                .await,
+        );
+        explanation.record(
+            Check::Hooks,
+            match self.simulate_hooks(&data, owner, &hooks).await {
+                Ok(Some(gas)) => CheckOutcome::Passed(Some(format!(
+                    "pre-hooks used {:?} gas, post-hooks used {:?} gas",
+                    gas.pre, gas.post
+                ))),
+                Ok(None) => CheckOutcome::NotApplicable,
+                Err(err) => CheckOutcome::Failed(err),
+            },
+        );
+
+        let classification = async {
+            let quote_parameters = quote_parameters(
+                &order,
+                &data,
+                owner,
+                &app_data,
+                &hooks,
+                verification_gas_limit,
+            )?;
+            // Explanations must not leave any quotes behind.
+            let classification = self
+                .classify(class, &data, order.quote_id, &quote_parameters, false)
+                .await?;
+            Ok::<_, ValidationError>((quote_parameters, classification))
+        };
+        let (quote_parameters, classification) = match classification.await {
+            Ok((quote_parameters, classification)) => {
+                let details = match &classification.quote {
+                    Some(quote) => format!(
+                        "{:?} order, quoted sell amount {}, buy amount {} and fee amount {}",
+                        classification.class, quote.sell_amount, quote.buy_amount, quote.fee_amount
+                    ),
+                    None => format!("{:?} order without quote", classification.class),
+                };
+                explanation.record(Check::Quote, CheckOutcome::Passed(Some(details)));
+                (quote_parameters, classification)
+            }
+            Err(err) => {
+                explanation.record(Check::Quote, CheckOutcome::Failed(err));
+                return Ok(explanation.skip_remaining());
+            }
+        };
+
+        if classification.counts_as_limit_order {
+            explanation.record(
+                Check::LimitOrders,
+                self.check_max_limit_orders(owner, &limits).await,
+            );
+        } else {
+            explanation.record(Check::LimitOrders, CheckOutcome::NotApplicable);
+        }
+
+        explanation.record(
+            Check::Gas,
+            self.check_gas(classification.quote.as_ref(), &quote_parameters),
+        );
+
+        Ok(explanation)
+    }
+}
+
+/// Order validity period configuration.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct OrderValidPeriodConfiguration {
+    pub min: Duration,
+    pub max_market: Duration,
+    pub max_limit: Duration,
+}
+
+impl OrderValidPeriodConfiguration {
     /// Creates an configuration where any `validTo` is accepted.
     pub fn any() -> Self {
         Self {
@@ -865,14 +1445,16 @@ fn has_same_buy_and_sell_token(order: &PreOrderData, native_token: &Address) ->
 /// Retrieves the quote for an order that is being created and verify that its
 /// fee is sufficient.
 ///
-/// The fee is checked only if `fee_amount` is specified.
+/// The fee is checked only if `fee_amount` is specified. A fresh quote is only
+/// stored if `persist` is set.
 pub async fn get_quote_and_check_fee(
     quoter: &dyn OrderQuoting,
     quote_search_parameters: &QuoteSearchParameters,
     quote_id: Option<i64>,
     fee_amount: Option<U256>,
+    persist: bool,
 ) -> Result<Quote, ValidationError> {
-    let quote = get_or_create_quote(quoter, quote_search_parameters, quote_id).await?;
+    let quote = get_or_create_quote(quoter, quote_search_parameters, quote_id, persist).await?;
 
     if fee_amount.is_some_and(|fee| !fee.is_zero()) {
         return Err(ValidationError::NonZeroFee);
@@ -884,12 +1466,14 @@ pub async fn get_quote_and_check_fee(
 /// Retrieves the quote for an order that is being created
 ///
 /// This works by first trying to find an existing quote, and then falling back
-/// to calculating a brand new one if none can be found.
+/// to calculating a brand new one if none can be found. The new quote is only
+/// stored if `persist` is set.
 #[instrument(skip_all)]
 async fn get_or_create_quote(
     quoter: &dyn OrderQuoting,
     quote_search_parameters: &QuoteSearchParameters,
     quote_id: Option<i64>,
+    persist: bool,
 ) -> Result<Quote, ValidationError> {
     let quote = match quoter
         .find_quote(quote_id, quote_search_parameters.clone())
@@ -928,6 +1512,10 @@ async fn get_or_create_quote(
             };
 
             let quote = quoter.calculate_quote(parameters).await?;
+            if !persist {
+                tracing::debug!("computed fresh quote without storing it");
+                return Ok(quote);
+            }
             let quote = quoter
                 .store_quote(quote)
                 .await
@@ -1018,6 +1606,7 @@ mod tests {
             account_balances::MockBalanceFetching,
             bad_token::{MockBadTokenDetecting, TokenQuality},
             code_fetching::MockCodeFetching,
+            hook_simulation::{Execution, MockHookSimulating},
             order_quoting::{FindQuoteError, MockOrderQuoting},
             signature_validator::MockSignatureValidating,
         },
@@ -1038,6 +1627,13 @@ mod tests {
         std::str::FromStr,
     };
 
+    fn uniform_limits(max_limit_orders: u64) -> Arc<limits::Tiers> {
+        Arc::new(limits::Tiers::uniform(limits::Limits {
+            max_limit_orders,
+            ..Default::default()
+        }))
+    }
+
     #[test]
     fn detects_orders_with_same_buy_and_sell_token() {
         let native_token = [0xef; 20].into();
@@ -1103,11 +1699,12 @@ mod tests {
                     .connect_mocked_client(Asserter::new())
                     .erased(),
             ),
+            Default::default(),
             Arc::new(MockOrderQuoting::new()),
             Arc::new(MockBalanceFetching::new()),
             Arc::new(MockSignatureValidating::new()),
             Arc::new(limit_order_counter),
-            0,
+            uniform_limits(0),
             Arc::new(MockCodeFetching::new()),
             Default::default(),
             u64::MAX,
@@ -1256,11 +1853,12 @@ mod tests {
                     .connect_mocked_client(Asserter::new())
                     .erased(),
             ),
+            Default::default(),
             Arc::new(MockOrderQuoting::new()),
             Arc::new(MockBalanceFetching::new()),
             Arc::new(MockSignatureValidating::new()),
             Arc::new(limit_order_counter),
-            0,
+            uniform_limits(0),
             Arc::new(MockCodeFetching::new()),
             Default::default(),
             u64::MAX,
@@ -1356,11 +1954,12 @@ mod tests {
             false,
             Arc::new(bad_token_detector),
             hooks.clone(),
+            Default::default(),
             Arc::new(order_quoter),
             Arc::new(balance_fetcher),
             signature_validating,
             Arc::new(limit_order_counter),
-            max_limit_orders_per_user,
+            uniform_limits(max_limit_orders_per_user),
             Arc::new(MockCodeFetching::new()),
             Default::default(),
             u64::MAX,
@@ -1385,6 +1984,7 @@ mod tests {
                 &Default::default(),
                 Default::default(),
                 None,
+                None,
             )
             .await
             .unwrap();
@@ -1458,6 +2058,7 @@ mod tests {
                     creation.clone(),
                     &domain_separator,
                     Default::default(),
+                    None,
                     None
                 )
                 .await
@@ -1488,41 +2089,719 @@ mod tests {
                     creation.clone(),
                     &domain_separator,
                     Default::default(),
+                    None,
                     None
                 )
                 .await
                 .is_ok()
         );
 
-        let creation_ = OrderCreation {
-            fee_amount: U256::zero(),
-            ..creation.clone()
-        };
-        let (order, _) = validator
-            .validate_and_construct_order(creation_, &domain_separator, Default::default(), None)
-            .await
-            .unwrap();
-        assert!(order.metadata.quote.is_some());
-        assert!(order.metadata.class.is_limit());
-
-        let creation_ = OrderCreation {
-            fee_amount: U256::zero(),
-            partially_fillable: true,
+        let creation_ = OrderCreation {
+            fee_amount: U256::zero(),
+            ..creation.clone()
+        };
+        let (order, _) = validator
+            .validate_and_construct_order(
+                creation_,
+                &domain_separator,
+                Default::default(),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(order.metadata.quote.is_some());
+        assert!(order.metadata.class.is_limit());
+
+        let creation_ = OrderCreation {
+            fee_amount: U256::zero(),
+            partially_fillable: true,
+            app_data: OrderCreationAppData::Full {
+                full: "{}".to_string(),
+            },
+            ..creation
+        };
+        let (order, _) = validator
+            .validate_and_construct_order(
+                creation_,
+                &domain_separator,
+                Default::default(),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(order.metadata.quote.is_some());
+        assert!(order.metadata.class.is_limit());
+    }
+
+    #[tokio::test]
+    async fn post_validate_permit() {
+        let owner = H160([1; 20]);
+        let vault_relayer = H160([2; 20]);
+        let signature = [vec![3; 32], vec![4; 32], vec![27]].concat();
+        let hooks = HooksTrampoline::Instance::new(
+            Address::from([0xcf; 20]),
+            ProviderBuilder::new()
+                .connect_mocked_client(Asserter::new())
+                .erased(),
+        );
+        let permit_interactions = vec![InteractionData {
+            target: *hooks.address(),
+            value: alloy::primitives::U256::ZERO,
+            call_data: hooks
+                .execute(vec![HooksTrampoline::HooksTrampoline::Hook {
+                    target: address!("1111111111111111111111111111111111111111"),
+                    callData: IERC20Permit::IERC20Permit::permitCall {
+                        owner: owner.into_alloy(),
+                        spender: vault_relayer.into_alloy(),
+                        value: alloy::primitives::U256::from(100),
+                        deadline: alloy::primitives::U256::MAX,
+                        v: 27,
+                        r: [3; 32].into(),
+                        s: [4; 32].into(),
+                    }
+                    .abi_encode()
+                    .into(),
+                    gasLimit: alloy::primitives::U256::from(Permit::GAS_LIMIT),
+                }])
+                .calldata()
+                .to_vec(),
+        }];
+
+        let mut order_quoter = MockOrderQuoting::new();
+        order_quoter
+            .expect_find_quote()
+            .returning(|_, _| Ok(Default::default()));
+        let mut bad_token_detector = MockBadTokenDetecting::new();
+        bad_token_detector
+            .expect_detect()
+            .returning(|_| Ok(TokenQuality::Good));
+        let mut balance_fetcher = MockBalanceFetching::new();
+        let expected_interactions = permit_interactions.clone();
+        balance_fetcher
+            .expect_can_transfer()
+            .withf(move |query, _| query.interactions == expected_interactions)
+            .returning(|_, _| Ok(()));
+        let mut signature_validator = MockSignatureValidating::new();
+        signature_validator
+            .expect_validate_signature_and_get_additional_gas()
+            .returning(|_| Ok(0u64));
+        let mut limit_order_counter = MockLimitOrderCounting::new();
+        limit_order_counter.expect_count().returning(|_| Ok(0u64));
+        let validator = OrderValidator::new(
+            WETH9::Instance::new([0xef; 20].into(), ethrpc::mock::web3().alloy),
+            Arc::new(order_validation::banned::Users::none()),
+            OrderValidPeriodConfiguration::any(),
+            false,
+            Arc::new(bad_token_detector),
+            hooks,
+            vault_relayer,
+            Arc::new(order_quoter),
+            Arc::new(balance_fetcher),
+            Arc::new(signature_validator),
+            Arc::new(limit_order_counter),
+            uniform_limits(1),
+            Arc::new(MockCodeFetching::new()),
+            Default::default(),
+            u64::MAX,
+        );
+
+        let creation = |permit: serde_json::Value| OrderCreation {
+            valid_to: time::now_in_epoch_seconds() + 2,
+            sell_token: H160([0x11; 20]),
+            buy_token: H160([0x22; 20]),
+            buy_amount: U256::from(1),
+            sell_amount: U256::from(100),
+            from: Some(owner),
+            signature: Signature::Eip1271(vec![1, 2, 3]),
+            app_data: OrderCreationAppData::Full {
+                full: json!({ "metadata": { "permit": permit } }).to_string(),
+            },
+            ..Default::default()
+        };
+        let permit = json!({
+            "value": "100",
+            "deadline": alloy::primitives::U256::MAX.to_string(),
+            "signature": const_hex::encode_prefixed(&signature),
+        });
+
+        let (order, _) = validator
+            .validate_and_construct_order(
+                creation(permit.clone()),
+                &Default::default(),
+                Default::default(),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(order.interactions.pre, permit_interactions);
+
+        let mut insufficient = permit.clone();
+        insufficient["value"] = json!("99");
+        let mut expired = permit.clone();
+        expired["deadline"] = json!("1");
+        let mut malformed = permit;
+        malformed["signature"] = json!("0x1234");
+        for permit in [insufficient, expired, malformed] {
+            let result = validator
+                .validate_and_construct_order(
+                    creation(permit),
+                    &Default::default(),
+                    Default::default(),
+                    None,
+                    None,
+                )
+                .await;
+            assert!(matches!(result, Err(ValidationError::InvalidPermit(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn post_validate_simulates_hooks() {
+        let owner = H160([1; 20]);
+        let mut order_quoter = MockOrderQuoting::new();
+        order_quoter
+            .expect_find_quote()
+            .returning(|_, _| Ok(Default::default()));
+        let mut bad_token_detector = MockBadTokenDetecting::new();
+        bad_token_detector
+            .expect_detect()
+            .returning(|_| Ok(TokenQuality::Good));
+        let mut balance_fetcher = MockBalanceFetching::new();
+        balance_fetcher
+            .expect_can_transfer()
+            .returning(|_, _| Ok(()));
+        let mut signature_validator = MockSignatureValidating::new();
+        signature_validator
+            .expect_validate_signature_and_get_additional_gas()
+            .returning(|_| Ok(0u64));
+        let mut limit_order_counter = MockLimitOrderCounting::new();
+        limit_order_counter.expect_count().returning(|_| Ok(0u64));
+        let mut hook_simulator = MockHookSimulating::new();
+        hook_simulator
+            .expect_simulate()
+            .withf(move |request| {
+                let permit_first = request.hooks.pre.len() < 2
+                    || request.hooks.pre[0].target == Address::from([0x11; 20]);
+                request.owner == owner.into_alloy()
+                    && request.receiver == owner.into_alloy()
+                    && permit_first
+            })
+            .returning(|request| match request.hooks.post.as_slice() {
+                [] => Ok(HooksGas {
+                    pre: vec![30_000; request.hooks.pre.len()],
+                    post: vec![],
+                }),
+                _ => Err(HookSimulationError::GasLimitExceeded {
+                    execution: Execution::Post,
+                    index: 0,
+                    gas_limit: 50_000,
+                }),
+            });
+        let validator = OrderValidator::new(
+            WETH9::Instance::new([0xef; 20].into(), ethrpc::mock::web3().alloy),
+            Arc::new(order_validation::banned::Users::none()),
+            OrderValidPeriodConfiguration::any(),
+            false,
+            Arc::new(bad_token_detector),
+            HooksTrampoline::Instance::new(
+                Address::from([0xcf; 20]),
+                ProviderBuilder::new()
+                    .connect_mocked_client(Asserter::new())
+                    .erased(),
+            ),
+            H160([2; 20]),
+            Arc::new(order_quoter),
+            Arc::new(balance_fetcher),
+            Arc::new(signature_validator),
+            Arc::new(limit_order_counter),
+            uniform_limits(1),
+            Arc::new(MockCodeFetching::new()),
+            Default::default(),
+            u64::MAX,
+        )
+        .with_hook_simulator(Arc::new(hook_simulator));
+
+        let creation = |metadata: serde_json::Value| OrderCreation {
+            valid_to: time::now_in_epoch_seconds() + 2,
+            sell_token: H160([0x11; 20]),
+            buy_token: H160([0x22; 20]),
+            buy_amount: U256::from(1),
+            sell_amount: U256::from(100),
+            from: Some(owner),
+            signature: Signature::Eip1271(vec![1, 2, 3]),
+            app_data: OrderCreationAppData::Full {
+                full: json!({ "metadata": metadata }).to_string(),
+            },
+            ..Default::default()
+        };
+        let hook = json!({
+            "target": "0x1111111111111111111111111111111111111111",
+            "callData": "0x",
+            "gasLimit": "50000",
+        });
+        let domain_separator = DomainSeparator::default();
+        let validate = |metadata| {
+            validator.validate_and_construct_order(
+                creation(metadata),
+                &domain_separator,
+                Default::default(),
+                None,
+                None,
+            )
+        };
+
+        let (order, _) = validate(json!({ "hooks": { "pre": [hook.clone()] } }))
+            .await
+            .unwrap();
+        assert_eq!(
+            order.metadata.hooks_gas,
+            Some(HooksGas {
+                pre: vec![30_000],
+                post: vec![],
+            })
+        );
+
+        // The hook executing the permit gets simulated before the other
+        // pre-hooks.
+        let permit = json!({
+            "value": "100",
+            "deadline": alloy::primitives::U256::MAX.to_string(),
+            "signature": const_hex::encode_prefixed([vec![3; 32], vec![4; 32], vec![27]].concat()),
+        });
+        let (order, _) = validate(json!({ "hooks": { "pre": [hook.clone()] }, "permit": permit }))
+            .await
+            .unwrap();
+        assert_eq!(
+            order.metadata.hooks_gas,
+            Some(HooksGas {
+                pre: vec![30_000; 2],
+                post: vec![],
+            })
+        );
+        assert_eq!(order.interactions.pre.len(), 2);
+
+        let result = validate(json!({ "hooks": { "post": [hook] } })).await;
+        assert!(matches!(result, Err(ValidationError::InvalidHooks(_))));
+
+        // Orders without hooks don't get simulated.
+        let (order, _) = validate(json!({ "hooks": {} })).await.unwrap();
+        assert_eq!(order.metadata.hooks_gas, None);
+    }
+
+    #[tokio::test]
+    async fn post_validate_too_many_limit_orders() {
+        let mut order_quoter = MockOrderQuoting::new();
+        let mut bad_token_detector = MockBadTokenDetecting::new();
+        let mut balance_fetcher = MockBalanceFetching::new();
+        order_quoter.expect_find_quote().returning(|_, _| {
+            Ok(Quote {
+                id: None,
+                data: Default::default(),
+                sell_amount: U256::from(1),
+                buy_amount: U256::from(1),
+                fee_amount: Default::default(),
+            })
+        });
+        bad_token_detector
+            .expect_detect()
+            .returning(|_| Ok(TokenQuality::Good));
+        balance_fetcher
+            .expect_can_transfer()
+            .returning(|_, _| Ok(()));
+
+        let mut signature_validating = MockSignatureValidating::new();
+        signature_validating
+            .expect_validate_signature_and_get_additional_gas()
+            .never();
+        let signature_validating = Arc::new(signature_validating);
+
+        const MAX_LIMIT_ORDERS_PER_USER: u64 = 2;
+
+        let mut limit_order_counter = MockLimitOrderCounting::new();
+        limit_order_counter
+            .expect_count()
+            .returning(|_| Ok(MAX_LIMIT_ORDERS_PER_USER));
+
+        let native_token = WETH9::Instance::new([0xef; 20].into(), ethrpc::mock::web3().alloy);
+        let validator = OrderValidator::new(
+            native_token,
+            Arc::new(order_validation::banned::Users::none()),
+            OrderValidPeriodConfiguration {
+                min: Duration::from_secs(1),
+                max_market: Duration::from_secs(100),
+                max_limit: Duration::from_secs(200),
+            },
+            false,
+            Arc::new(bad_token_detector),
+            HooksTrampoline::Instance::new(
+                Address::from([0xcf; 20]),
+                ProviderBuilder::new()
+                    .connect_mocked_client(Asserter::new())
+                    .erased(),
+            ),
+            Default::default(),
+            Arc::new(order_quoter),
+            Arc::new(balance_fetcher),
+            signature_validating,
+            Arc::new(limit_order_counter),
+            uniform_limits(MAX_LIMIT_ORDERS_PER_USER),
+            Arc::new(MockCodeFetching::new()),
+            Default::default(),
+            u64::MAX,
+        );
+
+        let creation = OrderCreation {
+            valid_to: model::time::now_in_epoch_seconds() + 2,
+            sell_token: H160::from_low_u64_be(1),
+            buy_token: H160::from_low_u64_be(2),
+            buy_amount: U256::from(10),
+            sell_amount: U256::from(1),
+            signature: Signature::Eip712(EcdsaSignature::non_zero()),
+            app_data: OrderCreationAppData::Full {
+                full: "{}".to_string(),
+            },
+            ..Default::default()
+        };
+        let res = validator
+            .validate_and_construct_order(
+                creation.clone(),
+                &Default::default(),
+                Default::default(),
+                None,
+                None,
+            )
+            .await;
+        assert!(
+            matches!(res, Err(ValidationError::TooManyLimitOrders)),
+            "{res:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn post_validate_limits_depend_on_tier() {
+        let mut order_quoter = MockOrderQuoting::new();
+        let mut bad_token_detector = MockBadTokenDetecting::new();
+        let mut balance_fetcher = MockBalanceFetching::new();
+        order_quoter.expect_find_quote().returning(|_, _| {
+            Ok(Quote {
+                id: None,
+                data: Default::default(),
+                sell_amount: U256::from(1),
+                buy_amount: U256::from(1),
+                fee_amount: Default::default(),
+            })
+        });
+        bad_token_detector
+            .expect_detect()
+            .returning(|_| Ok(TokenQuality::Good));
+        balance_fetcher
+            .expect_can_transfer()
+            .returning(|_, _| Ok(()));
+
+        let mut signature_validating = MockSignatureValidating::new();
+        signature_validating
+            .expect_validate_signature_and_get_additional_gas()
+            .never();
+        let signature_validating = Arc::new(signature_validating);
+
+        const MAX_LIMIT_ORDERS_PER_USER: u64 = 2;
+
+        let mut limit_order_counter = MockLimitOrderCounting::new();
+        limit_order_counter
+            .expect_count()
+            .returning(|_| Ok(MAX_LIMIT_ORDERS_PER_USER));
+
+        let native_token = WETH9::Instance::new([0xef; 20].into(), ethrpc::mock::web3().alloy);
+        let validator = OrderValidator::new(
+            native_token,
+            Arc::new(order_validation::banned::Users::none()),
+            OrderValidPeriodConfiguration {
+                min: Duration::from_secs(1),
+                max_market: Duration::from_secs(100),
+                max_limit: Duration::from_secs(200),
+            },
+            false,
+            Arc::new(bad_token_detector),
+            HooksTrampoline::Instance::new(
+                Address::from([0xcf; 20]),
+                ProviderBuilder::new()
+                    .connect_mocked_client(Asserter::new())
+                    .erased(),
+            ),
+            Default::default(),
+            Arc::new(order_quoter),
+            Arc::new(balance_fetcher),
+            signature_validating,
+            Arc::new(limit_order_counter),
+            Arc::new(limits::Tiers::new(
+                limits::Limits {
+                    max_limit_orders: MAX_LIMIT_ORDERS_PER_USER,
+                    ..Default::default()
+                },
+                vec![limits::Tier {
+                    name: "partner".to_string(),
+                    limits: limits::Limits {
+                        max_limit_orders: MAX_LIMIT_ORDERS_PER_USER + 1,
+                        orders_per_minute: Some(1),
+                        quotes_per_minute: None,
+                        cancellation_checks_per_minute: None,
+                    },
+                    owners: Default::default(),
+                    app_codes: ["Partner".to_string()].into(),
+                    api_key_hashes: Default::default(),
+                }],
+            )),
+            Arc::new(MockCodeFetching::new()),
+            Default::default(),
+            u64::MAX,
+        );
+
+        let creation = |full: &str| OrderCreation {
+            valid_to: model::time::now_in_epoch_seconds() + 2,
+            sell_token: H160::from_low_u64_be(1),
+            buy_token: H160::from_low_u64_be(2),
+            buy_amount: U256::from(10),
+            sell_amount: U256::from(1),
+            signature: Signature::Eip712(EcdsaSignature::non_zero()),
+            app_data: OrderCreationAppData::Full {
+                full: full.to_string(),
+            },
+            ..Default::default()
+        };
+        let domain_separator = DomainSeparator::default();
+        let validate = |full| {
+            validator.validate_and_construct_order(
+                creation(full),
+                &domain_separator,
+                Default::default(),
+                None,
+                None,
+            )
+        };
+
+        let partner = r#"{"appCode":"Partner"}"#;
+        let res = validate(partner).await;
+        assert!(res.is_ok(), "{res:?}");
+        let res = validate(partner).await;
+        assert!(
+            matches!(res, Err(ValidationError::TooManyOrders)),
+            "{res:?}"
+        );
+        let res = validate("{}").await;
+        assert!(
+            matches!(res, Err(ValidationError::TooManyLimitOrders)),
+            "{res:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn post_validate_rate_limit_ignores_invalid_signatures() {
+        let mut order_quoter = MockOrderQuoting::new();
+        let mut bad_token_detector = MockBadTokenDetecting::new();
+        let mut balance_fetcher = MockBalanceFetching::new();
+        order_quoter.expect_find_quote().returning(|_, _| {
+            Ok(Quote {
+                id: None,
+                data: Default::default(),
+                sell_amount: U256::from(1),
+                buy_amount: U256::from(1),
+                fee_amount: Default::default(),
+            })
+        });
+        bad_token_detector
+            .expect_detect()
+            .returning(|_| Ok(TokenQuality::Good));
+        balance_fetcher
+            .expect_can_transfer()
+            .returning(|_, _| Ok(()));
+
+        let mut signature_validating = MockSignatureValidating::new();
+        let mut forged = true;
+        signature_validating
+            .expect_validate_signature_and_get_additional_gas()
+            .returning(move |_| {
+                if std::mem::replace(&mut forged, false) {
+                    Err(SignatureValidationError::Invalid)
+                } else {
+                    Ok(0)
+                }
+            });
+
+        let mut limit_order_counter = MockLimitOrderCounting::new();
+        limit_order_counter.expect_count().returning(|_| Ok(0));
+
+        let native_token = WETH9::Instance::new([0xef; 20].into(), ethrpc::mock::web3().alloy);
+        let validator = OrderValidator::new(
+            native_token,
+            Arc::new(order_validation::banned::Users::none()),
+            OrderValidPeriodConfiguration {
+                min: Duration::from_secs(1),
+                max_market: Duration::from_secs(100),
+                max_limit: Duration::from_secs(200),
+            },
+            false,
+            Arc::new(bad_token_detector),
+            HooksTrampoline::Instance::new(
+                Address::from([0xcf; 20]),
+                ProviderBuilder::new()
+                    .connect_mocked_client(Asserter::new())
+                    .erased(),
+            ),
+            Default::default(),
+            Arc::new(order_quoter),
+            Arc::new(balance_fetcher),
+            Arc::new(signature_validating),
+            Arc::new(limit_order_counter),
+            Arc::new(limits::Tiers::uniform(limits::Limits {
+                max_limit_orders: 10,
+                orders_per_minute: Some(1),
+                quotes_per_minute: None,
+                cancellation_checks_per_minute: None,
+            })),
+            Arc::new(MockCodeFetching::new()),
+            Default::default(),
+            u64::MAX,
+        );
+
+        let creation = OrderCreation {
+            valid_to: model::time::now_in_epoch_seconds() + 2,
+            sell_token: H160::from_low_u64_be(1),
+            buy_token: H160::from_low_u64_be(2),
+            buy_amount: U256::from(10),
+            sell_amount: U256::from(1),
+            from: Some(H160::from_low_u64_be(3)),
+            signature: Signature::Eip1271(vec![1, 2, 3]),
+            app_data: OrderCreationAppData::Full {
+                full: "{}".to_string(),
+            },
+            ..Default::default()
+        };
+        let domain_separator = DomainSeparator::default();
+        let validate = || {
+            validator.validate_and_construct_order(
+                creation.clone(),
+                &domain_separator,
+                Default::default(),
+                None,
+                None,
+            )
+        };
+
+        // A forged order doesn't use up the limit of the owner.
+        let res = validate().await;
+        assert!(
+            matches!(res, Err(ValidationError::InvalidEip1271Signature(_))),
+            "{res:?}"
+        );
+        let res = validate().await;
+        assert!(res.is_ok(), "{res:?}");
+        let res = validate().await;
+        assert!(
+            matches!(res, Err(ValidationError::TooManyOrders)),
+            "{res:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn post_validate_rate_limits_presigned_orders_per_requester() {
+        let mut order_quoter = MockOrderQuoting::new();
+        let mut bad_token_detector = MockBadTokenDetecting::new();
+        let mut balance_fetcher = MockBalanceFetching::new();
+        order_quoter.expect_find_quote().returning(|_, _| {
+            Ok(Quote {
+                id: None,
+                data: Default::default(),
+                sell_amount: U256::from(1),
+                buy_amount: U256::from(1),
+                fee_amount: Default::default(),
+            })
+        });
+        bad_token_detector
+            .expect_detect()
+            .returning(|_| Ok(TokenQuality::Good));
+        balance_fetcher
+            .expect_can_transfer()
+            .returning(|_, _| Ok(()));
+        let mut limit_order_counter = MockLimitOrderCounting::new();
+        limit_order_counter.expect_count().returning(|_| Ok(0));
+
+        let native_token = WETH9::Instance::new([0xef; 20].into(), ethrpc::mock::web3().alloy);
+        let validator = OrderValidator::new(
+            native_token,
+            Arc::new(order_validation::banned::Users::none()),
+            OrderValidPeriodConfiguration {
+                min: Duration::from_secs(1),
+                max_market: Duration::from_secs(100),
+                max_limit: Duration::from_secs(200),
+            },
+            false,
+            Arc::new(bad_token_detector),
+            HooksTrampoline::Instance::new(
+                Address::from([0xcf; 20]),
+                ProviderBuilder::new()
+                    .connect_mocked_client(Asserter::new())
+                    .erased(),
+            ),
+            Default::default(),
+            Arc::new(order_quoter),
+            Arc::new(balance_fetcher),
+            Arc::new(MockSignatureValidating::new()),
+            Arc::new(limit_order_counter),
+            Arc::new(limits::Tiers::uniform(limits::Limits {
+                max_limit_orders: 10,
+                orders_per_minute: Some(1),
+                quotes_per_minute: None,
+                cancellation_checks_per_minute: None,
+            })),
+            Arc::new(MockCodeFetching::new()),
+            Default::default(),
+            u64::MAX,
+        );
+
+        let creation = OrderCreation {
+            valid_to: model::time::now_in_epoch_seconds() + 2,
+            sell_token: H160::from_low_u64_be(1),
+            buy_token: H160::from_low_u64_be(2),
+            buy_amount: U256::from(10),
+            sell_amount: U256::from(1),
+            from: Some(H160::from_low_u64_be(3)),
+            signature: Signature::PreSign,
             app_data: OrderCreationAppData::Full {
                 full: "{}".to_string(),
             },
-            ..creation
+            ..Default::default()
         };
-        let (order, _) = validator
-            .validate_and_construct_order(creation_, &domain_separator, Default::default(), None)
-            .await
-            .unwrap();
-        assert!(order.metadata.quote.is_some());
-        assert!(order.metadata.class.is_limit());
+        let domain_separator = DomainSeparator::default();
+        let validate = |requester| {
+            validator.validate_and_construct_order(
+                creation.clone(),
+                &domain_separator,
+                Default::default(),
+                None,
+                requester,
+            )
+        };
+
+        // Pre-signed orders aren't proven to come from the owner so the
+        // requester gets charged instead.
+        let spammer = limits::Requester::Ip([127, 0, 0, 1].into());
+        let res = validate(Some(&spammer)).await;
+        assert!(res.is_ok(), "{res:?}");
+        let res = validate(Some(&spammer)).await;
+        assert!(
+            matches!(res, Err(ValidationError::TooManyOrders)),
+            "{res:?}"
+        );
+        let owner = limits::Requester::Ip([127, 0, 0, 2].into());
+        let res = validate(Some(&owner)).await;
+        assert!(res.is_ok(), "{res:?}");
     }
 
     #[tokio::test]
-    async fn post_validate_too_many_limit_orders() {
+    async fn post_validate_rate_limits_unverified_eip1271_orders_per_requester() {
         let mut order_quoter = MockOrderQuoting::new();
         let mut bad_token_detector = MockBadTokenDetecting::new();
         let mut balance_fetcher = MockBalanceFetching::new();
@@ -1541,19 +2820,8 @@ mod tests {
         balance_fetcher
             .expect_can_transfer()
             .returning(|_, _| Ok(()));
-
-        let mut signature_validating = MockSignatureValidating::new();
-        signature_validating
-            .expect_validate_signature_and_get_additional_gas()
-            .never();
-        let signature_validating = Arc::new(signature_validating);
-
-        const MAX_LIMIT_ORDERS_PER_USER: u64 = 2;
-
         let mut limit_order_counter = MockLimitOrderCounting::new();
-        limit_order_counter
-            .expect_count()
-            .returning(|_| Ok(MAX_LIMIT_ORDERS_PER_USER));
+        limit_order_counter.expect_count().returning(|_| Ok(0));
 
         let native_token = WETH9::Instance::new([0xef; 20].into(), ethrpc::mock::web3().alloy);
         let validator = OrderValidator::new(
@@ -1564,7 +2832,7 @@ mod tests {
                 max_market: Duration::from_secs(100),
                 max_limit: Duration::from_secs(200),
             },
-            false,
+            true,
             Arc::new(bad_token_detector),
             HooksTrampoline::Instance::new(
                 Address::from([0xcf; 20]),
@@ -1572,11 +2840,17 @@ mod tests {
                     .connect_mocked_client(Asserter::new())
                     .erased(),
             ),
+            Default::default(),
             Arc::new(order_quoter),
             Arc::new(balance_fetcher),
-            signature_validating,
+            Arc::new(MockSignatureValidating::new()),
             Arc::new(limit_order_counter),
-            MAX_LIMIT_ORDERS_PER_USER,
+            Arc::new(limits::Tiers::uniform(limits::Limits {
+                max_limit_orders: 10,
+                orders_per_minute: Some(1),
+                quotes_per_minute: None,
+                cancellation_checks_per_minute: None,
+            })),
             Arc::new(MockCodeFetching::new()),
             Default::default(),
             u64::MAX,
@@ -1588,24 +2862,37 @@ mod tests {
             buy_token: H160::from_low_u64_be(2),
             buy_amount: U256::from(10),
             sell_amount: U256::from(1),
-            signature: Signature::Eip712(EcdsaSignature::non_zero()),
+            from: Some(H160::from_low_u64_be(3)),
+            signature: Signature::Eip1271(vec![1, 2, 3]),
             app_data: OrderCreationAppData::Full {
                 full: "{}".to_string(),
             },
             ..Default::default()
         };
-        let res = validator
-            .validate_and_construct_order(
+        let domain_separator = DomainSeparator::default();
+        let validate = |requester| {
+            validator.validate_and_construct_order(
                 creation.clone(),
-                &Default::default(),
+                &domain_separator,
                 Default::default(),
                 None,
+                requester,
             )
-            .await;
+        };
+
+        // Without verifying the signature the order isn't proven to come from
+        // the owner so the requester gets charged instead.
+        let spammer = limits::Requester::Ip([127, 0, 0, 1].into());
+        let res = validate(Some(&spammer)).await;
+        assert!(res.is_ok(), "{res:?}");
+        let res = validate(Some(&spammer)).await;
         assert!(
-            matches!(res, Err(ValidationError::TooManyLimitOrders)),
+            matches!(res, Err(ValidationError::TooManyOrders)),
             "{res:?}"
         );
+        let owner = limits::Requester::Ip([127, 0, 0, 2].into());
+        let res = validate(Some(&owner)).await;
+        assert!(res.is_ok(), "{res:?}");
     }
 
     #[tokio::test]
@@ -1649,11 +2936,12 @@ mod tests {
                     .connect_mocked_client(Asserter::new())
                     .erased(),
             ),
+            Default::default(),
             Arc::new(order_quoter),
             Arc::new(balance_fetcher),
             signature_validating,
             Arc::new(limit_order_counter),
-            MAX_LIMIT_ORDERS_PER_USER,
+            uniform_limits(MAX_LIMIT_ORDERS_PER_USER),
             Arc::new(MockCodeFetching::new()),
             Default::default(),
             u64::MAX,
@@ -1678,6 +2966,7 @@ mod tests {
                     &Default::default(),
                     Default::default(),
                     None,
+                    None,
                 )
                 .await
                 .is_ok()
@@ -1713,11 +3002,12 @@ mod tests {
                     .connect_mocked_client(Asserter::new())
                     .erased(),
             ),
+            Default::default(),
             Arc::new(order_quoter),
             Arc::new(balance_fetcher),
             Arc::new(MockSignatureValidating::new()),
             Arc::new(limit_order_counter),
-            0,
+            uniform_limits(0),
             Arc::new(MockCodeFetching::new()),
             Default::default(),
             u64::MAX,
@@ -1736,7 +3026,13 @@ mod tests {
             ..Default::default()
         };
         let result = validator
-            .validate_and_construct_order(order, &Default::default(), Default::default(), None)
+            .validate_and_construct_order(
+                order,
+                &Default::default(),
+                Default::default(),
+                None,
+                None,
+            )
             .await;
         assert!(matches!(result, Err(ValidationError::ZeroAmount)));
     }
@@ -1770,11 +3066,12 @@ mod tests {
                     .connect_mocked_client(Asserter::new())
                     .erased(),
             ),
+            Default::default(),
             Arc::new(order_quoter),
             Arc::new(balance_fetcher),
             Arc::new(MockSignatureValidating::new()),
             Arc::new(limit_order_counter),
-            0,
+            uniform_limits(0),
             Arc::new(MockCodeFetching::new()),
             Default::default(),
             u64::MAX,
@@ -1794,7 +3091,13 @@ mod tests {
             ..Default::default()
         };
         let result = validator
-            .validate_and_construct_order(order, &Default::default(), Default::default(), None)
+            .validate_and_construct_order(
+                order,
+                &Default::default(),
+                Default::default(),
+                None,
+                None,
+            )
             .await;
         assert!(matches!(result, Err(ValidationError::WrongOwner(_))));
     }
@@ -1830,11 +3133,12 @@ mod tests {
                     .connect_mocked_client(Asserter::new())
                     .erased(),
             ),
+            Default::default(),
             Arc::new(order_quoter),
             Arc::new(balance_fetcher),
             Arc::new(MockSignatureValidating::new()),
             Arc::new(limit_order_counter),
-            0,
+            uniform_limits(0),
             Arc::new(MockCodeFetching::new()),
             Default::default(),
             u64::MAX,
@@ -1853,7 +3157,13 @@ mod tests {
             ..Default::default()
         };
         let result = validator
-            .validate_and_construct_order(order, &Default::default(), Default::default(), None)
+            .validate_and_construct_order(
+                order,
+                &Default::default(),
+                Default::default(),
+                None,
+                None,
+            )
             .await;
         dbg!(&result);
         assert!(matches!(
@@ -1893,11 +3203,12 @@ mod tests {
                     .connect_mocked_client(Asserter::new())
                     .erased(),
             ),
+            Default::default(),
             Arc::new(order_quoter),
             Arc::new(balance_fetcher),
             Arc::new(MockSignatureValidating::new()),
             Arc::new(limit_order_counter),
-            0,
+            uniform_limits(0),
             Arc::new(MockCodeFetching::new()),
             Default::default(),
             u64::MAX,
@@ -1916,12 +3227,229 @@ mod tests {
             ..Default::default()
         };
         let result = validator
-            .validate_and_construct_order(order, &Default::default(), Default::default(), None)
+            .validate_and_construct_order(
+                order,
+                &Default::default(),
+                Default::default(),
+                None,
+                None,
+            )
             .await;
         dbg!(&result);
         assert!(matches!(result, Err(ValidationError::InsufficientBalance)));
     }
 
+    #[tokio::test]
+    async fn explain_reports_every_check() {
+        let mut order_quoter = MockOrderQuoting::new();
+        let mut bad_token_detector = MockBadTokenDetecting::new();
+        let mut balance_fetcher = MockBalanceFetching::new();
+        order_quoter
+            .expect_find_quote()
+            .returning(|_, _| Ok(Default::default()));
+        bad_token_detector
+            .expect_detect()
+            .returning(|_| Ok(TokenQuality::Good));
+        balance_fetcher
+            .expect_can_transfer()
+            .returning(|_, _| Err(TransferSimulationError::InsufficientBalance));
+        let mut limit_order_counter = MockLimitOrderCounting::new();
+        limit_order_counter.expect_count().returning(|_| Ok(0u64));
+        let native_token = WETH9::Instance::new([0xef; 20].into(), ethrpc::mock::web3().alloy);
+        let validator = OrderValidator::new(
+            native_token,
+            Arc::new(order_validation::banned::Users::none()),
+            OrderValidPeriodConfiguration::any(),
+            false,
+            Arc::new(bad_token_detector),
+            HooksTrampoline::Instance::new(
+                Address::from([0xcf; 20]),
+                ProviderBuilder::new()
+                    .connect_mocked_client(Asserter::new())
+                    .erased(),
+            ),
+            Default::default(),
+            Arc::new(order_quoter),
+            Arc::new(balance_fetcher),
+            Arc::new(MockSignatureValidating::new()),
+            Arc::new(limit_order_counter),
+            Arc::new(limits::Tiers::uniform(limits::Limits {
+                orders_per_minute: Some(2),
+                ..Default::default()
+            })),
+            Arc::new(MockCodeFetching::new()),
+            Default::default(),
+            u64::MAX,
+        );
+        let order = OrderCreation {
+            valid_to: time::now_in_epoch_seconds() + 2,
+            sell_token: H160::from_low_u64_be(1),
+            buy_token: H160::from_low_u64_be(2),
+            buy_amount: U256::from(1),
+            sell_amount: U256::from(0),
+            fee_amount: U256::from(0),
+            signature: Signature::Eip712(EcdsaSignature::non_zero()),
+            app_data: OrderCreationAppData::Full {
+                full: "{}".to_string(),
+            },
+            ..Default::default()
+        };
+
+        let explanation = validator
+            .explain_order(order.clone(), &Default::default(), None, None)
+            .await
+            .unwrap();
+        assert!(!explanation.is_valid());
+        assert!(explanation.owner.is_some());
+        let checks: Vec<_> = explanation
+            .checks
+            .iter()
+            .map(|result| result.check)
+            .collect();
+        assert_eq!(checks, Check::ALL);
+        let outcome = |check| {
+            &explanation
+                .checks
+                .iter()
+                .find(|result| result.check == check)
+                .unwrap()
+                .outcome
+        };
+        assert!(matches!(outcome(Check::AppData), CheckOutcome::Passed(_)));
+        assert!(matches!(outcome(Check::Signature), CheckOutcome::Passed(_)));
+        assert!(matches!(
+            outcome(Check::Eip1271Signature),
+            CheckOutcome::NotApplicable
+        ));
+        // Unlike the regular validation the explanation doesn't stop at the
+        // first failing check.
+        assert!(matches!(
+            outcome(Check::Amounts),
+            CheckOutcome::Failed(ValidationError::ZeroAmount)
+        ));
+        assert!(matches!(
+            outcome(Check::OrderParameters),
+            CheckOutcome::Passed(_)
+        ));
+        assert!(matches!(
+            outcome(Check::Transferability),
+            CheckOutcome::Failed(ValidationError::InsufficientBalance)
+        ));
+        assert!(matches!(outcome(Check::Hooks), CheckOutcome::NotApplicable));
+        assert!(matches!(outcome(Check::Quote), CheckOutcome::Passed(_)));
+
+        // Invalid app data makes all other checks impossible.
+        let explanation = validator
+            .explain_order(
+                OrderCreation {
+                    app_data: OrderCreationAppData::Full {
+                        full: "invalid".to_string(),
+                    },
+                    ..order.clone()
+                },
+                &Default::default(),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(matches!(
+            explanation.checks[0].outcome,
+            CheckOutcome::Failed(ValidationError::AppData(_))
+        ));
+        assert!(
+            explanation.checks[1..]
+                .iter()
+                .all(|result| matches!(result.outcome, CheckOutcome::Skipped))
+        );
+
+        // Explanations are rate limited per requester.
+        let requester = limits::Requester::Ip([127, 0, 0, 1].into());
+        let explain = |requester| {
+            validator.explain_order(order.clone(), &Default::default(), None, requester)
+        };
+        for _ in 0..2 {
+            assert!(explain(Some(&requester)).await.is_ok());
+        }
+        assert!(matches!(
+            explain(Some(&requester)).await,
+            Err(ValidationError::TooManyOrders)
+        ));
+        let other = limits::Requester::Ip([127, 0, 0, 2].into());
+        assert!(explain(Some(&other)).await.is_ok());
+        assert!(explain(None).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn explain_does_not_store_quotes() {
+        let mut order_quoter = MockOrderQuoting::new();
+        let mut bad_token_detector = MockBadTokenDetecting::new();
+        let mut balance_fetcher = MockBalanceFetching::new();
+        order_quoter
+            .expect_find_quote()
+            .returning(|_, _| Err(FindQuoteError::NotFound(None)));
+        order_quoter
+            .expect_calculate_quote()
+            .times(1)
+            .returning(|_| Ok(Default::default()));
+        order_quoter.expect_store_quote().never();
+        bad_token_detector
+            .expect_detect()
+            .returning(|_| Ok(TokenQuality::Good));
+        balance_fetcher
+            .expect_can_transfer()
+            .returning(|_, _| Ok(()));
+        let mut limit_order_counter = MockLimitOrderCounting::new();
+        limit_order_counter.expect_count().returning(|_| Ok(0u64));
+        let native_token = WETH9::Instance::new([0xef; 20].into(), ethrpc::mock::web3().alloy);
+        let validator = OrderValidator::new(
+            native_token,
+            Arc::new(order_validation::banned::Users::none()),
+            OrderValidPeriodConfiguration::any(),
+            false,
+            Arc::new(bad_token_detector),
+            HooksTrampoline::Instance::new(
+                Address::from([0xcf; 20]),
+                ProviderBuilder::new()
+                    .connect_mocked_client(Asserter::new())
+                    .erased(),
+            ),
+            Default::default(),
+            Arc::new(order_quoter),
+            Arc::new(balance_fetcher),
+            Arc::new(MockSignatureValidating::new()),
+            Arc::new(limit_order_counter),
+            Arc::new(limits::Tiers::uniform(Default::default())),
+            Arc::new(MockCodeFetching::new()),
+            Default::default(),
+            u64::MAX,
+        );
+        let order = OrderCreation {
+            valid_to: time::now_in_epoch_seconds() + 2,
+            sell_token: H160::from_low_u64_be(1),
+            buy_token: H160::from_low_u64_be(2),
+            buy_amount: U256::from(1),
+            sell_amount: U256::from(1),
+            fee_amount: U256::from(0),
+            signature: Signature::Eip712(EcdsaSignature::non_zero()),
+            app_data: OrderCreationAppData::Full {
+                full: "{}".to_string(),
+            },
+            ..Default::default()
+        };
+
+        let explanation = validator
+            .explain_order(order, &Default::default(), None, None)
+            .await
+            .unwrap();
+        let quote = explanation
+            .checks
+            .iter()
+            .find(|result| result.check == Check::Quote)
+            .unwrap();
+        assert!(matches!(quote.outcome, CheckOutcome::Passed(_)));
+    }
+
     #[tokio::test]
     async fn post_validate_err_invalid_eip1271_signature() {
         let mut order_quoter = MockOrderQuoting::new();
@@ -1955,11 +3483,12 @@ mod tests {
                     .connect_mocked_client(Asserter::new())
                     .erased(),
             ),
+            Default::default(),
             Arc::new(order_quoter),
             Arc::new(balance_fetcher),
             Arc::new(signature_validator),
             Arc::new(limit_order_counter),
-            0,
+            uniform_limits(0),
             Arc::new(MockCodeFetching::new()),
             Default::default(),
             u64::MAX,
@@ -1983,7 +3512,7 @@ mod tests {
 
         assert!(matches!(
             validator
-                .validate_and_construct_order(creation.clone(), &domain, Default::default(), None)
+                .validate_and_construct_order(creation.clone(), &domain, Default::default(), None, None)
                 .await
                 .unwrap_err(),
             ValidationError::InvalidEip1271Signature(hash)
@@ -2024,11 +3553,12 @@ mod tests {
                         .connect_mocked_client(Asserter::new())
                         .erased(),
                 ),
+                Default::default(),
                 Arc::new(order_quoter),
                 Arc::new(balance_fetcher),
                 Arc::new(MockSignatureValidating::new()),
                 Arc::new(limit_order_counter),
-                0,
+                uniform_limits(0),
                 Arc::new(MockCodeFetching::new()),
                 Default::default(),
                 u64::MAX,
@@ -2056,7 +3586,7 @@ mod tests {
                         ),
                         &Default::default(),
                         Default::default(),
-                        None,
+                        None, None,
                     )
                     .now_or_never()
                     .unwrap()
@@ -2070,7 +3600,13 @@ mod tests {
                 ..order
             };
             validator
-                .validate_and_construct_order(order, &Default::default(), Default::default(), None)
+                .validate_and_construct_order(
+                    order,
+                    &Default::default(),
+                    Default::default(),
+                    None,
+                    None,
+                )
                 .now_or_never()
                 .unwrap()
                 .unwrap();
@@ -2115,11 +3651,12 @@ mod tests {
                     .connect_mocked_client(Asserter::new())
                     .erased(),
             ),
+            Default::default(),
             Arc::new(order_quoter),
             Arc::new(balance_fetcher),
             Arc::new(MockSignatureValidating::new()),
             Arc::new(limit_order_counter),
-            0,
+            uniform_limits(0),
             Arc::new(MockCodeFetching::new()),
             Default::default(),
             u64::MAX,
@@ -2158,6 +3695,7 @@ mod tests {
                 &Default::default(),
                 Default::default(),
                 None,
+                None,
             )
             .now_or_never()
             .unwrap()
@@ -2195,6 +3733,7 @@ mod tests {
                 &Default::default(),
                 Default::default(),
                 None,
+                None,
             )
             .now_or_never()
             .unwrap();
@@ -2232,6 +3771,7 @@ mod tests {
                 &Default::default(),
                 Default::default(),
                 None,
+                None,
             )
             .now_or_never()
             .unwrap();
@@ -2274,6 +3814,7 @@ mod tests {
             &quote_search_parameters,
             quote_id,
             Some(fee_amount),
+            true,
         )
         .await
         .unwrap();
@@ -2346,6 +3887,7 @@ mod tests {
             &quote_search_parameters,
             None,
             Some(fee_amount),
+            true,
         )
         .await
         .unwrap();
@@ -2530,11 +4072,12 @@ mod tests {
                     .connect_mocked_client(Asserter::new())
                     .erased(),
             ),
+            Default::default(),
             Arc::new(order_quoter),
             Arc::new(balance_fetcher),
             Arc::new(signature_validating),
             Arc::new(limit_order_counter),
-            0,
+            uniform_limits(0),
             Arc::new(MockCodeFetching::new()),
             Default::default(),
             u64::MAX,
@@ -2562,6 +4105,7 @@ mod tests {
                 &Default::default(),
                 Default::default(),
                 None,
+                None,
             )
             .await
             .unwrap();
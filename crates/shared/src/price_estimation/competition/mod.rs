@@ -243,8 +243,10 @@ mod tests {
         super::*,
         crate::price_estimation::{
             Estimate,
+            EstimateUpdate,
             HEALTHY_PRICE_ESTIMATION_TIME,
             MockPriceEstimating,
+            PriceEstimateResult,
             PriceEstimating,
             Query,
         },
@@ -445,6 +447,65 @@ mod tests {
         assert_eq!(result.as_ref().unwrap(), &estimate(1));
     }
 
+    #[tokio::test]
+    async fn reports_every_estimate_progressively() {
+        let query = Arc::new(Query {
+            verification: Default::default(),
+            sell_token: Address::with_last_byte(0),
+            buy_token: Address::with_last_byte(1),
+            in_amount: NonZeroU256::try_from(1).unwrap(),
+            kind: OrderKind::Sell,
+            block_dependent: false,
+            timeout: HEALTHY_PRICE_ESTIMATION_TIME,
+        });
+
+        fn estimate(amount: u64, gas: u64) -> Estimate {
+            Estimate {
+                out_amount: amount.into(),
+                gas,
+                ..Default::default()
+            }
+        }
+
+        let estimator = |delay: u64, result: PriceEstimateResult| {
+            let mut estimator = MockPriceEstimating::new();
+            estimator.expect_estimate().times(1).returning(move |_| {
+                let result = result.clone();
+                async move {
+                    sleep(Duration::from_millis(delay)).await;
+                    result
+                }
+                .boxed()
+            });
+            Arc::new(estimator) as Arc<dyn PriceEstimating>
+        };
+        let racing: CompetitionEstimator<Arc<dyn PriceEstimating>> = CompetitionEstimator::new(
+            vec![vec![
+                ("first".to_owned(), estimator(0, Ok(estimate(1, 1)))),
+                (
+                    "second".to_owned(),
+                    estimator(10, Err(PriceEstimationError::NoLiquidity)),
+                ),
+                // estimates without gas never get reported
+                ("third".to_owned(), estimator(20, Ok(estimate(5, 0)))),
+                ("fourth".to_owned(), estimator(30, Ok(estimate(2, 1)))),
+            ]],
+            PriceRanking::MaxOutAmount,
+        );
+
+        let updates: Vec<_> = racing.estimate_progressively(query).collect().await;
+        assert!(matches!(
+            updates.as_slice(),
+            [
+                EstimateUpdate::Preliminary(first),
+                EstimateUpdate::Preliminary(fourth),
+                EstimateUpdate::Final(Ok(winner)),
+            ] if first == &estimate(1, 1)
+                && fourth == &estimate(2, 1)
+                && winner == &estimate(2, 1)
+        ));
+    }
+
     #[tokio::test]
     async fn queries_stages_sequentially() {
         let query = Arc::new(Query {
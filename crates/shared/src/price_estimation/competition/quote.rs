@@ -2,6 +2,7 @@ use {
     super::{CompetitionEstimator, PriceRanking, compare_error},
     crate::price_estimation::{
         Estimate,
+        EstimateUpdate,
         PriceEstimateResult,
         PriceEstimating,
         PriceEstimationError,
@@ -10,7 +11,11 @@ use {
     },
     anyhow::Context,
     ethrpc::alloy::conversions::{IntoAlloy, IntoLegacy},
-    futures::future::{BoxFuture, FutureExt, TryFutureExt},
+    futures::{
+        channel::mpsc,
+        future::{self, BoxFuture, FutureExt, TryFutureExt},
+        stream::{self, BoxStream, StreamExt},
+    },
     model::order::OrderKind,
     primitive_types::{H160, U256},
     std::{cmp::Ordering, sync::Arc, time::Duration},
@@ -19,7 +24,35 @@ use {
 
 impl PriceEstimating for CompetitionEstimator<Arc<dyn PriceEstimating>> {
     #[instrument(skip_all)]
-    fn estimate(&self, mut query: Arc<Query>) -> BoxFuture<'_, PriceEstimateResult> {
+    fn estimate(&self, query: Arc<Query>) -> BoxFuture<'_, PriceEstimateResult> {
+        self.estimate_with_progress(query, None)
+    }
+
+    #[instrument(skip_all)]
+    fn estimate_progressively(&self, query: Arc<Query>) -> BoxStream<'_, EstimateUpdate> {
+        // All updates go through the same channel so the final estimate is
+        // guaranteed to be reported after all preliminary ones.
+        let (sender, receiver) = mpsc::unbounded();
+        let estimate = async move {
+            let result = self
+                .estimate_with_progress(query, Some(sender.clone()))
+                .await;
+            let _ = sender.unbounded_send(EstimateUpdate::Final(result));
+        };
+        stream::select(receiver.map(Some), estimate.into_stream().map(|()| None))
+            .filter_map(future::ready)
+            .boxed()
+    }
+}
+
+impl CompetitionEstimator<Arc<dyn PriceEstimating>> {
+    /// Computes the winning estimate and sends every usable estimate of the
+    /// individual price sources to `progress` as soon as it arrives.
+    fn estimate_with_progress(
+        &self,
+        mut query: Arc<Query>,
+        progress: Option<mpsc::UnboundedSender<EstimateUpdate>>,
+    ) -> BoxFuture<'_, PriceEstimateResult> {
         Arc::make_mut(&mut query).timeout /= self.stages.len() as u32;
 
         async move {
@@ -36,8 +69,21 @@ impl PriceEstimating for CompetitionEstimator<Arc<dyn PriceEstimating>> {
             // subsidies.
             let gas_is_reasonable = |r: &PriceEstimateResult| r.as_ref().is_ok_and(|r| r.gas > 0);
             let get_results = self
-                .produce_results(query.clone(), gas_is_reasonable, |context| {
-                    context.estimator.estimate(context.query)
+                .produce_results(query.clone(), gas_is_reasonable, move |context| {
+                    let progress = progress.clone();
+                    context
+                        .estimator
+                        .estimate(context.query)
+                        .inspect(move |result| {
+                            if let Some(progress) = &progress
+                                && let Ok(estimate) = result
+                                && estimate.gas > 0
+                            {
+                                let _ = progress
+                                    .unbounded_send(EstimateUpdate::Preliminary(estimate.clone()));
+                            }
+                        })
+                        .boxed()
                 })
                 .map(Result::Ok);
 
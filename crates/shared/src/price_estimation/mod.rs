@@ -8,7 +8,10 @@ use {
     anyhow::{Result, ensure},
     bigdecimal::BigDecimal,
     ethcontract::{H160, U256},
-    futures::future::BoxFuture,
+    futures::{
+        future::{BoxFuture, FutureExt},
+        stream::{BoxStream, StreamExt},
+    },
     itertools::Itertools,
     model::order::{BuyTokenDestination, OrderKind, SellTokenSource},
     number::nonzero::U256 as NonZeroU256,
@@ -559,9 +562,32 @@ impl Estimate {
 
 pub type PriceEstimateResult = Result<Estimate, PriceEstimationError>;
 
+/// Update produced while progressively estimating a price.
+#[derive(Clone, Debug)]
+pub enum EstimateUpdate {
+    /// Successful estimate of a single price source that arrived before the
+    /// final estimate was decided.
+    Preliminary(Estimate),
+    /// The estimate [`PriceEstimating::estimate`] would have returned.
+    Final(PriceEstimateResult),
+}
+
 #[cfg_attr(any(test, feature = "test-util"), mockall::automock)]
 pub trait PriceEstimating: Send + Sync + 'static {
     fn estimate(&self, query: Arc<Query>) -> BoxFuture<'_, PriceEstimateResult>;
+
+    /// Estimates the price like [`Self::estimate`] but additionally reports
+    /// the estimates of the individual price sources as they arrive. The
+    /// stream always ends with exactly one [`EstimateUpdate::Final`].
+    ///
+    /// Estimators that don't aggregate multiple price sources only report the
+    /// final estimate.
+    fn estimate_progressively(&self, query: Arc<Query>) -> BoxStream<'_, EstimateUpdate> {
+        self.estimate(query)
+            .map(EstimateUpdate::Final)
+            .into_stream()
+            .boxed()
+    }
 }
 
 pub const HEALTHY_PRICE_ESTIMATION_TIME: Duration = Duration::from_millis(5_000);
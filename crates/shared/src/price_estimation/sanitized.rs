@@ -3,6 +3,8 @@ use {
         bad_token::{BadTokenDetecting, TokenQuality},
         price_estimation::{
             Estimate,
+            EstimateUpdate,
+            PriceEstimateResult,
             PriceEstimating,
             PriceEstimationError,
             Query,
@@ -12,7 +14,10 @@ use {
     alloy::primitives::Address,
     anyhow::anyhow,
     ethrpc::alloy::conversions::IntoAlloy,
-    futures::FutureExt,
+    futures::{
+        future::{self, BoxFuture, FutureExt},
+        stream::{self, BoxStream, StreamExt},
+    },
     model::order::BUY_ETH_ADDRESS,
     std::sync::Arc,
     tracing::instrument,
@@ -54,98 +59,166 @@ impl SanitizedPriceEstimator {
     }
 }
 
-impl PriceEstimating for SanitizedPriceEstimator {
-    #[instrument(skip_all)]
-    fn estimate(
-        &self,
-        query: Arc<Query>,
-    ) -> futures::future::BoxFuture<'_, super::PriceEstimateResult> {
-        async move {
-            self.handle_bad_tokens(&query).await?;
+/// How a query gets answered after it got sanitized.
+enum Sanitized {
+    /// The query can be answered without asking the inner estimator.
+    Trivial(Estimate),
+    /// The adjusted query needs to be forwarded to the inner estimator.
+    Forward {
+        query: Query,
+        modification: Option<Modification>,
+    },
+}
 
-            // buy_token == sell_token => 1 to 1 conversion
-            if query.buy_token == query.sell_token {
-                let estimation = Estimate {
-                    out_amount: query.in_amount.get(),
-                    gas: 0,
-                    solver: Default::default(),
-                    verified: true,
-                    execution: Default::default(),
-                };
-                tracing::debug!(?query, ?estimation, "generate trivial price estimation");
-                return Ok(estimation);
-            }
+enum Modification {
+    AddGas(u64),
+}
 
-            // sell WETH for ETH => 1 to 1 conversion with cost for unwrapping
-            if query.sell_token == self.native_token
-                && query.buy_token == BUY_ETH_ADDRESS.into_alloy()
-            {
-                let estimation = Estimate {
-                    out_amount: query.in_amount.get(),
-                    gas: GAS_PER_WETH_UNWRAP,
-                    solver: Default::default(),
-                    verified: true,
-                    execution: Default::default(),
-                };
-                tracing::debug!(?query, ?estimation, "generate trivial unwrap estimation");
-                return Ok(estimation);
-            }
+impl SanitizedPriceEstimator {
+    async fn sanitize(&self, query: &Query) -> Result<Sanitized, PriceEstimationError> {
+        self.handle_bad_tokens(query).await?;
 
-            // sell ETH for WETH => 1 to 1 conversion with cost for wrapping
-            if query.sell_token == BUY_ETH_ADDRESS.into_alloy()
-                && query.buy_token == self.native_token
-            {
-                let estimation = Estimate {
-                    out_amount: query.in_amount.get(),
-                    gas: GAS_PER_WETH_WRAP,
-                    solver: Default::default(),
-                    verified: true,
-                    execution: Default::default(),
-                };
-                tracing::debug!(?query, ?estimation, "generate trivial wrap estimation");
-                return Ok(estimation);
-            }
+        // buy_token == sell_token => 1 to 1 conversion
+        if query.buy_token == query.sell_token {
+            let estimation = Estimate {
+                out_amount: query.in_amount.get(),
+                gas: 0,
+                solver: Default::default(),
+                verified: true,
+                execution: Default::default(),
+            };
+            tracing::debug!(?query, ?estimation, "generate trivial price estimation");
+            return Ok(Sanitized::Trivial(estimation));
+        }
 
-            enum Modification {
-                AddGas(u64),
-            }
+        // sell WETH for ETH => 1 to 1 conversion with cost for unwrapping
+        if query.sell_token == self.native_token && query.buy_token == BUY_ETH_ADDRESS.into_alloy()
+        {
+            let estimation = Estimate {
+                out_amount: query.in_amount.get(),
+                gas: GAS_PER_WETH_UNWRAP,
+                solver: Default::default(),
+                verified: true,
+                execution: Default::default(),
+            };
+            tracing::debug!(?query, ?estimation, "generate trivial unwrap estimation");
+            return Ok(Sanitized::Trivial(estimation));
+        }
 
-            let mut adjusted_query = Query::clone(&*query);
-            let modification = if query.sell_token != self.native_token
-                && query.buy_token == BUY_ETH_ADDRESS.into_alloy()
-            {
-                tracing::debug!(?query, "estimate price for buying native asset");
-                adjusted_query.buy_token = self.native_token;
-                Some(Modification::AddGas(GAS_PER_WETH_UNWRAP))
-            } else if query.sell_token == BUY_ETH_ADDRESS.into_alloy()
-                && query.buy_token != self.native_token
-            {
-                tracing::debug!(?query, "estimate price for selling native asset");
-                adjusted_query.sell_token = self.native_token;
-                Some(Modification::AddGas(GAS_PER_WETH_WRAP))
-            } else {
-                None
+        // sell ETH for WETH => 1 to 1 conversion with cost for wrapping
+        if query.sell_token == BUY_ETH_ADDRESS.into_alloy() && query.buy_token == self.native_token
+        {
+            let estimation = Estimate {
+                out_amount: query.in_amount.get(),
+                gas: GAS_PER_WETH_WRAP,
+                solver: Default::default(),
+                verified: true,
+                execution: Default::default(),
             };
+            tracing::debug!(?query, ?estimation, "generate trivial wrap estimation");
+            return Ok(Sanitized::Trivial(estimation));
+        }
 
-            let mut estimate = self.inner.estimate(Arc::new(adjusted_query)).await?;
+        let mut adjusted_query = query.clone();
+        let modification = if query.sell_token != self.native_token
+            && query.buy_token == BUY_ETH_ADDRESS.into_alloy()
+        {
+            tracing::debug!(?query, "estimate price for buying native asset");
+            adjusted_query.buy_token = self.native_token;
+            Some(Modification::AddGas(GAS_PER_WETH_UNWRAP))
+        } else if query.sell_token == BUY_ETH_ADDRESS.into_alloy()
+            && query.buy_token != self.native_token
+        {
+            tracing::debug!(?query, "estimate price for selling native asset");
+            adjusted_query.sell_token = self.native_token;
+            Some(Modification::AddGas(GAS_PER_WETH_WRAP))
+        } else {
+            None
+        };
+
+        Ok(Sanitized::Forward {
+            query: adjusted_query,
+            modification,
+        })
+    }
+}
+
+/// Applies the modification of the sanitized query to the estimate of the
+/// inner estimator.
+fn modify(
+    query: &Query,
+    mut estimate: Estimate,
+    modification: Option<&Modification>,
+) -> PriceEstimateResult {
+    match modification {
+        Some(Modification::AddGas(gas)) => {
+            estimate.gas = estimate.gas.checked_add(*gas).ok_or_else(|| {
+                PriceEstimationError::ProtocolInternal(anyhow!(
+                    "cost of converting native asset would overflow gas price"
+                ))
+            })?;
+            tracing::debug!(
+                ?query,
+                ?estimate,
+                "added cost of converting native asset to price estimation"
+            );
+            Ok(estimate)
+        }
+        None => Ok(estimate),
+    }
+}
 
-            match modification {
-                Some(Modification::AddGas(gas)) => {
-                    estimate.gas = estimate.gas.checked_add(gas).ok_or_else(|| {
-                        PriceEstimationError::ProtocolInternal(anyhow!(
-                            "cost of converting native asset would overflow gas price"
-                        ))
-                    })?;
-                    tracing::debug!(
-                        ?query,
-                        ?estimate,
-                        "added cost of converting native asset to price estimation"
-                    );
-                    Ok(estimate)
+impl PriceEstimating for SanitizedPriceEstimator {
+    #[instrument(skip_all)]
+    fn estimate(&self, query: Arc<Query>) -> BoxFuture<'_, PriceEstimateResult> {
+        async move {
+            match self.sanitize(&query).await? {
+                Sanitized::Trivial(estimate) => Ok(estimate),
+                Sanitized::Forward {
+                    query: adjusted_query,
+                    modification,
+                } => {
+                    let estimate = self.inner.estimate(Arc::new(adjusted_query)).await?;
+                    modify(&query, estimate, modification.as_ref())
+                }
+            }
+        }
+        .boxed()
+    }
+
+    #[instrument(skip_all)]
+    fn estimate_progressively(&self, query: Arc<Query>) -> BoxStream<'_, EstimateUpdate> {
+        async move {
+            match self.sanitize(&query).await {
+                Err(err) => stream::once(future::ready(EstimateUpdate::Final(Err(err)))).boxed(),
+                Ok(Sanitized::Trivial(estimate)) => {
+                    stream::once(future::ready(EstimateUpdate::Final(Ok(estimate)))).boxed()
                 }
-                None => Ok(estimate),
+                Ok(Sanitized::Forward {
+                    query: adjusted_query,
+                    modification,
+                }) => self
+                    .inner
+                    .estimate_progressively(Arc::new(adjusted_query))
+                    .filter_map(move |update| {
+                        let update = match update {
+                            EstimateUpdate::Preliminary(estimate) => {
+                                modify(&query, estimate, modification.as_ref())
+                                    .ok()
+                                    .map(EstimateUpdate::Preliminary)
+                            }
+                            EstimateUpdate::Final(result) => {
+                                Some(EstimateUpdate::Final(result.and_then(|estimate| {
+                                    modify(&query, estimate, modification.as_ref())
+                                })))
+                            }
+                        };
+                        future::ready(update)
+                    })
+                    .boxed(),
             }
         }
+        .flatten_stream()
         .boxed()
     }
 }
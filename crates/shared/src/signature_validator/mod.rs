@@ -80,6 +80,14 @@ pub trait SignatureValidating: Send + Sync {
         &self,
         check: SignatureCheck,
     ) -> Result<u64, SignatureValidationError>;
+
+    /// Checks that the Safe `safe` approved the EIP-712 message `hash`
+    /// on-chain, i.e. that it pre-signed the message with `signMessage`.
+    async fn validate_safe_signed_message(
+        &self,
+        safe: H160,
+        hash: [u8; 32],
+    ) -> Result<(), SignatureValidationError>;
 }
 
 /// The Magical value as defined by EIP-1271
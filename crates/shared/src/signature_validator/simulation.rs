@@ -8,7 +8,7 @@ use {
     crate::price_estimation::trade_verifier::balance_overrides::BalanceOverriding,
     alloy::{
         dyn_abi::SolType,
-        primitives::Address,
+        primitives::{Address, keccak256},
         sol_types::{SolCall, sol_data},
         transports::RpcError,
     },
@@ -16,6 +16,7 @@ use {
     contracts::alloy::{
         ERC1271SignatureValidator::ERC1271SignatureValidator,
         GPv2Settlement,
+        GnosisSafe,
         support::Signatures,
     },
     ethcontract::state_overrides::StateOverrides,
@@ -23,7 +24,8 @@ use {
         Web3,
         alloy::conversions::{IntoAlloy, IntoLegacy},
     },
-    primitive_types::U256,
+    hex_literal::hex,
+    primitive_types::{H160, U256},
     std::sync::Arc,
     tracing::instrument,
 };
@@ -39,6 +41,9 @@ pub struct Validator {
 impl Validator {
     /// The result returned from `isValidSignature` if the signature is correct
     const IS_VALID_SIGNATURE_MAGIC_BYTES: &'static str = "1626ba7e";
+    /// `keccak256("SafeMessage(bytes message)")`
+    const SAFE_MSG_TYPEHASH: [u8; 32] =
+        hex!("60b3cbf8b4a223d68d641b3b6ddf9a298e7f33710cf3d3a9d1146b5a6150fbca");
 
     pub fn new(
         web3: &Web3,
@@ -73,13 +78,7 @@ impl Validator {
             .call()
             .await
             .map(|value| const_hex::encode(value.0))
-            .map_err(|err| match err {
-                alloy::contract::Error::TransportError(RpcError::ErrorResp(err)) => {
-                    tracing::error!(?err, "failed to call isValidSignature");
-                    SignatureValidationError::Invalid
-                }
-                err => SignatureValidationError::Other(err.into()),
-            })?;
+            .map_err(|err| call_error(err, "isValidSignature"))?;
 
         if magic_bytes != Self::IS_VALID_SIGNATURE_MAGIC_BYTES {
             return Err(SignatureValidationError::Invalid);
@@ -190,6 +189,67 @@ impl SignatureValidating for Validator {
             .try_into()
             .unwrap_or(u64::MAX))
     }
+
+    /// Looks up the message in the `signedMessages` of the Safe. This is what
+    /// the Safe's fallback handler checks for an empty EIP-1271 signature but
+    /// doesn't depend on the fallback handler being set.
+    #[instrument(skip_all)]
+    async fn validate_safe_signed_message(
+        &self,
+        safe: H160,
+        hash: [u8; 32],
+    ) -> Result<(), SignatureValidationError> {
+        let safe = GnosisSafe::Instance::new(safe.into_alloy(), self.web3.alloy.clone());
+        let domain_separator = safe
+            .domainSeparator()
+            .call()
+            .await
+            .map_err(|err| safe_call_error(err, "domainSeparator"))?;
+        // Safes sign the ABI encoded hash, see `getMessageHashForSafe` of the
+        // compatibility fallback handler.
+        let safe_message = keccak256([Self::SAFE_MSG_TYPEHASH, keccak256(hash).0].concat());
+        let message_hash = keccak256(
+            [
+                [0x19, 0x01].as_slice(),
+                domain_separator.as_slice(),
+                safe_message.as_slice(),
+            ]
+            .concat(),
+        );
+        let signed = safe
+            .signedMessages(message_hash)
+            .call()
+            .await
+            .map_err(|err| safe_call_error(err, "signedMessages"))?;
+        if signed.is_zero() {
+            return Err(SignatureValidationError::Invalid);
+        }
+        Ok(())
+    }
+}
+
+/// Treats reverts (e.g. because the signer isn't a contract implementing the
+/// called function) as invalid signatures.
+fn call_error(err: alloy::contract::Error, function: &str) -> SignatureValidationError {
+    match err {
+        alloy::contract::Error::TransportError(RpcError::ErrorResp(err)) => {
+            tracing::error!(?err, function, "failed to call signer contract");
+            SignatureValidationError::Invalid
+        }
+        err => SignatureValidationError::Other(err.into()),
+    }
+}
+
+/// Like [`call_error`] but also treats results that can't be decoded as
+/// invalid signatures since they mean that the signer isn't a Safe.
+fn safe_call_error(err: alloy::contract::Error, function: &str) -> SignatureValidationError {
+    match err {
+        alloy::contract::Error::TransportError(_) => call_error(err, function),
+        err => {
+            tracing::debug!(?err, function, "signer is not a Safe");
+            SignatureValidationError::Invalid
+        }
+    }
 }
 
 #[derive(Debug)]
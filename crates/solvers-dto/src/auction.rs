@@ -50,6 +50,8 @@ pub struct Order {
     pub partially_fillable: bool,
     pub pre_interactions: Vec<InteractionData>,
     pub post_interactions: Vec<InteractionData>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub hooks_gas: Option<HooksGas>,
     pub sell_token_source: SellTokenSource,
     pub buy_token_destination: BuyTokenDestination,
     pub class: Class,
@@ -97,6 +99,15 @@ pub struct InteractionData {
     pub call_data: Vec<u8>,
 }
 
+/// Gas used by every pre- and post-interaction of an order when they got
+/// simulated at order placement.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HooksGas {
+    pub pre: Vec<u64>,
+    pub post: Vec<u64>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum SigningScheme {
@@ -0,0 +1,18 @@
+-- Conditional orders created through the ComposableCoW framework. The orderbook
+-- polls them to post the discrete orders they currently want to trade.
+CREATE TABLE conditional_orders (
+    owner BYTEA NOT NULL,
+    -- keccak256 of the ABI encoded `ConditionalOrderParams`
+    params_hash BYTEA NOT NULL,
+    handler BYTEA NOT NULL,
+    salt BYTEA NOT NULL,
+    static_input BYTEA NOT NULL,
+    block_number BIGINT NOT NULL,
+    log_index BIGINT NOT NULL,
+    -- set to false once the handler indicated that the order will never trade again
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    PRIMARY KEY (owner, params_hash)
+);
+
+-- Index for efficient reorg handling
+CREATE INDEX conditional_orders_block_number ON conditional_orders (block_number);
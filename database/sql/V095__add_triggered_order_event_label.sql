@@ -0,0 +1 @@
+ALTER TYPE OrderEventLabel ADD VALUE 'triggered';
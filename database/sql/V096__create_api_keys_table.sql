@@ -0,0 +1,15 @@
+-- API keys identifying integrators of the orderbook API. Only a hash of the key
+-- is stored so that leaking the table doesn't leak usable keys.
+CREATE TABLE api_keys (
+    -- keccak256 of the API key
+    key_hash BYTEA PRIMARY KEY,
+    -- human readable name of the integrator, used to label metrics
+    name TEXT NOT NULL,
+    -- which groups of endpoints the key can be used for: 'quote', 'order', 'read'
+    -- or 'analytics'
+    scopes TEXT[] NOT NULL,
+    -- maximum number of requests per minute, NULL means unlimited
+    requests_per_minute INTEGER,
+    revoked BOOLEAN NOT NULL DEFAULT FALSE,
+    creation_timestamp TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
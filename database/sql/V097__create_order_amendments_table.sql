@@ -0,0 +1,15 @@
+-- Lineage of orders that were amended through the orderbook API. Amending an
+-- order cancels it and creates a new one in its place, so the amounts the old
+-- order executed are stored alongside to track progress across the lineage.
+CREATE TABLE order_amendments (
+    new_order_uid BYTEA PRIMARY KEY,
+    old_order_uid BYTEA NOT NULL,
+    executed_sell_amount NUMERIC(78,0) NOT NULL,
+    executed_buy_amount NUMERIC(78,0) NOT NULL,
+    executed_fee_amount NUMERIC(78,0) NOT NULL,
+    amendment_timestamp TIMESTAMPTZ NOT NULL
+);
+
+-- Every order can only be amended once which prevents concurrent amendments
+-- from forking the lineage.
+CREATE UNIQUE INDEX order_amendments_old_order_uid ON order_amendments (old_order_uid);
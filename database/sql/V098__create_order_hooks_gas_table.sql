@@ -0,0 +1,8 @@
+-- Gas used by the pre- and post-hooks of an order as measured by simulating
+-- them when the order got placed. Solvers can use this instead of the gas
+-- limits declared in the order's app data.
+CREATE TABLE order_hooks_gas (
+    order_uid BYTEA PRIMARY KEY,
+    pre_hooks BIGINT[] NOT NULL,
+    post_hooks BIGINT[] NOT NULL
+);
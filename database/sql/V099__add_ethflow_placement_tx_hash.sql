@@ -0,0 +1,8 @@
+-- Hash of the transaction that placed an ethflow order so users can look up
+-- their orders by it. The autopilot backfills it for orders indexed before this
+-- column was added and flags orders whose placement event can't be found so
+-- they don't get looked up again.
+ALTER TABLE ethflow_orders ADD COLUMN placement_tx_hash bytea;
+ALTER TABLE ethflow_orders ADD COLUMN placement_tx_hash_unavailable boolean NOT NULL DEFAULT false;
+
+CREATE INDEX ethflow_placement_tx_hash ON ethflow_orders (placement_tx_hash);
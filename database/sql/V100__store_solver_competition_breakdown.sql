@@ -0,0 +1,20 @@
+-- Makes the outcome of the winner selection auditable: how every order
+-- contributed to the score of a solution, why a solution got filtered out and
+-- which fairness baselines were used for that decision.
+CREATE TYPE SolutionFilterReason AS ENUM ('unfair');
+
+ALTER TABLE proposed_solutions
+    ADD COLUMN filter_reason SolutionFilterReason;
+
+ALTER TABLE proposed_trade_executions
+    ADD COLUMN surplus_score NUMERIC(78),
+    ADD COLUMN protocol_fee_score NUMERIC(78);
+
+CREATE TABLE fairness_baselines
+(
+    auction_id BIGINT      NOT NULL,
+    sell_token BYTEA       NOT NULL,
+    buy_token  BYTEA       NOT NULL,
+    score      NUMERIC(78) NOT NULL,
+    PRIMARY KEY (auction_id, sell_token, buy_token)
+);
@@ -0,0 +1,38 @@
+-- Solver rewards and penalties computed by the autopilot for every accounting
+-- period (a fixed range of blocks).
+CREATE TABLE accounting_periods
+(
+    id                      BIGINT           PRIMARY KEY,
+    start_block             BIGINT           NOT NULL,
+    end_block               BIGINT           NOT NULL,
+    upper_cap               NUMERIC(78)      NOT NULL,
+    lower_cap               NUMERIC(78)      NOT NULL,
+    protocol_fee_cap_factor DOUBLE PRECISION NOT NULL,
+    created_at              TIMESTAMPTZ      NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE solver_rewards
+(
+    auction_id      BIGINT      NOT NULL,
+    solver          BYTEA       NOT NULL,
+    period_id       BIGINT      NOT NULL,
+    score           NUMERIC(78) NOT NULL,
+    observed_score  NUMERIC(78) NOT NULL,
+    reference_score NUMERIC(78) NOT NULL,
+    protocol_fees   NUMERIC(78) NOT NULL,
+    reward          NUMERIC(78) NOT NULL,
+    PRIMARY KEY (auction_id, solver)
+);
+
+CREATE INDEX solver_rewards_period_id ON solver_rewards USING BTREE (period_id);
+
+CREATE TABLE solver_payouts
+(
+    period_id     BIGINT      NOT NULL,
+    solver        BYTEA       NOT NULL,
+    wins          BIGINT      NOT NULL,
+    settlements   BIGINT      NOT NULL,
+    protocol_fees NUMERIC(78) NOT NULL,
+    reward        NUMERIC(78) NOT NULL,
+    PRIMARY KEY (period_id, solver)
+);
@@ -0,0 +1,13 @@
+-- Orders whose stop-loss or take-profit trigger condition has been met. Once
+-- triggered an order stays solvable even if the price moves back so this state
+-- must not be lost when order events get cleaned up.
+CREATE TABLE triggered_orders (
+    order_uid BYTEA PRIMARY KEY,
+    triggered_at TIMESTAMPTZ NOT NULL
+);
+
+INSERT INTO triggered_orders (order_uid, triggered_at)
+SELECT order_uid, MIN(timestamp)
+FROM order_events
+WHERE label = 'triggered'
+GROUP BY order_uid;
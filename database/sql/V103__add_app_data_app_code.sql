@@ -0,0 +1,7 @@
+-- The `appCode` of the full app data so that orders can be filtered by the
+-- integrator that created them without parsing the JSON of every order. The
+-- column is set when app data gets stored. App data stored before this
+-- migration gets backfilled by the orderbook, so NULL means not backfilled yet
+-- and an empty string means the document has no `appCode`.
+-- A nullable column without a default doesn't rewrite the table.
+ALTER TABLE app_data ADD COLUMN app_code text;
@@ -0,0 +1,4 @@
+-- Built concurrently so app data can still be stored while the index gets
+-- built. This has to be the only statement of the migration because it can't
+-- run inside of a transaction.
+CREATE INDEX CONCURRENTLY app_data_app_code ON app_data (app_code);